@@ -0,0 +1,27 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// GetDepositHistoryResponse : Response to get deposit history request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GetDepositHistoryResponse {
+    /// The events in the history of the deposit, oldest first.
+    #[serde(rename = "events")]
+    pub events: Vec<models::DepositHistoryEvent>,
+}
+
+impl GetDepositHistoryResponse {
+    /// Response to get deposit history request.
+    pub fn new(events: Vec<models::DepositHistoryEvent>) -> GetDepositHistoryResponse {
+        GetDepositHistoryResponse { events }
+    }
+}
@@ -0,0 +1,27 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// GetRbfChainResponse : Response to get RBF chain request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GetRbfChainResponse {
+    /// The transactions in the RBF chain, starting with the requested one and ending with the latest known replacement.
+    #[serde(rename = "transactions")]
+    pub transactions: Vec<models::RbfChainTransaction>,
+}
+
+impl GetRbfChainResponse {
+    /// Response to get RBF chain request.
+    pub fn new(transactions: Vec<models::RbfChainTransaction>) -> GetRbfChainResponse {
+        GetRbfChainResponse { transactions }
+    }
+}
@@ -0,0 +1,49 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// RbfChainTransaction : A transaction in the RBF chain of a deposit.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RbfChainTransaction {
+    /// Output index on the bitcoin transaction associated with this specific deposit.
+    #[serde(rename = "bitcoinTxOutputIndex")]
+    pub bitcoin_tx_output_index: u32,
+    /// Bitcoin transaction id.
+    #[serde(rename = "bitcoinTxid")]
+    pub bitcoin_txid: String,
+    /// Transaction ID of the transaction that replaced this one via RBF.
+    #[serde(
+        rename = "replacedByTx",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub replaced_by_tx: Option<Option<String>>,
+    #[serde(rename = "status")]
+    pub status: models::DepositStatus,
+}
+
+impl RbfChainTransaction {
+    /// A transaction in the RBF chain of a deposit.
+    pub fn new(
+        bitcoin_tx_output_index: u32,
+        bitcoin_txid: String,
+        status: models::DepositStatus,
+    ) -> RbfChainTransaction {
+        RbfChainTransaction {
+            bitcoin_tx_output_index,
+            bitcoin_txid,
+            replaced_by_tx: None,
+            status,
+        }
+    }
+}
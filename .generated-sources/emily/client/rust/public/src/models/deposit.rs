@@ -17,6 +17,14 @@ pub struct Deposit {
     /// Amount of BTC being deposited in satoshis.
     #[serde(rename = "amount")]
     pub amount: u64,
+    /// The bitcoin chain tip height that the API was aware of when the deposit was first accepted or confirmed. This is an upper bound on, and not the same as, the height of the block that confirmed the deposit transaction. This is not set for deposits that have not been accepted or confirmed yet.
+    #[serde(
+        rename = "bitcoinHeightAtAcceptance",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub bitcoin_height_at_acceptance: Option<Option<u64>>,
     /// Output index on the bitcoin transaction associated with this specific deposit.
     #[serde(rename = "bitcoinTxOutputIndex")]
     pub bitcoin_tx_output_index: u32,
@@ -79,6 +87,7 @@ impl Deposit {
     ) -> Deposit {
         Deposit {
             amount,
+            bitcoin_height_at_acceptance: None,
             bitcoin_tx_output_index,
             bitcoin_txid,
             deposit_script,
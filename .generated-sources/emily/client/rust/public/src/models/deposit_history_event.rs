@@ -0,0 +1,45 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// DepositHistoryEvent : An event in the history of a deposit.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DepositHistoryEvent {
+    /// The status message of the event.
+    #[serde(rename = "message")]
+    pub message: String,
+    /// The Stacks block hash the API was aware of when the event happened.
+    #[serde(rename = "stacksBlockHash")]
+    pub stacks_block_hash: String,
+    /// The Stacks block height the API was aware of when the event happened.
+    #[serde(rename = "stacksBlockHeight")]
+    pub stacks_block_height: u64,
+    #[serde(rename = "status")]
+    pub status: models::DepositStatus,
+}
+
+impl DepositHistoryEvent {
+    /// An event in the history of a deposit.
+    pub fn new(
+        message: String,
+        stacks_block_hash: String,
+        stacks_block_height: u64,
+        status: models::DepositStatus,
+    ) -> DepositHistoryEvent {
+        DepositHistoryEvent {
+            message,
+            stacks_block_hash,
+            stacks_block_height,
+            status,
+        }
+    }
+}
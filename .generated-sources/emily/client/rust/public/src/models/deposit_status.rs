@@ -25,6 +25,10 @@ pub enum DepositStatus {
     Failed,
     #[serde(rename = "rbf")]
     Rbf,
+    #[serde(rename = "reclaimed")]
+    Reclaimed,
+    #[serde(rename = "expired")]
+    Expired,
 }
 
 impl std::fmt::Display for DepositStatus {
@@ -35,6 +39,8 @@ impl std::fmt::Display for DepositStatus {
             Self::Confirmed => write!(f, "confirmed"),
             Self::Failed => write!(f, "failed"),
             Self::Rbf => write!(f, "rbf"),
+            Self::Reclaimed => write!(f, "reclaimed"),
+            Self::Expired => write!(f, "expired"),
         }
     }
 }
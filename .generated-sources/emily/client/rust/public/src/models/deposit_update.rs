@@ -27,6 +27,14 @@ pub struct DepositUpdate {
         skip_serializing_if = "Option::is_none"
     )]
     pub fulfillment: Option<Option<Box<models::Fulfillment>>>,
+    /// Transaction ID of the transaction that reclaimed the deposited funds.
+    #[serde(
+        rename = "reclaimTxid",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub reclaim_txid: Option<Option<String>>,
     /// Transaction ID of the transaction that replaced this one via RBF.
     #[serde(
         rename = "replacedByTx",
@@ -54,6 +62,7 @@ impl DepositUpdate {
             bitcoin_tx_output_index,
             bitcoin_txid,
             fulfillment: None,
+            reclaim_txid: None,
             replaced_by_tx: None,
             status,
             status_message,
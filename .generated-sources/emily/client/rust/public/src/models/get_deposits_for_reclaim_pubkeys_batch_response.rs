@@ -0,0 +1,33 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// GetDepositsForReclaimPubkeysBatchResponse : Response to get deposits for reclaim pubkeys batch request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GetDepositsForReclaimPubkeysBatchResponse {
+    /// Deposit infos keyed by the requested reclaim pubkeys entry. Every requested entry is present, with an empty list if nothing was found.
+    #[serde(rename = "deposits")]
+    pub deposits: std::collections::HashMap<String, Vec<models::DepositInfo>>,
+    /// Next tokens for the entries that have more results.
+    #[serde(rename = "nextTokens")]
+    pub next_tokens: std::collections::HashMap<String, String>,
+}
+
+impl GetDepositsForReclaimPubkeysBatchResponse {
+    /// Response to get deposits for reclaim pubkeys batch request.
+    pub fn new(
+        deposits: std::collections::HashMap<String, Vec<models::DepositInfo>>,
+        next_tokens: std::collections::HashMap<String, String>,
+    ) -> GetDepositsForReclaimPubkeysBatchResponse {
+        GetDepositsForReclaimPubkeysBatchResponse { deposits, next_tokens }
+    }
+}
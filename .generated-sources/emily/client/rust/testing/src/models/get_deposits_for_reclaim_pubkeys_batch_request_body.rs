@@ -0,0 +1,42 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// GetDepositsForReclaimPubkeysBatchRequestBody : Request structure for the get deposits for reclaim pubkeys batch request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GetDepositsForReclaimPubkeysBatchRequestBody {
+    /// Next tokens from a previous call, keyed by the requested reclaim pubkeys entry they continue.
+    #[serde(rename = "nextTokens", skip_serializing_if = "Option::is_none")]
+    pub next_tokens: Option<std::collections::HashMap<String, String>>,
+    /// Maximum number of results to show for each reclaim pubkeys entry.
+    #[serde(
+        rename = "pageSize",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub page_size: Option<Option<u32>>,
+    /// The reclaim pubkeys to search by. Each entry is a dash-separated list of hex-encoded x-only pubkeys used to generate the reclaim_script.
+    #[serde(rename = "reclaimPubkeys")]
+    pub reclaim_pubkeys: Vec<String>,
+}
+
+impl GetDepositsForReclaimPubkeysBatchRequestBody {
+    /// Request structure for the get deposits for reclaim pubkeys batch request.
+    pub fn new(reclaim_pubkeys: Vec<String>) -> GetDepositsForReclaimPubkeysBatchRequestBody {
+        GetDepositsForReclaimPubkeysBatchRequestBody {
+            next_tokens: None,
+            page_size: None,
+            reclaim_pubkeys,
+        }
+    }
+}
@@ -4,10 +4,16 @@ pub mod chainstate;
 pub use self::chainstate::Chainstate;
 pub mod create_deposit_request_body;
 pub use self::create_deposit_request_body::CreateDepositRequestBody;
+pub mod create_deposits_request_body;
+pub use self::create_deposits_request_body::CreateDepositsRequestBody;
+pub mod create_deposits_response;
+pub use self::create_deposits_response::CreateDepositsResponse;
 pub mod create_withdrawal_request_body;
 pub use self::create_withdrawal_request_body::CreateWithdrawalRequestBody;
 pub mod deposit;
 pub use self::deposit::Deposit;
+pub mod deposit_history_event;
+pub use self::deposit_history_event::DepositHistoryEvent;
 pub mod deposit_info;
 pub use self::deposit_info::DepositInfo;
 pub mod deposit_parameters;
@@ -22,16 +28,26 @@ pub mod error_response;
 pub use self::error_response::ErrorResponse;
 pub mod fulfillment;
 pub use self::fulfillment::Fulfillment;
+pub mod get_deposit_history_response;
+pub use self::get_deposit_history_response::GetDepositHistoryResponse;
+pub mod get_deposits_for_reclaim_pubkeys_batch_request_body;
+pub use self::get_deposits_for_reclaim_pubkeys_batch_request_body::GetDepositsForReclaimPubkeysBatchRequestBody;
+pub mod get_deposits_for_reclaim_pubkeys_batch_response;
+pub use self::get_deposits_for_reclaim_pubkeys_batch_response::GetDepositsForReclaimPubkeysBatchResponse;
 pub mod get_deposits_for_transaction_response;
 pub use self::get_deposits_for_transaction_response::GetDepositsForTransactionResponse;
 pub mod get_deposits_response;
 pub use self::get_deposits_response::GetDepositsResponse;
+pub mod get_rbf_chain_response;
+pub use self::get_rbf_chain_response::GetRbfChainResponse;
 pub mod get_withdrawals_response;
 pub use self::get_withdrawals_response::GetWithdrawalsResponse;
 pub mod health_data;
 pub use self::health_data::HealthData;
 pub mod limits;
 pub use self::limits::Limits;
+pub mod rbf_chain_transaction;
+pub use self::rbf_chain_transaction::RbfChainTransaction;
 pub mod update_deposits_request_body;
 pub use self::update_deposits_request_body::UpdateDepositsRequestBody;
 pub mod update_deposits_response;
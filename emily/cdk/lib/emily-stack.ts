@@ -205,6 +205,31 @@ export class EmilyStack extends cdk.Stack {
                 "DepositScript",
             ]
         });
+
+        // Index to efficiently query deposits fulfilled in a specific bitcoin block.
+        const byFulfillmentBitcoinBlockHashIndexName: string = "DepositFulfillmentBitcoinBlockHashIndex";
+        table.addGlobalSecondaryIndex({
+            indexName: byFulfillmentBitcoinBlockHashIndexName,
+            partitionKey: {
+                name: 'FulfillmentBitcoinBlockHash',
+                type: dynamodb.AttributeType.STRING
+            },
+            sortKey: {
+                name: 'LastUpdateHeight',
+                type: dynamodb.AttributeType.NUMBER
+            },
+            projectionType: dynamodb.ProjectionType.INCLUDE,
+            nonKeyAttributes: [
+                "BitcoinTxid",
+                "BitcoinTxOutputIndex",
+                "Recipient",
+                "OpStatus",
+                "Amount",
+                "LastUpdateBlockHash",
+                "ReclaimScript",
+                "DepositScript",
+            ]
+        });
         return table;
     }
 
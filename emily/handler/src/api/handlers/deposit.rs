@@ -1,4 +1,6 @@
 //! Handlers for Deposit endpoints.
+use std::collections::HashSet;
+
 use bitcoin::ScriptBuf;
 use bitcoin::opcodes::all::{self as opcodes};
 use sbtc::deposits::ReclaimScriptInputs;
@@ -11,13 +13,16 @@ use warp::reply::{Reply, json, with_status};
 use crate::api::models::common::DepositStatus;
 use crate::api::models::common::requests::BasicPaginationQuery;
 use crate::api::models::deposit::responses::{
-    DepositWithStatus, GetDepositsForTransactionResponse, UpdateDepositsResponse,
+    CreateDepositsResponse, DepositWithStatus, GetDepositHistoryResponse,
+    GetDepositsForReclaimPubkeysBatchResponse, GetDepositsForTransactionResponse,
+    GetRbfChainResponse, UpdateDepositsResponse,
 };
-use crate::api::models::deposit::{Deposit, DepositInfo};
+use crate::api::models::deposit::{Deposit, DepositInfo, RbfChainTransaction};
 use crate::api::models::{
     deposit::requests::{
-        CreateDepositRequestBody, GetDepositsForTransactionQuery, GetDepositsQuery,
-        UpdateDepositsRequestBody,
+        CreateDepositRequestBody, CreateDepositsRequestBody,
+        GetDepositsByStatusAndHeightRangeQuery, GetDepositsForReclaimPubkeysBatchRequestBody,
+        GetDepositsForTransactionQuery, GetDepositsQuery, UpdateDepositsRequestBody,
     },
     deposit::responses::GetDepositsResponse,
 };
@@ -31,6 +36,14 @@ use crate::database::entries::deposit::{
     ValidatedUpdateDepositsRequest,
 };
 
+/// The maximum number of reclaim pubkeys entries that can be looked up in
+/// a single batch request.
+const MAX_RECLAIM_PUBKEYS_BATCH_SIZE: usize = 100;
+
+/// The maximum number of deposits that can be created in a single batch
+/// request.
+const MAX_CREATE_DEPOSITS_BATCH_SIZE: usize = 100;
+
 /// Get deposit handler.
 #[utoipa::path(
     get,
@@ -82,6 +95,165 @@ pub async fn get_deposit(
         .map_or_else(Reply::into_response, Reply::into_response)
 }
 
+/// Get deposit history handler.
+#[utoipa::path(
+    get,
+    operation_id = "getDepositHistory",
+    path = "/deposit/{txid}/{index}/history",
+    params(
+        ("txid" = String, Path, description = "txid associated with the Deposit."),
+        ("index" = String, Path, description = "output index associated with the Deposit."),
+    ),
+    tag = "deposit",
+    responses(
+        (status = 200, description = "Deposit history retrieved successfully", body = GetDepositHistoryResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 404, description = "Address not found", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[instrument(skip(context))]
+pub async fn get_deposit_history(
+    context: EmilyContext,
+    bitcoin_txid: String,
+    bitcoin_tx_output_index: u32,
+) -> impl warp::reply::Reply {
+    tracing::debug!("in get deposit history");
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        bitcoin_txid: String,
+        bitcoin_tx_output_index: u32,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        // Make key.
+        let key = DepositEntryKey {
+            bitcoin_txid,
+            bitcoin_tx_output_index,
+        };
+        // Get deposit.
+        let entry = accessors::get_deposit_entry(&context, &key).await?;
+        entry.validate()?;
+        // Convert the history into resource types, it is already in
+        // chronological order.
+        let events = entry
+            .history
+            .into_iter()
+            .map(|event| event.into())
+            .collect();
+        let response = GetDepositHistoryResponse { events };
+
+        // Respond.
+        Ok(with_status(json(&response), StatusCode::OK))
+    }
+
+    // Handle and respond.
+    handler(context, bitcoin_txid, bitcoin_tx_output_index)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
+/// Get RBF chain handler.
+#[utoipa::path(
+    get,
+    operation_id = "getRbfChain",
+    path = "/deposit/{txid}/{index}/rbf-chain",
+    params(
+        ("txid" = String, Path, description = "txid associated with the Deposit."),
+        ("index" = String, Path, description = "output index associated with the Deposit."),
+    ),
+    tag = "deposit",
+    responses(
+        (status = 200, description = "RBF chain retrieved successfully", body = GetRbfChainResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 404, description = "Address not found", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[instrument(skip(context))]
+pub async fn get_rbf_chain(
+    context: EmilyContext,
+    bitcoin_txid: String,
+    bitcoin_tx_output_index: u32,
+) -> impl warp::reply::Reply {
+    tracing::debug!("in get rbf chain");
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        bitcoin_txid: String,
+        bitcoin_tx_output_index: u32,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        // Make key.
+        let key = DepositEntryKey {
+            bitcoin_txid,
+            bitcoin_tx_output_index,
+        };
+        // Get the deposit the chain starts at.
+        let mut entry = accessors::get_deposit_entry(&context, &key).await?;
+        let mut visited = HashSet::from([entry.key.bitcoin_txid.clone()]);
+        let mut transactions = Vec::new();
+
+        // Follow the replacements until we reach a transaction that was not
+        // replaced or one that we do not know about. The data is written by
+        // the signers so guard against it looping back on itself.
+        loop {
+            transactions.push(RbfChainTransaction::from(&entry));
+            let Some(replaced_by_tx) = entry.replaced_by_tx.clone() else {
+                break;
+            };
+            if !visited.insert(replaced_by_tx.clone()) {
+                tracing::warn!(
+                    bitcoin_txid = %replaced_by_tx,
+                    "cycle detected in deposit RBF chain"
+                );
+                break;
+            }
+            let output_index = entry.key.bitcoin_tx_output_index;
+            match find_replacement_deposit(&context, replaced_by_tx, output_index).await? {
+                Some(next) => entry = next,
+                None => break,
+            }
+        }
+        let response = GetRbfChainResponse { transactions };
+
+        // Respond.
+        Ok(with_status(json(&response), StatusCode::OK))
+    }
+
+    // Handle and respond.
+    handler(context, bitcoin_txid, bitcoin_tx_output_index)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
+/// Find the deposit in the replacement transaction. The deposit usually
+/// keeps its output index across replacements, but when it does not and the
+/// replacement has exactly one deposit, that deposit is used instead.
+async fn find_replacement_deposit(
+    context: &EmilyContext,
+    bitcoin_txid: String,
+    bitcoin_tx_output_index: u32,
+) -> Result<Option<DepositEntry>, Error> {
+    let key = DepositEntryKey {
+        bitcoin_txid,
+        bitcoin_tx_output_index,
+    };
+    match accessors::get_deposit_entry(context, &key).await {
+        Ok(entry) => return Ok(Some(entry)),
+        Err(Error::NotFound) => {}
+        Err(error) => return Err(error),
+    }
+    let (mut entries, _) =
+        accessors::get_deposit_entries_for_transaction(context, &key.bitcoin_txid, None, None)
+            .await?;
+    if entries.len() == 1 {
+        Ok(entries.pop())
+    } else {
+        Ok(None)
+    }
+}
+
 /// Get deposits for transaction handler.
 #[utoipa::path(
     get,
@@ -147,7 +319,9 @@ pub async fn get_deposits_for_transaction(
     params(
         ("status" = DepositStatus, Query, description = "the status to search by when getting all deposits."),
         ("nextToken" = Option<String>, Query, description = "the next token value from the previous return of this api call."),
-        ("pageSize" = Option<u16>, Query, description = "the maximum number of items in the response list.")
+        ("pageSize" = Option<u16>, Query, description = "the maximum number of items in the response list."),
+        ("minAmount" = Option<u64>, Query, description = "the minimum amount, in sats, of the returned deposits."),
+        ("maxAmount" = Option<u64>, Query, description = "the maximum amount, in sats, of the returned deposits.")
     ),
     tag = "deposit",
     responses(
@@ -169,10 +343,72 @@ pub async fn get_deposits(
         context: EmilyContext,
         query: GetDepositsQuery,
     ) -> Result<impl warp::reply::Reply, Error> {
+        query.validate()?;
         // Deserialize next token into the exclusive start key if present/
         let (entries, next_token) = accessors::get_deposit_entries(
             &context,
             &query.status,
+            query.next_token.clone(),
+            query.page_size,
+        )
+        .await?;
+        // The amount is not part of any index, so the amount range is
+        // applied to each page after the query. This means that a page
+        // may have fewer items than the page size while there are still
+        // more pages to go.
+        let deposits: Vec<DepositInfo> = entries
+            .into_iter()
+            .filter(|entry| query.contains_amount(entry.amount))
+            .map(|entry| entry.into())
+            .collect();
+        // Create response.
+        let response = GetDepositsResponse { deposits, next_token };
+        // Respond.
+        Ok(with_status(json(&response), StatusCode::OK))
+    }
+    // Handle and respond.
+    handler(context, query)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
+/// Get deposits by status and height range handler.
+#[utoipa::path(
+    get,
+    operation_id = "getDepositsByStatusAndHeightRange",
+    path = "/deposit/height-range",
+    params(
+        ("status" = DepositStatus, Query, description = "the status to search by when getting the deposits."),
+        ("fromHeight" = u64, Query, description = "the lowest stacks height, inclusive, at which the deposits were last updated."),
+        ("toHeight" = u64, Query, description = "the highest stacks height, inclusive, at which the deposits were last updated."),
+        ("nextToken" = Option<String>, Query, description = "the next token value from the previous return of this api call."),
+        ("pageSize" = Option<u16>, Query, description = "the maximum number of items in the response list.")
+    ),
+    tag = "deposit",
+    responses(
+        (status = 200, description = "Deposits retrieved successfully", body = GetDepositsResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 404, description = "Address not found", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[instrument(skip(context))]
+pub async fn get_deposits_by_status_and_height_range(
+    context: EmilyContext,
+    query: GetDepositsByStatusAndHeightRangeQuery,
+) -> impl warp::reply::Reply {
+    tracing::debug!("in get deposits by status and height range");
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        query: GetDepositsByStatusAndHeightRangeQuery,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        let (entries, next_token) = accessors::get_deposit_entries_by_status_and_height_range(
+            &context,
+            &query.status,
+            query.from_height,
+            query.to_height,
             query.next_token,
             query.page_size,
         )
@@ -296,6 +532,135 @@ pub async fn get_deposits_for_reclaim_pubkeys(
         .map_or_else(Reply::into_response, Reply::into_response)
 }
 
+/// Get deposits for a batch of reclaim pubkeys handler.
+#[utoipa::path(
+    post,
+    operation_id = "getDepositsForReclaimPubkeysBatch",
+    path = "/deposit/reclaim-pubkeys",
+    tag = "deposit",
+    request_body = GetDepositsForReclaimPubkeysBatchRequestBody,
+    responses(
+        (status = 200, description = "Deposits retrieved successfully", body = GetDepositsForReclaimPubkeysBatchResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 404, description = "Address not found", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[instrument(skip(context))]
+pub async fn get_deposits_for_reclaim_pubkeys_batch(
+    context: EmilyContext,
+    body: GetDepositsForReclaimPubkeysBatchRequestBody,
+) -> impl warp::reply::Reply {
+    tracing::debug!("in get deposits for reclaim pubkeys batch");
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        mut body: GetDepositsForReclaimPubkeysBatchRequestBody,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        if body.reclaim_pubkeys.len() > MAX_RECLAIM_PUBKEYS_BATCH_SIZE {
+            return Err(Error::HttpRequest(
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "at most {MAX_RECLAIM_PUBKEYS_BATCH_SIZE} reclaim pubkeys entries are allowed"
+                ),
+            ));
+        }
+        // Validate everything up front so that we do not make any queries
+        // for a request that we are going to reject.
+        let reclaim_pubkeys_hashes = body
+            .reclaim_pubkeys
+            .iter()
+            .map(|reclaim_pubkeys| {
+                validate_reclaim_pubkeys(reclaim_pubkeys)
+                    .map(|bytes| (reclaim_pubkeys.clone(), sorted_sha256(bytes)))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let mut response = GetDepositsForReclaimPubkeysBatchResponse::default();
+        for (reclaim_pubkeys, reclaim_pubkeys_hash) in reclaim_pubkeys_hashes {
+            // Duplicate entries would only give the same answer again.
+            if response.deposits.contains_key(&reclaim_pubkeys) {
+                continue;
+            }
+            let (entries, next_token) = accessors::get_deposit_entries_by_reclaim_pubkeys_hash(
+                &context,
+                &reclaim_pubkeys_hash,
+                body.next_tokens.remove(&reclaim_pubkeys),
+                body.page_size,
+            )
+            .await?;
+            // Convert data into resource types.
+            let deposits: Vec<DepositInfo> =
+                entries.into_iter().map(|entry| entry.into()).collect();
+            if let Some(next_token) = next_token {
+                response
+                    .next_tokens
+                    .insert(reclaim_pubkeys.clone(), next_token);
+            }
+            response.deposits.insert(reclaim_pubkeys, deposits);
+        }
+        // Respond.
+        Ok(with_status(json(&response), StatusCode::OK))
+    }
+    // Handle and respond.
+    handler(context, body)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
+/// Get deposits by confirming bitcoin block handler.
+#[utoipa::path(
+    get,
+    operation_id = "getDepositsForBitcoinBlock",
+    path = "/deposit/bitcoin-block/{blockHash}",
+    params(
+        ("blockHash" = String, Path, description = "The hash of the bitcoin block that confirmed the deposit fulfillments."),
+        ("nextToken" = Option<String>, Query, description = "the next token value from the previous return of this api call."),
+        ("pageSize" = Option<u16>, Query, description = "the maximum number of items in the response list.")
+    ),
+    tag = "deposit",
+    responses(
+        (status = 200, description = "Deposits retrieved successfully", body = GetDepositsResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 404, description = "Address not found", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[instrument(skip(context))]
+pub async fn get_deposits_for_bitcoin_block(
+    context: EmilyContext,
+    block_hash: String,
+    query: BasicPaginationQuery,
+) -> impl warp::reply::Reply {
+    tracing::debug!("in get deposits for bitcoin block: {block_hash}");
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        block_hash: String,
+        query: BasicPaginationQuery,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        let (entries, next_token) = accessors::get_deposit_entries_by_bitcoin_block(
+            &context,
+            &block_hash,
+            query.next_token,
+            query.page_size,
+        )
+        .await?;
+        // Convert data into resource types.
+        let deposits: Vec<DepositInfo> = entries.into_iter().map(|entry| entry.into()).collect();
+        // Create response.
+        let response = GetDepositsResponse { deposits, next_token };
+        // Respond.
+        Ok(with_status(json(&response), StatusCode::OK))
+    }
+    // Handle and respond.
+    handler(context, block_hash, query)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
 /// Create deposit handler.
 #[utoipa::path(
     post,
@@ -331,72 +696,79 @@ pub async fn create_deposit(
         let api_state = accessors::get_api_state(&context).await?;
         api_state.error_if_reorganizing()?;
 
-        let chaintip = api_state.chaintip();
-        let stacks_block_hash = chaintip.key.hash;
-        let stacks_block_height = chaintip.key.height;
+        let (deposit, status) = create_deposit_entry(&context, &api_state, body).await?;
+        Ok(with_status(json(&deposit), status))
+    }
+    // Handle and respond.
+    handler(context, body)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
 
-        let deposit_info = body.validate(context.settings.is_mainnet)?;
+/// Create deposits handler.
+#[utoipa::path(
+    post,
+    operation_id = "createDeposits",
+    path = "/deposit/batch",
+    tag = "deposit",
+    request_body = CreateDepositsRequestBody,
+    responses(
+        (status = 200, description = "Deposits processed, see the status of each deposit", body = CreateDepositsResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 404, description = "Address not found", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[instrument(skip(context))]
+pub async fn create_deposits(
+    context: EmilyContext,
+    body: CreateDepositsRequestBody,
+) -> impl warp::reply::Reply {
+    tracing::debug!(count = body.deposits.len(), "creating deposits");
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        body: CreateDepositsRequestBody,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        if body.deposits.len() > MAX_CREATE_DEPOSITS_BATCH_SIZE {
+            return Err(Error::HttpRequest(
+                StatusCode::BAD_REQUEST,
+                format!("at most {MAX_CREATE_DEPOSITS_BATCH_SIZE} deposits are allowed"),
+            ));
+        }
+        let api_state = accessors::get_api_state(&context).await?;
+        api_state.error_if_reorganizing()?;
 
-        // Check if deposit with such txid and outindex already exists.
-        let entry = accessors::get_deposit_entry(
-            &context,
-            &DepositEntryKey {
-                bitcoin_txid: body.bitcoin_txid.clone(),
-                bitcoin_tx_output_index: body.bitcoin_tx_output_index,
-            },
-        )
-        .await;
+        let mut deposits: Vec<DepositWithStatus> = Vec::with_capacity(body.deposits.len());
+        for deposit in body.deposits {
+            let bitcoin_txid = deposit.bitcoin_txid.clone();
+            let bitcoin_tx_output_index = deposit.bitcoin_tx_output_index;
 
-        match entry {
-            Ok(deposit_entry) => {
-                // The deposit already exists, return it.
-                let response: Deposit = deposit_entry.try_into()?;
-                return Ok(with_status(json(&response), StatusCode::OK));
-            }
-            Err(Error::NotFound) => {}
-            Err(e) => return Err(e),
-        }
-        let reclaim_pubkeys_hash = extract_reclaim_pubkeys_hash(&deposit_info.reclaim_script);
-        if reclaim_pubkeys_hash.is_none() {
-            tracing::warn!(
-                bitcoin_txid = %body.bitcoin_txid,
-                bitcoin_tx_output_index = %body.bitcoin_tx_output_index,
-                "unknown reclaim script"
-            );
+            let deposit_with_status =
+                match create_deposit_entry(&context, &api_state, deposit).await {
+                    Ok((deposit, status)) => DepositWithStatus {
+                        deposit,
+                        status: status.as_u16(),
+                    },
+                    Err(error) => {
+                        tracing::warn!(
+                            %bitcoin_txid,
+                            bitcoin_tx_output_index,
+                            %error,
+                            "failed to create deposit"
+                        );
+                        DepositWithStatus {
+                            deposit: Deposit::default(),
+                            status: error.status_code().as_u16(),
+                        }
+                    }
+                };
+            deposits.push(deposit_with_status);
         }
-        // Make table entry.
-        let deposit_entry: DepositEntry = DepositEntry {
-            key: DepositEntryKey {
-                bitcoin_txid: body.bitcoin_txid,
-                bitcoin_tx_output_index: body.bitcoin_tx_output_index,
-            },
-            recipient: hex::encode(deposit_info.recipient.serialize_to_vec()),
-            parameters: DepositParametersEntry {
-                max_fee: deposit_info.max_fee,
-                lock_time: deposit_info.lock_time.to_consensus_u32(),
-            },
-            history: vec![DepositEvent {
-                status: DepositStatusEntry::Pending,
-                message: "Just received deposit".to_string(),
-                stacks_block_hash: stacks_block_hash.clone(),
-                stacks_block_height,
-            }],
-            status: DepositStatus::Pending,
-            last_update_block_hash: stacks_block_hash,
-            last_update_height: stacks_block_height,
-            amount: deposit_info.amount,
-            reclaim_script: body.reclaim_script,
-            deposit_script: body.deposit_script,
-            reclaim_pubkeys_hash,
-            ..Default::default()
-        };
-        // Validate deposit entry.
-        deposit_entry.validate()?;
-        // Add entry to the table.
-        accessors::add_deposit_entry(&context, &deposit_entry).await?;
-        // Respond.
-        let response: Deposit = deposit_entry.try_into()?;
-        Ok(with_status(json(&response), StatusCode::CREATED))
+
+        let response = CreateDepositsResponse { deposits };
+        Ok(with_status(json(&response), StatusCode::OK))
     }
     // Handle and respond.
     handler(context, body)
@@ -404,6 +776,82 @@ pub async fn create_deposit(
         .map_or_else(Reply::into_response, Reply::into_response)
 }
 
+/// Create the deposit entry for the given request if it does not already
+/// exist, returning the deposit along with [`StatusCode::CREATED`] if it
+/// was created, or [`StatusCode::OK`] if it already existed.
+async fn create_deposit_entry(
+    context: &EmilyContext,
+    api_state: &ApiStateEntry,
+    body: CreateDepositRequestBody,
+) -> Result<(Deposit, StatusCode), Error> {
+    let chaintip = api_state.chaintip();
+    let stacks_block_hash = chaintip.key.hash;
+    let stacks_block_height = chaintip.key.height;
+
+    let deposit_info = body.validate(context.settings.is_mainnet)?;
+
+    // Check if deposit with such txid and outindex already exists.
+    let entry = accessors::get_deposit_entry(
+        context,
+        &DepositEntryKey {
+            bitcoin_txid: body.bitcoin_txid.clone(),
+            bitcoin_tx_output_index: body.bitcoin_tx_output_index,
+        },
+    )
+    .await;
+
+    match entry {
+        Ok(deposit_entry) => {
+            // The deposit already exists, return it.
+            let deposit: Deposit = deposit_entry.try_into()?;
+            return Ok((deposit, StatusCode::OK));
+        }
+        Err(Error::NotFound) => {}
+        Err(e) => return Err(e),
+    }
+    let reclaim_pubkeys_hash = extract_reclaim_pubkeys_hash(&deposit_info.reclaim_script);
+    if reclaim_pubkeys_hash.is_none() {
+        tracing::warn!(
+            bitcoin_txid = %body.bitcoin_txid,
+            bitcoin_tx_output_index = %body.bitcoin_tx_output_index,
+            "unknown reclaim script"
+        );
+    }
+    // Make table entry.
+    let deposit_entry: DepositEntry = DepositEntry {
+        key: DepositEntryKey {
+            bitcoin_txid: body.bitcoin_txid,
+            bitcoin_tx_output_index: body.bitcoin_tx_output_index,
+        },
+        recipient: hex::encode(deposit_info.recipient.serialize_to_vec()),
+        parameters: DepositParametersEntry {
+            max_fee: deposit_info.max_fee,
+            lock_time: deposit_info.lock_time.to_consensus_u32(),
+        },
+        history: vec![DepositEvent {
+            status: DepositStatusEntry::Pending,
+            message: "Just received deposit".to_string(),
+            stacks_block_hash: stacks_block_hash.clone(),
+            stacks_block_height,
+        }],
+        status: DepositStatus::Pending,
+        last_update_block_hash: stacks_block_hash,
+        last_update_height: stacks_block_height,
+        amount: deposit_info.amount,
+        reclaim_script: body.reclaim_script,
+        deposit_script: body.deposit_script,
+        reclaim_pubkeys_hash,
+        ..Default::default()
+    };
+    // Validate deposit entry.
+    deposit_entry.validate()?;
+    // Add entry to the table.
+    accessors::add_deposit_entry(context, &deposit_entry).await?;
+    // Respond.
+    let deposit: Deposit = deposit_entry.try_into()?;
+    Ok((deposit, StatusCode::CREATED))
+}
+
 /// Update deposits handler.
 #[utoipa::path(
     put,
@@ -605,6 +1053,18 @@ async fn update_deposits(
         ));
     }
 
+    // Updates that were collapsed into another one report the result of
+    // the update that was applied in their place.
+    for (index, kept_index) in validated_request.duplicates {
+        let kept = updated_deposits
+            .iter()
+            .find(|(updated_index, _)| *updated_index == kept_index)
+            .map(|(_, deposit)| deposit.clone());
+        if let Some(deposit) = kept {
+            updated_deposits.push((index, deposit));
+        }
+    }
+
     updated_deposits.sort_by_key(|(index, _)| *index);
     let deposits = updated_deposits
         .into_iter()
@@ -637,7 +1097,7 @@ fn sorted_sha256(mut pubkeys: Vec<[u8; 32]>) -> String {
 /// Parse the reclaim script to extract the pubkeys and hash them with sha256 in
 /// an order-independent way.
 /// Currently supports the sBTC Bridge, Leather and Asigna reclaim scripts.
-fn extract_reclaim_pubkeys_hash(reclaim_script: &ScriptBuf) -> Option<String> {
+pub(crate) fn extract_reclaim_pubkeys_hash(reclaim_script: &ScriptBuf) -> Option<String> {
     let reclaim = ReclaimScriptInputs::parse(reclaim_script).ok()?;
 
     match reclaim.user_script().as_bytes() {
@@ -9,7 +9,7 @@ use crate::common::error::{Error, Inconsistency};
 use crate::context::EmilyContext;
 use crate::database::accessors;
 use crate::database::entries::chainstate::{ApiStateEntry, ApiStatus};
-use crate::database::entries::deposit::DepositEntry;
+use crate::database::entries::deposit::{DepositEntry, ReorgOutcome};
 use crate::database::entries::withdrawal::WithdrawalEntry;
 
 const MAX_SET_API_STATE_ATTEMPTS_DURING_REORG: u32 = 20;
@@ -144,8 +144,16 @@ pub async fn execute_reorg_handler(
         for attempt in 0..ENTRY_UPDATE_RETRIES {
             let mut entry =
                 accessors::get_deposit_entry(context, &deposit.primary_index_key).await?;
-            entry.reorganize_around(&request.canonical_tip)?;
+            let outcome = entry.reorganize_around(&request.canonical_tip)?;
             match accessors::set_deposit_entry(context, &mut entry).await {
+                Ok(_) if outcome != ReorgOutcome::Unchanged => {
+                    warn!(
+                        deposit = %entry.key,
+                        ?outcome,
+                        "Deposit status was rolled back by a reorg",
+                    );
+                    break;
+                }
                 Ok(_) => break,
                 Err(Error::VersionConflict(error)) => {
                     warn!(
@@ -277,6 +277,7 @@ async fn handle_completed_deposit(
         }),
         status_message: format!("Included in block {}", event.block_id.to_hex()),
         replaced_by_tx: None,
+        reclaim_txid: None,
     })
 }
 
@@ -49,6 +49,12 @@ pub enum DepositStatus {
     Failed,
     /// Transaction was replaced by another transaction via RBF.
     Rbf,
+    /// The depositor reclaimed the funds on chain after the lock time
+    /// expired.
+    Reclaimed,
+    /// The lock time of the deposit elapsed before the deposit was swept,
+    /// so the signers will not sweep it.
+    Expired,
 }
 
 /// The status of the in-flight sBTC withdrawal.
@@ -59,6 +59,12 @@ pub struct Deposit {
     /// Transaction ID of the transaction that replaced this one via RBF.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub replaced_by_tx: Option<String>,
+    /// The bitcoin chain tip height that the API was aware of when the deposit
+    /// was first accepted or confirmed. This is an upper bound on, and not the
+    /// same as, the height of the block that confirmed the deposit transaction.
+    /// This is not set for deposits that have not been accepted or confirmed yet.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bitcoin_height_at_acceptance: Option<u64>,
 }
 
 /// Deposit parameters.
@@ -85,6 +91,61 @@ pub struct DepositParameters {
     pub lock_time: u32,
 }
 
+/// An event in the history of a deposit.
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Eq,
+    PartialEq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    ToResponse,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositHistoryEvent {
+    /// The status of the deposit after this event.
+    pub status: DepositStatus,
+    /// The status message of the event.
+    pub message: String,
+    /// The Stacks block height the API was aware of when the event happened.
+    pub stacks_block_height: u64,
+    /// The Stacks block hash the API was aware of when the event happened.
+    pub stacks_block_hash: String,
+}
+
+/// A transaction in the RBF chain of a deposit.
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Eq,
+    PartialEq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    ToResponse,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct RbfChainTransaction {
+    /// Bitcoin transaction id.
+    pub bitcoin_txid: String,
+    /// Output index on the bitcoin transaction associated with this specific deposit.
+    pub bitcoin_tx_output_index: u32,
+    /// The status of the deposit in this transaction.
+    pub status: DepositStatus,
+    /// Transaction ID of the transaction that replaced this one via RBF.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replaced_by_tx: Option<String>,
+}
+
 /// Reduced version of the Deposit data.
 #[derive(
     Clone,
@@ -1,9 +1,9 @@
 //! Request structures for deposit api calls.
 
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
-use bitcoin::blockdata::transaction::Transaction;
-use bitcoin::consensus::encode;
 use bitcoin::{OutPoint, ScriptBuf, Txid};
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
@@ -43,6 +43,75 @@ pub struct GetDepositsQuery {
     /// Maximum number of results to show.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub page_size: Option<u16>,
+    /// Minimum deposit amount, in sats, of the returned deposits.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_amount: Option<u64>,
+    /// Maximum deposit amount, in sats, of the returned deposits.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_amount: Option<u64>,
+}
+
+/// Query structure for getting deposits by status within a range of
+/// stacks heights.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDepositsByStatusAndHeightRangeQuery {
+    /// Operation status.
+    pub status: DepositStatus,
+    /// The lowest stacks height, inclusive, of the last update of the
+    /// returned deposits.
+    pub from_height: u64,
+    /// The highest stacks height, inclusive, of the last update of the
+    /// returned deposits.
+    pub to_height: u64,
+    /// Next token for the search.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_token: Option<String>,
+    /// Maximum number of results to show.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u16>,
+}
+
+impl GetDepositsQuery {
+    /// Validates that the amount range in the query is not empty.
+    pub fn validate(&self) -> Result<(), Error> {
+        match (self.min_amount, self.max_amount) {
+            (Some(min_amount), Some(max_amount)) if min_amount > max_amount => {
+                Err(Error::HttpRequest(
+                    StatusCode::BAD_REQUEST,
+                    "min amount must not be greater than max amount".to_string(),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether the given deposit amount is within the amount range of the
+    /// query.
+    pub fn contains_amount(&self, amount: u64) -> bool {
+        self.min_amount
+            .is_none_or(|min_amount| amount >= min_amount)
+            && self
+                .max_amount
+                .is_none_or(|max_amount| amount <= max_amount)
+    }
+}
+
+/// Request structure for the get deposits for reclaim pubkeys batch request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDepositsForReclaimPubkeysBatchRequestBody {
+    /// The reclaim pubkeys to search by. Each entry is a dash-separated
+    /// list of hex-encoded x-only pubkeys used to generate the
+    /// reclaim_script.
+    pub reclaim_pubkeys: Vec<String>,
+    /// Next tokens from a previous call, keyed by the requested reclaim
+    /// pubkeys entry they continue.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub next_tokens: HashMap<String, String>,
+    /// Maximum number of results to show for each reclaim pubkeys entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u16>,
 }
 
 /// Request structure for create deposit request.
@@ -61,6 +130,14 @@ pub struct CreateDepositRequestBody {
     pub transaction_hex: String,
 }
 
+/// Request structure for the create deposits request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateDepositsRequestBody {
+    /// The deposits to create.
+    pub deposits: Vec<CreateDepositRequestBody>,
+}
+
 fn parse_with_custom_error<T, F, E>(input: &str, parser: F, error_msg: &str) -> Result<T, Error>
 where
     F: Fn(&str) -> Result<T, E>,
@@ -93,23 +170,21 @@ impl CreateDepositRequestBody {
             )?,
         };
 
-        let tx: Transaction = parse_with_custom_error(
-            &self.transaction_hex,
-            encode::deserialize_hex,
-            "invalid transaction hex",
-        )?;
-
-        tx.tx_out(self.bitcoin_tx_output_index as usize)
-            .map_err(|_| {
-                Error::HttpRequest(
-                    StatusCode::BAD_REQUEST,
-                    "invalid bitcoin output index".to_string(),
-                )
-            })?;
-
         deposit_req
-            .validate_tx(&tx, is_mainnet)
-            .map_err(|e| Error::HttpRequest(StatusCode::BAD_REQUEST, e.to_string()))
+            .validate_tx_hex(&self.transaction_hex, is_mainnet)
+            .map_err(|error| {
+                let message = match error {
+                    sbtc::error::Error::DecodeTransactionHex(_)
+                    | sbtc::error::Error::DeserializeTransaction(_) => {
+                        "invalid transaction hex".to_string()
+                    }
+                    sbtc::error::Error::OutpointIndex(..) => {
+                        "invalid bitcoin output index".to_string()
+                    }
+                    error => error.to_string(),
+                };
+                Error::HttpRequest(StatusCode::BAD_REQUEST, message)
+            })
     }
 }
 
@@ -133,6 +208,10 @@ pub struct DepositUpdate {
     /// Transaction ID of the transaction that replaced this one via RBF.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub replaced_by_tx: Option<String>,
+    /// Transaction ID of the transaction that reclaimed the deposited
+    /// funds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reclaim_txid: Option<String>,
 }
 
 impl DepositUpdate {
@@ -141,6 +220,8 @@ impl DepositUpdate {
     /// # Errors
     ///
     /// - `ValidationError::DepositMissingFulfillment`: If the deposit update is missing a fulfillment.
+    /// - `ValidationError::InvalidFulfillmentStatus`: If the deposit update has a fulfillment but
+    ///   is not confirmed.
     pub fn try_into_validated_deposit_update(
         self,
         chainstate: Chainstate,
@@ -158,6 +239,22 @@ impl DepositUpdate {
                 self.bitcoin_tx_output_index,
             ));
         }
+        // Only confirmed deposits can have a fulfillment.
+        if self.status != DepositStatus::Confirmed && self.fulfillment.is_some() {
+            return Err(error::ValidationError::InvalidFulfillmentStatus(
+                self.status,
+                self.bitcoin_txid,
+                self.bitcoin_tx_output_index,
+            ));
+        }
+        // Only reclaimed deposits can have a reclaim_txid.
+        if self.status != DepositStatus::Reclaimed && self.reclaim_txid.is_some() {
+            return Err(error::ValidationError::InvalidReclaimTxidStatus(
+                self.status,
+                self.bitcoin_txid,
+                self.bitcoin_tx_output_index,
+            ));
+        }
         // Make status entry.
         let status_entry: DepositStatusEntry = match self.status {
             DepositStatus::Confirmed => {
@@ -178,6 +275,13 @@ impl DepositUpdate {
                     self.bitcoin_tx_output_index,
                 ),
             )?),
+            DepositStatus::Reclaimed => DepositStatusEntry::Reclaimed(self.reclaim_txid.ok_or(
+                ValidationError::DepositMissingReclaimTxid(
+                    self.bitcoin_txid,
+                    self.bitcoin_tx_output_index,
+                ),
+            )?),
+            DepositStatus::Expired => DepositStatusEntry::Expired,
         };
         // Make the new event.
         let event = DepositEvent {
@@ -187,7 +291,11 @@ impl DepositUpdate {
             stacks_block_hash: chainstate.stacks_block_hash,
         };
         // Return the validated update.
-        Ok(ValidatedDepositUpdate { key, event })
+        Ok(ValidatedDepositUpdate {
+            key,
+            event,
+            bitcoin_block_height: chainstate.bitcoin_block_height,
+        })
     }
 }
 
@@ -231,6 +339,21 @@ impl UpdateDepositsRequestBody {
                     );
                     deposits.push((index, Err(error.clone())));
                 }
+                Err(
+                    ref error @ ValidationError::InvalidFulfillmentStatus(
+                        ref status,
+                        ref bitcoin_txid,
+                        bitcoin_tx_output_index,
+                    ),
+                ) => {
+                    tracing::warn!(
+                        %bitcoin_txid,
+                        bitcoin_tx_output_index,
+                        ?status,
+                        "failed to update deposit: request has fulfillment for a non-confirmed status."
+                    );
+                    deposits.push((index, Err(error.clone())));
+                }
                 Err(error) => {
                     tracing::error!(
                         bitcoin_txid = update.bitcoin_txid,
@@ -243,13 +366,68 @@ impl UpdateDepositsRequestBody {
             }
         }
 
+        // Collapse updates for the same deposit at the same height so that
+        // each is applied once. We keep the update with the highest status
+        // rank, and the last one sent if the ranks are the same.
+        let mut kept: HashMap<(&DepositEntryKey, u64), (usize, &DepositStatusEntry)> =
+            HashMap::new();
+        let mut dropped: HashSet<usize> = HashSet::new();
+        for (index, update) in deposits.iter() {
+            let Ok(update) = update else {
+                continue;
+            };
+            let key = (&update.key, update.event.stacks_block_height);
+            let candidate = (*index, &update.event.status);
+            match kept.entry(key) {
+                Entry::Vacant(entry) => {
+                    entry.insert(candidate);
+                }
+                Entry::Occupied(mut entry)
+                    if status_rank(candidate.1) >= status_rank(entry.get().1) =>
+                {
+                    dropped.insert(entry.get().0);
+                    entry.insert(candidate);
+                }
+                Entry::Occupied(_) => {
+                    dropped.insert(candidate.0);
+                }
+            }
+        }
+        let duplicates = deposits
+            .iter()
+            .filter(|(index, _)| dropped.contains(index))
+            .filter_map(|(index, update)| {
+                let update = update.as_ref().ok()?;
+                let key = (&update.key, update.event.stacks_block_height);
+                kept.get(&key).map(|(kept_index, _)| (*index, *kept_index))
+            })
+            .collect();
+        deposits.retain(|(index, _)| !dropped.contains(index));
+
         // Sort updates by stacks_block_height to process them in chronological order.
         deposits.sort_by_key(|(_, update)| match update {
             Ok(validated_update) => validated_update.event.stacks_block_height,
             Err(_) => u64::MAX, // Place errors at the end
         });
 
-        ValidatedUpdateDepositsRequest { deposits }
+        ValidatedUpdateDepositsRequest { deposits, duplicates }
+    }
+}
+
+/// The rank of a deposit status when collapsing duplicate updates.
+///
+/// Statuses further along in the lifecycle of a deposit rank higher. All
+/// final statuses share the same rank, so the last one sent wins among
+/// them.
+fn status_rank(status: &DepositStatusEntry) -> u8 {
+    match status {
+        DepositStatusEntry::Pending => 0,
+        DepositStatusEntry::Accepted => 1,
+        DepositStatusEntry::Confirmed(_)
+        | DepositStatusEntry::Failed
+        | DepositStatusEntry::Rbf(_)
+        | DepositStatusEntry::Reclaimed(_)
+        | DepositStatusEntry::Expired => 2,
     }
 }
 
@@ -319,4 +497,92 @@ mod tests {
             format!("HTTP request failed with status code 400 Bad Request: {expected_error}")
         );
     }
+
+    #[test_case(DepositStatus::Confirmed, None; "confirmed_without_fulfillment")]
+    #[test_case(DepositStatus::Pending, Some(Fulfillment::default()); "pending_with_fulfillment")]
+    #[test_case(DepositStatus::Accepted, Some(Fulfillment::default()); "accepted_with_fulfillment")]
+    #[test_case(DepositStatus::Failed, Some(Fulfillment::default()); "failed_with_fulfillment")]
+    #[test_case(DepositStatus::Expired, Some(Fulfillment::default()); "expired_with_fulfillment")]
+    fn deposit_update_fulfillment_must_match_status(
+        status: DepositStatus,
+        fulfillment: Option<Fulfillment>,
+    ) {
+        let update = DepositUpdate {
+            bitcoin_txid: "txid".to_string(),
+            bitcoin_tx_output_index: 0,
+            status: status.clone(),
+            status_message: "message".to_string(),
+            fulfillment,
+            replaced_by_tx: None,
+            reclaim_txid: None,
+        };
+
+        let error = update
+            .try_into_validated_deposit_update(Chainstate::default())
+            .unwrap_err();
+        let expected = match status {
+            DepositStatus::Confirmed => {
+                ValidationError::DepositMissingFulfillment("txid".into(), 0)
+            }
+            _ => ValidationError::InvalidFulfillmentStatus(status, "txid".into(), 0),
+        };
+        assert_eq!(error, expected);
+    }
+
+    #[test]
+    fn validated_update_request_collapses_duplicates() {
+        let update = |bitcoin_txid: &str, status: DepositStatus| DepositUpdate {
+            bitcoin_txid: bitcoin_txid.to_string(),
+            bitcoin_tx_output_index: 0,
+            status,
+            status_message: "message".to_string(),
+            fulfillment: None,
+            replaced_by_tx: None,
+            reclaim_txid: None,
+        };
+        let body = UpdateDepositsRequestBody {
+            deposits: vec![
+                update("txid1", DepositStatus::Accepted),
+                update("txid1", DepositStatus::Accepted),
+                update("txid2", DepositStatus::Accepted),
+                update("txid2", DepositStatus::Pending),
+            ],
+        };
+
+        let request = body.into_validated_update_request(Chainstate::default());
+
+        // The last of two identical updates is kept, and otherwise the
+        // update with the highest status is kept.
+        let indices: Vec<usize> = request.deposits.iter().map(|(index, _)| *index).collect();
+        assert_eq!(indices, vec![1, 2]);
+        assert_eq!(request.duplicates, vec![(0, 1), (3, 2)]);
+    }
+
+    #[test]
+    fn validated_update_request_keeps_the_last_final_status() {
+        let update = |status: DepositStatus| DepositUpdate {
+            bitcoin_txid: "txid".to_string(),
+            bitcoin_tx_output_index: 0,
+            status,
+            status_message: "message".to_string(),
+            fulfillment: None,
+            replaced_by_tx: None,
+            reclaim_txid: None,
+        };
+        let body = UpdateDepositsRequestBody {
+            deposits: vec![
+                update(DepositStatus::Expired),
+                update(DepositStatus::Accepted),
+                update(DepositStatus::Failed),
+            ],
+        };
+
+        let request = body.into_validated_update_request(Chainstate::default());
+
+        // Final statuses rank the same, so the last one sent is kept even
+        // though it comes first in the derived ordering.
+        let indices: Vec<usize> = request.deposits.iter().map(|(index, _)| *index).collect();
+        assert_eq!(indices, vec![2]);
+        assert_eq!(request.duplicates, vec![(0, 2), (1, 2)]);
+    }
 }
@@ -1,9 +1,11 @@
 //! Response structures for deposit api calls.
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use utoipa::{ToResponse, ToSchema};
 
-use crate::api::models::deposit::{Deposit, DepositInfo};
+use crate::api::models::deposit::{Deposit, DepositHistoryEvent, DepositInfo, RbfChainTransaction};
 
 /// Response to get deposits for transaction request.
 #[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
@@ -15,6 +17,23 @@ pub struct GetDepositsForTransactionResponse {
     pub deposits: Vec<Deposit>,
 }
 
+/// Response to get deposit history request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDepositHistoryResponse {
+    /// The events in the history of the deposit, oldest first.
+    pub events: Vec<DepositHistoryEvent>,
+}
+
+/// Response to get RBF chain request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
+#[serde(rename_all = "camelCase")]
+pub struct GetRbfChainResponse {
+    /// The transactions in the RBF chain, starting with the requested one
+    /// and ending with the latest known replacement.
+    pub transactions: Vec<RbfChainTransaction>,
+}
+
 /// Response to get deposits request.
 #[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
 #[serde(rename_all = "camelCase")]
@@ -25,6 +44,26 @@ pub struct GetDepositsResponse {
     pub deposits: Vec<DepositInfo>,
 }
 
+/// Response to get deposits for reclaim pubkeys batch request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize, ToSchema, ToResponse)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDepositsForReclaimPubkeysBatchResponse {
+    /// Deposit infos keyed by the requested reclaim pubkeys entry. Every
+    /// requested entry is present, with an empty list if nothing was found.
+    pub deposits: HashMap<String, Vec<DepositInfo>>,
+    /// Next tokens for the entries that have more results.
+    pub next_tokens: HashMap<String, String>,
+}
+
+/// Response to create deposits request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateDepositsResponse {
+    /// The deposits in the order they were requested, each with the status
+    /// code of its creation.
+    pub deposits: Vec<DepositWithStatus>,
+}
+
 /// Response to update deposits request.
 #[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
 #[serde(rename_all = "camelCase")]
@@ -10,11 +10,19 @@ pub fn routes(
     context: EmilyContext,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     get_deposit(context.clone())
+        .or(get_deposit_history(context.clone()))
+        .or(get_rbf_chain(context.clone()))
+        // This must come before the deposits for transaction endpoint,
+        // which would otherwise treat "height-range" as a txid.
+        .or(get_deposits_by_status_and_height_range(context.clone()))
         .or(get_deposits_for_transaction(context.clone()))
         .or(get_deposits(context.clone()))
         .or(get_deposits_for_recipient(context.clone()))
         .or(get_deposits_for_reclaim_pubkeys(context.clone()))
+        .or(get_deposits_for_reclaim_pubkeys_batch(context.clone()))
+        .or(get_deposits_for_bitcoin_block(context.clone()))
         .or(create_deposit(context.clone()))
+        .or(create_deposits(context.clone()))
         .or(update_deposits_sidecar(context.clone()))
         .or(update_deposits_signer(context))
 }
@@ -30,6 +38,28 @@ fn get_deposit(
         .then(handlers::deposit::get_deposit)
 }
 
+/// Get deposit history endpoint.
+fn get_deposit_history(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("deposit" / String / u32 / "history"))
+        .and(warp::get())
+        .then(handlers::deposit::get_deposit_history)
+}
+
+/// Get RBF chain endpoint.
+fn get_rbf_chain(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("deposit" / String / u32 / "rbf-chain"))
+        .and(warp::get())
+        .then(handlers::deposit::get_rbf_chain)
+}
+
 /// Get deposits for transaction endpoint.
 fn get_deposits_for_transaction(
     context: EmilyContext,
@@ -54,6 +84,18 @@ fn get_deposits(
         .then(handlers::deposit::get_deposits)
 }
 
+/// Get deposits by status and height range endpoint.
+fn get_deposits_by_status_and_height_range(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("deposit" / "height-range"))
+        .and(warp::get())
+        .and(warp::query())
+        .then(handlers::deposit::get_deposits_by_status_and_height_range)
+}
+
 /// Get deposits for recipient endpoint.
 fn get_deposits_for_recipient(
     context: EmilyContext,
@@ -78,6 +120,30 @@ fn get_deposits_for_reclaim_pubkeys(
         .then(handlers::deposit::get_deposits_for_reclaim_pubkeys)
 }
 
+/// Get deposits for a batch of reclaim pubkeys endpoint.
+fn get_deposits_for_reclaim_pubkeys_batch(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("deposit" / "reclaim-pubkeys"))
+        .and(warp::post())
+        .and(warp::body::json())
+        .then(handlers::deposit::get_deposits_for_reclaim_pubkeys_batch)
+}
+
+/// Get deposits for confirming bitcoin block endpoint.
+fn get_deposits_for_bitcoin_block(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("deposit" / "bitcoin-block" / String))
+        .and(warp::get())
+        .and(warp::query())
+        .then(handlers::deposit::get_deposits_for_bitcoin_block)
+}
+
 /// Create deposit endpoint.
 fn create_deposit(
     context: EmilyContext,
@@ -90,6 +156,18 @@ fn create_deposit(
         .then(handlers::deposit::create_deposit)
 }
 
+/// Create deposits endpoint.
+fn create_deposits(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("deposit" / "batch"))
+        .and(warp::post())
+        .and(warp::body::json())
+        .then(handlers::deposit::create_deposits)
+}
+
 /// Update deposits from signer endpoint.
 fn update_deposits_signer(
     context: EmilyContext,
@@ -48,6 +48,13 @@ pub enum ValidationError {
     #[error("missing fulfillment for confirmed deposit request with txid: {0}, vout: {1}")]
     DepositMissingFulfillment(String, u32),
 
+    /// The deposit includes fulfillment data, but its status is not
+    /// Confirmed. Only confirmed deposits may include a fulfillment.
+    #[error(
+        "deposit with fulfillment is only valid if status is Confirmed, but got status {0:?} for txid: {1}, vout: {2}"
+    )]
+    InvalidFulfillmentStatus(DepositStatus, String, u32),
+
     /// One of rolling_withdrawal_blocks or rolling_withdrawal_cap is missing while the other is set.
     /// Fields must be provided together to configure withdrawal limits.
     #[error(
@@ -65,6 +72,18 @@ pub enum ValidationError {
     /// The deposit has status RBF but is missing the replaced_by_tx field.
     #[error("missing replaced_by_tx for RBF deposit with txid: {0}, vout: {1}")]
     DepositMissingReplacementTx(String, u32),
+
+    /// The deposit includes a reclaim_txid field, but its status is not
+    /// Reclaimed. Only reclaimed deposits may include a reclaim_txid.
+    #[error(
+        "deposit with reclaim_txid is only valid if status is Reclaimed, but got status {0:?} for txid: {1}, vout: {2}"
+    )]
+    InvalidReclaimTxidStatus(DepositStatus, String, u32),
+
+    /// The deposit has status Reclaimed but is missing the reclaim_txid
+    /// field.
+    #[error("missing reclaim_txid for reclaimed deposit with txid: {0}, vout: {1}")]
+    DepositMissingReclaimTxid(String, u32),
 }
 
 /// Errors from the internal API logic.
@@ -18,9 +18,9 @@ use crate::{
 };
 
 use super::entries::deposit::{
-    DepositInfoByRecipientEntry, DepositInfoByReclaimPubkeysEntry,
-    DepositTableByRecipientSecondaryIndex, DepositTableByReclaimPubkeysSecondaryIndex,
-    ValidatedDepositUpdate,
+    DepositInfoByBitcoinBlockEntry, DepositInfoByRecipientEntry, DepositInfoByReclaimPubkeysEntry,
+    DepositTableByBitcoinBlockSecondaryIndex, DepositTableByRecipientSecondaryIndex,
+    DepositTableByReclaimPubkeysSecondaryIndex, ValidatedDepositUpdate,
 };
 use super::entries::limits::{
     GLOBAL_CAP_ACCOUNT, LimitEntry, LimitEntryKey, LimitTablePrimaryIndex,
@@ -121,6 +121,48 @@ pub async fn get_deposit_entries_by_reclaim_pubkeys_hash(
     .await
 }
 
+/// Get deposit entries whose fulfillment was confirmed in the given bitcoin block.
+pub async fn get_deposit_entries_by_bitcoin_block(
+    context: &EmilyContext,
+    bitcoin_block_hash: &String,
+    maybe_next_token: Option<String>,
+    maybe_page_size: Option<u16>,
+) -> Result<(Vec<DepositInfoByBitcoinBlockEntry>, Option<String>), Error> {
+    query_with_partition_key::<DepositTableByBitcoinBlockSecondaryIndex>(
+        context,
+        bitcoin_block_hash,
+        maybe_next_token,
+        maybe_page_size,
+    )
+    .await
+}
+
+/// Get deposit entries with the given status that were last updated at a
+/// stacks height within `[from_height, to_height]`, ordered by height.
+pub async fn get_deposit_entries_by_status_and_height_range(
+    context: &EmilyContext,
+    status: &DepositStatus,
+    from_height: u64,
+    to_height: u64,
+    maybe_next_token: Option<String>,
+    maybe_page_size: Option<u16>,
+) -> Result<(Vec<DepositInfoEntry>, Option<String>), Error> {
+    // DynamoDB rejects a `BETWEEN` condition with an empty range, and
+    // there is nothing to return for one anyway.
+    if from_height > to_height {
+        return Ok((Vec::new(), None));
+    }
+    query_with_partition_and_sort_key_range::<DepositTableSecondaryIndex>(
+        context,
+        status,
+        &from_height,
+        &to_height,
+        maybe_next_token,
+        maybe_page_size,
+    )
+    .await
+}
+
 /// Gets all deposit entries modified from (on or after) a given height.
 pub async fn get_all_deposit_entries_modified_from_height(
     context: &EmilyContext,
@@ -204,16 +246,33 @@ pub async fn pull_and_update_deposit_with_retry(
         {
             return Ok(deposit_entry);
         }
-        let is_valid_untrusted_status_update = update.event.status == DepositStatusEntry::Accepted
-            && deposit_entry.status == DepositStatus::Pending;
+        // Signers may accept pending deposits, and may expire accepted
+        // deposits, but only once we can tell from our own chainstate
+        // that the lock time has elapsed. Anything else needs the
+        // trusted key.
+        let is_valid_untrusted_status_update = match update.event.status {
+            DepositStatusEntry::Accepted => deposit_entry.status == DepositStatus::Pending,
+            DepositStatusEntry::Expired => {
+                deposit_entry.status == DepositStatus::Accepted
+                    && deposit_entry.is_known_reclaimable_at(update.bitcoin_block_height)
+            }
+            _ => false,
+        };
         if !is_trusted_key && !is_valid_untrusted_status_update {
             return Err(Error::Forbidden);
         }
-        // Make the update package.
-        let update_package: DepositUpdatePackage =
-            DepositUpdatePackage::try_from(&deposit_entry, update.clone())?;
-        // Attempt to update the deposit.
-        match update_deposit(context, &update_package).await {
+        // Attempt to update the deposit. A message-only update changes
+        // the message of the latest event in place instead of appending
+        // a new event.
+        let result = if update.is_message_only(&deposit_entry) {
+            update_deposit_latest_message(context, &deposit_entry, &update.event.message).await
+        } else {
+            // Make the update package.
+            let update_package: DepositUpdatePackage =
+                DepositUpdatePackage::try_from(&deposit_entry, update.clone())?;
+            update_deposit(context, &update_package).await
+        };
+        match result {
             Err(Error::VersionConflict(error)) => {
                 warn!(%error, "received an error when updating a deposit request");
                 err = *error;
@@ -234,22 +293,33 @@ pub async fn update_deposit(
     context: &EmilyContext,
     update: &DepositUpdatePackage,
 ) -> Result<DepositEntry, Error> {
+    // Get simplified status enum.
+    let status: DepositStatus = (&update.event.status).into();
+    // The bitcoin height is recorded the first time the deposit is
+    // accepted or confirmed and is left untouched afterwards.
+    let acceptance_height = update
+        .bitcoin_block_height
+        .filter(|_| matches!(status, DepositStatus::Accepted | DepositStatus::Confirmed));
     // Setup the update procedure.
-    let update_expression: &str = " SET
+    let mut update_expression = String::from(
+        " SET
         History = list_append(History, :new_event),
         Version = Version + :one,
         OpStatus = :new_op_status,
         LastUpdateHeight = :new_height,
-        LastUpdateBlockHash = :new_hash
-    ";
+        LastUpdateBlockHash = :new_hash",
+    );
+    if acceptance_height.is_some() {
+        update_expression.push_str(
+            ", BitcoinHeightAtAcceptance = if_not_exists(BitcoinHeightAtAcceptance, :acceptance_height)",
+        );
+    }
     // Ensure the version field is what we expect it to be.
     let condition_expression = "attribute_exists(Version) AND Version = :expected_version";
     // Make the key item.
     let key_item: Item = serde_dynamo::to_item(&update.key)?;
-    // Get simplified status enum.
-    let status: DepositStatus = (&update.event.status).into();
     // Build the update.
-    context
+    let mut request = context
         .dynamodb_client
         .update_item()
         .table_name(&context.settings.deposit_table_name)
@@ -271,7 +341,14 @@ pub async fn update_deposit(
             ":expected_version",
             serde_dynamo::to_attribute_value(update.version)?,
         )
-        .expression_attribute_values(":one", AttributeValue::N(1.to_string()))
+        .expression_attribute_values(":one", AttributeValue::N(1.to_string()));
+    if let Some(height) = acceptance_height {
+        request = request.expression_attribute_values(
+            ":acceptance_height",
+            serde_dynamo::to_attribute_value(height)?,
+        );
+    }
+    request
         .condition_expression(condition_expression)
         .return_values(aws_sdk_dynamodb::types::ReturnValue::AllNew)
         .update_expression(update_expression)
@@ -284,6 +361,55 @@ pub async fn update_deposit(
         })
 }
 
+/// Updates the message of the latest event of a deposit in place.
+pub async fn update_deposit_latest_message(
+    context: &EmilyContext,
+    entry: &DepositEntry,
+    message: &str,
+) -> Result<DepositEntry, Error> {
+    let latest_index = entry
+        .history
+        .len()
+        .checked_sub(1)
+        .ok_or(Error::InvalidDepositEntry(
+            "Deposit entry must always have at least one event but did not",
+            entry.key.clone(),
+        ))?;
+    // Setup the update procedure.
+    let update_expression = format!(
+        " SET
+        History[{latest_index}].Message = :new_message,
+        Version = Version + :one
+    "
+    );
+    // Ensure the version field is what we expect it to be.
+    let condition_expression = "attribute_exists(Version) AND Version = :expected_version";
+    // Make the key item.
+    let key_item: Item = serde_dynamo::to_item(&entry.key)?;
+    // Build the update.
+    context
+        .dynamodb_client
+        .update_item()
+        .table_name(&context.settings.deposit_table_name)
+        .set_key(Some(key_item.into()))
+        .expression_attribute_values(":new_message", serde_dynamo::to_attribute_value(message)?)
+        .expression_attribute_values(
+            ":expected_version",
+            serde_dynamo::to_attribute_value(entry.version)?,
+        )
+        .expression_attribute_values(":one", AttributeValue::N(1.to_string()))
+        .condition_expression(condition_expression)
+        .return_values(aws_sdk_dynamodb::types::ReturnValue::AllNew)
+        .update_expression(update_expression)
+        .send()
+        .await?
+        .attributes
+        .ok_or(Error::MissingAttributesDeposit(entry.key.clone()))
+        .and_then(|attributes| {
+            serde_dynamo::from_item::<Item, DepositEntry>(attributes.into()).map_err(Error::from)
+        })
+}
+
 // Withdrawal ------------------------------------------------------------------
 
 /// Add withdrawal entry.
@@ -1004,6 +1130,26 @@ async fn query_with_partition_key<T: TableIndexTrait>(
     .await
 }
 
+async fn query_with_partition_and_sort_key_range<T: TableIndexTrait>(
+    context: &EmilyContext,
+    partition_key: &<<<T as TableIndexTrait>::Entry as EntryTrait>::Key as KeyTrait>::PartitionKey,
+    from_sort_key: &<<<T as TableIndexTrait>::Entry as EntryTrait>::Key as KeyTrait>::SortKey,
+    to_sort_key: &<<<T as TableIndexTrait>::Entry as EntryTrait>::Key as KeyTrait>::SortKey,
+    maybe_next_token: Option<String>,
+    maybe_page_size: Option<u16>,
+) -> Result<(Vec<<T as TableIndexTrait>::Entry>, Option<String>), Error> {
+    <T as TableIndexTrait>::query_with_partition_and_sort_key_range(
+        &context.dynamodb_client,
+        &context.settings,
+        partition_key,
+        from_sort_key,
+        to_sort_key,
+        maybe_next_token,
+        maybe_page_size,
+    )
+    .await
+}
+
 async fn query_all_with_partition_and_sort_key<T: TableIndexTrait>(
     context: &EmilyContext,
     partition_key: &<<<T as TableIndexTrait>::Entry as EntryTrait>::Key as KeyTrait>::PartitionKey,
@@ -1,5 +1,6 @@
 //! Entries into the deposit table.
 
+use bitcoin::ScriptBuf;
 use serde::{Deserialize, Serialize};
 
 use super::{
@@ -7,10 +8,13 @@ use super::{
     SecondaryIndexTrait, VersionedEntryTrait,
 };
 use crate::{
+    api::handlers::deposit::extract_reclaim_pubkeys_hash,
     api::models::{
         chainstate::Chainstate,
         common::{DepositStatus, Fulfillment},
-        deposit::{Deposit, DepositInfo, DepositParameters},
+        deposit::{
+            Deposit, DepositHistoryEvent, DepositInfo, DepositParameters, RbfChainTransaction,
+        },
     },
     common::error::{Error, Inconsistency, ValidationError},
 };
@@ -33,6 +37,25 @@ impl std::fmt::Display for DepositEntryKey {
     }
 }
 
+/// What happened to a deposit entry when it was reorganized around a
+/// chainstate.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReorgOutcome {
+    /// None of the events in the history were removed.
+    Unchanged,
+    /// Some events were removed and the entry now reflects an earlier
+    /// event in its history.
+    Trimmed {
+        /// The status of the deposit before the reorg.
+        previous_status: DepositStatus,
+    },
+    /// All events were removed and the deposit was reset to pending.
+    ResetToPending {
+        /// The status of the deposit before the reorg.
+        previous_status: DepositStatus,
+    },
+}
+
 /// Deposit table entry.
 #[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -77,6 +100,19 @@ pub struct DepositEntry {
     /// Transaction ID of transaction which replaced this transaction during an RBF.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub replaced_by_tx: Option<String>,
+    /// The hash of the bitcoin block containing the transaction that fulfilled
+    /// this deposit. This mirrors the block hash in `fulfillment` as a top level
+    /// field so that deposits can be indexed by their confirming bitcoin block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fulfillment_bitcoin_block_hash: Option<String>,
+    /// The bitcoin chain tip height that the API was aware of when the
+    /// deposit first moved to accepted or confirmed, which is an upper bound
+    /// on the height of the block that confirmed the deposit transaction.
+    /// This is set once and is only cleared when a reorg removes every
+    /// accepted or confirmed event from the history. It is not projected
+    /// into any of the secondary indexes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bitcoin_height_at_acceptance: Option<u64>,
 }
 
 /// Implements versioned entry trait for the deposit entry.
@@ -159,6 +195,28 @@ impl DepositEntry {
         Ok(())
     }
 
+    /// Returns whether the API knows that the depositor can reclaim this
+    /// deposit as of the given bitcoin block height.
+    ///
+    /// The `bitcoin_height_at_acceptance` is an upper bound on the height
+    /// of the block that confirmed the deposit, so the lock time has
+    /// certainly elapsed once the given height reaches it plus the lock
+    /// time. Deposits that were never accepted, or whose lock time is not
+    /// a number of blocks, are never known to be reclaimable.
+    pub fn is_known_reclaimable_at(&self, bitcoin_block_height: Option<u64>) -> bool {
+        let (Some(height), Some(accepted_at)) =
+            (bitcoin_block_height, self.bitcoin_height_at_acceptance)
+        else {
+            return false;
+        };
+        match bitcoin::relative::LockTime::from_consensus(self.parameters.lock_time) {
+            Ok(bitcoin::relative::LockTime::Blocks(blocks)) => {
+                height >= accepted_at.saturating_add(u64::from(blocks.value()))
+            }
+            _ => false,
+        }
+    }
+
     /// Gets the latest event.
     pub fn latest_event(&self) -> Result<&DepositEvent, Error> {
         self.history.last().ok_or(Error::InvalidDepositEntry(
@@ -167,9 +225,29 @@ impl DepositEntry {
         ))
     }
 
-    /// Reorgs around a given chainstate.
+    /// Recomputes the reclaim pubkeys hash from the reclaim script of the
+    /// entry, using the same parsing as when the deposit is created, and
+    /// returns whether the stored hash changed.
+    ///
+    /// This is meant for backfilling entries created before the hash was
+    /// recorded. No other field is touched, including the version, which
+    /// gets incremented as usual when the entry is written back.
+    pub fn recompute_reclaim_pubkeys_hash(&mut self) -> Result<bool, Error> {
+        let reclaim_script = ScriptBuf::from_hex(&self.reclaim_script).map_err(|_| {
+            Error::InvalidDepositEntry("reclaim script is not valid hex", self.key.clone())
+        })?;
+        let reclaim_pubkeys_hash = extract_reclaim_pubkeys_hash(&reclaim_script);
+        let changed = self.reclaim_pubkeys_hash != reclaim_pubkeys_hash;
+        self.reclaim_pubkeys_hash = reclaim_pubkeys_hash;
+        Ok(changed)
+    }
+
+    /// Reorgs around a given chainstate, returning what happened to the
+    /// entry so that callers can report rolled back deposits.
     /// TODO(TBD): Remove duplicate code around deposits and withdrawals if possible.
-    pub fn reorganize_around(&mut self, chainstate: &Chainstate) -> Result<(), Error> {
+    pub fn reorganize_around(&mut self, chainstate: &Chainstate) -> Result<ReorgOutcome, Error> {
+        let previous_status = self.status.clone();
+        let previous_history_len = self.history.len();
         // Update the history to have the histories wiped after the reorg.
         self.history.retain(|event| {
             // The event is younger than the reorg...
@@ -178,6 +256,13 @@ impl DepositEntry {
                 || ((chainstate.stacks_block_height == event.stacks_block_height)
                     && (chainstate.stacks_block_hash == event.stacks_block_hash))
         });
+        let outcome = if self.history.len() == previous_history_len {
+            ReorgOutcome::Unchanged
+        } else if self.history.is_empty() {
+            ReorgOutcome::ResetToPending { previous_status }
+        } else {
+            ReorgOutcome::Trimmed { previous_status }
+        };
         // If the history is empty, just say that the deposit is pending again where its
         // latest update is the point at which the reorg happened.
         if self.history.is_empty() {
@@ -191,7 +276,7 @@ impl DepositEntry {
         // Synchronize self with the new history.
         self.synchronize_with_history()?;
         // Return.
-        Ok(())
+        Ok(outcome)
     }
 
     /// Synchronizes the entry with its history.
@@ -223,6 +308,19 @@ impl DepositEntry {
         } else {
             self.fulfillment = None;
         }
+        self.fulfillment_bitcoin_block_hash = self
+            .fulfillment
+            .as_ref()
+            .map(|fulfillment| fulfillment.bitcoin_block_hash.clone());
+        let observed_on_chain = self.history.iter().any(|event| {
+            matches!(
+                event.status,
+                DepositStatusEntry::Accepted | DepositStatusEntry::Confirmed(_)
+            )
+        });
+        if !observed_on_chain {
+            self.bitcoin_height_at_acceptance = None;
+        }
         if new_status == DepositStatus::Rbf {
             self.replaced_by_tx = match &latest_event.status {
                 DepositStatusEntry::Rbf(replaced_by_tx) => Some(replaced_by_tx.clone()),
@@ -277,6 +375,7 @@ impl TryFrom<DepositEntry> for Deposit {
             deposit_script: deposit_entry.deposit_script,
             fulfillment,
             replaced_by_tx,
+            bitcoin_height_at_acceptance: deposit_entry.bitcoin_height_at_acceptance,
         })
     }
 }
@@ -331,12 +430,62 @@ impl DepositEvent {
                 message
             );
             return Err(Error::InconsistentState(Inconsistency::ItemUpdate(message)));
+        } else if self.stacks_block_height < next_event.stacks_block_height
+            && matches!(self.status, DepositStatusEntry::Confirmed(_))
+            && matches!(
+                next_event.status,
+                DepositStatusEntry::Accepted | DepositStatusEntry::Pending
+            )
+        {
+            // A fulfilled deposit can only go back to accepted or pending
+            // through a reorg, and those go through `reorganize_around`.
+            let message = "Attempting to move a confirmed deposit back to accepted or pending outside of a reorg";
+            tracing::warn!(
+                new_event = ?next_event,
+                last_existing_event = ?self,
+                message
+            );
+            return Err(Error::InconsistentState(Inconsistency::ItemUpdate(message)));
+        } else if matches!(self.status, DepositStatusEntry::Confirmed(_))
+            && next_event.status == DepositStatusEntry::Expired
+        {
+            // Once swept, the depositor can no longer reclaim the funds,
+            // so the lock time of the deposit is irrelevant.
+            let message = "Attempting to expire a confirmed deposit";
+            tracing::warn!(
+                new_event = ?next_event,
+                last_existing_event = ?self,
+                message
+            );
+            return Err(Error::InconsistentState(Inconsistency::ItemUpdate(message)));
         }
 
         Ok(())
     }
 }
 
+impl From<&DepositEntry> for RbfChainTransaction {
+    fn from(entry: &DepositEntry) -> Self {
+        RbfChainTransaction {
+            bitcoin_txid: entry.key.bitcoin_txid.clone(),
+            bitcoin_tx_output_index: entry.key.bitcoin_tx_output_index,
+            status: entry.status.clone(),
+            replaced_by_tx: entry.replaced_by_tx.clone(),
+        }
+    }
+}
+
+impl From<DepositEvent> for DepositHistoryEvent {
+    fn from(event: DepositEvent) -> Self {
+        DepositHistoryEvent {
+            status: (&event.status).into(),
+            message: event.message,
+            stacks_block_height: event.stacks_block_height,
+            stacks_block_hash: event.stacks_block_hash,
+        }
+    }
+}
+
 // Deposit info entry ----------------------------------------------------------
 
 /// Search token for GSI.
@@ -651,6 +800,114 @@ impl From<DepositInfoByReclaimPubkeysEntry> for DepositInfo {
     }
 }
 
+/// Search token for confirming bitcoin block GSI.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DepositInfoByBitcoinBlockEntrySearchToken {
+    /// Primary index key.
+    #[serde(flatten)]
+    pub primary_index_key: DepositEntryKey,
+    /// Global secondary index key.
+    #[serde(flatten)]
+    pub secondary_index_key: DepositInfoByBitcoinBlockEntryKey,
+}
+
+/// Key for deposit info entry that's indexed by the bitcoin block that
+/// confirmed the deposit fulfillment.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DepositInfoByBitcoinBlockEntryKey {
+    /// The hash of the bitcoin block containing the transaction that fulfilled
+    /// the deposit.
+    pub fulfillment_bitcoin_block_hash: String,
+    /// The most recent Stacks block height the API was aware of when the deposit was last
+    /// updated. If the most recent update is tied to an artifact on the Stacks blockchain
+    /// then this height is the Stacks block height that contains that artifact.
+    pub last_update_height: u64,
+}
+
+/// Reduced version of the deposit data that is indexed by the confirming
+/// bitcoin block hash.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DepositInfoByBitcoinBlockEntry {
+    /// Gsi key data.
+    #[serde(flatten)]
+    pub key: DepositInfoByBitcoinBlockEntryKey,
+    /// Primary index key data.
+    #[serde(flatten)]
+    pub primary_index_key: DepositEntryKey,
+    /// The status of the entry.
+    #[serde(rename = "OpStatus")]
+    pub status: DepositStatus,
+    /// The recipient of the deposit encoded in hex.
+    pub recipient: String,
+    /// Amount of BTC being deposited in satoshis.
+    pub amount: u64,
+    /// The raw reclaim script.
+    pub reclaim_script: String,
+    /// The raw deposit script.
+    pub deposit_script: String,
+    /// The most recent Stacks block hash the API was aware of when the deposit was last
+    /// updated. If the most recent update is tied to an artifact on the Stacks blockchain
+    /// then this hash is the Stacks block hash that contains that artifact.
+    pub last_update_block_hash: String,
+}
+
+/// Implements the key trait for the deposit entry key.
+impl KeyTrait for DepositInfoByBitcoinBlockEntryKey {
+    /// The type of the partition key.
+    type PartitionKey = String;
+    /// the type of the sort key.
+    type SortKey = u64;
+    /// The table field name of the partition key.
+    const PARTITION_KEY_NAME: &'static str = "FulfillmentBitcoinBlockHash";
+    /// The table field name of the sort key.
+    const SORT_KEY_NAME: &'static str = "LastUpdateHeight";
+}
+
+/// Implements the entry trait for the deposit entry.
+impl EntryTrait for DepositInfoByBitcoinBlockEntry {
+    /// The type of the key for this entry type.
+    type Key = DepositInfoByBitcoinBlockEntryKey;
+    /// Extract the key from the deposit info entry.
+    fn key(&self) -> Self::Key {
+        DepositInfoByBitcoinBlockEntryKey {
+            fulfillment_bitcoin_block_hash: self.key.fulfillment_bitcoin_block_hash.clone(),
+            last_update_height: self.key.last_update_height,
+        }
+    }
+}
+
+/// Primary index struct.
+pub struct DepositTableByBitcoinBlockSecondaryIndexInner;
+/// Deposit table primary index type.
+pub type DepositTableByBitcoinBlockSecondaryIndex =
+    SecondaryIndex<DepositTableByBitcoinBlockSecondaryIndexInner>;
+/// Definition of Primary index trait.
+impl SecondaryIndexTrait for DepositTableByBitcoinBlockSecondaryIndexInner {
+    type PrimaryIndex = DepositTablePrimaryIndex;
+    type Entry = DepositInfoByBitcoinBlockEntry;
+    const INDEX_NAME: &'static str = "DepositFulfillmentBitcoinBlockHashIndex";
+}
+
+impl From<DepositInfoByBitcoinBlockEntry> for DepositInfo {
+    fn from(deposit_info_entry: DepositInfoByBitcoinBlockEntry) -> Self {
+        // Create deposit info resource from deposit info table entry.
+        DepositInfo {
+            bitcoin_txid: deposit_info_entry.primary_index_key.bitcoin_txid,
+            bitcoin_tx_output_index: deposit_info_entry.primary_index_key.bitcoin_tx_output_index,
+            recipient: deposit_info_entry.recipient,
+            amount: deposit_info_entry.amount,
+            last_update_height: deposit_info_entry.key.last_update_height,
+            last_update_block_hash: deposit_info_entry.last_update_block_hash,
+            status: deposit_info_entry.status,
+            reclaim_script: deposit_info_entry.reclaim_script,
+            deposit_script: deposit_info_entry.deposit_script,
+        }
+    }
+}
+
 // -----------------------------------------------------------------------------
 
 /// Validated version of the update deposit request.
@@ -663,6 +920,12 @@ pub struct ValidatedUpdateDepositsRequest {
     /// This allows the updates to be executed in chronological order but returned in the order
     /// that the client sent them.
     pub deposits: Vec<(usize, Result<ValidatedDepositUpdate, ValidationError>)>,
+    /// Updates that were collapsed into another update in the same request
+    /// because they are for the same deposit at the same stacks block
+    /// height. The first value of the tuple is the index of the dropped
+    /// update in the original request and the second is the index of the
+    /// update that was kept, whose result is reported for both.
+    pub duplicates: Vec<(usize, usize)>,
 }
 
 /// Validated deposit update.
@@ -672,11 +935,20 @@ pub struct ValidatedDepositUpdate {
     pub key: DepositEntryKey,
     /// Deposit event.
     pub event: DepositEvent,
+    /// The bitcoin block height the API was aware of when the update was
+    /// received.
+    pub bitcoin_block_height: Option<u64>,
 }
 
 impl ValidatedDepositUpdate {
     /// Returns true if the update is not necessary.
+    ///
+    /// A message-only update is always necessary, even if an older event
+    /// in the history has the same message.
     pub fn is_unnecessary(&self, entry: &DepositEntry) -> bool {
+        if self.is_message_only(entry) {
+            return false;
+        }
         entry
             .history
             .iter()
@@ -684,6 +956,24 @@ impl ValidatedDepositUpdate {
             .take_while(|event| event.stacks_block_height >= self.event.stacks_block_height)
             .any(|event| event == &self.event)
     }
+
+    /// Returns true if the update has the same status and stacks block as
+    /// the latest event of the entry and only changes its message.
+    ///
+    /// Such an update is applied by changing the message of the latest
+    /// event in place rather than by appending a new event, so that
+    /// retries with refined messages do not bloat the history.
+    pub fn is_message_only(&self, entry: &DepositEntry) -> bool {
+        entry.latest_event().is_ok_and(|latest_event| {
+            latest_event.status == self.event.status
+                && latest_event.stacks_block_height == self.event.stacks_block_height
+                && latest_event.stacks_block_hash == self.event.stacks_block_hash
+                && latest_event.message != self.event.message
+                && latest_event
+                    .ensure_following_event_is_valid(&self.event)
+                    .is_ok()
+        })
+    }
 }
 
 /// Packaged deposit update.
@@ -694,6 +984,9 @@ pub struct DepositUpdatePackage {
     pub version: u64,
     /// Deposit event.
     pub event: DepositEvent,
+    /// The bitcoin block height the API was aware of when the update was
+    /// received.
+    pub bitcoin_block_height: Option<u64>,
 }
 
 /// Implementation of deposit update package.
@@ -713,6 +1006,7 @@ impl DepositUpdatePackage {
             key: entry.key.clone(),
             version: entry.version,
             event: update.event,
+            bitcoin_block_height: update.bitcoin_block_height,
         })
     }
 }
@@ -722,6 +1016,53 @@ mod tests {
     use super::*;
     use test_case::test_case;
 
+    #[test]
+    fn recompute_reclaim_pubkeys_hash_backfills_missing_hash() {
+        let pubkey = [1u8; 32];
+        let user_script = ScriptBuf::builder()
+            .push_opcode(bitcoin::opcodes::all::OP_DROP)
+            .push_slice(pubkey)
+            .push_opcode(bitcoin::opcodes::all::OP_CHECKSIG)
+            .into_script();
+        let reclaim_script = sbtc::deposits::ReclaimScriptInputs::try_new(14, user_script)
+            .unwrap()
+            .reclaim_script();
+
+        let mut deposit = DepositEntry {
+            reclaim_script: reclaim_script.to_hex_string(),
+            reclaim_pubkeys_hash: None,
+            ..Default::default()
+        };
+        let original = deposit.clone();
+
+        assert!(deposit.recompute_reclaim_pubkeys_hash().unwrap());
+        let expected_hash = extract_reclaim_pubkeys_hash(&reclaim_script);
+        assert!(expected_hash.is_some());
+        assert_eq!(deposit.reclaim_pubkeys_hash, expected_hash);
+        // Nothing else about the entry changes.
+        assert_eq!(
+            DepositEntry {
+                reclaim_pubkeys_hash: None,
+                ..deposit.clone()
+            },
+            original
+        );
+
+        // Running it again is a no-op.
+        assert!(!deposit.recompute_reclaim_pubkeys_hash().unwrap());
+        assert_eq!(deposit.reclaim_pubkeys_hash, expected_hash);
+    }
+
+    #[test]
+    fn recompute_reclaim_pubkeys_hash_rejects_invalid_hex() {
+        let mut deposit = DepositEntry {
+            reclaim_script: "not hex".to_string(),
+            ..Default::default()
+        };
+        let result = deposit.recompute_reclaim_pubkeys_hash();
+        assert!(matches!(result, Err(Error::InvalidDepositEntry(_, _))));
+    }
+
     #[test]
     fn deposit_update_should_be_unnecessary_when_event_is_present() {
         let pending = DepositEvent {
@@ -753,10 +1094,13 @@ mod tests {
             history: vec![pending, accepted.clone()],
             reclaim_pubkeys_hash: None,
             replaced_by_tx: None,
+            fulfillment_bitcoin_block_hash: None,
+            bitcoin_height_at_acceptance: None,
         };
 
         let update = ValidatedDepositUpdate {
             key: Default::default(),
+            bitcoin_block_height: None,
             event: accepted,
         };
 
@@ -794,27 +1138,250 @@ mod tests {
             history: vec![pending.clone()],
             reclaim_pubkeys_hash: None,
             replaced_by_tx: None,
+            fulfillment_bitcoin_block_hash: None,
+            bitcoin_height_at_acceptance: None,
         };
 
         let update = ValidatedDepositUpdate {
             key: Default::default(),
+            bitcoin_block_height: None,
             event: accepted,
         };
 
         assert!(!update.is_unnecessary(&deposit));
     }
 
-    #[test_case(0, "hash0", 0, "hash0", DepositStatusEntry::Pending; "reorg around genesis sets status to pending at genesis")]
-    #[test_case(5, "hash5", 4, "hash4", DepositStatusEntry::Accepted; "reorg goes to earliest canonical event 1")]
-    #[test_case(4, "hash4", 4, "hash4", DepositStatusEntry::Accepted; "reorg setting a height consistent with an event keeps it")]
-    #[test_case(4, "hash4-1", 2, "hash2", DepositStatusEntry::Pending; "reorg setting a height inconsistent with an event removes it")]
-    #[test_case(3, "hash3", 2, "hash2", DepositStatusEntry::Pending; "reorg  goes to earliest canonical event 2")]
+    #[test]
+    fn deposit_update_with_only_a_new_message_is_message_only_and_necessary() {
+        let pending = DepositEvent {
+            status: DepositStatusEntry::Pending,
+            message: "first".to_string(),
+            stacks_block_height: 0,
+            stacks_block_hash: "".to_string(),
+        };
+
+        let accepted = DepositEvent {
+            status: DepositStatusEntry::Accepted,
+            message: "first".to_string(),
+            stacks_block_height: 1,
+            stacks_block_hash: "".to_string(),
+        };
+
+        let refined_accepted = DepositEvent {
+            message: "refined".to_string(),
+            ..accepted.clone()
+        };
+
+        let deposit = DepositEntry {
+            status: DepositStatus::Accepted,
+            last_update_height: 1,
+            history: vec![pending, accepted.clone(), refined_accepted.clone()],
+            ..Default::default()
+        };
+
+        // Going back to a message of an older event with the same status
+        // is a message-only update, so it must still be applied.
+        let update = ValidatedDepositUpdate {
+            key: Default::default(),
+            bitcoin_block_height: None,
+            event: accepted.clone(),
+        };
+        assert!(update.is_message_only(&deposit));
+        assert!(!update.is_unnecessary(&deposit));
+
+        // Repeating the latest event is neither.
+        let update = ValidatedDepositUpdate {
+            key: Default::default(),
+            bitcoin_block_height: None,
+            event: refined_accepted,
+        };
+        assert!(!update.is_message_only(&deposit));
+        assert!(update.is_unnecessary(&deposit));
+
+        // A status transition is not a message-only update.
+        let update = ValidatedDepositUpdate {
+            key: Default::default(),
+            bitcoin_block_height: None,
+            event: DepositEvent {
+                status: DepositStatusEntry::Failed,
+                message: "failed".to_string(),
+                ..accepted.clone()
+            },
+        };
+        assert!(!update.is_message_only(&deposit));
+
+        // Neither is a stale update from before the latest event.
+        let update = ValidatedDepositUpdate {
+            key: Default::default(),
+            bitcoin_block_height: None,
+            event: DepositEvent {
+                message: "stale".to_string(),
+                stacks_block_height: 0,
+                ..accepted.clone()
+            },
+        };
+        assert!(!update.is_message_only(&deposit));
+
+        // Nor an update with the same status at a later stacks block,
+        // which gets its own event in the history.
+        let update = ValidatedDepositUpdate {
+            key: Default::default(),
+            bitcoin_block_height: None,
+            event: DepositEvent {
+                message: "later".to_string(),
+                stacks_block_height: 2,
+                stacks_block_hash: "hash2".to_string(),
+                ..accepted.clone()
+            },
+        };
+        assert!(!update.is_message_only(&deposit));
+        assert!(!update.is_unnecessary(&deposit));
+
+        // Nor one at the same height but on a different stacks block.
+        let update = ValidatedDepositUpdate {
+            key: Default::default(),
+            bitcoin_block_height: None,
+            event: DepositEvent {
+                message: "forked".to_string(),
+                stacks_block_hash: "fork".to_string(),
+                ..accepted
+            },
+        };
+        assert!(!update.is_message_only(&deposit));
+    }
+
+    #[test_case(DepositStatusEntry::Accepted, 5, "hash5", false; "confirmed to accepted at a later height")]
+    #[test_case(DepositStatusEntry::Pending, 5, "hash5", false; "confirmed to pending at a later height")]
+    #[test_case(DepositStatusEntry::Accepted, 4, "hash4", true; "confirmed to accepted at the same block")]
+    #[test_case(DepositStatusEntry::Failed, 5, "hash5", true; "confirmed to failed at a later height")]
+    #[test_case(DepositStatusEntry::Confirmed(Fulfillment::default()), 5, "hash5", true; "confirmed to confirmed at a later height")]
+    #[test_case(DepositStatusEntry::Expired, 5, "hash5", false; "confirmed to expired at a later height")]
+    #[test_case(DepositStatusEntry::Expired, 4, "hash4", false; "confirmed to expired at the same block")]
+    fn following_a_confirmed_event(
+        next_status: DepositStatusEntry,
+        next_height: u64,
+        next_hash: &str,
+        is_valid: bool,
+    ) {
+        let confirmed = DepositEvent {
+            status: DepositStatusEntry::Confirmed(Fulfillment::default()),
+            message: "confirmed".to_string(),
+            stacks_block_height: 4,
+            stacks_block_hash: "hash4".to_string(),
+        };
+        let next_event = DepositEvent {
+            status: next_status,
+            message: "next".to_string(),
+            stacks_block_height: next_height,
+            stacks_block_hash: next_hash.to_string(),
+        };
+
+        let result = confirmed.ensure_following_event_is_valid(&next_event);
+        if is_valid {
+            assert!(result.is_ok());
+        } else {
+            assert!(matches!(
+                result,
+                Err(Error::InconsistentState(Inconsistency::ItemUpdate(_)))
+            ));
+        }
+    }
+
+    #[test_case(DepositStatusEntry::Pending; "pending")]
+    #[test_case(DepositStatusEntry::Accepted; "accepted")]
+    fn unswept_deposits_can_expire(previous_status: DepositStatusEntry) {
+        let previous = DepositEvent {
+            status: previous_status,
+            message: "previous".to_string(),
+            stacks_block_height: 2,
+            stacks_block_hash: "hash2".to_string(),
+        };
+        let expired = DepositEvent {
+            status: DepositStatusEntry::Expired,
+            message: "expired".to_string(),
+            stacks_block_height: 4,
+            stacks_block_hash: "hash4".to_string(),
+        };
+        previous.ensure_following_event_is_valid(&expired).unwrap();
+
+        let mut deposit = DepositEntry {
+            history: vec![previous, expired],
+            ..Default::default()
+        };
+        deposit.synchronize_with_history().unwrap();
+
+        assert_eq!(deposit.status, DepositStatus::Expired);
+        assert_eq!(deposit.last_update_height, 4);
+        assert_eq!(deposit.last_update_block_hash, "hash4");
+        assert!(deposit.validate().is_ok());
+
+        let deposit = Deposit::try_from(deposit).unwrap();
+        assert_eq!(deposit.status, DepositStatus::Expired);
+        assert!(deposit.fulfillment.is_none());
+    }
+
+    #[test]
+    fn reorg_can_move_a_confirmed_deposit_back_to_pending() {
+        let pending = DepositEvent {
+            status: DepositStatusEntry::Pending,
+            message: "pending".to_string(),
+            stacks_block_height: 2,
+            stacks_block_hash: "hash2".to_string(),
+        };
+        let fulfillment = Fulfillment::default();
+        let confirmed = DepositEvent {
+            status: DepositStatusEntry::Confirmed(fulfillment.clone()),
+            message: "confirmed".to_string(),
+            stacks_block_height: 4,
+            stacks_block_hash: "hash4".to_string(),
+        };
+        let mut deposit = DepositEntry {
+            status: DepositStatus::Confirmed,
+            last_update_height: 4,
+            last_update_block_hash: "hash4".to_string(),
+            fulfillment: Some(fulfillment),
+            history: vec![pending, confirmed],
+            bitcoin_height_at_acceptance: Some(100),
+            ..Default::default()
+        };
+
+        let chainstate = Chainstate {
+            stacks_block_height: 3,
+            stacks_block_hash: "hash3".to_string(),
+            bitcoin_block_height: Some(0),
+        };
+        let outcome = deposit.reorganize_around(&chainstate).unwrap();
+
+        assert_eq!(
+            outcome,
+            ReorgOutcome::Trimmed {
+                previous_status: DepositStatus::Confirmed
+            }
+        );
+        assert!(deposit.validate().is_ok());
+        assert_eq!(deposit.status, DepositStatus::Pending);
+        assert_eq!(deposit.fulfillment, None);
+        assert_eq!(deposit.bitcoin_height_at_acceptance, None);
+    }
+
+    const TRIMMED: ReorgOutcome = ReorgOutcome::Trimmed {
+        previous_status: DepositStatus::Confirmed,
+    };
+
+    #[test_case(0, "hash0", 0, "hash0", DepositStatusEntry::Pending, ReorgOutcome::ResetToPending { previous_status: DepositStatus::Confirmed }; "reorg around genesis sets status to pending at genesis")]
+    #[test_case(5, "hash5", 4, "hash4", DepositStatusEntry::Accepted, TRIMMED; "reorg goes to earliest canonical event 1")]
+    #[test_case(4, "hash4", 4, "hash4", DepositStatusEntry::Accepted, TRIMMED; "reorg setting a height consistent with an event keeps it")]
+    #[test_case(4, "hash4-1", 2, "hash2", DepositStatusEntry::Pending, TRIMMED; "reorg setting a height inconsistent with an event removes it")]
+    #[test_case(3, "hash3", 2, "hash2", DepositStatusEntry::Pending, TRIMMED; "reorg  goes to earliest canonical event 2")]
+    #[test_case(6, "hash6", 6, "hash6", DepositStatusEntry::Confirmed(Fulfillment::default()), ReorgOutcome::Unchanged; "reorg at the latest event leaves the deposit unchanged")]
+    #[test_case(1, "hash1", 1, "hash1", DepositStatusEntry::Pending, ReorgOutcome::ResetToPending { previous_status: DepositStatus::Confirmed }; "reorg before the first event resets to pending and keeps the reclaim pubkeys hash")]
     fn reorganizing_around_a_new_chainstate_results_in_valid_deposit(
         reorg_height: u64,
         reorg_hash: &str,
         expected_height: u64,
         expected_hash: &str,
         expected_status: DepositStatusEntry,
+        expected_outcome: ReorgOutcome,
     ) {
         let pending = DepositEvent {
             status: DepositStatusEntry::Pending,
@@ -838,6 +1405,7 @@ mod tests {
             stacks_block_hash: "hash6".to_string(),
         };
 
+        let reclaim_pubkeys_hash = Some(hex::encode([1u8; 32]));
         let mut deposit = DepositEntry {
             key: Default::default(),
             version: 3,
@@ -851,8 +1419,10 @@ mod tests {
             last_update_block_hash: "hash6".to_string(),
             fulfillment: Some(fulfillment.clone()),
             history: vec![pending.clone(), accepted.clone(), confirmed.clone()],
-            reclaim_pubkeys_hash: Some(hex::encode([1u8; 32])),
+            reclaim_pubkeys_hash: reclaim_pubkeys_hash.clone(),
             replaced_by_tx: None,
+            fulfillment_bitcoin_block_hash: None,
+            bitcoin_height_at_acceptance: None,
         };
 
         // Ensure the deposit is valid.
@@ -867,7 +1437,8 @@ mod tests {
             stacks_block_hash: reorg_hash.to_string(),
             bitcoin_block_height: Some(0),
         };
-        deposit.reorganize_around(&chainstate).unwrap();
+        let outcome = deposit.reorganize_around(&chainstate).unwrap();
+        assert_eq!(outcome, expected_outcome);
 
         // Ensure the deposit is valid.
         assert!(
@@ -887,5 +1458,104 @@ mod tests {
         assert_eq!(latest_event.stacks_block_height, expected_height);
         assert_eq!(latest_event.stacks_block_hash, expected_hash);
         assert_eq!(latest_event.status, expected_status);
+
+        // The reclaim pubkeys hash comes from the reclaim script, which a
+        // reorg never changes.
+        assert_eq!(deposit.reclaim_pubkeys_hash, reclaim_pubkeys_hash);
+    }
+
+    #[test_case(DepositStatusEntry::Confirmed(Fulfillment::default()); "confirmed")]
+    #[test_case(DepositStatusEntry::Rbf("replacement-txid".to_string()); "rbf")]
+    fn transitioning_to_reclaimed_clears_fulfillment_and_replacement(
+        previous_status: DepositStatusEntry,
+    ) {
+        let pending = DepositEvent {
+            status: DepositStatusEntry::Pending,
+            message: "initial test pending".to_string(),
+            stacks_block_height: 2,
+            stacks_block_hash: "hash2".to_string(),
+        };
+        let previous = DepositEvent {
+            status: previous_status,
+            message: "previous".to_string(),
+            stacks_block_height: 4,
+            stacks_block_hash: "hash4".to_string(),
+        };
+
+        let mut deposit = DepositEntry {
+            key: Default::default(),
+            version: 1,
+            recipient: "test-recipient".to_string(),
+            amount: 100,
+            parameters: Default::default(),
+            status: DepositStatus::Pending,
+            reclaim_script: "test-reclaim".to_string(),
+            deposit_script: "test-deposit".to_string(),
+            last_update_height: 2,
+            last_update_block_hash: "hash2".to_string(),
+            fulfillment: None,
+            history: vec![pending, previous],
+            reclaim_pubkeys_hash: None,
+            replaced_by_tx: None,
+            fulfillment_bitcoin_block_hash: None,
+            bitcoin_height_at_acceptance: None,
+        };
+        deposit.synchronize_with_history().unwrap();
+        assert!(deposit.fulfillment.is_some() || deposit.replaced_by_tx.is_some());
+
+        let reclaim_txid = "reclaim-txid".to_string();
+        deposit.history.push(DepositEvent {
+            status: DepositStatusEntry::Reclaimed(reclaim_txid.clone()),
+            message: "reclaimed".to_string(),
+            stacks_block_height: 6,
+            stacks_block_hash: "hash6".to_string(),
+        });
+        deposit.synchronize_with_history().unwrap();
+
+        assert_eq!(deposit.status, DepositStatus::Reclaimed);
+        assert_eq!(deposit.last_update_height, 6);
+        assert_eq!(deposit.last_update_block_hash, "hash6");
+        assert!(deposit.fulfillment.is_none());
+        assert!(deposit.fulfillment_bitcoin_block_hash.is_none());
+        assert!(deposit.replaced_by_tx.is_none());
+
+        let deposit = Deposit::try_from(deposit).unwrap();
+        assert_eq!(deposit.status, DepositStatus::Reclaimed);
+        assert!(deposit.fulfillment.is_none());
+        assert!(deposit.replaced_by_tx.is_none());
+    }
+
+    #[test_case(None, Some(113), false; "never accepted")]
+    #[test_case(Some(100), None, false; "unknown chain tip")]
+    #[test_case(Some(100), Some(113), false; "one block before the lock time elapses")]
+    #[test_case(Some(100), Some(114), true; "lock time elapsed")]
+    fn is_known_reclaimable_at_uses_the_acceptance_height(
+        bitcoin_height_at_acceptance: Option<u64>,
+        bitcoin_block_height: Option<u64>,
+        expected: bool,
+    ) {
+        let deposit = DepositEntry {
+            parameters: DepositParametersEntry { max_fee: 0, lock_time: 14 },
+            bitcoin_height_at_acceptance,
+            ..Default::default()
+        };
+        assert_eq!(
+            deposit.is_known_reclaimable_at(bitcoin_block_height),
+            expected
+        );
+    }
+
+    #[test]
+    fn is_known_reclaimable_at_ignores_time_based_lock_times() {
+        let lock_time = bitcoin::relative::LockTime::from_512_second_intervals(14);
+        let deposit = DepositEntry {
+            parameters: DepositParametersEntry {
+                max_fee: 0,
+                lock_time: lock_time.to_consensus_u32(),
+            },
+            bitcoin_height_at_acceptance: Some(100),
+            ..Default::default()
+        };
+        assert!(!deposit.is_known_reclaimable_at(Some(u64::MAX)));
     }
 }
@@ -98,6 +98,14 @@ pub enum DepositStatusEntry {
     /// Transaction was replaced by another transaction via RBF.
     /// Inner string is transaction ID of replacement transaction.
     Rbf(String),
+    /// The depositor reclaimed the deposited funds on chain after the
+    /// lock time expired. Inner string is the transaction ID of the
+    /// reclaim transaction.
+    Reclaimed(String),
+    /// The lock time of the deposit elapsed before the deposit was swept,
+    /// so the depositor can reclaim the funds and the signers will not
+    /// sweep it.
+    Expired,
 }
 
 /// Deposit Status entry.
@@ -134,6 +142,8 @@ impl From<&DepositStatusEntry> for DepositStatus {
             DepositStatusEntry::Confirmed(_) => DepositStatus::Confirmed,
             DepositStatusEntry::Failed => DepositStatus::Failed,
             DepositStatusEntry::Rbf(_) => DepositStatus::Rbf,
+            DepositStatusEntry::Reclaimed(_) => DepositStatus::Reclaimed,
+            DepositStatusEntry::Expired => DepositStatus::Expired,
         }
     }
 }
@@ -357,6 +367,55 @@ pub(crate) trait TableIndexTrait {
         Ok((entries, next_token))
     }
 
+    /// Generic table query for all attributes with a given primary key
+    /// and a sort key within the inclusive range `[from, to]`, in
+    /// ascending order of the sort key.
+    async fn query_with_partition_and_sort_key_range(
+        dynamodb_client: &aws_sdk_dynamodb::Client,
+        settings: &Settings,
+        partition_key: &<<Self::Entry as EntryTrait>::Key as KeyTrait>::PartitionKey,
+        from_sort_key: &<<Self::Entry as EntryTrait>::Key as KeyTrait>::SortKey,
+        to_sort_key: &<<Self::Entry as EntryTrait>::Key as KeyTrait>::SortKey,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<(Vec<Self::Entry>, Option<String>), Error> {
+        // Convert inputs into the types needed for querying.
+        let exclusive_start_key =
+            maybe_exclusive_start_key_from_next_token::<Self::SearchToken>(maybe_next_token)?;
+
+        // Query the database.
+        let query_output = dynamodb_client
+            .query()
+            .table_name(Self::table_name(settings))
+            .set_index_name(Self::INDEX_NAME_IF_GSI.map(|s| s.to_string()))
+            .set_exclusive_start_key(exclusive_start_key)
+            .set_limit(maybe_page_size.map(|u| u as i32))
+            .key_condition_expression("#pk = :pk AND #sk BETWEEN :from AND :to")
+            .expression_attribute_names(
+                "#pk",
+                <<Self::Entry as EntryTrait>::Key as KeyTrait>::PARTITION_KEY_NAME,
+            )
+            .expression_attribute_names(
+                "#sk",
+                <<Self::Entry as EntryTrait>::Key as KeyTrait>::SORT_KEY_NAME,
+            )
+            .expression_attribute_values(":pk", serde_dynamo::to_attribute_value(partition_key)?)
+            .expression_attribute_values(":from", serde_dynamo::to_attribute_value(from_sort_key)?)
+            .expression_attribute_values(":to", serde_dynamo::to_attribute_value(to_sort_key)?)
+            .scan_index_forward(true)
+            .send()
+            .await
+            .map_err(Box::new)?;
+        // Convert data into output format.
+        let entries: Vec<Self::Entry> =
+            serde_dynamo::from_items(query_output.items.unwrap_or_default())?;
+        let next_token = maybe_next_token_from_last_evaluated_key::<Self::SearchToken>(
+            query_output.last_evaluated_key,
+        )?;
+        // Return.
+        Ok((entries, next_token))
+    }
+
     /// Generic put table entry.
     async fn put_entry(
         dynamodb_client: &aws_sdk_dynamodb::Client,
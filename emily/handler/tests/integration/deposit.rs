@@ -12,7 +12,8 @@ use sbtc::testing;
 use sbtc::testing::deposits::TxSetup;
 use testing_emily_client::apis::chainstate_api::set_chainstate;
 use testing_emily_client::models::{
-    Chainstate, DepositStatus, Fulfillment, UpdateDepositsRequestBody,
+    Chainstate, CreateDepositsRequestBody, DepositStatus, Fulfillment,
+    GetDepositsForReclaimPubkeysBatchRequestBody, UpdateDepositsRequestBody,
 };
 use testing_emily_client::{
     apis::{self, configuration::Configuration},
@@ -167,6 +168,7 @@ async fn create_and_get_deposit_happy_path() {
     };
 
     let expected_deposit = Deposit {
+        bitcoin_height_at_acceptance: None,
         amount: DEPOSIT_AMOUNT_SATS,
         bitcoin_tx_output_index,
         bitcoin_txid: bitcoin_txid.clone(),
@@ -295,6 +297,7 @@ async fn get_deposits_for_transaction() {
         create_requests.push(request);
 
         let expected_deposit = Deposit {
+            bitcoin_height_at_acceptance: None,
             amount: DEPOSIT_AMOUNT_SATS,
             bitcoin_tx_output_index,
             bitcoin_txid: bitcoin_txid.clone(),
@@ -411,6 +414,8 @@ async fn get_deposits() {
             status,
             next_token.as_deref(),
             Some(chunksize as u32),
+            None,
+            None,
         )
         .await
         .expect("Received an error after making a valid get deposits api call.");
@@ -442,6 +447,103 @@ async fn get_deposits() {
     assert_eq!(expected_deposit_infos, gotten_deposit_infos);
 }
 
+#[tokio::test]
+async fn get_deposits_filters_by_amount() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let amounts = vec![
+        100_000, 500_000, 1_000_000, 2_000_000, 5_000_000, 10_000_000,
+    ];
+    let min_amount = 500_000;
+    let max_amount = 2_000_000;
+
+    let DepositTxnData {
+        reclaim_scripts,
+        deposit_scripts,
+        bitcoin_txid,
+        transaction_hex,
+        ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &amounts);
+
+    let create_requests = (0..amounts.len())
+        .map(|index| CreateDepositRequestBody {
+            bitcoin_tx_output_index: index as u32,
+            bitcoin_txid: bitcoin_txid.clone(),
+            deposit_script: deposit_scripts[index].clone(),
+            reclaim_script: reclaim_scripts[index].clone(),
+            transaction_hex: transaction_hex.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    let mut expected_output_indices: Vec<u32> = amounts
+        .iter()
+        .enumerate()
+        .filter(|(_, amount)| (min_amount..=max_amount).contains(*amount))
+        .map(|(index, _)| index as u32)
+        .collect();
+
+    // Act.
+    // ----
+    batch_create_deposits(&configuration, create_requests).await;
+
+    let status = testing_emily_client::models::DepositStatus::Pending;
+    let mut next_token: Option<String> = None;
+    let mut gotten_deposit_infos: Vec<DepositInfo> = Vec::new();
+    loop {
+        let response = apis::deposit_api::get_deposits(
+            &configuration,
+            status,
+            next_token.as_deref(),
+            Some(2),
+            Some(min_amount),
+            Some(max_amount),
+        )
+        .await
+        .expect("Received an error after making a valid get deposits api call.");
+        assert!(response.deposits.len() <= 2);
+        gotten_deposit_infos.extend(response.deposits);
+        // If there's no next token then break.
+        next_token = match response.next_token.flatten() {
+            Some(token) => Some(token),
+            None => break,
+        };
+    }
+
+    // Assert.
+    // -------
+    for deposit_info in gotten_deposit_infos.iter() {
+        assert!((min_amount..=max_amount).contains(&deposit_info.amount));
+    }
+    let mut gotten_output_indices: Vec<u32> = gotten_deposit_infos
+        .iter()
+        .map(|deposit_info| deposit_info.bitcoin_tx_output_index)
+        .collect();
+    expected_output_indices.sort();
+    gotten_output_indices.sort();
+    assert_eq!(expected_output_indices, gotten_output_indices);
+}
+
+#[tokio::test]
+async fn get_deposits_rejects_empty_amount_range() {
+    let configuration = clean_setup().await;
+
+    let attempted_get: StandardError = apis::deposit_api::get_deposits(
+        &configuration,
+        testing_emily_client::models::DepositStatus::Pending,
+        None,
+        None,
+        Some(2_000_000),
+        Some(1_000_000),
+    )
+    .await
+    .expect_err("Received a successful response with a min amount above the max amount.")
+    .into();
+
+    assert_eq!(attempted_get.status_code, 400);
+}
+
 #[tokio::test]
 async fn get_deposits_for_recipient() {
     let configuration = clean_setup().await;
@@ -696,6 +798,109 @@ async fn get_deposits_for_reclaim_pubkeys() {
     }
 }
 
+#[tokio::test]
+async fn get_deposits_for_reclaim_pubkeys_batch() {
+    let configuration = clean_setup().await;
+    // Arrange.
+    // --------
+    let num_deposits: u32 = 3;
+    let reclaim_pubkeys = [[1u8; 32], [2u8; 32]];
+    // A key that was never used in a reclaim script.
+    let unknown_pubkey = hex::encode([9u8; 32]);
+
+    let mut expected_pubkey_data: HashMap<String, Vec<DepositInfo>> = HashMap::new();
+    let mut create_requests: Vec<CreateDepositRequestBody> = Vec::new();
+    for pubkey in reclaim_pubkeys.iter() {
+        let reclaim_user_script = ScriptBuf::builder()
+            .push_opcode(opcodes::OP_DROP)
+            .push_slice(pubkey)
+            .push_opcode(opcodes::OP_CHECKSIG)
+            .into_script();
+        let amounts = vec![DEPOSIT_AMOUNT_SATS; num_deposits as usize];
+        let DepositTxnData {
+            recipients,
+            reclaim_scripts,
+            deposit_scripts,
+            bitcoin_txid,
+            transaction_hex,
+        } = DepositTxnData::new_with_reclaim_user_script(
+            DEPOSIT_LOCK_TIME,
+            DEPOSIT_MAX_FEE,
+            &amounts,
+            &reclaim_user_script,
+        );
+
+        let mut expected_deposit_infos: Vec<DepositInfo> = Vec::new();
+        for bitcoin_tx_output_index in 0..num_deposits {
+            let tx_output_index = bitcoin_tx_output_index as usize;
+            create_requests.push(CreateDepositRequestBody {
+                bitcoin_tx_output_index,
+                bitcoin_txid: bitcoin_txid.clone(),
+                deposit_script: deposit_scripts[tx_output_index].clone(),
+                reclaim_script: reclaim_scripts[tx_output_index].clone(),
+                transaction_hex: transaction_hex.clone(),
+            });
+            expected_deposit_infos.push(DepositInfo {
+                amount: DEPOSIT_AMOUNT_SATS,
+                bitcoin_tx_output_index,
+                bitcoin_txid: bitcoin_txid.clone(),
+                last_update_block_hash: BLOCK_HASH.into(),
+                last_update_height: BLOCK_HEIGHT,
+                recipient: recipients[tx_output_index].clone(),
+                status: DepositStatus::Pending,
+                reclaim_script: reclaim_scripts[tx_output_index].clone(),
+                deposit_script: deposit_scripts[tx_output_index].clone(),
+            });
+        }
+        expected_pubkey_data.insert(hex::encode(pubkey), expected_deposit_infos);
+    }
+    expected_pubkey_data.insert(unknown_pubkey.clone(), Vec::new());
+
+    // Act.
+    // ----
+    batch_create_deposits(&configuration, create_requests).await;
+
+    let mut actual_pubkey_data: HashMap<String, Vec<DepositInfo>> = HashMap::new();
+    let mut requested_pubkeys: Vec<String> = expected_pubkey_data.keys().cloned().collect();
+    let mut next_tokens: HashMap<String, String> = HashMap::new();
+    while !requested_pubkeys.is_empty() {
+        let request = GetDepositsForReclaimPubkeysBatchRequestBody {
+            next_tokens: Some(next_tokens),
+            page_size: Some(Some(2)),
+            reclaim_pubkeys: requested_pubkeys.clone(),
+        };
+        let response =
+            apis::deposit_api::get_deposits_for_reclaim_pubkeys_batch(&configuration, request)
+                .await
+                .expect("Received an error after making a valid get deposits batch api call.");
+
+        // Every requested entry is in the response, even if it had no
+        // deposits.
+        assert_eq!(response.deposits.len(), requested_pubkeys.len());
+        for (pubkey, deposits) in response.deposits {
+            actual_pubkey_data
+                .entry(pubkey)
+                .or_default()
+                .extend(deposits);
+        }
+        // Only resume the entries that have more results.
+        assert!(!response.next_tokens.contains_key(&unknown_pubkey));
+        next_tokens = response.next_tokens;
+        requested_pubkeys = next_tokens.keys().cloned().collect();
+    }
+
+    // Assert.
+    // -------
+    assert_eq!(actual_pubkey_data.len(), expected_pubkey_data.len());
+    for (pubkey, expected_deposit_infos) in expected_pubkey_data.iter_mut() {
+        expected_deposit_infos.sort_by(arbitrary_deposit_info_partial_cmp);
+        let mut actual_deposit_infos = actual_pubkey_data.get(pubkey).unwrap().clone();
+        actual_deposit_infos.sort_by(arbitrary_deposit_info_partial_cmp);
+        assert_eq!(expected_deposit_infos, &actual_deposit_infos);
+    }
+    assert!(actual_pubkey_data[&unknown_pubkey].is_empty());
+}
+
 #[tokio::test]
 async fn update_deposits() {
     let configuration = clean_setup().await;
@@ -758,11 +963,13 @@ async fn update_deposits() {
                 fulfillment: Some(Some(Box::new(update_fulfillment.clone()))),
                 status: update_status,
                 status_message: update_status_message.into(),
+                reclaim_txid: None,
                 replaced_by_tx: None,
             };
             deposit_updates.push(deposit_update);
 
             let expected_deposit = Deposit {
+                bitcoin_height_at_acceptance: update_chainstate.bitcoin_block_height,
                 amount: DEPOSIT_AMOUNT_SATS,
                 bitcoin_tx_output_index: i as u32,
                 bitcoin_txid: bitcoin_txid.clone(),
@@ -813,157 +1020,693 @@ async fn update_deposits() {
     assert_eq!(expected_deposits, updated_deposits);
 }
 
-#[test_case(DepositStatus::Pending; "pending")]
-#[test_case(DepositStatus::Confirmed; "confirmed")]
-#[test_case(DepositStatus::Failed; "failed")]
-#[test_case(DepositStatus::Accepted; "accepted")]
-#[test_case(DepositStatus::Rbf; "rbf")]
 #[tokio::test]
-async fn create_deposit_handles_duplicates(status: DepositStatus) {
+async fn update_deposits_changes_only_the_message() {
     let configuration = clean_setup().await;
+
     // Arrange.
     // --------
-    let bitcoin_tx_output_index = 0;
-
-    // Setup test deposit transaction.
+    let amounts = vec![DEPOSIT_AMOUNT_SATS];
     let DepositTxnData {
         reclaim_scripts,
         deposit_scripts,
         bitcoin_txid,
         transaction_hex,
         ..
-    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &[DEPOSIT_AMOUNT_SATS]);
-    let reclaim_script = reclaim_scripts.first().unwrap().clone();
-    let deposit_script = deposit_scripts.first().unwrap().clone();
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &amounts);
 
-    let create_deposit_body = CreateDepositRequestBody {
-        bitcoin_tx_output_index,
+    let create_request = CreateDepositRequestBody {
+        bitcoin_tx_output_index: 0,
         bitcoin_txid: bitcoin_txid.clone(),
-        deposit_script: deposit_script.clone(),
-        reclaim_script: reclaim_script.clone(),
+        deposit_script: deposit_scripts[0].clone(),
+        reclaim_script: reclaim_scripts[0].clone(),
         transaction_hex: transaction_hex.clone(),
     };
+    batch_create_deposits(&configuration, vec![create_request]).await;
 
-    apis::deposit_api::create_deposit(&configuration, create_deposit_body.clone())
+    let update_chainstate = Chainstate {
+        stacks_block_hash: "update_block_hash".to_string(),
+        stacks_block_height: 42,
+        bitcoin_block_height: Some(Some(42)),
+    };
+    set_chainstate(&configuration, update_chainstate.clone())
         .await
-        .expect("Received an error after making a valid create deposit request api call.");
-
-    let response = apis::deposit_api::get_deposit(
-        &configuration,
-        &bitcoin_txid,
-        &bitcoin_tx_output_index.to_string(),
-    )
-    .await
-    .expect("Received an error after making a valid get deposit api call.");
-    assert_eq!(response.bitcoin_txid, bitcoin_txid);
-    assert_eq!(response.status, DepositStatus::Pending);
-
-    let mut fulfillment: Option<Option<Box<Fulfillment>>> = None;
+        .expect("Received an error after making a valid set chainstate api call.");
 
-    if status == DepositStatus::Confirmed {
-        fulfillment = Some(Some(Box::new(Fulfillment {
-            bitcoin_block_hash: "bitcoin_block_hash".to_string(),
-            bitcoin_block_height: 23,
-            bitcoin_tx_index: 45,
-            bitcoin_txid: "test_fulfillment_bitcoin_txid".to_string(),
-            btc_fee: 2314,
-            stacks_txid: "test_fulfillment_stacks_txid".to_string(),
-        })));
-    }
-    let replaced_by_tx = if status == DepositStatus::Rbf {
-        Some(Some("replaced_by_txid".to_string()))
-    } else {
-        None
+    let fulfillment = Fulfillment {
+        bitcoin_block_hash: "bitcoin_block_hash".to_string(),
+        bitcoin_block_height: 23,
+        bitcoin_tx_index: 45,
+        bitcoin_txid: "test_fulfillment_bitcoin_txid".to_string(),
+        btc_fee: 2314,
+        stacks_txid: "test_fulfillment_stacks_txid".to_string(),
+    };
+    let update_request = |status_message: &str| UpdateDepositsRequestBody {
+        deposits: vec![DepositUpdate {
+            bitcoin_tx_output_index: 0,
+            bitcoin_txid: bitcoin_txid.clone(),
+            fulfillment: Some(Some(Box::new(fulfillment.clone()))),
+            status: DepositStatus::Confirmed,
+            status_message: status_message.into(),
+            reclaim_txid: None,
+            replaced_by_tx: None,
+        }],
     };
 
-    apis::deposit_api::update_deposits_sidecar(
+    // Act.
+    // ----
+    apis::deposit_api::update_deposits_sidecar(&configuration, update_request("confirmed"))
+        .await
+        .expect("Received an error after making a valid update deposits api call.");
+
+    // Move the chain tip forward, the message-only update must not
+    // change when the deposit was last updated.
+    set_chainstate(
         &configuration,
-        UpdateDepositsRequestBody {
-            deposits: vec![DepositUpdate {
-                bitcoin_tx_output_index,
-                bitcoin_txid: bitcoin_txid.clone(),
-                fulfillment,
-                status,
-                status_message: "foo".into(),
-                replaced_by_tx,
-            }],
+        Chainstate {
+            stacks_block_hash: "later_block_hash".to_string(),
+            stacks_block_height: 43,
+            bitcoin_block_height: Some(Some(43)),
         },
     )
     .await
-    .expect("Received an error after making a valid update deposit request api call.");
+    .expect("Received an error after making a valid set chainstate api call.");
 
-    let response = apis::deposit_api::get_deposit(
+    let response = apis::deposit_api::update_deposits_sidecar(
         &configuration,
-        &bitcoin_txid,
-        &bitcoin_tx_output_index.to_string(),
+        update_request("confirmed with more detail"),
     )
     .await
-    .expect("Received an error after making a valid get deposit api call.");
-    assert_eq!(response.bitcoin_txid, bitcoin_txid);
-    assert_eq!(response.status, status);
-
-    let duplicate_deposit =
-        apis::deposit_api::create_deposit(&configuration, create_deposit_body).await;
+    .expect("Received an error after making a valid update deposits api call.");
 
-    assert!(duplicate_deposit.is_ok());
-
-    assert_eq!(response, duplicate_deposit.unwrap());
+    // Assert.
+    // -------
+    let updated_deposit = &response.deposits[0].deposit;
+    assert_eq!(updated_deposit.status, DepositStatus::Confirmed);
+    assert_eq!(updated_deposit.status_message, "confirmed with more detail");
 
-    let response = apis::deposit_api::get_deposit(
-        &configuration,
-        &bitcoin_txid,
-        &bitcoin_tx_output_index.to_string(),
-    )
-    .await
-    .expect("Received an error after making a valid get deposit api call.");
-    assert_eq!(response.bitcoin_txid, bitcoin_txid);
-    assert_eq!(response.status, status);
+    let deposit = apis::deposit_api::get_deposit(&configuration, &bitcoin_txid, "0")
+        .await
+        .expect("Received an error after making a valid get deposit api call.");
+    assert_eq!(deposit.status, DepositStatus::Confirmed);
+    assert_eq!(deposit.status_message, "confirmed with more detail");
+    assert_eq!(
+        deposit.last_update_height,
+        update_chainstate.stacks_block_height
+    );
+    assert_eq!(
+        deposit.last_update_block_hash,
+        update_chainstate.stacks_block_hash
+    );
 }
 
-#[test_case(DepositStatus::Pending, DepositStatus::Pending, true; "pending_to_pending")]
-#[test_case(DepositStatus::Pending, DepositStatus::Accepted, false; "pending_to_accepted")]
-#[test_case(DepositStatus::Pending, DepositStatus::Confirmed, true; "pending_to_confirmed")]
-#[test_case(DepositStatus::Pending, DepositStatus::Failed, true; "pending_to_failed")]
-#[test_case(DepositStatus::Accepted, DepositStatus::Pending, true; "accepted_to_pending")]
-#[test_case(DepositStatus::Failed, DepositStatus::Pending, true; "failed_to_pending")]
-#[test_case(DepositStatus::Confirmed, DepositStatus::Pending, true; "confirmed_to_pending")]
-#[test_case(DepositStatus::Accepted, DepositStatus::Accepted, false; "accepted_to_accepted")]
-#[test_case(DepositStatus::Failed, DepositStatus::Accepted, true; "failed_to_accepted")]
-#[test_case(DepositStatus::Confirmed, DepositStatus::Accepted, true; "confirmed_to_accepted")]
-#[test_case(DepositStatus::Pending, DepositStatus::Rbf, true; "pending_to_rbf")]
-#[test_case(DepositStatus::Accepted, DepositStatus::Rbf, true; "accepted_to_rbf")]
-#[test_case(DepositStatus::Confirmed, DepositStatus::Rbf, true; "confirmed_to_rbf")]
-#[test_case(DepositStatus::Failed, DepositStatus::Rbf, true; "failed_to_rbf")]
-#[test_case(DepositStatus::Rbf, DepositStatus::Pending, true; "rbf_to_pending")]
-#[test_case(DepositStatus::Rbf, DepositStatus::Accepted, true; "rbf_to_accepted")]
-#[test_case(DepositStatus::Rbf, DepositStatus::Confirmed, true; "rbf_to_confirmed")]
-#[test_case(DepositStatus::Rbf, DepositStatus::Rbf, true; "rbf_to_rbf")]
-#[test_case(DepositStatus::Rbf, DepositStatus::Failed, true; "rbf_to_failed")]
 #[tokio::test]
-async fn update_deposits_is_forbidden_for_signer(
-    previous_status: DepositStatus,
-    new_status: DepositStatus,
-    is_forbidden: bool,
-) {
-    // the testing configuration has privileged access to all endpoints.
-    let testing_configuration = clean_setup().await;
+async fn get_deposit_history_returns_all_events_in_order() {
+    let configuration = clean_setup().await;
 
-    // the user configuration access depends on the api_key.
-    let user_configuration = testing_configuration.clone();
     // Arrange.
     // --------
-    let bitcoin_tx_output_index = 0;
-
-    // Setup test deposit transaction.
+    let amounts = vec![DEPOSIT_AMOUNT_SATS];
     let DepositTxnData {
         reclaim_scripts,
         deposit_scripts,
         bitcoin_txid,
         transaction_hex,
         ..
-    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &[DEPOSIT_AMOUNT_SATS]);
-    let reclaim_script = reclaim_scripts.first().unwrap().clone();
-    let deposit_script = deposit_scripts.first().unwrap().clone();
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &amounts);
+
+    let create_request = CreateDepositRequestBody {
+        bitcoin_tx_output_index: 0,
+        bitcoin_txid: bitcoin_txid.clone(),
+        deposit_script: deposit_scripts[0].clone(),
+        reclaim_script: reclaim_scripts[0].clone(),
+        transaction_hex: transaction_hex.clone(),
+    };
+    batch_create_deposits(&configuration, vec![create_request]).await;
+
+    let accepted_chainstate = Chainstate {
+        stacks_block_hash: "accepted_block_hash".to_string(),
+        stacks_block_height: 42,
+        bitcoin_block_height: Some(Some(42)),
+    };
+    let confirmed_chainstate = Chainstate {
+        stacks_block_hash: "confirmed_block_hash".to_string(),
+        stacks_block_height: 43,
+        bitcoin_block_height: Some(Some(43)),
+    };
+    let fulfillment = Fulfillment {
+        bitcoin_block_hash: "bitcoin_block_hash".to_string(),
+        bitcoin_block_height: 23,
+        bitcoin_tx_index: 45,
+        bitcoin_txid: "test_fulfillment_bitcoin_txid".to_string(),
+        btc_fee: 2314,
+        stacks_txid: "test_fulfillment_stacks_txid".to_string(),
+    };
+    let update_request =
+        |status: DepositStatus, fulfillment: Option<Fulfillment>| UpdateDepositsRequestBody {
+            deposits: vec![DepositUpdate {
+                bitcoin_tx_output_index: 0,
+                bitcoin_txid: bitcoin_txid.clone(),
+                fulfillment: fulfillment.map(|f| Some(Box::new(f))),
+                status,
+                status_message: format!("{status:?}"),
+                reclaim_txid: None,
+                replaced_by_tx: None,
+            }],
+        };
+
+    // Act.
+    // ----
+    set_chainstate(&configuration, accepted_chainstate.clone())
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+    apis::deposit_api::update_deposits_sidecar(
+        &configuration,
+        update_request(DepositStatus::Accepted, None),
+    )
+    .await
+    .expect("Received an error after making a valid update deposits api call.");
+
+    set_chainstate(&configuration, confirmed_chainstate.clone())
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+    apis::deposit_api::update_deposits_sidecar(
+        &configuration,
+        update_request(DepositStatus::Confirmed, Some(fulfillment)),
+    )
+    .await
+    .expect("Received an error after making a valid update deposits api call.");
+
+    let history = apis::deposit_api::get_deposit_history(&configuration, &bitcoin_txid, "0")
+        .await
+        .expect("Received an error after making a valid get deposit history api call.");
+
+    // Assert.
+    // -------
+    let statuses: Vec<DepositStatus> = history.events.iter().map(|event| event.status).collect();
+    assert_eq!(
+        statuses,
+        vec![
+            DepositStatus::Pending,
+            DepositStatus::Accepted,
+            DepositStatus::Confirmed
+        ]
+    );
+
+    let pending = &history.events[0];
+    assert_eq!(pending.message, INITIAL_DEPOSIT_STATUS_MESSAGE);
+
+    let accepted = &history.events[1];
+    assert_eq!(accepted.message, format!("{:?}", DepositStatus::Accepted));
+    assert_eq!(
+        accepted.stacks_block_height,
+        accepted_chainstate.stacks_block_height
+    );
+    assert_eq!(
+        accepted.stacks_block_hash,
+        accepted_chainstate.stacks_block_hash
+    );
+
+    let confirmed = &history.events[2];
+    assert_eq!(confirmed.message, format!("{:?}", DepositStatus::Confirmed));
+    assert_eq!(
+        confirmed.stacks_block_height,
+        confirmed_chainstate.stacks_block_height
+    );
+    assert_eq!(
+        confirmed.stacks_block_hash,
+        confirmed_chainstate.stacks_block_hash
+    );
+
+    // The latest event is the one reported by the get deposit endpoint.
+    let deposit = apis::deposit_api::get_deposit(&configuration, &bitcoin_txid, "0")
+        .await
+        .expect("Received an error after making a valid get deposit api call.");
+    assert_eq!(deposit.status, confirmed.status);
+    assert_eq!(deposit.status_message, confirmed.message);
+}
+
+#[tokio::test]
+async fn get_deposits_by_status_and_height_range() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let amounts = vec![DEPOSIT_AMOUNT_SATS; 4];
+    let DepositTxnData {
+        reclaim_scripts,
+        deposit_scripts,
+        bitcoin_txid,
+        transaction_hex,
+        ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &amounts);
+
+    let create_requests = (0..amounts.len())
+        .map(|index| CreateDepositRequestBody {
+            bitcoin_tx_output_index: index as u32,
+            bitcoin_txid: bitcoin_txid.clone(),
+            deposit_script: deposit_scripts[index].clone(),
+            reclaim_script: reclaim_scripts[index].clone(),
+            transaction_hex: transaction_hex.clone(),
+        })
+        .collect::<Vec<_>>();
+    batch_create_deposits(&configuration, create_requests).await;
+
+    // Accept each deposit at a different stacks height, so deposit `i`
+    // is last updated at height `i + 1`.
+    for index in 0..amounts.len() {
+        let height = index as u64 + 1;
+        let chainstate = Chainstate {
+            stacks_block_hash: format!("test-hash-{height}"),
+            stacks_block_height: height,
+            bitcoin_block_height: Some(Some(height)),
+        };
+        set_chainstate(&configuration, chainstate)
+            .await
+            .expect("Received an error after making a valid set chainstate api call.");
+
+        let update_request = UpdateDepositsRequestBody {
+            deposits: vec![DepositUpdate {
+                bitcoin_tx_output_index: index as u32,
+                bitcoin_txid: bitcoin_txid.clone(),
+                fulfillment: None,
+                status: DepositStatus::Accepted,
+                status_message: "accepted".into(),
+                reclaim_txid: None,
+                replaced_by_tx: None,
+            }],
+        };
+        apis::deposit_api::update_deposits_sidecar(&configuration, update_request)
+            .await
+            .expect("Received an error after making a valid update deposits api call.");
+    }
+
+    // Act.
+    // ----
+    let mut next_token: Option<String> = None;
+    let mut gotten_deposit_infos: Vec<DepositInfo> = Vec::new();
+    loop {
+        let response = apis::deposit_api::get_deposits_by_status_and_height_range(
+            &configuration,
+            DepositStatus::Accepted,
+            2,
+            3,
+            next_token.as_deref(),
+            Some(1),
+        )
+        .await
+        .expect("Received an error after making a valid get deposits api call.");
+        gotten_deposit_infos.extend(response.deposits);
+        // If there's no next token then break.
+        next_token = match response.next_token.flatten() {
+            Some(token) => Some(token),
+            None => break,
+        };
+    }
+
+    let empty_range_response = apis::deposit_api::get_deposits_by_status_and_height_range(
+        &configuration,
+        DepositStatus::Accepted,
+        3,
+        2,
+        None,
+        None,
+    )
+    .await
+    .expect("Received an error after making a valid get deposits api call.");
+
+    // Assert.
+    // -------
+    let gotten_heights: Vec<u64> = gotten_deposit_infos
+        .iter()
+        .map(|deposit_info| deposit_info.last_update_height)
+        .collect();
+    assert_eq!(gotten_heights, vec![2, 3]);
+    let gotten_output_indices: Vec<u32> = gotten_deposit_infos
+        .iter()
+        .map(|deposit_info| deposit_info.bitcoin_tx_output_index)
+        .collect();
+    assert_eq!(gotten_output_indices, vec![1, 2]);
+
+    assert!(empty_range_response.deposits.is_empty());
+    assert!(empty_range_response.next_token.flatten().is_none());
+}
+
+#[tokio::test]
+async fn get_deposits_for_bitcoin_block() {
+    let configuration = clean_setup().await;
+    // Arrange.
+    // --------
+    let confirming_block_hash = "confirming_bitcoin_block_hash";
+    // A single transaction with two deposits that get confirmed, and a
+    // second transaction with a deposit that never gets confirmed.
+    let confirmed_tx = DepositTxnData::new(
+        DEPOSIT_LOCK_TIME,
+        DEPOSIT_MAX_FEE,
+        &[DEPOSIT_AMOUNT_SATS, DEPOSIT_AMOUNT_SATS],
+    );
+    let pending_tx =
+        DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &[DEPOSIT_AMOUNT_SATS]);
+
+    let update_chainstate = Chainstate {
+        stacks_block_hash: "update_block_hash".to_string(),
+        stacks_block_height: 42,
+        bitcoin_block_height: Some(Some(42)),
+    };
+
+    let fulfillment = Fulfillment {
+        bitcoin_block_hash: confirming_block_hash.to_string(),
+        bitcoin_block_height: 23,
+        bitcoin_tx_index: 45,
+        bitcoin_txid: "test_fulfillment_bitcoin_txid".to_string(),
+        btc_fee: 2314,
+        stacks_txid: "test_fulfillment_stacks_txid".to_string(),
+    };
+
+    let mut create_requests = Vec::new();
+    let mut deposit_updates = Vec::new();
+    let mut expected_deposit_infos = Vec::new();
+    for (tx, confirm) in [(&confirmed_tx, true), (&pending_tx, false)] {
+        for (i, ((recipient, reclaim_script), deposit_script)) in tx
+            .recipients
+            .iter()
+            .zip(tx.reclaim_scripts.iter())
+            .zip(tx.deposit_scripts.iter())
+            .enumerate()
+        {
+            create_requests.push(CreateDepositRequestBody {
+                bitcoin_tx_output_index: i as u32,
+                bitcoin_txid: tx.bitcoin_txid.clone(),
+                deposit_script: deposit_script.clone(),
+                reclaim_script: reclaim_script.clone(),
+                transaction_hex: tx.transaction_hex.clone(),
+            });
+            if !confirm {
+                continue;
+            }
+            deposit_updates.push(DepositUpdate {
+                bitcoin_tx_output_index: i as u32,
+                bitcoin_txid: tx.bitcoin_txid.clone(),
+                fulfillment: Some(Some(Box::new(fulfillment.clone()))),
+                status: DepositStatus::Confirmed,
+                status_message: "confirmed".into(),
+                reclaim_txid: None,
+                replaced_by_tx: None,
+            });
+            expected_deposit_infos.push(DepositInfo {
+                amount: DEPOSIT_AMOUNT_SATS,
+                bitcoin_tx_output_index: i as u32,
+                bitcoin_txid: tx.bitcoin_txid.clone(),
+                last_update_block_hash: update_chainstate.stacks_block_hash.clone(),
+                last_update_height: update_chainstate.stacks_block_height,
+                recipient: recipient.clone(),
+                status: DepositStatus::Confirmed,
+                reclaim_script: reclaim_script.clone(),
+                deposit_script: deposit_script.clone(),
+            });
+        }
+    }
+
+    batch_create_deposits(&configuration, create_requests).await;
+    set_chainstate(&configuration, update_chainstate.clone())
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+    let update_request = UpdateDepositsRequestBody { deposits: deposit_updates };
+    apis::deposit_api::update_deposits_sidecar(&configuration, update_request)
+        .await
+        .expect("Received an error after making a valid update deposits api call.");
+
+    // Act.
+    // ----
+    let response = apis::deposit_api::get_deposits_for_bitcoin_block(
+        &configuration,
+        confirming_block_hash,
+        None,
+        None,
+    )
+    .await
+    .expect("Received an error after making a valid get deposits for bitcoin block api call.");
+
+    // Assert.
+    // -------
+    let mut actual_deposit_infos = response.deposits;
+    actual_deposit_infos.sort_by(arbitrary_deposit_info_partial_cmp);
+    expected_deposit_infos.sort_by(arbitrary_deposit_info_partial_cmp);
+    assert_eq!(actual_deposit_infos.len(), 2);
+    assert_eq!(expected_deposit_infos, actual_deposit_infos);
+
+    // A block that did not confirm anything has no deposits.
+    let response = apis::deposit_api::get_deposits_for_bitcoin_block(
+        &configuration,
+        "unknown_bitcoin_block_hash",
+        None,
+        None,
+    )
+    .await
+    .expect("Received an error after making a valid get deposits for bitcoin block api call.");
+    assert!(response.deposits.is_empty());
+}
+
+#[test_case(DepositStatus::Pending; "pending")]
+#[test_case(DepositStatus::Confirmed; "confirmed")]
+#[test_case(DepositStatus::Failed; "failed")]
+#[test_case(DepositStatus::Accepted; "accepted")]
+#[test_case(DepositStatus::Rbf; "rbf")]
+#[test_case(DepositStatus::Reclaimed; "reclaimed")]
+#[test_case(DepositStatus::Expired; "expired")]
+#[tokio::test]
+async fn create_deposit_handles_duplicates(status: DepositStatus) {
+    let configuration = clean_setup().await;
+    // Arrange.
+    // --------
+    let bitcoin_tx_output_index = 0;
+
+    // Setup test deposit transaction.
+    let DepositTxnData {
+        reclaim_scripts,
+        deposit_scripts,
+        bitcoin_txid,
+        transaction_hex,
+        ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &[DEPOSIT_AMOUNT_SATS]);
+    let reclaim_script = reclaim_scripts.first().unwrap().clone();
+    let deposit_script = deposit_scripts.first().unwrap().clone();
+
+    let create_deposit_body = CreateDepositRequestBody {
+        bitcoin_tx_output_index,
+        bitcoin_txid: bitcoin_txid.clone(),
+        deposit_script: deposit_script.clone(),
+        reclaim_script: reclaim_script.clone(),
+        transaction_hex: transaction_hex.clone(),
+    };
+
+    apis::deposit_api::create_deposit(&configuration, create_deposit_body.clone())
+        .await
+        .expect("Received an error after making a valid create deposit request api call.");
+
+    let response = apis::deposit_api::get_deposit(
+        &configuration,
+        &bitcoin_txid,
+        &bitcoin_tx_output_index.to_string(),
+    )
+    .await
+    .expect("Received an error after making a valid get deposit api call.");
+    assert_eq!(response.bitcoin_txid, bitcoin_txid);
+    assert_eq!(response.status, DepositStatus::Pending);
+
+    let mut fulfillment: Option<Option<Box<Fulfillment>>> = None;
+
+    if status == DepositStatus::Confirmed {
+        fulfillment = Some(Some(Box::new(Fulfillment {
+            bitcoin_block_hash: "bitcoin_block_hash".to_string(),
+            bitcoin_block_height: 23,
+            bitcoin_tx_index: 45,
+            bitcoin_txid: "test_fulfillment_bitcoin_txid".to_string(),
+            btc_fee: 2314,
+            stacks_txid: "test_fulfillment_stacks_txid".to_string(),
+        })));
+    }
+    let replaced_by_tx = if status == DepositStatus::Rbf {
+        Some(Some("replaced_by_txid".to_string()))
+    } else {
+        None
+    };
+    let reclaim_txid = if status == DepositStatus::Reclaimed {
+        Some(Some("reclaim_txid".to_string()))
+    } else {
+        None
+    };
+
+    apis::deposit_api::update_deposits_sidecar(
+        &configuration,
+        UpdateDepositsRequestBody {
+            deposits: vec![DepositUpdate {
+                bitcoin_tx_output_index,
+                bitcoin_txid: bitcoin_txid.clone(),
+                fulfillment,
+                status,
+                status_message: "foo".into(),
+                reclaim_txid,
+                replaced_by_tx,
+            }],
+        },
+    )
+    .await
+    .expect("Received an error after making a valid update deposit request api call.");
+
+    let response = apis::deposit_api::get_deposit(
+        &configuration,
+        &bitcoin_txid,
+        &bitcoin_tx_output_index.to_string(),
+    )
+    .await
+    .expect("Received an error after making a valid get deposit api call.");
+    assert_eq!(response.bitcoin_txid, bitcoin_txid);
+    assert_eq!(response.status, status);
+
+    let duplicate_deposit =
+        apis::deposit_api::create_deposit(&configuration, create_deposit_body).await;
+
+    assert!(duplicate_deposit.is_ok());
+
+    assert_eq!(response, duplicate_deposit.unwrap());
+
+    let response = apis::deposit_api::get_deposit(
+        &configuration,
+        &bitcoin_txid,
+        &bitcoin_tx_output_index.to_string(),
+    )
+    .await
+    .expect("Received an error after making a valid get deposit api call.");
+    assert_eq!(response.bitcoin_txid, bitcoin_txid);
+    assert_eq!(response.status, status);
+}
+
+#[tokio::test]
+async fn create_deposits_reports_a_status_for_each_deposit() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let amounts = vec![DEPOSIT_AMOUNT_SATS, DEPOSIT_AMOUNT_SATS + 1];
+    let DepositTxnData {
+        reclaim_scripts,
+        deposit_scripts,
+        bitcoin_txid,
+        transaction_hex,
+        ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &amounts);
+
+    let create_requests: Vec<CreateDepositRequestBody> = (0..amounts.len())
+        .map(|index| CreateDepositRequestBody {
+            bitcoin_tx_output_index: index as u32,
+            bitcoin_txid: bitcoin_txid.clone(),
+            deposit_script: deposit_scripts[index].clone(),
+            reclaim_script: reclaim_scripts[index].clone(),
+            transaction_hex: transaction_hex.clone(),
+        })
+        .collect();
+
+    // The first deposit already exists before the batch request.
+    let existing_deposit =
+        apis::deposit_api::create_deposit(&configuration, create_requests[0].clone())
+            .await
+            .expect("Received an error after making a valid create deposit request api call.");
+
+    let invalid_request = CreateDepositRequestBody {
+        transaction_hex: "not a transaction".to_string(),
+        ..create_requests[1].clone()
+    };
+
+    // Act.
+    // ----
+    let response = apis::deposit_api::create_deposits(
+        &configuration,
+        CreateDepositsRequestBody {
+            deposits: vec![
+                create_requests[0].clone(),
+                create_requests[1].clone(),
+                invalid_request,
+            ],
+        },
+    )
+    .await
+    .expect("Received an error after making a valid create deposits request api call.");
+
+    // Assert.
+    // -------
+    let statuses: Vec<u16> = response.deposits.iter().map(|item| item.status).collect();
+    assert_eq!(statuses, vec![200, 201, 400]);
+
+    // The existing deposit is returned as it was.
+    assert_eq!(*response.deposits[0].deposit, existing_deposit);
+
+    // The new deposit was created.
+    let created_deposit = &response.deposits[1].deposit;
+    assert_eq!(created_deposit.bitcoin_tx_output_index, 1);
+    assert_eq!(created_deposit.amount, amounts[1]);
+    assert_eq!(created_deposit.status, DepositStatus::Pending);
+
+    let deposit = apis::deposit_api::get_deposit(&configuration, &bitcoin_txid, "1")
+        .await
+        .expect("Received an error after making a valid get deposit api call.");
+    assert_eq!(deposit, **created_deposit);
+
+    // Repeating the batch is idempotent for the valid deposits.
+    let response = apis::deposit_api::create_deposits(
+        &configuration,
+        CreateDepositsRequestBody { deposits: create_requests },
+    )
+    .await
+    .expect("Received an error after making a valid create deposits request api call.");
+    let statuses: Vec<u16> = response.deposits.iter().map(|item| item.status).collect();
+    assert_eq!(statuses, vec![200, 200]);
+}
+
+#[test_case(DepositStatus::Pending, DepositStatus::Pending, true; "pending_to_pending")]
+#[test_case(DepositStatus::Pending, DepositStatus::Accepted, false; "pending_to_accepted")]
+#[test_case(DepositStatus::Pending, DepositStatus::Confirmed, true; "pending_to_confirmed")]
+#[test_case(DepositStatus::Pending, DepositStatus::Failed, true; "pending_to_failed")]
+#[test_case(DepositStatus::Accepted, DepositStatus::Pending, true; "accepted_to_pending")]
+#[test_case(DepositStatus::Failed, DepositStatus::Pending, true; "failed_to_pending")]
+#[test_case(DepositStatus::Confirmed, DepositStatus::Pending, true; "confirmed_to_pending")]
+#[test_case(DepositStatus::Accepted, DepositStatus::Accepted, false; "accepted_to_accepted")]
+#[test_case(DepositStatus::Failed, DepositStatus::Accepted, true; "failed_to_accepted")]
+#[test_case(DepositStatus::Confirmed, DepositStatus::Accepted, true; "confirmed_to_accepted")]
+#[test_case(DepositStatus::Pending, DepositStatus::Rbf, true; "pending_to_rbf")]
+#[test_case(DepositStatus::Accepted, DepositStatus::Rbf, true; "accepted_to_rbf")]
+#[test_case(DepositStatus::Confirmed, DepositStatus::Rbf, true; "confirmed_to_rbf")]
+#[test_case(DepositStatus::Failed, DepositStatus::Rbf, true; "failed_to_rbf")]
+#[test_case(DepositStatus::Rbf, DepositStatus::Pending, true; "rbf_to_pending")]
+#[test_case(DepositStatus::Rbf, DepositStatus::Accepted, true; "rbf_to_accepted")]
+#[test_case(DepositStatus::Rbf, DepositStatus::Confirmed, true; "rbf_to_confirmed")]
+#[test_case(DepositStatus::Rbf, DepositStatus::Rbf, true; "rbf_to_rbf")]
+#[test_case(DepositStatus::Rbf, DepositStatus::Failed, true; "rbf_to_failed")]
+#[tokio::test]
+async fn update_deposits_is_forbidden_for_signer(
+    previous_status: DepositStatus,
+    new_status: DepositStatus,
+    is_forbidden: bool,
+) {
+    // the testing configuration has privileged access to all endpoints.
+    let testing_configuration = clean_setup().await;
+
+    // the user configuration access depends on the api_key.
+    let user_configuration = testing_configuration.clone();
+    // Arrange.
+    // --------
+    let bitcoin_tx_output_index = 0;
+
+    // Setup test deposit transaction.
+    let DepositTxnData {
+        reclaim_scripts,
+        deposit_scripts,
+        bitcoin_txid,
+        transaction_hex,
+        ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &[DEPOSIT_AMOUNT_SATS]);
+    let reclaim_script = reclaim_scripts.first().unwrap().clone();
+    let deposit_script = deposit_scripts.first().unwrap().clone();
 
     let create_deposit_body = CreateDepositRequestBody {
         bitcoin_tx_output_index,
@@ -1008,6 +1751,7 @@ async fn update_deposits_is_forbidden_for_signer(
                     fulfillment,
                     status: previous_status,
                     status_message: "foo".into(),
+                    reclaim_txid: None,
                     replaced_by_tx,
                 }],
             },
@@ -1043,6 +1787,7 @@ async fn update_deposits_is_forbidden_for_signer(
                 fulfillment,
                 status: new_status,
                 status_message: "foo".into(),
+                reclaim_txid: None,
                 replaced_by_tx,
             }],
         },
@@ -1162,6 +1907,7 @@ async fn update_deposits_is_not_forbidden_for_sidecar(
                     fulfillment,
                     status: previous_status,
                     status_message: "foo".into(),
+                    reclaim_txid: None,
                     replaced_by_tx,
                 }],
             },
@@ -1197,6 +1943,7 @@ async fn update_deposits_is_not_forbidden_for_sidecar(
                 fulfillment,
                 status: new_status,
                 status_message: "foo".into(),
+                reclaim_txid: None,
                 replaced_by_tx,
             }],
         },
@@ -1261,6 +2008,7 @@ async fn rbf_status_saved_successfully() {
             fulfillment: None,
             status: DepositStatus::Rbf,
             status_message: "RBF initiated".into(),
+            reclaim_txid: None,
             replaced_by_tx: Some(Some("replaced_by_txid".to_string())),
         }],
     };
@@ -1275,34 +2023,277 @@ async fn rbf_status_saved_successfully() {
     assert_eq!(deposit.deposit.bitcoin_txid, bitcoin_txid);
     assert_eq!(deposit.deposit.status, DepositStatus::Rbf);
 
-    // Check that the deposit can be retrieved with the correct status.
-    let response = apis::deposit_api::get_deposit(&user_configuration, &txid, &index)
+    // Check that the deposit can be retrieved with the correct status.
+    let response = apis::deposit_api::get_deposit(&user_configuration, &txid, &index)
+        .await
+        .expect("Deposit with this txid and index should be available");
+    assert_eq!(response.bitcoin_txid, bitcoin_txid);
+    assert_eq!(response.status, DepositStatus::Rbf);
+    assert_eq!(
+        response.replaced_by_tx,
+        Some(Some("replaced_by_txid".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn get_rbf_chain_follows_replacements() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let bitcoin_tx_output_index = 0;
+    let index = bitcoin_tx_output_index.to_string();
+
+    // Create three deposits, each in its own transaction.
+    let mut txids = Vec::new();
+    for _ in 0..3 {
+        let DepositTxnData {
+            reclaim_scripts,
+            deposit_scripts,
+            bitcoin_txid,
+            transaction_hex,
+            ..
+        } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &[DEPOSIT_AMOUNT_SATS]);
+        let create_deposit_body = CreateDepositRequestBody {
+            bitcoin_tx_output_index,
+            bitcoin_txid: bitcoin_txid.clone(),
+            deposit_script: deposit_scripts.first().unwrap().clone(),
+            reclaim_script: reclaim_scripts.first().unwrap().clone(),
+            transaction_hex,
+        };
+        apis::deposit_api::create_deposit(&configuration, create_deposit_body)
+            .await
+            .expect("Received an error after making a valid create deposit request api call.");
+        txids.push(bitcoin_txid);
+    }
+
+    // The first transaction is replaced by the second, and the second by
+    // the third.
+    let rbf_update = |bitcoin_txid: &String, replaced_by_tx: &String| DepositUpdate {
+        bitcoin_tx_output_index,
+        bitcoin_txid: bitcoin_txid.clone(),
+        fulfillment: None,
+        status: DepositStatus::Rbf,
+        status_message: "RBF initiated".into(),
+        reclaim_txid: None,
+        replaced_by_tx: Some(Some(replaced_by_tx.clone())),
+    };
+    let update_body = UpdateDepositsRequestBody {
+        deposits: vec![
+            rbf_update(&txids[0], &txids[1]),
+            rbf_update(&txids[1], &txids[2]),
+        ],
+    };
+    apis::deposit_api::update_deposits_sidecar(&configuration, update_body)
+        .await
+        .expect("Received an error after making a valid update deposits request api call.");
+
+    // Act.
+    // ----
+    let response = apis::deposit_api::get_rbf_chain(&configuration, &txids[0], &index)
+        .await
+        .expect("Received an error after making a valid get rbf chain request api call.");
+
+    // Assert.
+    // -------
+    let chain: Vec<_> = response
+        .transactions
+        .iter()
+        .map(|tx| (tx.bitcoin_txid.clone(), tx.status))
+        .collect();
+    let expected = vec![
+        (txids[0].clone(), DepositStatus::Rbf),
+        (txids[1].clone(), DepositStatus::Rbf),
+        (txids[2].clone(), DepositStatus::Pending),
+    ];
+    assert_eq!(chain, expected);
+
+    // Point the last transaction back at the first. The walk should stop
+    // instead of looping forever.
+    let update_body = UpdateDepositsRequestBody {
+        deposits: vec![rbf_update(&txids[2], &txids[0])],
+    };
+    apis::deposit_api::update_deposits_sidecar(&configuration, update_body)
+        .await
+        .expect("Received an error after making a valid update deposits request api call.");
+
+    let response = apis::deposit_api::get_rbf_chain(&configuration, &txids[0], &index)
+        .await
+        .expect("Received an error after making a valid get rbf chain request api call.");
+    let chain: Vec<_> = response
+        .transactions
+        .iter()
+        .map(|tx| tx.bitcoin_txid.clone())
+        .collect();
+    assert_eq!(chain, txids);
+}
+
+#[test_case(DepositStatus::Pending; "pending")]
+#[test_case(DepositStatus::Accepted; "accepted")]
+#[test_case(DepositStatus::Confirmed; "confirmed")]
+#[test_case(DepositStatus::Failed; "failed")]
+#[tokio::test]
+async fn replaced_by_tx_for_not_rbf_transactions_is_bad_request(status: DepositStatus) {
+    // the testing configuration has privileged access to all endpoints.
+    let testing_configuration = clean_setup().await;
+
+    // the user configuration access depends on the api_key.
+    let user_configuration = testing_configuration.clone();
+    // Arrange.
+    // --------
+    let bitcoin_tx_output_index = 0;
+
+    // Setup test deposit transaction.
+    let DepositTxnData {
+        reclaim_scripts,
+        deposit_scripts,
+        bitcoin_txid,
+        transaction_hex,
+        ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &[DEPOSIT_AMOUNT_SATS]);
+    let reclaim_script = reclaim_scripts.first().unwrap().clone();
+    let deposit_script = deposit_scripts.first().unwrap().clone();
+
+    let txid = bitcoin_txid.clone();
+    let index = bitcoin_tx_output_index.to_string();
+
+    let create_deposit_body = CreateDepositRequestBody {
+        bitcoin_tx_output_index,
+        bitcoin_txid: bitcoin_txid.clone(),
+        deposit_script: deposit_script.clone(),
+        reclaim_script: reclaim_script.clone(),
+        transaction_hex: transaction_hex.clone(),
+    };
+
+    // Update the deposit status with the privileged configuration.
+    apis::deposit_api::create_deposit(&testing_configuration, create_deposit_body.clone())
+        .await
+        .expect("Received an error after making a valid create deposit request api call.");
+
+    // Update deposit setting status to rbf.
+    let update_body = UpdateDepositsRequestBody {
+        deposits: vec![DepositUpdate {
+            bitcoin_tx_output_index,
+            bitcoin_txid: bitcoin_txid.clone(),
+            fulfillment: None,
+            status,
+            status_message: "dummy".into(),
+            reclaim_txid: None,
+            replaced_by_tx: Some(Some("replaced_by_txid".to_string())),
+        }],
+    };
+
+    // Check that response to update request is correct.
+    let response =
+        apis::deposit_api::update_deposits_sidecar(&user_configuration, update_body).await;
+
+    // Response itself should be ok since update_deposits is a batch request with multistatus.
+    assert!(response.is_ok());
+    let deposits = response.unwrap();
+    assert_eq!(deposits.deposits.len(), 1);
+    let deposit = deposits.deposits.first().expect("No deposit in response");
+
+    // Expect a bad request error because the transaction is not in RBF status.
+    assert!(
+        deposit.status == 400,
+        "Expected a 400 Bad Request status code"
+    );
+
+    // Check that the deposit status wasn't updated.
+    let response = apis::deposit_api::get_deposit(&user_configuration, &txid, &index)
+        .await
+        .expect("Deposit with this txid and index should be available");
+    assert_eq!(response.bitcoin_txid, bitcoin_txid);
+    assert!(response.replaced_by_tx.is_none());
+    assert_eq!(response.status, DepositStatus::Pending);
+}
+
+#[test_case(DepositStatus::Confirmed, false; "confirmed_without_fulfillment")]
+#[test_case(DepositStatus::Pending, true; "pending_with_fulfillment")]
+#[test_case(DepositStatus::Accepted, true; "accepted_with_fulfillment")]
+#[test_case(DepositStatus::Failed, true; "failed_with_fulfillment")]
+#[tokio::test]
+async fn fulfillment_not_matching_confirmed_status_is_bad_request(
+    status: DepositStatus,
+    with_fulfillment: bool,
+) {
+    let configuration = clean_setup().await;
+    // Arrange.
+    // --------
+    let bitcoin_tx_output_index = 0;
+
+    // Setup test deposit transaction.
+    let DepositTxnData {
+        reclaim_scripts,
+        deposit_scripts,
+        bitcoin_txid,
+        transaction_hex,
+        ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &[DEPOSIT_AMOUNT_SATS]);
+
+    let txid = bitcoin_txid.clone();
+    let index = bitcoin_tx_output_index.to_string();
+
+    let create_deposit_body = CreateDepositRequestBody {
+        bitcoin_tx_output_index,
+        bitcoin_txid: bitcoin_txid.clone(),
+        deposit_script: deposit_scripts.first().unwrap().clone(),
+        reclaim_script: reclaim_scripts.first().unwrap().clone(),
+        transaction_hex,
+    };
+    apis::deposit_api::create_deposit(&configuration, create_deposit_body)
+        .await
+        .expect("Received an error after making a valid create deposit request api call.");
+
+    let fulfillment = with_fulfillment.then(|| {
+        Some(Box::new(Fulfillment {
+            bitcoin_block_hash: "bitcoin_block_hash".to_string(),
+            bitcoin_block_height: 23,
+            bitcoin_tx_index: 45,
+            bitcoin_txid: "test_fulfillment_bitcoin_txid".to_string(),
+            btc_fee: 2314,
+            stacks_txid: "test_fulfillment_stacks_txid".to_string(),
+        }))
+    });
+    let update_body = UpdateDepositsRequestBody {
+        deposits: vec![DepositUpdate {
+            bitcoin_tx_output_index,
+            bitcoin_txid: bitcoin_txid.clone(),
+            fulfillment,
+            status,
+            status_message: "dummy".into(),
+            reclaim_txid: None,
+            replaced_by_tx: None,
+        }],
+    };
+
+    // Act.
+    // ----
+    let response = apis::deposit_api::update_deposits_signer(&configuration, update_body).await;
+
+    // Assert.
+    // -------
+    // Response itself should be ok since update_deposits is a batch request with multistatus.
+    let deposits = response.expect("Batch update should return 200 OK");
+    assert_eq!(deposits.deposits.len(), 1);
+    let deposit = deposits.deposits.first().expect("No deposit in response");
+    assert_eq!(deposit.status, 400);
+
+    // Check that the deposit wasn't updated.
+    let response = apis::deposit_api::get_deposit(&configuration, &txid, &index)
         .await
         .expect("Deposit with this txid and index should be available");
-    assert_eq!(response.bitcoin_txid, bitcoin_txid);
-    assert_eq!(response.status, DepositStatus::Rbf);
-    assert_eq!(
-        response.replaced_by_tx,
-        Some(Some("replaced_by_txid".to_string()))
-    );
+    assert_eq!(response.status, DepositStatus::Pending);
+    assert!(response.fulfillment.is_none());
 }
 
-#[test_case(DepositStatus::Pending; "pending")]
-#[test_case(DepositStatus::Accepted; "accepted")]
-#[test_case(DepositStatus::Confirmed; "confirmed")]
-#[test_case(DepositStatus::Failed; "failed")]
 #[tokio::test]
-async fn replaced_by_tx_for_not_rbf_transactions_is_bad_request(status: DepositStatus) {
-    // the testing configuration has privileged access to all endpoints.
-    let testing_configuration = clean_setup().await;
-
-    // the user configuration access depends on the api_key.
-    let user_configuration = testing_configuration.clone();
+async fn update_deposits_collapses_duplicates_within_a_batch() {
+    let configuration = clean_setup().await;
     // Arrange.
     // --------
     let bitcoin_tx_output_index = 0;
 
-    // Setup test deposit transaction.
     let DepositTxnData {
         reclaim_scripts,
         deposit_scripts,
@@ -1310,8 +2301,6 @@ async fn replaced_by_tx_for_not_rbf_transactions_is_bad_request(status: DepositS
         transaction_hex,
         ..
     } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &[DEPOSIT_AMOUNT_SATS]);
-    let reclaim_script = reclaim_scripts.first().unwrap().clone();
-    let deposit_script = deposit_scripts.first().unwrap().clone();
 
     let txid = bitcoin_txid.clone();
     let index = bitcoin_tx_output_index.to_string();
@@ -1319,51 +2308,52 @@ async fn replaced_by_tx_for_not_rbf_transactions_is_bad_request(status: DepositS
     let create_deposit_body = CreateDepositRequestBody {
         bitcoin_tx_output_index,
         bitcoin_txid: bitcoin_txid.clone(),
-        deposit_script: deposit_script.clone(),
-        reclaim_script: reclaim_script.clone(),
-        transaction_hex: transaction_hex.clone(),
+        deposit_script: deposit_scripts.first().unwrap().clone(),
+        reclaim_script: reclaim_scripts.first().unwrap().clone(),
+        transaction_hex,
     };
-
-    // Update the deposit status with the privileged configuration.
-    apis::deposit_api::create_deposit(&testing_configuration, create_deposit_body.clone())
+    apis::deposit_api::create_deposit(&configuration, create_deposit_body)
         .await
         .expect("Received an error after making a valid create deposit request api call.");
 
-    // Update deposit setting status to rbf.
+    let deposit_update = DepositUpdate {
+        bitcoin_tx_output_index,
+        bitcoin_txid: bitcoin_txid.clone(),
+        fulfillment: None,
+        status: DepositStatus::Accepted,
+        status_message: "accepted".into(),
+        reclaim_txid: None,
+        replaced_by_tx: None,
+    };
     let update_body = UpdateDepositsRequestBody {
-        deposits: vec![DepositUpdate {
-            bitcoin_tx_output_index,
-            bitcoin_txid: bitcoin_txid.clone(),
-            fulfillment: None,
-            status,
-            status_message: "dummy".into(),
-            replaced_by_tx: Some(Some("replaced_by_txid".to_string())),
-        }],
+        deposits: vec![deposit_update.clone(), deposit_update],
     };
 
-    // Check that response to update request is correct.
-    let response =
-        apis::deposit_api::update_deposits_sidecar(&user_configuration, update_body).await;
-
-    // Response itself should be ok since update_deposits is a batch request with multistatus.
-    assert!(response.is_ok());
-    let deposits = response.unwrap();
-    assert_eq!(deposits.deposits.len(), 1);
-    let deposit = deposits.deposits.first().expect("No deposit in response");
+    // Act.
+    // ----
+    let response = apis::deposit_api::update_deposits_sidecar(&configuration, update_body)
+        .await
+        .expect("Received an error after making a valid update deposits api call.");
 
-    // Expect a bad request error because the transaction is not in RBF status.
-    assert!(
-        deposit.status == 400,
-        "Expected a 400 Bad Request status code"
-    );
+    // Assert.
+    // -------
+    // Both positions in the batch report the applied update.
+    assert_eq!(response.deposits.len(), 2);
+    for deposit in response.deposits.iter() {
+        assert_eq!(deposit.status, 200);
+        assert_eq!(deposit.deposit.bitcoin_txid, bitcoin_txid);
+        assert_eq!(deposit.deposit.status, DepositStatus::Accepted);
+    }
 
-    // Check that the deposit status wasn't updated.
-    let response = apis::deposit_api::get_deposit(&user_configuration, &txid, &index)
+    // The update was applied once.
+    let history = apis::deposit_api::get_deposit_history(&configuration, &txid, &index)
         .await
-        .expect("Deposit with this txid and index should be available");
-    assert_eq!(response.bitcoin_txid, bitcoin_txid);
-    assert!(response.replaced_by_tx.is_none());
-    assert_eq!(response.status, DepositStatus::Pending);
+        .expect("Received an error after making a valid get deposit history api call.");
+    let statuses: Vec<DepositStatus> = history.events.iter().map(|event| event.status).collect();
+    assert_eq!(
+        statuses,
+        vec![DepositStatus::Pending, DepositStatus::Accepted]
+    );
 }
 
 #[tokio::test]
@@ -1427,10 +2417,16 @@ async fn emily_process_deposit_updates_when_some_of_them_already_accepted() {
         .expect("Received an error after making a valid create deposit request api call.");
 
     // Now we should have 2 pending deposits.
-    let deposits =
-        apis::deposit_api::get_deposits(&testing_configuration, DepositStatus::Pending, None, None)
-            .await
-            .expect("Received an error after making a valid get deposits api call.");
+    let deposits = apis::deposit_api::get_deposits(
+        &testing_configuration,
+        DepositStatus::Pending,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .expect("Received an error after making a valid get deposits api call.");
     assert_eq!(deposits.deposits.len(), 2);
 
     // Update first deposit to Accepted.
@@ -1441,6 +2437,7 @@ async fn emily_process_deposit_updates_when_some_of_them_already_accepted() {
             fulfillment: None,
             status: DepositStatus::Accepted,
             status_message: "First update".into(),
+            reclaim_txid: None,
             replaced_by_tx: None,
         }],
     };
@@ -1460,16 +2457,24 @@ async fn emily_process_deposit_updates_when_some_of_them_already_accepted() {
     assert_eq!(response.deposits.len(), 1);
 
     // Now we should have 1 pending and 1 accepted deposit.
-    let deposits =
-        apis::deposit_api::get_deposits(&testing_configuration, DepositStatus::Pending, None, None)
-            .await
-            .expect("Received an error after making a valid get deposits api call.");
+    let deposits = apis::deposit_api::get_deposits(
+        &testing_configuration,
+        DepositStatus::Pending,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .expect("Received an error after making a valid get deposits api call.");
     assert_eq!(deposits.deposits.len(), 1);
     let deposits = apis::deposit_api::get_deposits(
         &testing_configuration,
         DepositStatus::Accepted,
         None,
         None,
+        None,
+        None,
     )
     .await
     .expect("Received an error after making a valid get deposits api call.");
@@ -1484,6 +2489,7 @@ async fn emily_process_deposit_updates_when_some_of_them_already_accepted() {
                 fulfillment: None,
                 status: DepositStatus::Accepted,
                 status_message: "Second update".into(),
+                reclaim_txid: None,
                 replaced_by_tx: None,
             },
             DepositUpdate {
@@ -1492,6 +2498,7 @@ async fn emily_process_deposit_updates_when_some_of_them_already_accepted() {
                 fulfillment: None,
                 status: DepositStatus::Accepted,
                 status_message: "Second update".into(),
+                reclaim_txid: None,
                 replaced_by_tx: None,
             },
         ],
@@ -1517,6 +2524,8 @@ async fn emily_process_deposit_updates_when_some_of_them_already_accepted() {
         DepositStatus::Accepted,
         None,
         None,
+        None,
+        None,
     )
     .await
     .expect("Received an error after making a valid get deposits api call.");
@@ -1582,10 +2591,16 @@ async fn emily_process_deposit_updates_when_some_of_them_are_unknown() {
         .expect("Received an error after making a valid create deposit request api call.");
 
     // Now we should have 1 pending deposit.
-    let deposits =
-        apis::deposit_api::get_deposits(&testing_configuration, DepositStatus::Pending, None, None)
-            .await
-            .expect("Received an error after making a valid get deposits api call.");
+    let deposits = apis::deposit_api::get_deposits(
+        &testing_configuration,
+        DepositStatus::Pending,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .expect("Received an error after making a valid get deposits api call.");
     assert_eq!(deposits.deposits.len(), 1);
 
     // Now we update both deposits to Accepted in a batch. This still should be a valid api call
@@ -1598,6 +2613,7 @@ async fn emily_process_deposit_updates_when_some_of_them_are_unknown() {
                 fulfillment: None,
                 status: DepositStatus::Accepted,
                 status_message: "Second update".into(),
+                reclaim_txid: None,
                 replaced_by_tx: None,
             },
             DepositUpdate {
@@ -1606,6 +2622,7 @@ async fn emily_process_deposit_updates_when_some_of_them_are_unknown() {
                 fulfillment: None,
                 status: DepositStatus::Accepted,
                 status_message: "Second update".into(),
+                reclaim_txid: None,
                 replaced_by_tx: None,
             },
         ],
@@ -1632,8 +2649,236 @@ async fn emily_process_deposit_updates_when_some_of_them_are_unknown() {
         DepositStatus::Accepted,
         None,
         None,
+        None,
+        None,
     )
     .await
     .expect("Received an error after making a valid get deposits api call.");
     assert_eq!(deposits.deposits.len(), 1);
 }
+
+#[tokio::test]
+async fn bitcoin_height_at_acceptance_is_set_when_first_accepted() {
+    let configuration = clean_setup().await;
+    // Arrange.
+    // --------
+    let bitcoin_tx_output_index = 0;
+
+    let DepositTxnData {
+        reclaim_scripts,
+        deposit_scripts,
+        bitcoin_txid,
+        transaction_hex,
+        ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &[DEPOSIT_AMOUNT_SATS]);
+
+    let txid = bitcoin_txid.clone();
+    let index = bitcoin_tx_output_index.to_string();
+
+    let create_deposit_body = CreateDepositRequestBody {
+        bitcoin_tx_output_index,
+        bitcoin_txid: bitcoin_txid.clone(),
+        deposit_script: deposit_scripts.first().unwrap().clone(),
+        reclaim_script: reclaim_scripts.first().unwrap().clone(),
+        transaction_hex,
+    };
+    apis::deposit_api::create_deposit(&configuration, create_deposit_body)
+        .await
+        .expect("Received an error after making a valid create deposit request api call.");
+
+    // A pending deposit has not been observed on chain yet.
+    let deposit = apis::deposit_api::get_deposit(&configuration, &txid, &index)
+        .await
+        .expect("Received an error after making a valid get deposit api call.");
+    assert_eq!(deposit.bitcoin_height_at_acceptance, None);
+
+    let accepted_chainstate = Chainstate {
+        stacks_block_hash: "accepted_block_hash".to_string(),
+        stacks_block_height: 42,
+        bitcoin_block_height: Some(Some(100)),
+    };
+    set_chainstate(&configuration, accepted_chainstate)
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+
+    let accept_body = UpdateDepositsRequestBody {
+        deposits: vec![DepositUpdate {
+            bitcoin_tx_output_index,
+            bitcoin_txid: bitcoin_txid.clone(),
+            fulfillment: None,
+            status: DepositStatus::Accepted,
+            status_message: "accepted".into(),
+            reclaim_txid: None,
+            replaced_by_tx: None,
+        }],
+    };
+    apis::deposit_api::update_deposits_signer(&configuration, accept_body)
+        .await
+        .expect("Received an error after making a valid update deposits api call.");
+
+    let deposit = apis::deposit_api::get_deposit(&configuration, &txid, &index)
+        .await
+        .expect("Received an error after making a valid get deposit api call.");
+    assert_eq!(deposit.bitcoin_height_at_acceptance, Some(Some(100)));
+
+    // Act.
+    // ----
+    let confirmed_chainstate = Chainstate {
+        stacks_block_hash: "confirmed_block_hash".to_string(),
+        stacks_block_height: 43,
+        bitcoin_block_height: Some(Some(101)),
+    };
+    set_chainstate(&configuration, confirmed_chainstate)
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+
+    let confirm_body = UpdateDepositsRequestBody {
+        deposits: vec![DepositUpdate {
+            bitcoin_tx_output_index,
+            bitcoin_txid: bitcoin_txid.clone(),
+            fulfillment: Some(Some(Box::new(Fulfillment {
+                bitcoin_block_hash: "bitcoin_block_hash".to_string(),
+                bitcoin_block_height: 101,
+                bitcoin_tx_index: 45,
+                bitcoin_txid: "test_fulfillment_bitcoin_txid".to_string(),
+                btc_fee: 2314,
+                stacks_txid: "test_fulfillment_stacks_txid".to_string(),
+            }))),
+            status: DepositStatus::Confirmed,
+            status_message: "confirmed".into(),
+            reclaim_txid: None,
+            replaced_by_tx: None,
+        }],
+    };
+    apis::deposit_api::update_deposits_sidecar(&configuration, confirm_body)
+        .await
+        .expect("Received an error after making a valid update deposits api call.");
+
+    // Assert.
+    // -------
+    // The height is recorded when the deposit is first accepted and is
+    // left alone by later updates.
+    let deposit = apis::deposit_api::get_deposit(&configuration, &txid, &index)
+        .await
+        .expect("Received an error after making a valid get deposit api call.");
+    assert_eq!(deposit.status, DepositStatus::Confirmed);
+    assert_eq!(deposit.bitcoin_height_at_acceptance, Some(Some(100)));
+}
+
+/// Signers may only expire a deposit once Emily can tell from its own
+/// chainstate that the lock time of the deposit has elapsed.
+#[tokio::test]
+async fn expiring_deposits_is_forbidden_for_signer_before_lock_time() {
+    let configuration = clean_setup().await;
+    // Arrange.
+    // --------
+    let bitcoin_tx_output_index = 0;
+
+    let DepositTxnData {
+        reclaim_scripts,
+        deposit_scripts,
+        bitcoin_txid,
+        transaction_hex,
+        ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &[DEPOSIT_AMOUNT_SATS]);
+
+    let txid = bitcoin_txid.clone();
+    let index = bitcoin_tx_output_index.to_string();
+
+    let create_deposit_body = CreateDepositRequestBody {
+        bitcoin_tx_output_index,
+        bitcoin_txid: bitcoin_txid.clone(),
+        deposit_script: deposit_scripts.first().unwrap().clone(),
+        reclaim_script: reclaim_scripts.first().unwrap().clone(),
+        transaction_hex,
+    };
+    apis::deposit_api::create_deposit(&configuration, create_deposit_body)
+        .await
+        .expect("Received an error after making a valid create deposit request api call.");
+
+    let expire_body = UpdateDepositsRequestBody {
+        deposits: vec![DepositUpdate {
+            bitcoin_tx_output_index,
+            bitcoin_txid: bitcoin_txid.clone(),
+            fulfillment: None,
+            status: DepositStatus::Expired,
+            status_message: "expired".into(),
+            reclaim_txid: None,
+            replaced_by_tx: None,
+        }],
+    };
+
+    // Emily has no way of telling when a pending deposit was confirmed,
+    // so signers cannot expire it.
+    let response = apis::deposit_api::update_deposits_signer(&configuration, expire_body.clone())
+        .await
+        .expect("Batch update should return 200 OK");
+    assert_eq!(response.deposits[0].status, 403);
+
+    let accepted_chainstate = Chainstate {
+        stacks_block_hash: "accepted_block_hash".to_string(),
+        stacks_block_height: 42,
+        bitcoin_block_height: Some(Some(100)),
+    };
+    set_chainstate(&configuration, accepted_chainstate)
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+
+    let accept_body = UpdateDepositsRequestBody {
+        deposits: vec![DepositUpdate {
+            bitcoin_tx_output_index,
+            bitcoin_txid: bitcoin_txid.clone(),
+            fulfillment: None,
+            status: DepositStatus::Accepted,
+            status_message: "accepted".into(),
+            reclaim_txid: None,
+            replaced_by_tx: None,
+        }],
+    };
+    apis::deposit_api::update_deposits_signer(&configuration, accept_body)
+        .await
+        .expect("Received an error after making a valid update deposits api call.");
+
+    // Act.
+    // ----
+    // One block before the lock time has elapsed the update is rejected.
+    let early_chainstate = Chainstate {
+        stacks_block_hash: "early_block_hash".to_string(),
+        stacks_block_height: 43,
+        bitcoin_block_height: Some(Some(100 + DEPOSIT_LOCK_TIME as u64 - 1)),
+    };
+    set_chainstate(&configuration, early_chainstate)
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+
+    let response = apis::deposit_api::update_deposits_signer(&configuration, expire_body.clone())
+        .await
+        .expect("Batch update should return 200 OK");
+
+    // Assert.
+    // -------
+    assert_eq!(response.deposits[0].status, 403);
+    let deposit = apis::deposit_api::get_deposit(&configuration, &txid, &index)
+        .await
+        .expect("Received an error after making a valid get deposit api call.");
+    assert_eq!(deposit.status, DepositStatus::Accepted);
+
+    // Once the lock time has elapsed the signers may expire the deposit.
+    let expired_chainstate = Chainstate {
+        stacks_block_hash: "expired_block_hash".to_string(),
+        stacks_block_height: 44,
+        bitcoin_block_height: Some(Some(100 + DEPOSIT_LOCK_TIME as u64)),
+    };
+    set_chainstate(&configuration, expired_chainstate)
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+
+    let response = apis::deposit_api::update_deposits_signer(&configuration, expire_body)
+        .await
+        .expect("Batch update should return 200 OK");
+    assert_eq!(response.deposits[0].status, 200);
+    let deposit = apis::deposit_api::get_deposit(&configuration, &txid, &index)
+        .await
+        .expect("Received an error after making a valid get deposit api call.");
+    assert_eq!(deposit.status, DepositStatus::Expired);
+}
@@ -15,11 +15,17 @@ use super::CorsSupport;
         api::handlers::health::get_health,
         // Deposit endpoints.
         api::handlers::deposit::get_deposit,
+        api::handlers::deposit::get_deposit_history,
+        api::handlers::deposit::get_rbf_chain,
         api::handlers::deposit::get_deposits_for_transaction,
         api::handlers::deposit::get_deposits_for_recipient,
         api::handlers::deposit::get_deposits_for_reclaim_pubkeys,
+        api::handlers::deposit::get_deposits_for_reclaim_pubkeys_batch,
+        api::handlers::deposit::get_deposits_for_bitcoin_block,
         api::handlers::deposit::get_deposits,
+        api::handlers::deposit::get_deposits_by_status_and_height_range,
         api::handlers::deposit::create_deposit,
+        api::handlers::deposit::create_deposits,
         api::handlers::deposit::update_deposits_sidecar,
         // Withdrawal endpoints.
         api::handlers::withdrawal::get_withdrawal,
@@ -49,11 +55,19 @@ use super::CorsSupport;
         // Deposit models.
         api::models::deposit::Deposit,
         api::models::deposit::responses::DepositWithStatus,
+        api::models::deposit::responses::CreateDepositsResponse,
         api::models::deposit::DepositParameters,
         api::models::deposit::DepositInfo,
+        api::models::deposit::DepositHistoryEvent,
+        api::models::deposit::RbfChainTransaction,
         api::models::deposit::requests::CreateDepositRequestBody,
+        api::models::deposit::requests::CreateDepositsRequestBody,
+        api::models::deposit::requests::GetDepositsForReclaimPubkeysBatchRequestBody,
         api::models::deposit::requests::DepositUpdate,
         api::models::deposit::requests::UpdateDepositsRequestBody,
+        api::models::deposit::responses::GetDepositHistoryResponse,
+        api::models::deposit::responses::GetRbfChainResponse,
+        api::models::deposit::responses::GetDepositsForReclaimPubkeysBatchResponse,
         api::models::deposit::responses::GetDepositsForTransactionResponse,
         api::models::deposit::responses::GetDepositsResponse,
         api::models::deposit::responses::UpdateDepositsResponse,
@@ -14,6 +14,8 @@ class RequestStatus(Enum):
     CONFIRMED = "confirmed"
     FAILED = "failed"
     RBF = "rbf"
+    RECLAIMED = "reclaimed"
+    EXPIRED = "expired"
 
 
 @dataclass
@@ -7,7 +7,13 @@ use bitcoin::OutPoint;
 use bitcoin::Script;
 use bitcoin::ScriptBuf;
 use bitcoin::Transaction;
+use bitcoin::TxIn;
+use bitcoin::TxOut;
 use bitcoin::XOnlyPublicKey;
+use bitcoin::absolute;
+use bitcoin::consensus::Decodable as _;
+use bitcoin::consensus::encode;
+use bitcoin::hex::FromHex as _;
 use bitcoin::locktime::relative::LockTime;
 use bitcoin::opcodes::all as opcodes;
 use bitcoin::script::PushBytesBuf;
@@ -23,6 +29,10 @@ use stacks_common::types::chainstate::STACKS_ADDRESS_ENCODED_SIZE;
 
 use crate::error::Error;
 
+/// The marker and flag bytes that follow the version in the witness
+/// serialization of a transaction, as defined in BIP-144.
+const SEGWIT_MARKER_AND_FLAG: [u8; 2] = [0x00, 0x01];
+
 /// This is the length of the fixed portion of the deposit script, which
 /// is:
 /// ```text
@@ -115,6 +125,18 @@ pub struct DepositInfo {
 }
 
 impl CreateDepositRequest {
+    /// Validate this deposit request against the hex encoded transaction.
+    ///
+    /// The transaction hex may be in either the witness or non-witness
+    /// serialization format, see [`parse_transaction_hex`]. An error is
+    /// returned if the hex is malformed, and an [`Error::TxidMismatch`]
+    /// is returned if it is a different transaction than the one in the
+    /// request. Otherwise, this is the same as [`Self::validate_tx`].
+    pub fn validate_tx_hex(&self, tx_hex: &str, is_mainnet: bool) -> Result<DepositInfo, Error> {
+        let tx = parse_transaction_hex(tx_hex)?;
+        self.validate_tx(&tx, is_mainnet)
+    }
+
     /// Validate this deposit request.
     ///
     /// This function checks the following
@@ -566,6 +588,46 @@ fn scriptint_parse(v: &[u8]) -> i64 {
     ret
 }
 
+/// Parse hex encoded bytes of a transaction.
+///
+/// Transactions with witness data can be serialized with or without that
+/// data, and both serializations have the same txid. We check for the
+/// segwit marker and flag bytes and deserialize the witness serialization
+/// if they are present, falling back to the non-witness serialization
+/// otherwise. The fallback matters for transactions without inputs,
+/// since the non-witness serialization of those starts with a zero byte
+/// that looks like the segwit marker.
+pub fn parse_transaction_hex(tx_hex: &str) -> Result<Transaction, Error> {
+    let bytes = Vec::<u8>::from_hex(tx_hex).map_err(Error::DecodeTransactionHex)?;
+
+    if bytes.get(4..6) == Some(SEGWIT_MARKER_AND_FLAG.as_slice()) {
+        if let Ok(tx) = encode::deserialize::<Transaction>(&bytes) {
+            return Ok(tx);
+        }
+    }
+
+    deserialize_without_witness(&bytes).map_err(Error::DeserializeTransaction)
+}
+
+/// Deserialize a transaction using the non-witness serialization format.
+fn deserialize_without_witness(bytes: &[u8]) -> Result<Transaction, encode::Error> {
+    let mut reader = bytes;
+    let tx = Transaction {
+        version: bitcoin::transaction::Version::consensus_decode(&mut reader)?,
+        input: Vec::<TxIn>::consensus_decode(&mut reader)?,
+        output: Vec::<TxOut>::consensus_decode(&mut reader)?,
+        lock_time: absolute::LockTime::consensus_decode(&mut reader)?,
+    };
+
+    if !reader.is_empty() {
+        return Err(encode::Error::ParseFailed(
+            "data not consumed entirely when deserializing the transaction",
+        ));
+    }
+
+    Ok(tx)
+}
+
 #[cfg(test)]
 mod tests {
     use bitcoin::AddressType;
@@ -948,6 +1010,78 @@ mod tests {
         assert!(matches!(error, Error::TxidMismatch { .. }));
     }
 
+    #[test_case(0; "no inputs")]
+    #[test_case(2; "with inputs")]
+    fn validate_tx_hex_accepts_witness_and_non_witness_encodings(num_inputs: usize) {
+        let max_fee: u64 = 15000;
+        let amount_sats = 500_000;
+        let lock_time = 150;
+
+        let mut setup: TxSetup = testing::deposits::tx_setup(lock_time, max_fee, &[amount_sats]);
+        setup.tx.input = (0..num_inputs)
+            .map(|vout| TxIn {
+                previous_output: OutPoint::new(Txid::all_zeros(), vout as u32),
+                witness: bitcoin::Witness::from_slice(&[[1; 64]]),
+                ..Default::default()
+            })
+            .collect();
+
+        let request = CreateDepositRequest {
+            outpoint: OutPoint::new(setup.tx.compute_txid(), 0),
+            deposit_script: setup.deposits.first().unwrap().deposit_script(),
+            reclaim_script: setup.reclaims.first().unwrap().reclaim_script(),
+        };
+
+        // The non-witness serialization is the version, inputs, outputs
+        // and lock-time, without the segwit marker, flag or witness data.
+        // It has the same txid as the witness serialization.
+        let witness_hex = encode::serialize_hex(&setup.tx);
+        let non_witness_hex = [
+            encode::serialize_hex(&setup.tx.version),
+            encode::serialize_hex(&setup.tx.input),
+            encode::serialize_hex(&setup.tx.output),
+            encode::serialize_hex(&setup.tx.lock_time),
+        ]
+        .concat();
+        assert_ne!(witness_hex, non_witness_hex);
+
+        for tx_hex in [witness_hex, non_witness_hex] {
+            let tx = parse_transaction_hex(&tx_hex).unwrap();
+            assert_eq!(tx.compute_txid(), setup.tx.compute_txid());
+            assert_eq!(tx.output, setup.tx.output);
+
+            let info = request.validate_tx_hex(&tx_hex, false).unwrap();
+            assert_eq!(info.outpoint, request.outpoint);
+            assert_eq!(info.amount, amount_sats);
+
+            // A well formed transaction with a different txid is reported
+            // as a mismatch rather than as malformed hex.
+            let wrong_request = CreateDepositRequest {
+                outpoint: OutPoint::new(Txid::all_zeros(), 0),
+                ..request.clone()
+            };
+            let error = wrong_request.validate_tx_hex(&tx_hex, false).unwrap_err();
+            assert!(matches!(error, Error::TxidMismatch { .. }));
+        }
+    }
+
+    #[test_case("zz"; "not hex")]
+    #[test_case("020000"; "truncated transaction")]
+    fn validate_tx_hex_rejects_malformed_hex(tx_hex: &str) {
+        let setup: TxSetup = testing::deposits::tx_setup(150, 15000, &[500_000]);
+        let request = CreateDepositRequest {
+            outpoint: OutPoint::new(setup.tx.compute_txid(), 0),
+            deposit_script: setup.deposits.first().unwrap().deposit_script(),
+            reclaim_script: setup.reclaims.first().unwrap().reclaim_script(),
+        };
+
+        let error = request.validate_tx_hex(tx_hex, false).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::DecodeTransactionHex(_) | Error::DeserializeTransaction(_)
+        ));
+    }
+
     #[test]
     fn correct_tx_request_has_invalid_deposit_or_reclaim_script() {
         let max_fee: u64 = 15000;
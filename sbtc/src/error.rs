@@ -60,6 +60,13 @@ pub enum Error {
     /// the given deposit script and reclaim script.
     #[error("mismatch in expected and actual ScriptPubKeys. outpoint: {0}")]
     UtxoScriptPubKeyMismatch(OutPoint),
+    /// The transaction hex could not be decoded into bytes.
+    #[error("could not decode the transaction hex into bytes: {0}")]
+    DecodeTransactionHex(#[source] bitcoin::hex::HexToBytesError),
+    /// The transaction bytes could not be deserialized into a
+    /// transaction, with or without witness data.
+    #[error("could not deserialize the transaction bytes: {0}")]
+    DeserializeTransaction(#[source] bitcoin::consensus::encode::Error),
     /// Failed to parse the hex as a bitcoin::Transaction.
     #[error("The txid of the transaction did not match the given txid")]
     TxidMismatch {
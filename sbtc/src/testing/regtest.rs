@@ -297,6 +297,30 @@ impl Faucet {
             .expect("failed to generate bitcoin block")
     }
 
+    /// Simulate a reorg of the top `depth` blocks of the blockchain.
+    ///
+    /// This invalidates the top `depth` blocks and mines `depth + 1` new
+    /// blocks on top of their parent, creating a longer competing chain.
+    /// Returns the hashes of the new blocks, in order of increasing
+    /// height.
+    ///
+    /// The new blocks pay their coinbase rewards to a fresh address, so
+    /// that they differ from the invalidated ones even if they end up with
+    /// the same transactions and timestamps.
+    pub fn reorg(&self, depth: u32) -> Vec<BlockHash> {
+        if depth > 0 {
+            let tip_height = self.rpc.get_block_count().unwrap();
+            let fork_height = tip_height + 1 - u64::from(depth);
+            let first_orphaned = self.rpc.get_block_hash(fork_height).unwrap();
+            self.rpc.invalidate_block(&first_orphaned).unwrap();
+        }
+
+        let miner = Recipient::new(AddressType::P2tr);
+        self.rpc
+            .generate_to_address(u64::from(depth) + 1, &miner.address)
+            .unwrap()
+    }
+
     /// Return all UTXOs for this recipient where the amount is greater
     /// than or equal to the given amount. The address must be tracked by
     /// the bitcoin-core wallet.
@@ -1,3 +1,4 @@
 //! The main file for the single integration test binary
 
+mod regtest;
 mod validation;
@@ -0,0 +1,37 @@
+//! Tests for the regtest helpers
+
+use bitcoincore_rpc::RpcApi as _;
+use sbtc::testing::regtest;
+
+#[test]
+fn faucet_reorg_replaces_the_top_blocks() {
+    let (rpc, faucet) = regtest::initialize_blockchain();
+
+    let orphaned = faucet.generate_blocks(2);
+    let old_tip = *orphaned.last().unwrap();
+    let old_tip_height = rpc.get_block_header_info(&old_tip).unwrap().height;
+    let fork_parent = rpc.get_block_header(&orphaned[0]).unwrap().prev_blockhash;
+
+    let new_blocks = faucet.reorg(2);
+    assert_eq!(new_blocks.len(), 3);
+
+    // The canonical chain tip is now the last of the new blocks, which
+    // sits one block above the old tip.
+    let new_tip = rpc.get_best_block_hash().unwrap();
+    assert_eq!(new_tip, *new_blocks.last().unwrap());
+    assert_ne!(new_tip, old_tip);
+
+    let new_tip_height = rpc.get_block_header_info(&new_tip).unwrap().height;
+    assert_eq!(new_tip_height, old_tip_height + 1);
+
+    // The new branch is built on top of the parent of the first orphaned
+    // block, and the orphaned blocks are no longer part of the chain.
+    let first_new = rpc.get_block_header(&new_blocks[0]).unwrap();
+    assert_eq!(first_new.prev_blockhash, fork_parent);
+
+    for block_hash in orphaned {
+        let info = rpc.get_block_header_info(&block_hash).unwrap();
+        assert_eq!(info.confirmations, -1);
+        assert!(!new_blocks.contains(&block_hash));
+    }
+}
@@ -0,0 +1,22 @@
+CREATE TYPE sbtc_signer.anomalous_spend_reason AS ENUM (
+    'signers_input_not_first',
+    'missing_signers_op_return'
+);
+
+-- A table for transactions that spend an output controlled by the signers
+-- but do not follow the layout of a transaction created by the signers.
+-- These are recorded so that operators can investigate a potential key
+-- compromise.
+CREATE TABLE sbtc_signer.anomalous_spends (
+    -- The ID of the transaction spending the signers' output.
+    txid BYTEA NOT NULL,
+    -- The hash of the bitcoin block that included the transaction.
+    block_hash BYTEA NOT NULL,
+    -- Why the transaction was deemed anomalous.
+    reason sbtc_signer.anomalous_spend_reason NOT NULL,
+    -- A timestamp of when this record was created in the database.
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+
+    PRIMARY KEY (txid, block_hash),
+    FOREIGN KEY (block_hash) REFERENCES sbtc_signer.bitcoin_blocks(block_hash) ON DELETE CASCADE
+);
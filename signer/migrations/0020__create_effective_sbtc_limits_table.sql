@@ -0,0 +1,12 @@
+-- A table recording the sBTC limits that were in effect when the signers
+-- processed a bitcoin block. These are kept for auditing purposes.
+CREATE TABLE sbtc_signer.effective_sbtc_limits (
+    -- The hash of the bitcoin chain tip that the limits were applied to.
+    block_hash BYTEA PRIMARY KEY,
+    -- The JSON serialized sBTC limits.
+    limits JSONB NOT NULL,
+    -- A timestamp of when this record was created in the database.
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+
+    FOREIGN KEY (block_hash) REFERENCES sbtc_signer.bitcoin_blocks(block_hash) ON DELETE CASCADE
+);
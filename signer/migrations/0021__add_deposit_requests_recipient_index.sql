@@ -0,0 +1 @@
+CREATE INDEX ix_deposit_requests_recipient ON sbtc_signer.deposit_requests(recipient);
@@ -0,0 +1,7 @@
+-- The magic bytes and the sBTC OP_RETURN format version that were parsed
+-- from the data of the signers' OP_RETURN output. These are NULL for all
+-- other outputs, and for OP_RETURN outputs whose magic bytes do not match
+-- the ones for the network that the signer is running on.
+ALTER TABLE sbtc_signer.bitcoin_tx_outputs
+    ADD COLUMN magic_bytes BYTEA,
+    ADD COLUMN op_return_version SMALLINT;
@@ -248,7 +248,11 @@ async fn handle_withdrawal_create(
         .write_withdrawal_request(&event)
         .await?;
 
-    tracing::debug!(topic = "withdrawal-create", "handled stacks event");
+    tracing::debug!(
+        topic = "withdrawal-create",
+        withdrawal_request = %event,
+        "handled stacks event"
+    );
 
     Ok(())
 }
@@ -288,6 +292,8 @@ async fn handle_key_rotation(ctx: &impl Context, event: KeyRotationEvent) -> Res
         .write_rotate_keys_transaction(&event)
         .await?;
 
+    ctx.state().set_latest_key_rotation_txid(event.txid);
+
     tracing::debug!(topic = "key-rotation", "handled stacks event");
 
     Ok(())
@@ -460,6 +466,7 @@ mod tests {
             num_withdraw_requests_per_block: 1,
             num_signers_per_request: 0,
             consecutive_blocks: false,
+            fork_points: Vec::new(),
         };
         let db = ctx.inner_storage();
         let test_data = TestData::generate(&mut rng, &[], &test_params);
@@ -516,6 +523,7 @@ mod tests {
             num_withdraw_requests_per_block: 2,
             num_signers_per_request: 0,
             consecutive_blocks: false,
+            fork_points: Vec::new(),
         };
 
         let db = ctx.inner_storage();
@@ -566,6 +574,7 @@ mod tests {
             num_withdraw_requests_per_block: 2,
             num_signers_per_request: 0,
             consecutive_blocks: false,
+            fork_points: Vec::new(),
         };
 
         let db = ctx.inner_storage();
@@ -617,6 +626,7 @@ mod tests {
             num_withdraw_requests_per_block: 2,
             num_signers_per_request: 0,
             consecutive_blocks: false,
+            fork_points: Vec::new(),
         };
 
         let test_data = TestData::generate(&mut rng, &[], &test_params);
@@ -751,4 +761,29 @@ mod tests {
         assert_eq!(res, StatusCode::OK);
         assert!(!db.lock().await.rotate_keys_transactions.is_empty());
     }
+
+    #[tokio::test]
+    async fn prometheus_recorder_reports_observed_blocks() {
+        // We use a recorder that is local to this thread rather than
+        // installing the global one, so that this test does not affect
+        // any other test in this binary.
+        let recorder = metrics_exporter_prometheus::PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        let _guard = metrics::set_default_local_recorder(&recorder);
+
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let state = State(ApiState { ctx: ctx.clone() });
+        let res = new_block_handler(state, COMPLETED_DEPOSIT_WEBHOOK.to_string()).await;
+        assert_eq!(res, StatusCode::OK);
+
+        let body = handle.render();
+        let observed_stacks_blocks = body.lines().find(|line| {
+            line.starts_with("blocks_observed_total{") && line.contains(r#"blockchain="stacks""#)
+        });
+        assert!(observed_stacks_blocks.is_some(), "{body}");
+    }
 }
@@ -12,6 +12,7 @@ use rpc::BitcoinBlockInfo;
 use rpc::BitcoinTxInfo;
 use rpc::GetTxResponse;
 
+use crate::config::NetworkKind;
 use crate::error::Error;
 
 pub mod client;
@@ -148,3 +149,73 @@ pub trait BitcoinInteract: Sync + Send {
         &self,
     ) -> impl Future<Output = Result<bitcoincore_rpc_json::GetNetworkInfoResult, Error>> + Send;
 }
+
+/// Check that bitcoin-core is running on the bitcoin network that the
+/// signer is configured for.
+///
+/// Returns [`Error::BitcoinNetworkMismatch`] if bitcoin-core reports a
+/// chain that does not match the configured network.
+pub async fn ensure_network_matches<B>(
+    bitcoin_client: &B,
+    network: NetworkKind,
+) -> Result<(), Error>
+where
+    B: BitcoinInteract,
+{
+    let reported = bitcoin_client.get_blockchain_info().await?.chain;
+    if !network.matches_bitcoin_network(reported) {
+        return Err(Error::BitcoinNetworkMismatch { configured: network, reported });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    fn blockchain_info(chain: bitcoin::Network) -> bitcoincore_rpc_json::GetBlockchainInfoResult {
+        let json = include_str!("../../tests/fixtures/bitcoind-getblockchaininfo-data.json");
+        let mut info: bitcoincore_rpc_json::GetBlockchainInfoResult =
+            serde_json::from_str(json).unwrap();
+        info.chain = chain;
+        info
+    }
+
+    #[test_case(NetworkKind::Regtest, bitcoin::Network::Bitcoin; "regtest on mainnet")]
+    #[test_case(NetworkKind::Mainnet, bitcoin::Network::Regtest; "mainnet on regtest")]
+    #[test_case(NetworkKind::Mainnet, bitcoin::Network::Testnet; "mainnet on testnet")]
+    #[test_case(NetworkKind::Testnet, bitcoin::Network::Bitcoin; "testnet on mainnet")]
+    #[tokio::test]
+    async fn mismatched_network_is_rejected(configured: NetworkKind, chain: bitcoin::Network) {
+        let mut client = MockBitcoinInteract::new();
+        client
+            .expect_get_blockchain_info()
+            .returning(move || Box::pin(std::future::ready(Ok(blockchain_info(chain)))));
+
+        let result = ensure_network_matches(&client, configured).await;
+        match result {
+            Err(Error::BitcoinNetworkMismatch { configured: c, reported }) => {
+                assert_eq!(c, configured);
+                assert_eq!(reported, chain);
+            }
+            _ => panic!("expected a network mismatch error, got {result:?}"),
+        }
+    }
+
+    #[test_case(NetworkKind::Regtest, bitcoin::Network::Regtest; "regtest")]
+    #[test_case(NetworkKind::Mainnet, bitcoin::Network::Bitcoin; "mainnet")]
+    #[test_case(NetworkKind::Testnet, bitcoin::Network::Testnet; "testnet")]
+    #[test_case(NetworkKind::Testnet, bitcoin::Network::Signet; "testnet on signet")]
+    #[tokio::test]
+    async fn matching_network_is_accepted(configured: NetworkKind, chain: bitcoin::Network) {
+        let mut client = MockBitcoinInteract::new();
+        client
+            .expect_get_blockchain_info()
+            .returning(move || Box::pin(std::future::ready(Ok(blockchain_info(chain)))));
+
+        ensure_network_matches(&client, configured).await.unwrap();
+    }
+}
@@ -29,9 +29,10 @@ const PACKAGE_MAX_VSIZE: u64 =
 /// Package a list of items into optimal bags according to specified
 /// constraints.
 ///
-/// This function implements a variant of the Best-Fit-Decreasing bin packing
-/// algorithm. Items are sorted by "weight" (votes against) in decreasing order
-/// before being placed into optimal bags.
+/// This function implements a variant of the Best-Fit bin packing
+/// algorithm. Items are placed into bags in the order that they are given,
+/// so callers that want reproducible packages should sort the items first,
+/// typically by "weight" (votes against) in decreasing order.
 ///
 /// ## Constraints
 ///
@@ -42,12 +43,15 @@ const PACKAGE_MAX_VSIZE: u64 =
 /// 3. Withdrawal IDs must fit within the OP_RETURN size limit (~77 bytes)
 /// 4. The total virtual size across all bags must not exceed
 ///    [`PACKAGE_MAX_VSIZE`]
+/// 5. The combined deposit amount cannot exceed `max_deposit_amount`
 ///
 /// ## Parameters
 /// - `items`: Collection of items to be packaged
 /// - `max_votes_against`: Maximum allowed votes against for any bag
 /// - `max_needs_signature`: Maximum number of items requiring signatures in a
 ///   bag
+/// - `max_deposit_amount`: Maximum combined deposit amount, in sats, for any
+///   bag
 ///
 /// ## Notes
 /// - Items that exceed constraints individually are silently ignored
@@ -59,6 +63,7 @@ pub fn compute_optimal_packages<I, T>(
     items: I,
     max_votes_against: u32,
     max_needs_signature: u16,
+    max_deposit_amount: u64,
 ) -> impl Iterator<Item = Vec<T>>
 where
     I: IntoIterator<Item = T>,
@@ -67,7 +72,8 @@ where
     // Now we just add each item into a bag, and return the
     // collection of bags afterward.
     // Create config and packager
-    let config = PackagerConfig::new(max_votes_against, max_needs_signature);
+    let config = PackagerConfig::new(max_votes_against, max_needs_signature)
+        .with_max_deposit_amount(max_deposit_amount);
     let mut packager = BestFitPackager::new(config);
 
     for item in items {
@@ -134,6 +140,18 @@ pub trait Weighted {
     fn withdrawal_id(&self) -> Option<u64> {
         None
     }
+
+    /// The amount, in sats, that this item sweeps into the signers' UTXO.
+    ///
+    /// Must return the deposit amount for deposits and zero otherwise. The
+    /// combined deposit amount of the items in a bag must not exceed the
+    /// `max_deposit_amount` threshold.
+    ///
+    /// ## Returns
+    /// The deposit amount in sats.
+    fn deposit_amount(&self) -> u64 {
+        0
+    }
 }
 
 /// Configuration parameters for the bin packing algorithm.
@@ -164,6 +182,10 @@ struct PackagerConfig {
     /// Enforcement of this limit prevents transaction rejection due to
     /// oversized OP_RETURN outputs.
     max_op_return_size: usize,
+    /// Maximum combined deposit amount, in sats, for any bag.
+    ///
+    /// This caps the total value that a single transaction sweeps in.
+    max_deposit_amount: u64,
 }
 
 impl PackagerConfig {
@@ -182,8 +204,15 @@ impl PackagerConfig {
             max_signatures,
             max_total_vsize: PACKAGE_MAX_VSIZE,
             max_op_return_size: OP_RETURN_AVAILABLE_SIZE,
+            max_deposit_amount: u64::MAX,
         }
     }
+
+    /// Set the maximum combined deposit amount, in sats, for any bag.
+    fn with_max_deposit_amount(mut self, max_deposit_amount: u64) -> Self {
+        self.max_deposit_amount = max_deposit_amount;
+        self
+    }
 }
 
 /// A container for compatible items that can be packaged together in a Bitcoin
@@ -208,6 +237,8 @@ struct Bag<T> {
     vsize: u64,
     /// Sorted list of withdrawal IDs in this bag
     withdrawal_ids: Vec<u64>,
+    /// Total deposit amount of items in this bag
+    deposit_amount: u64,
 }
 
 impl<T> Bag<T>
@@ -229,6 +260,7 @@ where
             vsize: 0,
             items: Vec::new(),
             withdrawal_ids: Vec::new(),
+            deposit_amount: 0,
         }
     }
 
@@ -257,6 +289,7 @@ where
         self.votes_bitmap |= item.votes();
         self.items_needing_signatures += item.needs_signature() as u16;
         self.vsize += item.vsize();
+        self.deposit_amount += item.deposit_amount();
 
         if let Some(id) = item.withdrawal_id() {
             match self.withdrawal_ids.binary_search(&id) {
@@ -275,6 +308,7 @@ where
     /// 1. Combined votes against ≤ max_votes_against
     /// 2. Combined signature requirements ≤ max_signatures
     /// 3. Withdrawal ID (if any) fits within remaining OP_RETURN space
+    /// 4. Combined deposit amount ≤ max_deposit_amount
     ///
     /// ## Parameters
     /// - `item`: Item to check for compatibility
@@ -285,6 +319,7 @@ where
         self.votes_compatible(item)
             && self.signatures_compatible(item)
             && self.withdrawal_id_compatible(item)
+            && self.deposit_amount_compatible(item)
     }
 
     /// Check if an item's votes are compatible with this bag.
@@ -326,6 +361,19 @@ where
         self.can_add_withdrawal_id(id)
     }
 
+    /// Check if an item's deposit amount is compatible with this bag.
+    ///
+    /// ## Parameters
+    /// - `item`: Item to check for deposit amount compatibility
+    ///
+    /// ## Returns
+    /// `true` if adding the item wouldn't exceed the deposit amount limit.
+    fn deposit_amount_compatible(&self, item: &T) -> bool {
+        self.deposit_amount
+            .checked_add(item.deposit_amount())
+            .is_some_and(|amount| amount <= self.config.max_deposit_amount)
+    }
+
     /// Calculate compatibility score between item and bag (smaller is better).
     ///
     /// The score is based on how different the vote patterns are (using XOR).
@@ -413,6 +461,7 @@ where
 /// 2. Respect signature limits for each bag
 /// 3. Ensure withdrawal IDs fit within OP_RETURN size limits
 /// 4. Keep total virtual size within Bitcoin network limits
+/// 5. Keep the combined deposit amount of each bag under a cap
 ///
 /// ## Implementation Notes
 /// - Items that exceed individual limits are silently ignored
@@ -471,6 +520,7 @@ impl<T: Weighted> BestFitPackager<T> {
         // Early exits for items exceeding our bag-independent limits.
         if votes_against > self.config.max_votes_against
             || total_package_vsize > self.config.max_total_vsize
+            || item.deposit_amount() > self.config.max_deposit_amount
         {
             return;
         }
@@ -539,6 +589,7 @@ mod tests {
                 items_needing_signatures: 0,
                 vsize: 0,
                 withdrawal_ids: Vec::new(),
+                deposit_amount: 0,
             };
             bag.add_items(items);
             bag
@@ -555,6 +606,8 @@ mod tests {
         vsize: u64,
         /// The withdrawal request ID for this item, if it's a withdrawal.
         withdrawal_id: Option<u64>,
+        /// The deposit amount of this item, if it's a deposit.
+        deposit_amount: u64,
     }
 
     static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(0);
@@ -618,6 +671,7 @@ mod tests {
                 needs_signature,
                 vsize,
                 withdrawal_id,
+                deposit_amount: 0,
             }
         }
 
@@ -639,6 +693,13 @@ mod tests {
             self.vsize = vsize;
             self
         }
+
+        /// Makes this item a deposit of the given amount.
+        fn deposit(mut self, amount: u64) -> Self {
+            self.needs_signature = true;
+            self.deposit_amount = amount;
+            self
+        }
     }
 
     impl Weighted for RequestItem {
@@ -661,6 +722,10 @@ mod tests {
         fn withdrawal_id(&self) -> Option<u64> {
             self.withdrawal_id
         }
+
+        fn deposit_amount(&self) -> u64 {
+            self.deposit_amount
+        }
     }
 
     struct VotesTestCase<const N: usize> {
@@ -742,8 +807,12 @@ mod tests {
         expected_bag_vsizes: [0, 0],
     } ; "votes-against-placement")]
     fn returns_optimal_placements<const N: usize>(case: VotesTestCase<N>) {
-        let ans =
-            compute_optimal_packages(case.items, case.max_votes_against, case.max_needs_signature);
+        let ans = compute_optimal_packages(
+            case.items,
+            case.max_votes_against,
+            case.max_needs_signature,
+            u64::MAX,
+        );
         let collection = ans.collect::<Vec<_>>();
         let iter = collection
             .iter()
@@ -788,14 +857,19 @@ mod tests {
 
         let max_needs_signature = 100;
         let max_votes_against = 3;
-        let packages1 =
-            compute_optimal_packages(items.clone(), max_votes_against, max_needs_signature)
-                .collect::<Vec<_>>();
+        let packages1 = compute_optimal_packages(
+            items.clone(),
+            max_votes_against,
+            max_needs_signature,
+            u64::MAX,
+        )
+        .collect::<Vec<_>>();
 
         items.shuffle(&mut rng);
 
-        let packages2 = compute_optimal_packages(items, max_votes_against, max_needs_signature)
-            .collect::<Vec<_>>();
+        let packages2 =
+            compute_optimal_packages(items, max_votes_against, max_needs_signature, u64::MAX)
+                .collect::<Vec<_>>();
 
         assert_ne!(packages1, packages2);
     }
@@ -1076,6 +1150,35 @@ mod tests {
         assert_eq!(best_bag.is_some(), best_bag_index.is_some());
     }
 
+    /// Tests that bags respect the maximum combined deposit amount, and
+    /// that deposits above the limit on their own are ignored.
+    #[test]
+    fn test_insert_item_respects_max_deposit_amount() {
+        let config = PackagerConfig::new(2, 5).with_max_deposit_amount(1000);
+        let mut packager = BestFitPackager::<RequestItem>::new(config);
+
+        // The first two deposits fit in one bag.
+        packager.insert_item(RequestItem::no_votes().deposit(600).vsize(10));
+        packager.insert_item(RequestItem::no_votes().deposit(400).vsize(10));
+        assert_eq!(packager.bags.len(), 1);
+        assert_eq!(packager.bags[0].deposit_amount, 1000);
+
+        // The bag is full, so the next deposit goes in a new bag.
+        packager.insert_item(RequestItem::no_votes().deposit(1).vsize(10));
+        assert_eq!(packager.bags.len(), 2);
+        assert_eq!(packager.bags[1].deposit_amount, 1);
+
+        // Withdrawals do not count against the deposit amount.
+        packager.insert_item(RequestItem::no_votes().wid(1).vsize(10));
+        assert_eq!(packager.bags.len(), 2);
+        assert_eq!(packager.bags[0].items.len(), 3);
+
+        // A deposit above the limit cannot go in any bag, so it is ignored.
+        packager.insert_item(RequestItem::no_votes().deposit(1001).vsize(10));
+        assert_eq!(packager.bags.len(), 2);
+        assert_eq!(packager.total_vsize, 40);
+    }
+
     /// Tests item insertion logic including:
     /// - Creating new bags
     /// - Adding to existing compatible bags
@@ -1150,7 +1253,7 @@ mod tests {
         items.push(RequestItem::with_vote(1).wid(3000)); // Different vote pattern
         items.push(RequestItem::no_votes().wid(10000)); // Large ID
 
-        let bags = compute_optimal_packages(items, 1, 5).collect::<Vec<_>>();
+        let bags = compute_optimal_packages(items, 1, 5, u64::MAX).collect::<Vec<_>>();
 
         // Verify multiple bags were created due to both vote and withdrawal ID constraints
         assert!(bags.len() > 1);
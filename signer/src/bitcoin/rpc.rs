@@ -1,6 +1,7 @@
 //! Contains client wrappers for bitcoin core and electrum.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use bitcoin::Amount;
 use bitcoin::BlockHash;
@@ -324,6 +325,18 @@ pub struct FeeEstimate {
     pub sats_per_vbyte: f64,
 }
 
+/// How often [`BitcoinCoreClient::wait_for_block`] asks bitcoin-core
+/// whether the target block is part of its best chain.
+const WAIT_FOR_BLOCK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often [`BitcoinCoreClient::wait_for_tx_confirmation`] asks
+/// bitcoin-core for the number of confirmations of the transaction.
+pub const WAIT_FOR_TX_CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The maximum number of concurrent RPC calls that
+/// [`BitcoinCoreClient::get_tx_infos`] makes to bitcoin-core.
+const MAX_CONCURRENT_TX_INFO_REQUESTS: usize = 8;
+
 /// A client for interacting with bitcoin-core
 #[derive(Debug, Clone)]
 pub struct BitcoinCoreClient {
@@ -407,6 +420,99 @@ impl BitcoinCoreClient {
         }
     }
 
+    /// Wait until bitcoin-core reports that the block identified by the
+    /// given block hash is part of its best chain.
+    ///
+    /// This polls bitcoin-core using the getblockheader RPC, and returns
+    /// an error if the block is not in the best chain before the timeout
+    /// elapses.
+    pub async fn wait_for_block(&self, target: &BlockHash, timeout: Duration) -> Result<(), Error> {
+        let poll = async {
+            loop {
+                match self.inner.get_block_header_info(target) {
+                    // Blocks that are not in the best chain have -1
+                    // confirmations.
+                    Ok(header) if header.confirmations > 0 => return Ok(()),
+                    Ok(_) => {}
+                    Err(BtcRpcError::JsonRpc(JsonRpcError::Rpc(RpcError { code: -5, .. }))) => {}
+                    Err(err) => return Err(Error::BitcoinCoreGetBlockHeader(err, *target)),
+                }
+                tokio::time::sleep(WAIT_FOR_BLOCK_POLL_INTERVAL).await;
+            }
+        };
+
+        tokio::time::timeout(timeout, poll)
+            .await
+            .map_err(|_| Error::BitcoinCoreWaitForBlockTimeout(*target))?
+    }
+
+    /// Wait until the transaction with the given txid is included in a
+    /// block on the best chain that is at least `min_confirmations` deep,
+    /// returning the transaction info from that block.
+    ///
+    /// This polls bitcoin-core every
+    /// [`WAIT_FOR_TX_CONFIRMATION_POLL_INTERVAL`], see
+    /// [`BitcoinCoreClient::wait_for_tx_confirmation_with_interval`] for
+    /// details.
+    pub async fn wait_for_tx_confirmation(
+        &self,
+        txid: &Txid,
+        min_confirmations: u32,
+        timeout: Duration,
+    ) -> Result<BitcoinTxInfo, Error> {
+        self.wait_for_tx_confirmation_with_interval(
+            txid,
+            min_confirmations,
+            timeout,
+            WAIT_FOR_TX_CONFIRMATION_POLL_INTERVAL,
+        )
+        .await
+    }
+
+    /// Wait until the transaction with the given txid is included in a
+    /// block on the best chain that is at least `min_confirmations` deep,
+    /// returning the transaction info from that block.
+    ///
+    /// This polls bitcoin-core using the getrawtransaction RPC every
+    /// `poll_interval`, and returns an error if the transaction does not
+    /// reach the requested depth before the timeout elapses. Since the
+    /// returned info comes from the confirming block, a
+    /// `min_confirmations` of zero is treated as one. The returned future
+    /// holds no state between polls, so it is safe to drop it at any
+    /// time.
+    pub async fn wait_for_tx_confirmation_with_interval(
+        &self,
+        txid: &Txid,
+        min_confirmations: u32,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> Result<BitcoinTxInfo, Error> {
+        let min_confirmations = min_confirmations.max(1);
+        let poll = async {
+            loop {
+                let confirming_block = self
+                    .get_tx(txid)?
+                    .filter(|response| {
+                        response
+                            .confirmations
+                            .is_some_and(|confirmations| confirmations >= min_confirmations)
+                    })
+                    .and_then(|response| response.block_hash);
+
+                if let Some(block_hash) = confirming_block {
+                    if let Some(tx_info) = self.get_tx_info(txid, &block_hash)? {
+                        return Ok(tx_info);
+                    }
+                }
+                tokio::time::sleep(poll_interval).await;
+            }
+        };
+
+        tokio::time::timeout(timeout, poll)
+            .await
+            .map_err(|_| Error::BitcoinCoreWaitForTxConfirmationTimeout(*txid, min_confirmations))?
+    }
+
     /// Fetch and decode raw transaction from bitcoin-core using the
     /// getrawtransaction RPC with a verbosity of 1. None is returned if
     /// the node cannot find the transaction in a bitcoin block or the
@@ -459,6 +565,65 @@ impl BitcoinCoreClient {
         &self,
         txid: &Txid,
         block_hash: &BlockHash,
+    ) -> Result<Option<BitcoinTxInfo>, Error> {
+        self.get_tx_infos(&[(*txid, *block_hash)])
+            .map(|mut tx_infos| tx_infos.pop().flatten())
+    }
+
+    /// Fetch and decode the raw transactions of many transactions from
+    /// bitcoin-core, returning them in the same order as the given
+    /// requests.
+    ///
+    /// This works like [`BitcoinCoreClient::get_tx_info`] for each
+    /// `(txid, block_hash)` pair, except that the RPC calls are spread
+    /// over at most [`MAX_CONCURRENT_TX_INFO_REQUESTS`] threads that run
+    /// concurrently.
+    pub fn get_tx_infos(
+        &self,
+        requests: &[(Txid, BlockHash)],
+    ) -> Result<Vec<Option<BitcoinTxInfo>>, Error> {
+        let chunk_size = requests
+            .len()
+            .div_ceil(MAX_CONCURRENT_TX_INFO_REQUESTS)
+            .max(1);
+        // There is no point in spawning a thread for a single chunk.
+        if requests.len() <= chunk_size {
+            return requests
+                .iter()
+                .map(|(txid, block_hash)| self.fetch_tx_info(txid, block_hash))
+                .collect();
+        }
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = requests
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|(txid, block_hash)| self.fetch_tx_info(txid, block_hash))
+                            .collect::<Result<Vec<_>, Error>>()
+                    })
+                })
+                .collect();
+
+            let mut tx_infos = Vec::with_capacity(requests.len());
+            for handle in handles {
+                let chunk_infos = handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))?;
+                tx_infos.extend(chunk_infos);
+            }
+            Ok(tx_infos)
+        })
+    }
+
+    /// Make the `getrawtransaction` RPC call for a transaction in the
+    /// given block.
+    fn fetch_tx_info(
+        &self,
+        txid: &Txid,
+        block_hash: &BlockHash,
     ) -> Result<Option<BitcoinTxInfo>, Error> {
         let args = [
             serde_json::to_value(txid).map_err(Error::JsonSerialize)?,
@@ -481,6 +646,62 @@ impl BitcoinCoreClient {
         }
     }
 
+    /// Fetch the detailed information of a transaction that is in the
+    /// mempool. None is returned if the transaction is not in the mempool.
+    ///
+    /// # Notes
+    ///
+    /// Bitcoin-core only includes the `fee` and `vin[*].prevout` fields in
+    /// the `getrawtransaction` response for transactions in a block with
+    /// undo data, so we resolve the previous outputs by fetching the
+    /// transactions that created them, and take the fee from the
+    /// `getmempoolentry` RPC. This requires -txindex to be enabled on
+    /// bitcoin-core if any previous output is confirmed.
+    pub fn get_mempool_tx_info(&self, txid: &Txid) -> Result<Option<BitcoinTxInfo>, Error> {
+        let Some(mempool_entry) = self.get_mempool_entry(txid)? else {
+            return Ok(None);
+        };
+        // The transaction could have been confirmed or evicted in between
+        // the two RPC calls.
+        let Some(response) = self.get_tx(txid)? else {
+            return Ok(None);
+        };
+
+        let vin = response
+            .tx
+            .input
+            .iter()
+            .map(|tx_in| {
+                let outpoint = tx_in.previous_output;
+                let prev_tx = self
+                    .get_tx(&outpoint.txid)?
+                    .ok_or(Error::BitcoinTxMissing(outpoint.txid, None))?;
+                let prev_out = prev_tx
+                    .tx
+                    .output
+                    .get(outpoint.vout as usize)
+                    .ok_or(Error::OutPointMissing(outpoint))?;
+
+                Ok(BitcoinTxVin {
+                    txid: Some(outpoint.txid),
+                    vout: Some(outpoint.vout),
+                    prevout: Some(BitcoinTxVinPrevout {
+                        value: prev_out.value,
+                        script_pubkey: OutputScriptPubKey {
+                            script: prev_out.script_pubkey.clone(),
+                        },
+                    }),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(Some(BitcoinTxInfo {
+            fee: Some(mempool_entry.fees.base),
+            tx: response.tx,
+            vin,
+        }))
+    }
+
     /// Fetch and decode raw transaction from bitcoin-core using the
     /// `getrawtransaction` RPC with a verbosity of 2. This method returns a
     /// highly slimmed-down version of the response, containing only the
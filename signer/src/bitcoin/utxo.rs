@@ -18,6 +18,7 @@ use bitcoin::Weight;
 use bitcoin::Witness;
 use bitcoin::absolute::LockTime;
 use bitcoin::consensus::Encodable as _;
+use bitcoin::hashes::Hash as _;
 use bitcoin::opcodes::all::OP_RETURN;
 use bitcoin::script::Instruction;
 use bitcoin::script::PushBytesBuf;
@@ -39,6 +40,7 @@ use secp256k1::SECP256K1;
 use secp256k1::XOnlyPublicKey;
 use serde::Deserialize;
 use serde::Serialize;
+use sha2::Digest as _;
 
 use crate::DEPOSIT_DUST_LIMIT;
 use crate::MAX_MEMPOOL_PACKAGE_TX_COUNT;
@@ -93,7 +95,7 @@ const SATS_PER_VBYTE_INCREMENT: f64 = 0.001;
 
 /// The OP_RETURN version byte for deposit or withdrawal sweep
 /// transactions.
-const OP_RETURN_VERSION: u8 = 1;
+pub(crate) const OP_RETURN_VERSION: u8 = 1;
 
 /// The OP_RETURN header size (magic bytes + version)
 const OP_RETURN_HEADER_SIZE: usize = 3;
@@ -104,12 +106,42 @@ const OP_RETURN_MAX_SIZE: usize = 80;
 /// The available size for encoded withdrawal IDs in OP_RETURN
 pub(super) const OP_RETURN_AVAILABLE_SIZE: usize = OP_RETURN_MAX_SIZE - OP_RETURN_HEADER_SIZE;
 
+/// The amount, in sats, locked in the anchor output of a sweep
+/// transaction. This is the dust limit for pay-to-anchor outputs, so the
+/// output is relayed even though the sweep transaction pays a fee.
+pub const ANCHOR_OUTPUT_AMOUNT: u64 = 240;
+
+/// The scriptPubKey of a keyless pay-to-anchor (P2A) output, which is
+/// `OP_1 <0x4e73>`. Anyone can spend it with an empty witness.
+const PAY_TO_ANCHOR_SCRIPT: [u8; 4] = [0x51, 0x02, 0x4e, 0x73];
+
 /// A dummy Schnorr signature.
 static DUMMY_SIGNATURE: LazyLock<Signature> = LazyLock::new(|| Signature {
     signature: secp256k1::schnorr::Signature::from_slice(&[0; 64]).unwrap(),
     sighash_type: TapSighashType::All,
 });
 
+/// Parse the magic bytes and the version byte from the data of an sBTC
+/// `OP_RETURN` output.
+///
+/// `None` is returned if the script is not an `OP_RETURN` followed by a
+/// single data push that is at least as long as the header.
+pub fn parse_op_return_header(script_pubkey: &bitcoin::Script) -> Option<([u8; 2], u8)> {
+    let instructions: Vec<_> = script_pubkey.instructions().collect();
+    let [
+        Ok(Instruction::Op(OP_RETURN)),
+        Ok(Instruction::PushBytes(push_bytes)),
+    ] = instructions[..]
+    else {
+        return None;
+    };
+
+    match push_bytes.as_bytes() {
+        [magic0, magic1, version, ..] => Some(([*magic0, *magic1], *version)),
+        _ => None,
+    }
+}
+
 /// Describes the fees for a transaction.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Fees {
@@ -231,9 +263,12 @@ impl<'a> RequestPreprocessor<'a> {
     }
 
     /// Filter sbtc deposits that don't meet the validation criteria.
-    pub fn filter_deposits(&self, deposits: &'a [DepositRequest]) -> Vec<RequestRef<'a>> {
+    pub fn filter_deposits<I>(&self, deposits: I) -> Vec<RequestRef<'a>>
+    where
+        I: IntoIterator<Item = &'a DepositRequest>,
+    {
         deposits
-            .iter()
+            .into_iter()
             .scan(Amount::from_sat(0), |amount_to_mint, deposit| {
                 Some(self.validate_deposit_amount(amount_to_mint, deposit))
             })
@@ -246,12 +281,15 @@ impl<'a> RequestPreprocessor<'a> {
     ///
     /// The returns vector of withdrawal requests that is sorted by request
     /// ID.
-    pub fn preprocess_withdrawals(&self, requests: &'a [WithdrawalRequest]) -> Vec<RequestRef<'a>> {
+    pub fn preprocess_withdrawals<I>(&self, requests: I) -> Vec<RequestRef<'a>>
+    where
+        I: IntoIterator<Item = &'a WithdrawalRequest>,
+    {
         let withdrawn_total = self.sbtc_limits.rolling_withdrawal_limits().withdrawn_total;
 
         // Let's ensure that the withdrawal requests are sorted by their
         // request ID.
-        let mut reqs: Vec<_> = requests.iter().map(RequestRef::Withdrawal).collect();
+        let mut reqs: Vec<_> = requests.into_iter().map(RequestRef::Withdrawal).collect();
         reqs.sort();
 
         reqs.iter()
@@ -280,6 +318,59 @@ pub struct SignerBtcState {
     /// Two byte prefix for BTC transactions that are related to the Stacks
     /// blockchain.
     pub magic_bytes: [u8; 2],
+    /// The fee rate, in sats per vbyte, that a replace-by-fee transaction
+    /// should target. This is only taken into account when `last_fees` is
+    /// set, and the replacement will still pay at least the absolute fee
+    /// required by BIP-125.
+    pub rbf_target_fee_rate: Option<f64>,
+    /// Whether sweep transactions should include a keyless anchor output
+    /// that a child transaction can spend to bump the fee with CPFP. This
+    /// is useful when the sweep cannot be replaced by fee.
+    pub anchor_output: bool,
+}
+
+impl SignerBtcState {
+    /// The fee rate to use when constructing a transaction that spends the
+    /// signers' UTXO.
+    ///
+    /// If this is a replace-by-fee transaction and an RBF target fee rate
+    /// has been set, then this is the larger of the target and the current
+    /// market fee rate. Otherwise it is the current market fee rate.
+    pub fn effective_fee_rate(&self) -> f64 {
+        match (self.last_fees, self.rbf_target_fee_rate) {
+            (Some(_), Some(target)) => self.fee_rate.max(target),
+            _ => self.fee_rate,
+        }
+    }
+
+    /// The anchor output that sweep transactions include when
+    /// `anchor_output` is set.
+    pub fn new_anchor_output() -> TxOut {
+        TxOut {
+            value: Amount::from_sat(ANCHOR_OUTPUT_AMOUNT),
+            script_pubkey: ScriptBuf::from_bytes(PAY_TO_ANCHOR_SCRIPT.to_vec()),
+        }
+    }
+
+    /// Whether the given output is a pay-to-anchor output.
+    pub fn is_anchor_output(tx_out: &TxOut) -> bool {
+        tx_out.script_pubkey.as_bytes() == PAY_TO_ANCHOR_SCRIPT
+    }
+
+    /// Validate the fee rates in this state.
+    ///
+    /// The market fee rate, and the RBF target fee rate if set, must be
+    /// finite and positive, otherwise the fees that we compute for the
+    /// transaction would be nonsensical, or would never get it mined.
+    pub fn validate(&self) -> Result<(), Error> {
+        let fee_rates = std::iter::once(self.fee_rate).chain(self.rbf_target_fee_rate);
+        for fee_rate in fee_rates {
+            if !fee_rate.is_finite() || fee_rate <= 0.0 {
+                return Err(Error::InvalidFeeRate(fee_rate));
+            }
+        }
+        Ok(())
+    }
 }
 
 /// The set of sBTC requests with additional relevant
@@ -305,37 +396,373 @@ pub struct SbtcRequests {
     /// that there is enough time for the signers to sign all the inputs
     /// during the tenure of a single bitcoin block.
     pub max_deposits_per_bitcoin_tx: u16,
+    /// The minimum batch of deposits that must be pending before the
+    /// signers sweep them in. If `None`, deposits are swept as soon as
+    /// they are pending.
+    ///
+    /// This policy is for library users that construct their own
+    /// [`SbtcRequests`]. The transaction coordinator always leaves it
+    /// unset, there is no setting for it, and it is not part of the
+    /// [`BitcoinPreSignRequest`](crate::message::BitcoinPreSignRequest).
+    pub min_batch: Option<MinBatch>,
+    /// What to do with withdrawal requests that pay to the signers' own
+    /// scriptPubKey.
+    pub withdrawals_to_signers: WithdrawalsToSignersPolicy,
+    /// The minimum amount, in sats, that the signers' output of each
+    /// transaction must keep as a reserve. Withdrawals that would take
+    /// the signers' output below this amount are left out. If `None`,
+    /// the signers' output can be drawn down to any amount.
+    pub min_signer_output: Option<u64>,
+    /// When set, the deposit inputs of each transaction are shuffled
+    /// deterministically using this seed and the signers' input of the
+    /// transaction, so that sweeps do not share a recognizable ordering.
+    /// The signers' input, all outputs and the order of the withdrawal
+    /// outputs are unchanged. If `None`, requests are kept in their
+    /// sorted order. The seed is sent to the other signers in the
+    /// [`BitcoinPreSignRequest`](crate::message::BitcoinPreSignRequest)
+    /// so that they can reconstruct the same transactions.
+    pub shuffle_seed: Option<[u8; 32]>,
+}
+
+/// A policy for withdrawal requests whose recipient is the signers' own
+/// scriptPubKey.
+///
+/// Such a withdrawal is effectively a send from the signers to themselves,
+/// which complicates accounting of the signers' funds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WithdrawalsToSignersPolicy {
+    /// Leave the withdrawal request out of the transactions, while the
+    /// other requests are serviced as usual.
+    #[default]
+    Reject,
+    /// Fulfill the withdrawal like any other, so that its output stays
+    /// under the control of the signers as change.
+    TreatAsChange,
+}
+
+/// A policy for holding back deposit sweeps until enough deposits are
+/// pending, so that the fees of a sweep are spread over more requests.
+///
+/// The policy is met if any of its thresholds is met. Withdrawal requests
+/// are never held back by this policy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MinBatch {
+    /// The minimum number of pending deposit requests.
+    pub num_deposits: Option<usize>,
+    /// The minimum total amount, in sats, of pending deposit requests.
+    pub amount: Option<u64>,
+}
+
+impl MinBatch {
+    /// Whether the given deposits meet this policy.
+    pub fn is_met_by(&self, deposits: &[RequestRef]) -> bool {
+        let num_deposits = deposits.len();
+        let amount: u64 = deposits
+            .iter()
+            .filter_map(RequestRef::as_deposit)
+            .map(|req| req.amount)
+            .sum();
+
+        match (self.num_deposits, self.amount) {
+            (None, None) => true,
+            (min_deposits, min_amount) => {
+                min_deposits.is_some_and(|min| num_deposits >= min)
+                    || min_amount.is_some_and(|min| amount >= min)
+            }
+        }
+    }
+}
+
+/// The reason that a request was left out of a transaction package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+pub enum SkipReason {
+    /// The fee that the request would be assessed in the transaction
+    /// exceeds the max fee that the user set for it.
+    FeeExceedsMaxFee,
+    /// Servicing the request would take the signers' output below the
+    /// `min_signer_output` reserve.
+    BelowMinSignerOutput,
+    /// The pending deposit requests do not meet the [`MinBatch`] policy,
+    /// so they are held back until more deposits are pending.
+    BelowMinimumBatch,
+}
+
+/// A deposit request that was left out of a transaction package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkippedDeposit {
+    /// The outpoint of the deposit request.
+    pub outpoint: OutPoint,
+    /// Why the deposit request was left out.
+    pub reason: SkipReason,
+}
+
+/// A withdrawal request that was left out of a transaction package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkippedWithdrawal {
+    /// The qualified ID of the withdrawal request.
+    pub id: QualifiedRequestId,
+    /// Why the withdrawal request was left out.
+    pub reason: SkipReason,
+}
+
+/// The requests that were left out of a transaction package.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SkippedRequests {
+    /// The deposit requests that were left out.
+    pub deposits: Vec<SkippedDeposit>,
+    /// The withdrawal requests that were left out.
+    pub withdrawals: Vec<SkippedWithdrawal>,
 }
 
 impl SbtcRequests {
     /// Construct the next transaction package given requests and the
     /// signers' UTXO.
     ///
+    /// Each transaction in the package is individually balanced, meaning
+    /// that its inputs cover its outputs and its fee. If the requests
+    /// cannot be chained in the order that they were packaged, packages
+    /// that add funds to the signers' UTXO are moved ahead of packages
+    /// that remove funds.
+    ///
     /// This function can fail if the output amounts are greater than the
     /// input amounts.
     pub fn construct_transactions(&self) -> Result<Vec<UnsignedTransaction>, Error> {
-        if self.deposits.is_empty() && self.withdrawals.is_empty() {
+        self.construct_transactions_excluding(&[], &[])
+    }
+
+    /// Construct the next transaction package given requests and the
+    /// signers' UTXO, leaving out the deposits and withdrawals with the
+    /// given identifiers.
+    ///
+    /// The excluded requests are treated as if they were not part of
+    /// these requests at all, so they do not count against any limits.
+    /// Otherwise this function behaves like
+    /// [`SbtcRequests::construct_transactions`].
+    pub fn construct_transactions_excluding(
+        &self,
+        exclude_deposits: &[OutPoint],
+        exclude_withdrawals: &[QualifiedRequestId],
+    ) -> Result<Vec<UnsignedTransaction>, Error> {
+        self.construct_transactions_and_skipped(exclude_deposits, exclude_withdrawals)
+            .map(|(transactions, _)| transactions)
+    }
+
+    /// Construct the next transaction package given requests and the
+    /// signers' UTXO, also returning the requests that were skipped.
+    ///
+    /// A deposit request is skipped if the fee that it would be assessed
+    /// in its sweep transaction exceeds its `max_fee`, since the user
+    /// capped the fee that they are willing to pay. Deposits that cannot
+    /// even pay for a transaction of their own are filtered out by the
+    /// usual request validation, while the package is rebuilt without any
+    /// deposit that is assessed too much once the transactions are
+    /// constructed, and those are the ones returned. Similarly, if
+    /// `min_signer_output` is set, withdrawals are left out, largest
+    /// first, until no transaction takes the signers' output below it,
+    /// and those are returned too. Deposits that are held back by the
+    /// `min_batch` policy are returned with
+    /// [`SkipReason::BelowMinimumBatch`]. Otherwise this function behaves like
+    /// [`SbtcRequests::construct_transactions_excluding`].
+    pub fn construct_transactions_and_skipped(
+        &self,
+        exclude_deposits: &[OutPoint],
+        exclude_withdrawals: &[QualifiedRequestId],
+    ) -> Result<(Vec<UnsignedTransaction>, SkippedRequests), Error> {
+        let mut exclude_deposits = exclude_deposits.to_vec();
+        let mut skipped = SkippedRequests::default();
+        let mut exclude_withdrawals = exclude_withdrawals.to_vec();
+
+        // Each pass excludes at least one more request, so this loop ends.
+        let transactions = loop {
+            let transactions =
+                self.build_transactions(&exclude_deposits, &exclude_withdrawals, &mut skipped)?;
+            let over_max_fee: Vec<OutPoint> = transactions
+                .iter()
+                .flat_map(UnsignedTransaction::deposits_over_max_fee)
+                .collect();
+            if !over_max_fee.is_empty() {
+                skipped
+                    .deposits
+                    .extend(over_max_fee.iter().map(|outpoint| SkippedDeposit {
+                        outpoint: *outpoint,
+                        reason: SkipReason::FeeExceedsMaxFee,
+                    }));
+                exclude_deposits.extend(over_max_fee);
+                continue;
+            }
+
+            let below_reserve = transactions
+                .iter()
+                .find_map(|tx| self.withdrawal_below_min_signer_output(tx));
+            match below_reserve {
+                Some(id) => {
+                    skipped.withdrawals.push(SkippedWithdrawal {
+                        id,
+                        reason: SkipReason::BelowMinSignerOutput,
+                    });
+                    exclude_withdrawals.push(id);
+                }
+                None => break transactions,
+            }
+        };
+
+        Ok((transactions, skipped))
+    }
+
+    /// Construct the transaction package, leaving out the given requests.
+    ///
+    /// Deposits that are held back by the `min_batch` policy are added to
+    /// the given skipped requests.
+    fn build_transactions(
+        &self,
+        exclude_deposits: &[OutPoint],
+        exclude_withdrawals: &[QualifiedRequestId],
+        skipped: &mut SkippedRequests,
+    ) -> Result<Vec<UnsignedTransaction>, Error> {
+        self.signer_state.validate()?;
+
+        let deposits: Vec<&DepositRequest> = self
+            .deposits
+            .iter()
+            .filter(|req| !exclude_deposits.contains(&req.outpoint))
+            .collect();
+        let withdrawals: Vec<&WithdrawalRequest> = self
+            .withdrawals
+            .iter()
+            .filter(|req| !exclude_withdrawals.contains(&req.qualified_id()))
+            .filter(|req| self.allows_withdrawal_to_signers(req))
+            .collect();
+
+        if deposits.is_empty() && withdrawals.is_empty() {
             tracing::info!("No deposits or withdrawals so no BTC transaction");
             return Ok(Vec::new());
         }
 
         let request_preprocessor = RequestPreprocessor {
             sbtc_limits: &self.sbtc_limits,
-            fee_rate: self.signer_state.fee_rate,
+            fee_rate: self.signer_state.effective_fee_rate(),
             last_fees: self.signer_state.last_fees,
         };
-        let deposits = request_preprocessor.filter_deposits(&self.deposits);
-        let withdrawals = request_preprocessor.preprocess_withdrawals(&self.withdrawals);
+        let deposits = request_preprocessor.filter_deposits(deposits);
+        let withdrawals = request_preprocessor.preprocess_withdrawals(withdrawals);
+
+        let below_min_batch = self
+            .min_batch
+            .is_some_and(|min_batch| !min_batch.is_met_by(&deposits));
+        if below_min_batch && withdrawals.is_empty() {
+            tracing::info!(
+                num_deposits = deposits.len(),
+                outcome = "below-minimum-batch",
+                "not enough pending deposits to sweep, so no BTC transaction"
+            );
+            let held_back = deposits
+                .iter()
+                .filter_map(RequestRef::as_deposit)
+                .map(|req| SkippedDeposit {
+                    outpoint: req.outpoint,
+                    reason: SkipReason::BelowMinimumBatch,
+                });
+            skipped.deposits.extend(held_back);
+            return Ok(Vec::new());
+        }
 
-        // Create a list of requests where each request can be approved on its own.
-        let items = deposits.into_iter().chain(withdrawals);
+        // Create a list of requests where each request can be approved on
+        // its own. The packager places items in the order that it gets
+        // them, so we sort them to make sure that the packages do not
+        // depend on the order of the requests that we were given.
+        let mut items: Vec<RequestRef> = deposits.into_iter().chain(withdrawals).collect();
+        items.sort_by(RequestRef::packaging_cmp);
 
         let max_votes_against = self.reject_capacity();
         let max_needs_signature = self.max_deposits_per_bitcoin_tx;
-        compute_optimal_packages(items, max_votes_against, max_needs_signature)
+        let max_deposit_amount = self.sbtc_limits.per_tx_cap().to_sat();
+        let packages: Vec<Vec<RequestRef>> = compute_optimal_packages(
+            items,
+            max_votes_against,
+            max_needs_signature,
+            max_deposit_amount,
+        )
+        .collect();
+
+        match self.chain_transactions(packages.clone()) {
+            Ok(transactions) => Ok(transactions),
+            Err(error) => {
+                // A package may withdraw more than the signers' UTXO holds
+                // at its point in the chain even though the requests as a
+                // whole can be serviced. Putting the packages with the
+                // largest net inflow first maximizes the signers' balance
+                // at every point in the chain.
+                tracing::debug!(%error, "reordering packages to balance the transactions");
+                let mut packages = packages;
+                packages.sort_by_key(|package| std::cmp::Reverse(net_amount(package)));
+                self.chain_transactions(packages)
+            }
+        }
+    }
+
+    /// Check whether the given withdrawal request may be included in the
+    /// transactions under the [`WithdrawalsToSignersPolicy`].
+    ///
+    /// Only withdrawal requests that pay to the signers' current or next
+    /// scriptPubKey are affected by the policy.
+    fn allows_withdrawal_to_signers(&self, req: &WithdrawalRequest) -> bool {
+        let signers_script_pubkeys: [ScriptPubKey; 2] = [
+            self.signer_state.public_key.signers_script_pubkey().into(),
+            self.signer_state
+                .utxo
+                .public_key
+                .signers_script_pubkey()
+                .into(),
+        ];
+
+        if !signers_script_pubkeys.contains(&req.script_pubkey) {
+            return true;
+        }
+
+        match self.withdrawals_to_signers {
+            WithdrawalsToSignersPolicy::Reject => {
+                tracing::warn!(
+                    request_id = %req.qualified_id(),
+                    "withdrawal request pays to the signers, leaving it out"
+                );
+                false
+            }
+            WithdrawalsToSignersPolicy::TreatAsChange => {
+                tracing::info!(
+                    request_id = %req.qualified_id(),
+                    "withdrawal request pays to the signers, treating it as change"
+                );
+                true
+            }
+        }
+    }
+
+    /// Construct a chain of transactions, one for each package, where each
+    /// transaction spends the signers' UTXO created by the one before it.
+    ///
+    /// This function fails if any of the transactions are not balanced.
+    fn chain_transactions<'a>(
+        &'a self,
+        packages: Vec<Vec<RequestRef<'a>>>,
+    ) -> Result<Vec<UnsignedTransaction<'a>>, Error> {
+        packages
+            .into_iter()
             .scan(self.signer_state, |state, request_refs| {
-                let requests = Requests::new(request_refs);
-                let tx = UnsignedTransaction::new(requests, state);
+                let mut requests = Requests::new(request_refs);
+                if let Some(seed) = self.shuffle_seed.as_ref() {
+                    requests.shuffle(&tx_shuffle_seed(seed, &state.utxo.outpoint));
+                }
+                let tx = UnsignedTransaction::new(requests, state).and_then(|tx| {
+                    // The signers' output pays the transaction fee, so its
+                    // amount before the fee must cover the fee.
+                    let other_outputs = tx.output_amounts() - tx.tx.output[0].value.to_sat();
+                    let signer_amount =
+                        tx.input_amounts() as i64 - other_outputs as i64 - tx.tx_fee as i64;
+                    if signer_amount < 0 {
+                        return Err(Error::InvalidAmount(signer_amount));
+                    }
+                    Ok(tx)
+                });
                 if let Ok(tx_ref) = tx.as_ref() {
                     state.utxo = tx_ref.new_signer_utxo();
                     // The first transaction is the only one whose input
@@ -353,11 +780,45 @@ impl SbtcRequests {
             .collect()
     }
 
+    /// The withdrawal to leave out of the given transaction so that the
+    /// signers' output keeps the `min_signer_output` reserve, if it
+    /// does not keep it already.
+    ///
+    /// This is the largest withdrawal in the transaction. `None` is
+    /// returned if there is no reserve, if the signers' output keeps it,
+    /// or if the transaction has no withdrawals, since deposits only add
+    /// to the signers' output.
+    fn withdrawal_below_min_signer_output(
+        &self,
+        tx: &UnsignedTransaction,
+    ) -> Option<QualifiedRequestId> {
+        let min_signer_output = self.min_signer_output?;
+        if tx.tx.output[0].value.to_sat() >= min_signer_output {
+            return None;
+        }
+        tx.requests
+            .iter()
+            .filter_map(RequestRef::as_withdrawal)
+            .max_by_key(|req| (req.amount, req.qualified_id()))
+            .map(WithdrawalRequest::qualified_id)
+    }
+
     fn reject_capacity(&self) -> u32 {
         self.num_signers.saturating_sub(self.accept_threshold) as u32
     }
 }
 
+/// The net change in the signers' funds from servicing the given requests.
+fn net_amount(requests: &[RequestRef]) -> i64 {
+    requests
+        .iter()
+        .map(|req| match req {
+            RequestRef::Deposit(req) => req.amount as i64,
+            RequestRef::Withdrawal(req) => -(req.amount as i64),
+        })
+        .sum()
+}
+
 /// Calculate the total fee necessary for a transaction of the given size
 /// to be accepted by the network. Supports computing the fee in case this
 /// is a replace-by-fee (RBF) transaction by specifying the fees paid
@@ -525,6 +986,17 @@ impl DepositRequest {
             signers_public_key: request.signers_public_key.into(),
         }
     }
+
+    /// The smallest fee, in sats, that this deposit can be assessed when
+    /// it is swept at the given fee rate.
+    ///
+    /// The fee assessed to a deposit is its share, by weight, of the fee
+    /// of the whole transaction, which also pays for the signers' input
+    /// and outputs. So it is never less than the fee for the deposit's
+    /// own input.
+    pub fn min_fee_share(&self, fee_rate: f64) -> u64 {
+        (self.vsize() as f64 * fee_rate).ceil() as u64
+    }
 }
 
 impl Weighted for DepositRequest {
@@ -539,6 +1011,9 @@ impl Weighted for DepositRequest {
             .segwit_weight()
             .to_vbytes_ceil()
     }
+    fn deposit_amount(&self) -> u64 {
+        self.amount
+    }
 }
 
 /// An accepted or pending withdrawal request.
@@ -612,6 +1087,20 @@ impl Weighted for WithdrawalRequest {
     }
 }
 
+/// The seed used to shuffle the requests of the transaction that spends
+/// the given signers' outpoint.
+///
+/// Each transaction in a package spends a different signers' outpoint, so
+/// each gets its own ordering even though they share the same seed.
+pub fn tx_shuffle_seed(seed: &[u8; 32], signer_outpoint: &OutPoint) -> [u8; 32] {
+    sha2::Sha256::new_with_prefix("SBTC_SWEEP_SHUFFLE_SEED")
+        .chain_update(seed)
+        .chain_update(signer_outpoint.txid.to_byte_array())
+        .chain_update(signer_outpoint.vout.to_be_bytes())
+        .finalize()
+        .into()
+}
+
 /// A reference to either a deposit or withdraw request
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum RequestRef<'a> {
@@ -645,6 +1134,27 @@ impl<'a> RequestRef<'a> {
             RequestRef::Withdrawal(req) => req.signer_bitmap,
         }
     }
+
+    /// The order in which requests are handed to the packager.
+    ///
+    /// Requests with more votes against them come first. Ties are broken
+    /// by placing deposits before withdrawals, ordering deposits by their
+    /// outpoint and withdrawals by their qualified request ID. This is a
+    /// total order on distinct requests, so sorting with it gives the same
+    /// packages regardless of the original order of the requests.
+    pub fn packaging_cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let votes_against = |req: &Self| req.votes().count_ones();
+        votes_against(other)
+            .cmp(&votes_against(self))
+            .then_with(|| match (self, other) {
+                (Self::Deposit(a), Self::Deposit(b)) => a.outpoint.cmp(&b.outpoint),
+                (Self::Withdrawal(a), Self::Withdrawal(b)) => {
+                    a.qualified_id().cmp(&b.qualified_id())
+                }
+                (Self::Deposit(_), Self::Withdrawal(_)) => std::cmp::Ordering::Less,
+                (Self::Withdrawal(_), Self::Deposit(_)) => std::cmp::Ordering::Greater,
+            })
+    }
 }
 
 impl Weighted for RequestRef<'_> {
@@ -669,13 +1179,16 @@ impl Weighted for RequestRef<'_> {
     fn withdrawal_id(&self) -> Option<u64> {
         self.as_withdrawal().map(|req| req.request_id)
     }
+    fn deposit_amount(&self) -> u64 {
+        self.as_deposit().map_or(0, |req| req.amount)
+    }
 }
 
 /// A struct for constructing transaction inputs and outputs from deposit
 /// and withdrawal requests.
 #[derive(Debug)]
 pub struct Requests<'a> {
-    /// A sorted list of requests.
+    /// A sorted, or deterministically shuffled, list of requests.
     request_refs: Vec<RequestRef<'a>>,
 }
 
@@ -696,6 +1209,43 @@ impl<'a> Requests<'a> {
         Self { request_refs }
     }
 
+    /// Reorder the deposit requests deterministically using the given
+    /// seed.
+    ///
+    /// The deposit requests are sorted by the hash of the seed and their
+    /// outpoint, so the same seed and requests always give the same order.
+    /// This changes the order of the deposit inputs in the constructed
+    /// transaction. Withdrawal requests keep their order, since the
+    /// withdrawal outputs must follow the order of the request IDs encoded
+    /// in the OP_RETURN output.
+    pub fn shuffle(&mut self, seed: &[u8; 32]) {
+        let mut deposits: Vec<&'a DepositRequest> = self
+            .request_refs
+            .iter()
+            .filter_map(RequestRef::as_deposit)
+            .collect();
+        let withdrawals = self
+            .request_refs
+            .iter()
+            .filter_map(RequestRef::as_withdrawal)
+            .map(RequestRef::Withdrawal);
+
+        deposits.sort_by_cached_key(|req| {
+            <[u8; 32]>::from(
+                sha2::Sha256::new_with_prefix(seed)
+                    .chain_update(req.outpoint.txid.to_byte_array())
+                    .chain_update(req.outpoint.vout.to_be_bytes())
+                    .finalize(),
+            )
+        });
+
+        self.request_refs = deposits
+            .into_iter()
+            .map(RequestRef::Deposit)
+            .chain(withdrawals)
+            .collect();
+    }
+
     /// Return an iterator for the transaction inputs for the deposit
     /// requests. These transaction inputs include a dummy signature so
     /// that the transaction inputs have the correct weight.
@@ -969,6 +1519,8 @@ impl<'a> UnsignedTransaction<'a> {
     ///      is the OP_RETURN data output.
     ///   4. Each input needs a signature in the witness data.
     ///   5. There is no witness data for deposit UTXOs.
+    ///   6. If the signer state asks for one, the anchor output is the
+    ///      last output.
     pub fn new(requests: Requests<'a>, state: &SignerBtcState) -> Result<Self, Error> {
         // Construct a transaction. This transaction's inputs have witness
         // data with dummy signatures so that our virtual size estimates
@@ -1005,7 +1557,8 @@ impl<'a> UnsignedTransaction<'a> {
         // We now compute the total fees for the transaction.
         let tx_vsize: u32 = tx.vsize().try_into().map_err(|_| Error::TypeConversion)?;
 
-        let tx_fee = compute_transaction_fee(tx_vsize as f64, state.fee_rate, state.last_fees);
+        let fee_rate = state.effective_fee_rate();
+        let tx_fee = compute_transaction_fee(tx_vsize as f64, fee_rate, state.last_fees);
         // Now adjust the amount for the signers UTXO for the transaction
         // fee.
         Self::adjust_amounts(&mut tx, tx_fee);
@@ -1066,7 +1619,7 @@ impl<'a> UnsignedTransaction<'a> {
                     .map(|sighash| (deposit, sighash))
                     .map_err(Error::from)
             })
-            .collect::<Result<_, _>>()?;
+            .collect::<Result<Vec<_>, _>>()?;
 
         // Combine them all together to get an ordered list of taproot
         // signature hashes.
@@ -1094,6 +1647,21 @@ impl<'a> UnsignedTransaction<'a> {
         self.tx.output.iter().map(|out| out.value.to_sat()).sum()
     }
 
+    /// The outpoints of the deposits in this transaction that are
+    /// assessed a fee that exceeds their max fee.
+    pub fn deposits_over_max_fee(&self) -> Vec<OutPoint> {
+        let tx_fee = Amount::from_sat(self.tx_fee);
+        self.requests
+            .iter()
+            .filter_map(RequestRef::as_deposit)
+            .filter(|req| {
+                FeeAssessment::assess_input_fee(self, &req.outpoint, tx_fee)
+                    .is_some_and(|fee| fee.to_sat() > req.max_fee)
+            })
+            .map(|req| req.outpoint)
+            .collect()
+    }
+
     /// Construct a "stub" BTC transaction from the given requests.
     ///
     /// The returned BTC transaction is signed with dummy signatures, so it
@@ -1116,10 +1684,20 @@ impl<'a> UnsignedTransaction<'a> {
             output: std::iter::once(signer_output)
                 .chain(Some(Self::new_op_return_output(reqs, state)?))
                 .chain(reqs.tx_outs())
+                .chain(state.anchor_output.then(SignerBtcState::new_anchor_output))
                 .collect(),
         })
     }
 
+    /// Return the txid that this transaction will have once it is signed.
+    ///
+    /// All inputs in the transaction are segwit inputs, so the witness
+    /// data that gets added during signing does not change the txid. This
+    /// allows the txid to be shared before the transaction is signed.
+    pub fn predicted_txid(&self) -> Txid {
+        self.tx.compute_txid()
+    }
+
     /// Create the new SignerUtxo for this transaction.
     pub fn new_signer_utxo(&self) -> SignerUtxo {
         SignerUtxo {
@@ -1194,14 +1772,22 @@ impl<'a> UnsignedTransaction<'a> {
     /// Compute the final amount for the signers' UTXO given the current
     /// UTXO amount and the incoming requests.
     ///
-    /// This amount does not take into account fees.
+    /// This amount does not take into account fees, but it does take into
+    /// account the amount locked in the anchor output, if there is one.
     fn compute_signer_amount(reqs: &Requests, state: &SignerBtcState) -> Result<u64, Error> {
-        let amount = reqs
-            .iter()
-            .fold(state.utxo.amount as i64, |amount, req| match req {
-                RequestRef::Deposit(req) => amount + req.amount as i64,
-                RequestRef::Withdrawal(req) => amount - req.amount as i64,
-            });
+        let anchor_amount = if state.anchor_output {
+            ANCHOR_OUTPUT_AMOUNT as i64
+        } else {
+            0
+        };
+        let amount =
+            reqs.iter().fold(
+                state.utxo.amount as i64 - anchor_amount,
+                |amount, req| match req {
+                    RequestRef::Deposit(req) => amount + req.amount as i64,
+                    RequestRef::Withdrawal(req) => amount - req.amount as i64,
+                },
+            );
 
         // This should never happen
         if amount < 0 {
@@ -1341,12 +1927,18 @@ pub trait FeeAssessment: BitcoinInputsOutputs {
     /// the ones related to the signers.
     fn request_weight(&self) -> Weight {
         // We skip the first input and first two outputs because those are
-        // always the signers' UTXO input and outputs.
+        // always the signers' UTXO input and outputs. The anchor output,
+        // if there is one, is not related to any request either.
+        let request_outputs = self
+            .outputs()
+            .iter()
+            .skip(2)
+            .filter(|tx_out| !SignerBtcState::is_anchor_output(tx_out));
         self.inputs()
             .iter()
             .skip(1)
             .map(|x| x.segwit_weight())
-            .chain(self.outputs().iter().skip(2).map(TxOut::weight))
+            .chain(request_outputs.map(TxOut::weight))
             .sum()
     }
 }
@@ -1465,12 +2057,15 @@ pub trait TxDeconstructor: BitcoinInputsOutputs {
                 .collect();
         }
 
+        // The anchor output, if there is one, is always the last output
+        // and isn't a withdrawal, so we leave it out.
         self.outputs()
             .iter()
             .enumerate()
+            .filter(|(index, tx_out)| *index < 2 || !SignerBtcState::is_anchor_output(tx_out))
             .filter_map(|(index, _)| match index {
                 0 => self.vout_to_output(index, TxOutputType::SignersOutput),
-                1 => self.vout_to_output(index, TxOutputType::SignersOpReturn),
+                1 => self.vout_to_op_return_output(index),
                 _ => self.vout_to_output(index, TxOutputType::Withdrawal),
             })
             .collect()
@@ -1580,9 +2175,22 @@ pub trait TxDeconstructor: BitcoinInputsOutputs {
             script_pubkey: tx_out.script_pubkey.clone().into(),
             amount: tx_out.value.to_sat(),
             output_type,
+            magic_bytes: None,
+            op_return_version: None,
         })
     }
 
+    /// Take the index of the signers' `OP_RETURN` output and return the
+    /// output along with the magic bytes and version parsed from its data.
+    fn vout_to_op_return_output(&self, index: usize) -> Option<TxOutput> {
+        let mut output = self.vout_to_output(index, TxOutputType::SignersOpReturn)?;
+        if let Some((magic_bytes, version)) = parse_op_return_header(&output.script_pubkey) {
+            output.magic_bytes = Some(magic_bytes.to_vec());
+            output.op_return_version = Some(version.into());
+        }
+        Some(output)
+    }
+
     /// Take an input index and the known output type and return a prevout.
     fn vin_to_prevout(&self, index: usize, input_type: TxPrevoutType) -> Option<TxPrevout> {
         let prevout = self.prevout(index)?;
@@ -1608,6 +2216,33 @@ pub trait TxDeconstructor: BitcoinInputsOutputs {
 
         signer_script_pubkeys.contains(signer_input.script_pubkey)
     }
+
+    /// Check whether this transaction spends an output controlled by the
+    /// signers without following the layout of a transaction created by
+    /// the signers, returning the reason if so.
+    ///
+    /// The signers always spend their UTXO as the first input and place
+    /// an `OP_RETURN` output second, so any other spend of an output that
+    /// the signers control may indicate that their key is compromised.
+    fn anomalous_spend_reason(
+        &self,
+        signer_script_pubkeys: &HashSet<ScriptBuf>,
+    ) -> Option<model::AnomalousSpendReason> {
+        if self.is_signer_created(signer_script_pubkeys) {
+            let has_op_return = self
+                .outputs()
+                .get(1)
+                .is_some_and(|tx_out| tx_out.script_pubkey.is_op_return());
+
+            return (!has_op_return).then_some(model::AnomalousSpendReason::MissingSignersOpReturn);
+        }
+
+        let spends_signer_output = (1..self.inputs().len())
+            .filter_map(|index| self.prevout(index))
+            .any(|prevout| signer_script_pubkeys.contains(prevout.script_pubkey));
+
+        spends_signer_output.then_some(model::AnomalousSpendReason::SignersInputNotFirst)
+    }
 }
 
 impl TxDeconstructor for BitcoinTxInfo {
@@ -1634,6 +2269,7 @@ mod tests {
     use bitcoin::Txid;
     use bitcoin::hashes::Hash as _;
     use bitcoin::key::TapTweak;
+    use bitcoin::opcodes::all::OP_NOP;
     use bitcoin::opcodes::all::OP_RETURN;
     use bitcoin::script::Instruction;
     use clarity::vm::types::PrincipalData;
@@ -1643,6 +2279,7 @@ mod tests {
     use rand::distributions::Distribution;
     use rand::distributions::Uniform;
     use rand::rngs::OsRng;
+    use rand::seq::SliceRandom as _;
     use sbtc::deposits::DepositScriptInputs;
     use secp256k1::Keypair;
     use secp256k1::SecretKey;
@@ -1716,6 +2353,7 @@ mod tests {
             None,
             None,
             Some(Amount::from_sat(max_mintable_cap)),
+            None,
         )
     }
 
@@ -1873,11 +2511,17 @@ mod tests {
                 public_key: generate_x_only_public_key(),
                 last_fees: None,
                 magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
             },
             num_signers: 10,
             accept_threshold: 2,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
         let keypair = Keypair::new_global(&mut OsRng);
 
@@ -1987,23 +2631,32 @@ mod tests {
     #[test]
     fn the_first_input_and_output_is_signers_second_output_data() {
         let requests = SbtcRequests {
-            deposits: vec![create_deposit(123456, 0, 0)],
-            withdrawals: vec![create_withdrawal(1000, 0, 0), create_withdrawal(2000, 0, 0)],
+            deposits: vec![create_deposit(123456, 1000, 0)],
+            withdrawals: vec![
+                create_withdrawal(1000, 1000, 0),
+                create_withdrawal(2000, 1000, 0),
+            ],
             signer_state: SignerBtcState {
                 utxo: SignerUtxo {
                     outpoint: generate_outpoint(5500, 0),
                     amount: 5500,
                     public_key: generate_x_only_public_key(),
                 },
-                fee_rate: 0.0,
+                fee_rate: 1.0,
                 public_key: generate_x_only_public_key(),
                 last_fees: None,
                 magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
             },
             num_signers: 10,
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
 
         // This should all be in one transaction since there are no votes
@@ -2027,11 +2680,12 @@ mod tests {
         assert_eq!(unsigned_tx.tx.output.len(), 4);
 
         // The signers' UTXO, the first one, contains the balance of all
-        // deposits and withdrawals. It's also a P2TR script.
+        // deposits and withdrawals, less the transaction fee. It's also a
+        // P2TR script.
         let signers_utxo_output = unsigned_tx.tx.output.first().unwrap();
         assert_eq!(
             signers_utxo_output.value.to_sat(),
-            5500 + 123456 - 1000 - 2000
+            5500 + 123456 - 1000 - 2000 - unsigned_tx.tx_fee
         );
         assert!(signers_utxo_output.script_pubkey.is_p2tr());
 
@@ -2062,6 +2716,8 @@ mod tests {
             public_key,
             last_fees: None,
             magic_bytes: [0; 2],
+            rbf_target_fee_rate: None,
+            anchor_output: false,
         };
 
         let requests = Requests::new(Vec::new());
@@ -2095,11 +2751,17 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [b'S', b'T'],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
             },
             num_signers: 10,
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
 
         // Generate transactions
@@ -2209,11 +2871,17 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
             },
             num_signers: 10,
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
 
         // This should all be in one transaction since there are no votes
@@ -2239,9 +2907,9 @@ mod tests {
         let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
         let requests = SbtcRequests {
             deposits: vec![
-                create_deposit(123456, 0, 0),
-                create_deposit(789012, 0, 0),
-                create_deposit(345678, 0, 0),
+                create_deposit(123456, 1000, 0),
+                create_deposit(789012, 1000, 0),
+                create_deposit(345678, 1000, 0),
             ],
             withdrawals: Vec::new(),
             signer_state: SignerBtcState {
@@ -2250,15 +2918,21 @@ mod tests {
                     amount: 55,
                     public_key,
                 },
-                fee_rate: 0.0,
+                fee_rate: 1.0,
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
             },
             num_signers: 10,
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
 
         // This should all be in one transaction since there are no votes
@@ -2274,14 +2948,18 @@ mod tests {
         assert!(unsigned_tx.tx.output[0].script_pubkey.is_p2tr());
         assert!(unsigned_tx.tx.output[1].script_pubkey.is_op_return());
 
-        // The new amount should be the sum of the old amount plus the deposits.
+        // The new amount should be the sum of the old amount plus the
+        // deposits, less the transaction fee.
         let new_amount: u64 = unsigned_tx
             .tx
             .output
             .iter()
             .map(|out| out.value.to_sat())
             .sum();
-        assert_eq!(new_amount, 55 + 123456 + 789012 + 345678)
+        assert_eq!(
+            new_amount,
+            55 + 123456 + 789012 + 345678 - unsigned_tx.tx_fee
+        )
     }
 
     /// Withdrawal requests remove funds from the signers' UTXO.
@@ -2291,9 +2969,9 @@ mod tests {
         let requests = SbtcRequests {
             deposits: Vec::new(),
             withdrawals: vec![
-                create_withdrawal(1000, 0, 0),
-                create_withdrawal(2000, 0, 0),
-                create_withdrawal(3000, 0, 0),
+                create_withdrawal(1000, 1000, 0),
+                create_withdrawal(2000, 1000, 0),
+                create_withdrawal(3000, 1000, 0),
             ],
             signer_state: SignerBtcState {
                 utxo: SignerUtxo {
@@ -2301,15 +2979,21 @@ mod tests {
                     amount: 9500,
                     public_key,
                 },
-                fee_rate: 0.0,
+                fee_rate: 1.0,
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
             },
             num_signers: 10,
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
 
         let mut transactions = requests.construct_transactions().unwrap();
@@ -2324,131 +3008,361 @@ mod tests {
         assert!(unsigned_tx.tx.output[1].script_pubkey.is_op_return());
 
         let signer_utxo = unsigned_tx.tx.output.first().unwrap();
-        assert_eq!(signer_utxo.value.to_sat(), 9500 - 1000 - 2000 - 3000);
+        assert_eq!(
+            signer_utxo.value.to_sat(),
+            9500 - 1000 - 2000 - 3000 - unsigned_tx.tx_fee
+        );
     }
 
-    /// We chain transactions so that we have a single signer UTXO at the end.
+    /// Withdrawal requests that would take the signers' UTXO below the
+    /// reserve are left out, largest first.
     #[test]
-    fn returned_txs_form_a_tx_chain() {
+    fn withdrawals_keep_min_signer_output_reserve() {
         let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
-        let requests = SbtcRequests {
-            deposits: vec![
-                create_deposit(1234, 0, 1 << 1),
-                create_deposit(5678, 0, 1 << 2),
-                create_deposit(9012, 0, (1 << 3) | (1 << 4)),
-            ],
+        let reserve = 1_000_000;
+        let mut requests = SbtcRequests {
+            deposits: Vec::new(),
             withdrawals: vec![
-                create_withdrawal(1000, 0, 1 << 5),
-                create_withdrawal(2000, 0, 1 << 6),
-                create_withdrawal(3000, 0, 1 << 7),
-                create_withdrawal(4000, 0, (1 << 8) | (1 << 9)),
+                create_withdrawal(300_000, 10_000, 0).wid(1),
+                create_withdrawal(400_000, 10_000, 0).wid(2),
+                create_withdrawal(500_000, 10_000, 0).wid(3),
             ],
             signer_state: SignerBtcState {
                 utxo: SignerUtxo {
-                    outpoint: generate_outpoint(300_000, 0),
-                    amount: 300_000,
+                    outpoint: generate_outpoint(2_000_000, 0),
+                    amount: 2_000_000,
                     public_key,
                 },
-                fee_rate: 0.0,
+                fee_rate: 1.0,
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
             },
             num_signers: 10,
-            accept_threshold: 8,
+            accept_threshold: 0,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
 
+        // Without a reserve, all withdrawals are serviced and the signers'
+        // output drops below the reserve amount.
         let transactions = requests.construct_transactions().unwrap();
-        more_asserts::assert_gt!(transactions.len(), 1);
-
-        transactions.windows(2).for_each(|unsigned| {
-            let utx0 = &unsigned[0];
-            let utx1 = &unsigned[1];
-
-            let previous_output1 = utx1.tx.input[0].previous_output;
-            assert_eq!(utx0.tx.compute_txid(), previous_output1.txid);
-            assert_eq!(previous_output1.vout, 0);
+        let num_withdrawals: usize = transactions.iter().map(|tx| tx.tx.output.len() - 2).sum();
+        assert_eq!(num_withdrawals, 3);
+        let last = transactions.last().unwrap();
+        more_asserts::assert_lt!(last.tx.output[0].value.to_sat(), reserve);
+
+        requests.min_signer_output = Some(reserve);
+        let (transactions, skipped) = requests
+            .construct_transactions_and_skipped(&[], &[])
+            .unwrap();
+        assert!(!transactions.is_empty());
+        for tx in transactions.iter() {
+            more_asserts::assert_ge!(tx.tx.output[0].value.to_sat(), reserve);
+        }
 
-            assert!(utx0.tx.output[0].script_pubkey.is_p2tr());
-            assert!(utx0.tx.output[1].script_pubkey.is_op_return());
+        // Only the largest withdrawal needed to be left out.
+        let mut serviced: Vec<u64> = transactions
+            .iter()
+            .flat_map(|tx| tx.requests.iter().filter_map(RequestRef::as_withdrawal))
+            .map(|req| req.request_id)
+            .collect();
+        serviced.sort();
+        assert_eq!(serviced, vec![1, 2]);
 
-            assert!(utx1.tx.output[0].script_pubkey.is_p2tr());
-            assert!(utx1.tx.output[1].script_pubkey.is_op_return());
-        })
+        let expected_skip = SkippedWithdrawal {
+            id: requests.withdrawals[2].qualified_id(),
+            reason: SkipReason::BelowMinSignerOutput,
+        };
+        assert!(skipped.deposits.is_empty());
+        assert_eq!(skipped.withdrawals, vec![expected_skip]);
     }
 
-    /// Check that each deposit and withdrawal is included as an input or
-    /// deposit in the transaction package.
+    /// Shuffling reorders the deposit inputs deterministically, but keeps
+    /// the signers' input and output and the OP_RETURN output in place.
     #[test]
-    fn requests_in_unsigned_transaction_are_in_btc_tx() {
-        // The requests in the UnsignedTransaction correspond to
-        // inputs and outputs in the transaction
+    fn shuffle_seed_reorders_deposit_inputs_only() {
         let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
-        let requests = SbtcRequests {
-            deposits: vec![
-                create_deposit(1234, 0, 1 << 1),
-                create_deposit(5678, 0, 1 << 2),
-                create_deposit(9012, 0, (1 << 3) | (1 << 4)),
-                create_deposit(3456, 0, 1 << 5),
-                create_deposit(7890, 0, 0),
-            ],
+        let signer_outpoint = generate_outpoint(1_000_000, 0);
+        let mut requests = SbtcRequests {
+            deposits: (1..=10)
+                .map(|i| create_deposit(100_000 + i, 10_000, 0))
+                .collect(),
             withdrawals: vec![
-                create_withdrawal(1000, 0, 1 << 6),
-                create_withdrawal(2000, 0, 1 << 7),
-                create_withdrawal(3000, 0, 1 << 8),
-                create_withdrawal(4000, 0, (1 << 9) | (1 << 10)),
-                create_withdrawal(5000, 0, 0),
-                create_withdrawal(6000, 0, 0),
-                create_withdrawal(7000, 0, 0),
+                create_withdrawal(10_000, 10_000, 0),
+                create_withdrawal(20_000, 10_000, 0),
+                create_withdrawal(30_000, 10_000, 0),
             ],
             signer_state: SignerBtcState {
                 utxo: SignerUtxo {
-                    outpoint: generate_outpoint(300_000, 0),
-                    amount: 300_000,
+                    outpoint: signer_outpoint,
+                    amount: 1_000_000,
                     public_key,
                 },
-                fee_rate: 0.0,
+                fee_rate: 1.0,
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
             },
             num_signers: 10,
-            accept_threshold: 8,
+            accept_threshold: 0,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
 
-        let transactions = requests.construct_transactions().unwrap();
-        more_asserts::assert_gt!(transactions.len(), 1);
+        let sorted = requests.construct_transactions().unwrap();
+        assert_eq!(sorted.len(), 1);
 
-        // Create collections of identifiers for each deposit and withdrawal
-        // request.
-        let mut input_txs: BTreeSet<Txid> =
-            requests.deposits.iter().map(|x| x.outpoint.txid).collect();
-        let mut output_scripts: BTreeSet<String> = requests
-            .withdrawals
+        requests.shuffle_seed = Some([7; 32]);
+        let shuffled = requests.construct_transactions().unwrap();
+        assert_eq!(shuffled.len(), 1);
+
+        let deposit_inputs = |tx: &UnsignedTransaction| -> Vec<OutPoint> {
+            tx.tx.input[1..]
+                .iter()
+                .map(|txin| txin.previous_output)
+                .collect()
+        };
+        let sorted_inputs = deposit_inputs(&sorted[0]);
+        let shuffled_inputs = deposit_inputs(&shuffled[0]);
+        assert_ne!(sorted_inputs, shuffled_inputs);
+
+        // The same deposits are swept, just in a different order.
+        let sorted_set: BTreeSet<_> = sorted_inputs.iter().collect();
+        let shuffled_set: BTreeSet<_> = shuffled_inputs.iter().collect();
+        assert_eq!(sorted_set, shuffled_set);
+
+        // The mandatory positions are unchanged.
+        let tx = &shuffled[0].tx;
+        assert_eq!(tx.input[0].previous_output, signer_outpoint);
+        assert_eq!(tx.output[0], sorted[0].tx.output[0]);
+        assert!(tx.output[1].script_pubkey.is_op_return());
+        assert_eq!(tx.output[1], sorted[0].tx.output[1]);
+
+        // The deposit inputs follow the order of the requests, so that the
+        // sighashes line up with the deposits.
+        let request_outpoints: Vec<OutPoint> = shuffled[0]
+            .requests
             .iter()
-            .map(|req| req.script_pubkey.to_hex_string())
+            .filter_map(RequestRef::as_deposit)
+            .map(|req| req.outpoint)
             .collect();
+        assert_eq!(request_outpoints, shuffled_inputs);
 
-        // Now we check that the counts of the withdrawals and deposits
-        // line up.
-        transactions.iter().for_each(|utx| {
-            let num_inputs = utx.tx.input.len();
-            let num_outputs = utx.tx.output.len();
-            assert_eq!(utx.requests.len() + 3, num_inputs + num_outputs);
-
-            let num_deposits = utx.requests.iter().filter_map(|x| x.as_deposit()).count();
-            assert_eq!(utx.tx.input.len(), num_deposits + 1);
+        // Shuffling is deterministic.
+        let again = requests.construct_transactions().unwrap();
+        assert_eq!(again[0].tx, shuffled[0].tx);
+    }
 
-            let num_withdrawals = utx
-                .requests
-                .iter()
-                .filter_map(|x| x.as_withdrawal())
-                .count();
-            assert_eq!(utx.tx.output.len(), num_withdrawals + 2);
+    /// The withdrawal IDs recovered from a shuffled transaction match the
+    /// outputs that service them.
+    #[test]
+    fn shuffled_transaction_round_trips_withdrawal_outputs() {
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let requests = SbtcRequests {
+            deposits: (1..=5)
+                .map(|i| create_deposit(100_000 + i, 10_000, 0))
+                .collect(),
+            withdrawals: (1..=5)
+                .map(|i| create_withdrawal(10_000 * i, 10_000, 0))
+                .collect(),
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(1_000_000, 0),
+                    amount: 1_000_000,
+                    public_key,
+                },
+                fee_rate: 1.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
+            },
+            num_signers: 10,
+            accept_threshold: 0,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: Some([7; 32]),
+        };
+
+        let transactions = requests.construct_transactions().unwrap();
+        assert_eq!(transactions.len(), 1);
+        let tx = transactions[0].tx.clone();
+
+        let tx_info = BitcoinTxInfo::from_tx(tx.clone(), Amount::ZERO);
+        let tx_outputs: Vec<TxOutput> = (0..tx.output.len())
+            .filter_map(|index| match index {
+                0 => tx_info.vout_to_output(index, TxOutputType::SignersOutput),
+                1 => tx_info.vout_to_op_return_output(index),
+                _ => tx_info.vout_to_output(index, TxOutputType::Withdrawal),
+            })
+            .collect();
+
+        let withdrawal_outputs = tx_info.to_withdrawal_outputs(&tx_outputs).unwrap();
+        assert_eq!(withdrawal_outputs.len(), requests.withdrawals.len());
+
+        for output in withdrawal_outputs {
+            let request = requests
+                .withdrawals
+                .iter()
+                .find(|req| req.request_id == output.request_id)
+                .unwrap();
+            let tx_out = &tx.output[output.output_index as usize];
+            assert_eq!(tx_out.value.to_sat(), request.amount);
+            assert_eq!(&tx_out.script_pubkey, &*request.script_pubkey);
+        }
+    }
+
+    /// We chain transactions so that we have a single signer UTXO at the end.
+    #[test]
+    fn returned_txs_form_a_tx_chain() {
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let requests = SbtcRequests {
+            deposits: vec![
+                create_deposit(1234, 1000, 1 << 1),
+                create_deposit(5678, 1000, 1 << 2),
+                create_deposit(9012, 1000, (1 << 3) | (1 << 4)),
+            ],
+            withdrawals: vec![
+                create_withdrawal(1000, 1000, 1 << 5),
+                create_withdrawal(2000, 1000, 1 << 6),
+                create_withdrawal(3000, 1000, 1 << 7),
+                create_withdrawal(4000, 1000, (1 << 8) | (1 << 9)),
+            ],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(300_000, 0),
+                    amount: 300_000,
+                    public_key,
+                },
+                fee_rate: 1.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
+            },
+            num_signers: 10,
+            accept_threshold: 8,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
+        };
+
+        let transactions = requests.construct_transactions().unwrap();
+        more_asserts::assert_gt!(transactions.len(), 1);
+
+        transactions.windows(2).for_each(|unsigned| {
+            let utx0 = &unsigned[0];
+            let utx1 = &unsigned[1];
+
+            let previous_output1 = utx1.tx.input[0].previous_output;
+            assert_eq!(utx0.tx.compute_txid(), previous_output1.txid);
+            assert_eq!(previous_output1.vout, 0);
+
+            assert!(utx0.tx.output[0].script_pubkey.is_p2tr());
+            assert!(utx0.tx.output[1].script_pubkey.is_op_return());
+
+            assert!(utx1.tx.output[0].script_pubkey.is_p2tr());
+            assert!(utx1.tx.output[1].script_pubkey.is_op_return());
+        })
+    }
+
+    /// Check that each deposit and withdrawal is included as an input or
+    /// deposit in the transaction package.
+    #[test]
+    fn requests_in_unsigned_transaction_are_in_btc_tx() {
+        // The requests in the UnsignedTransaction correspond to
+        // inputs and outputs in the transaction
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let requests = SbtcRequests {
+            deposits: vec![
+                create_deposit(1234, 1000, 1 << 1),
+                create_deposit(5678, 1000, 1 << 2),
+                create_deposit(9012, 1000, (1 << 3) | (1 << 4)),
+                create_deposit(3456, 1000, 1 << 5),
+                create_deposit(7890, 1000, 0),
+            ],
+            withdrawals: vec![
+                create_withdrawal(1000, 1000, 1 << 6),
+                create_withdrawal(2000, 1000, 1 << 7),
+                create_withdrawal(3000, 1000, 1 << 8),
+                create_withdrawal(4000, 1000, (1 << 9) | (1 << 10)),
+                create_withdrawal(5000, 1000, 0),
+                create_withdrawal(6000, 1000, 0),
+                create_withdrawal(7000, 1000, 0),
+            ],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(300_000, 0),
+                    amount: 300_000,
+                    public_key,
+                },
+                fee_rate: 1.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
+            },
+            num_signers: 10,
+            accept_threshold: 8,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
+        };
+
+        let transactions = requests.construct_transactions().unwrap();
+        more_asserts::assert_gt!(transactions.len(), 1);
+
+        // Create collections of identifiers for each deposit and withdrawal
+        // request.
+        let mut input_txs: BTreeSet<Txid> =
+            requests.deposits.iter().map(|x| x.outpoint.txid).collect();
+        let mut output_scripts: BTreeSet<String> = requests
+            .withdrawals
+            .iter()
+            .map(|req| req.script_pubkey.to_hex_string())
+            .collect();
+
+        // Now we check that the counts of the withdrawals and deposits
+        // line up.
+        transactions.iter().for_each(|utx| {
+            let num_inputs = utx.tx.input.len();
+            let num_outputs = utx.tx.output.len();
+            assert_eq!(utx.requests.len() + 3, num_inputs + num_outputs);
+
+            let num_deposits = utx.requests.iter().filter_map(|x| x.as_deposit()).count();
+            assert_eq!(utx.tx.input.len(), num_deposits + 1);
+
+            let num_withdrawals = utx
+                .requests
+                .iter()
+                .filter_map(|x| x.as_withdrawal())
+                .count();
+            assert_eq!(utx.tx.output.len(), num_withdrawals + 2);
 
             assert!(utx.tx.output[0].script_pubkey.is_p2tr());
             assert!(utx.tx.output[1].script_pubkey.is_op_return());
@@ -2469,171 +3383,986 @@ mod tests {
         assert!(output_scripts.is_empty());
     }
 
-    /// Check the following:
-    /// * The fees for each transaction is at least as large as the
-    ///   fee_rate in the signers' state.
-    /// * Each deposit and withdrawal request pays a fee proportional to
-    ///   their weight in the transaction.
-    /// * The total fees are equal to the number of request times the fee
-    ///   per request amount.
-    /// * Deposit requests pay fees too, but implicitly by the amounts
-    ///   deducted from the signers.
+    /// Check the following:
+    /// * The fees for each transaction is at least as large as the
+    ///   fee_rate in the signers' state.
+    /// * Each deposit and withdrawal request pays a fee proportional to
+    ///   their weight in the transaction.
+    /// * The total fees are equal to the number of request times the fee
+    ///   per request amount.
+    /// * Deposit requests pay fees too, but implicitly by the amounts
+    ///   deducted from the signers.
+    #[test]
+    fn returned_txs_match_fee_rate() {
+        // Each deposit and withdrawal has a max fee greater than the current market fee rate
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        // Any old keypair will do here, we need it to construct the
+        // witness data of the right size.
+        let keypair = Keypair::new_global(&mut OsRng);
+
+        let requests = SbtcRequests {
+            deposits: vec![
+                create_deposit(12340, 100_000, 1 << 1),
+                create_deposit(56780, 100_000, 1 << 2),
+                create_deposit(90120, 100_000, (1 << 3) | (1 << 4)),
+                create_deposit(34560, 100_000, 1 << 5),
+                create_deposit(78900, 100_000, 0),
+            ],
+            withdrawals: vec![
+                create_withdrawal(10000, 100_000, 1 << 6),
+                create_withdrawal(20000, 100_000, 1 << 7),
+                create_withdrawal(30000, 100_000, 1 << 8),
+                create_withdrawal(40000, 100_000, (1 << 9) | (1 << 10)),
+                create_withdrawal(50000, 100_000, 0),
+                create_withdrawal(60000, 100_000, 0),
+                create_withdrawal(70000, 100_000, 0),
+            ],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(300_000, 0),
+                    amount: 300_000_000,
+                    public_key,
+                },
+                fee_rate: 25.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
+            },
+            num_signers: 10,
+            accept_threshold: 8,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
+        };
+
+        let mut transactions = requests.construct_transactions().unwrap();
+        more_asserts::assert_gt!(transactions.len(), 1);
+
+        transactions.iter_mut().for_each(|utx| {
+            // The unsigned transaction has all witness data removed,
+            // so it should have a much smaller size than the "signed"
+            // version returned from UnsignedTransaction::new_transaction.
+            let unsigned_size = utx.tx.vsize();
+            testing::set_witness_data(utx, keypair);
+            let signed_vsize = utx.tx.vsize();
+
+            more_asserts::assert_lt!(unsigned_size, signed_vsize);
+
+            let output_amounts: u64 = utx.output_amounts();
+            let input_amounts: u64 = utx.input_amounts();
+
+            let reqs = utx.requests.iter().filter_map(RequestRef::as_withdrawal);
+            for (output, req) in utx.tx.output.iter().skip(2).zip(reqs) {
+                // One of the invariants is that the amount spent to the
+                // withdrawal recipient is the amount in the withdrawal
+                // request. The fees are already paid for separately.
+                assert_eq!(req.amount, output.value.to_sat());
+            }
+
+            more_asserts::assert_gt!(input_amounts, output_amounts);
+            more_asserts::assert_gt!(utx.requests.len(), 0);
+
+            // The final fee rate should still be greater than the market fee rate
+            let fee_rate = (input_amounts - output_amounts) as f64 / signed_vsize as f64;
+            more_asserts::assert_le!(requests.signer_state.fee_rate, fee_rate);
+        });
+    }
+
+    #[test]
+    fn predicted_txid_matches_signed_transaction() {
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let keypair = Keypair::new_global(&mut OsRng);
+
+        let requests = SbtcRequests {
+            deposits: vec![
+                create_deposit(12340, 100_000, 0),
+                create_deposit(56780, 100_000, 0),
+            ],
+            withdrawals: vec![create_withdrawal(10000, 100_000, 0)],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(300_000, 0),
+                    amount: 300_000_000,
+                    public_key,
+                },
+                fee_rate: 25.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
+            },
+            num_signers: 10,
+            accept_threshold: 8,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
+        };
+
+        let mut transactions = requests.construct_transactions().unwrap();
+        assert_eq!(transactions.len(), 1);
+
+        for utx in transactions.iter_mut() {
+            let predicted_txid = utx.predicted_txid();
+
+            testing::set_witness_data(utx, keypair);
+            assert!(utx.tx.input.iter().all(|tx_in| !tx_in.witness.is_empty()));
+
+            assert_eq!(predicted_txid, utx.tx.compute_txid());
+        }
+    }
+
+    #[test]
+    fn rbf_txs_have_greater_total_fee() {
+        // Each deposit and withdrawal has a max fee greater than the current market fee rate
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let mut requests = SbtcRequests {
+            deposits: vec![
+                create_deposit(12340, 100_000, 0),
+                create_deposit(56780, 100_000, 0),
+                create_deposit(90120, 100_000, 0),
+                create_deposit(34560, 100_000, 0),
+                create_deposit(78900, 100_000, 0),
+            ],
+            withdrawals: vec![
+                create_withdrawal(10000, 100_000, 0).wid(1),
+                create_withdrawal(20000, 100_000, 0).wid(1000),
+            ],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(300_000, 0),
+                    amount: 300_000_000,
+                    public_key,
+                },
+                fee_rate: 25.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
+            },
+            num_signers: 10,
+            accept_threshold: 8,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
+        };
+
+        // In the below code, we need to make sure that we take the _first_
+        // transaction in each package as that is the one that will be RBF'd.
+
+        let (old_fee_total, old_fee_rate) = {
+            let transactions = requests.construct_transactions().unwrap();
+            let utx = transactions.first().unwrap();
+
+            let output_amounts: u64 = utx.output_amounts();
+            let input_amounts: u64 = utx.input_amounts();
+
+            more_asserts::assert_gt!(input_amounts, output_amounts);
+            let fee_total = input_amounts - output_amounts;
+            let fee_rate = fee_total as f64 / utx.tx.vsize() as f64;
+            (fee_total, fee_rate)
+        };
+
+        requests.signer_state.last_fees = Some(Fees {
+            total: old_fee_total,
+            rate: old_fee_rate,
+        });
+
+        let transactions = requests.construct_transactions().unwrap();
+        let utx = transactions.first().unwrap();
+
+        let output_amounts: u64 = utx.output_amounts();
+        let input_amounts: u64 = utx.input_amounts();
+
+        more_asserts::assert_gt!(input_amounts, output_amounts);
+        more_asserts::assert_gt!(input_amounts - output_amounts, old_fee_total);
+        more_asserts::assert_gt!(utx.requests.len(), 0);
+
+        // Since there are often both deposits and withdrawal, the
+        // following assertion checks that we capture the fees that
+        // depositors must pay.
+        assert_eq!(input_amounts, output_amounts + utx.tx_fee);
+
+        let state = &requests.signer_state;
+        let signed_vsize = UnsignedTransaction::new_transaction(&utx.requests, state, &[])
+            .unwrap()
+            .vsize();
+
+        // The unsigned transaction has all witness data removed,
+        // so it should have a much smaller size than the "signed"
+        // version returned from UnsignedTransaction::new_transaction.
+        more_asserts::assert_lt!(utx.tx.vsize(), signed_vsize);
+        // The final fee rate should still be greater than the market fee rate
+        let fee_rate = (input_amounts - output_amounts) as f64 / signed_vsize as f64;
+        more_asserts::assert_le!(requests.signer_state.fee_rate, fee_rate);
+    }
+
+    #[test]
+    fn rbf_txs_meet_target_fee_rate_and_absolute_fee_increase() {
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let mut requests = SbtcRequests {
+            deposits: vec![
+                create_deposit(12340, 100_000, 0),
+                create_deposit(56780, 100_000, 0),
+            ],
+            withdrawals: vec![create_withdrawal(10000, 100_000, 0).wid(1)],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(300_000, 0),
+                    amount: 300_000_000,
+                    public_key,
+                },
+                fee_rate: 10.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
+            },
+            num_signers: 10,
+            accept_threshold: 8,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
+        };
+
+        let transactions = requests.construct_transactions().unwrap();
+        let original = transactions.first().unwrap();
+        let last_fees = Fees {
+            total: original.tx_fee,
+            rate: original.tx_fee as f64 / original.tx_vsize as f64,
+        };
+
+        // The target fee rate is well above both the market fee rate and
+        // the minimum fee rate bump that BIP-125 would require.
+        let target_fee_rate = 40.0;
+        requests.signer_state.last_fees = Some(last_fees);
+        requests.signer_state.rbf_target_fee_rate = Some(target_fee_rate);
+
+        let transactions = requests.construct_transactions().unwrap();
+        let replacement = transactions.first().unwrap();
+
+        // The replacement must pay for its own bandwidth on top of the
+        // absolute fee paid by the original transaction.
+        let min_absolute_fee = last_fees.total as f64
+            + replacement.tx_vsize as f64 * DEFAULT_INCREMENTAL_RELAY_FEE_RATE;
+        more_asserts::assert_ge!(replacement.tx_fee as f64, min_absolute_fee);
+
+        // And the replacement must also hit the target fee rate.
+        let fee_rate = replacement.tx_fee as f64 / replacement.tx_vsize as f64;
+        more_asserts::assert_ge!(fee_rate, target_fee_rate);
+        more_asserts::assert_gt!(fee_rate, last_fees.rate);
+
+        assert_eq!(
+            replacement.input_amounts(),
+            replacement.output_amounts() + replacement.tx_fee
+        );
+    }
+
+    #[test]
+    fn construct_transactions_produces_balanced_transactions() {
+        // Each request has a distinct vote against it, and we can only
+        // tolerate one vote against, so each request ends up in its own
+        // transaction.
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let requests = SbtcRequests {
+            deposits: vec![
+                create_deposit(1_000_000, 100_000, 1 << 0),
+                create_deposit(2_000_000, 100_000, 1 << 1),
+                create_deposit(3_000_000, 100_000, 1 << 2),
+            ],
+            withdrawals: vec![create_withdrawal(5_500_000, 100_000, 1 << 3)],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(300_000_000, 0),
+                    amount: 300_000_000,
+                    public_key,
+                },
+                fee_rate: 10.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
+            },
+            num_signers: 10,
+            accept_threshold: 9,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
+        };
+
+        let transactions = requests.construct_transactions().unwrap();
+        assert_eq!(transactions.len(), 4);
+
+        for tx in transactions.iter() {
+            assert_eq!(tx.input_amounts(), tx.output_amounts() + tx.tx_fee);
+        }
+    }
+
+    #[test]
+    fn construct_transactions_is_independent_of_request_order() {
+        // The requests have a mix of votes against them, including ties,
+        // so that the packages depend on the order in which the requests
+        // are handed to the packager.
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let mut requests = SbtcRequests {
+            deposits: vec![
+                create_deposit(1_000_000, 100_000, 1 << 0),
+                create_deposit(2_000_000, 100_000, 1 << 1),
+                create_deposit(3_000_000, 100_000, 1 << 1),
+                create_deposit(4_000_000, 100_000, (1 << 0) | (1 << 2)),
+                create_deposit(5_000_000, 100_000, 0),
+                create_deposit(6_000_000, 100_000, 0),
+            ],
+            withdrawals: vec![
+                create_withdrawal(100_000, 100_000, 1 << 2),
+                create_withdrawal(200_000, 100_000, 1 << 3),
+                create_withdrawal(300_000, 100_000, (1 << 1) | (1 << 3)),
+                create_withdrawal(400_000, 100_000, 0),
+            ],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(300_000_000, 0),
+                    amount: 300_000_000,
+                    public_key,
+                },
+                fee_rate: 10.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
+            },
+            num_signers: 10,
+            accept_threshold: 8,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
+        };
+
+        let serialize = |requests: &SbtcRequests| -> Vec<Vec<u8>> {
+            requests
+                .construct_transactions()
+                .unwrap()
+                .iter()
+                .map(|unsigned| bitcoin::consensus::serialize(&unsigned.tx))
+                .collect()
+        };
+
+        let expected = serialize(&requests);
+        more_asserts::assert_gt!(expected.len(), 1);
+
+        let mut rng = testing::get_rng();
+        for _ in 0..20 {
+            requests.deposits.shuffle(&mut rng);
+            requests.withdrawals.shuffle(&mut rng);
+            assert_eq!(serialize(&requests), expected);
+        }
+    }
+
+    #[test]
+    fn construct_transactions_respects_per_tx_cap() {
+        // All of the deposits could go in one transaction, but the
+        // per-transaction cap only allows two of them in each.
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let per_tx_cap = Amount::from_sat(25_000_000);
+        let requests = SbtcRequests {
+            deposits: std::iter::repeat_with(|| create_deposit(10_000_000, 100_000, 0))
+                .take(5)
+                .collect(),
+            withdrawals: Vec::new(),
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(300_000_000, 0),
+                    amount: 300_000_000,
+                    public_key,
+                },
+                fee_rate: 10.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
+            },
+            num_signers: 10,
+            accept_threshold: 8,
+            sbtc_limits: SbtcLimits::new(
+                Some(per_tx_cap),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            ),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
+        };
+
+        let transactions = requests.construct_transactions().unwrap();
+        assert_eq!(transactions.len(), 3);
+
+        let num_deposits: usize = transactions
+            .iter()
+            .map(|utx| {
+                let swept: u64 = utx
+                    .requests
+                    .iter()
+                    .filter_map(RequestRef::as_deposit)
+                    .map(|req| req.amount)
+                    .sum();
+                assert!(Amount::from_sat(swept) <= per_tx_cap);
+                utx.requests.len()
+            })
+            .sum();
+        assert_eq!(num_deposits, 5);
+    }
+
+    #[test]
+    fn construct_transactions_excluding_omits_excluded_requests() {
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let requests = SbtcRequests {
+            deposits: vec![
+                create_deposit(1_000_000, 100_000, 0),
+                create_deposit(2_000_000, 100_000, 0),
+                create_deposit(3_000_000, 100_000, 0),
+            ],
+            withdrawals: vec![
+                create_withdrawal(500_000, 100_000, 0),
+                create_withdrawal(600_000, 100_000, 0),
+            ],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(300_000_000, 0),
+                    amount: 300_000_000,
+                    public_key,
+                },
+                fee_rate: 10.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
+            },
+            num_signers: 10,
+            accept_threshold: 8,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
+        };
+
+        let excluded_deposit = requests.deposits[1].outpoint;
+        let excluded_withdrawal = requests.withdrawals[0].qualified_id();
+
+        let transactions = requests
+            .construct_transactions_excluding(&[excluded_deposit], &[])
+            .unwrap();
+        assert_eq!(transactions.len(), 1);
+        let utx = transactions.first().unwrap();
+
+        let deposit_outpoints: BTreeSet<OutPoint> = utx
+            .requests
+            .iter()
+            .filter_map(RequestRef::as_deposit)
+            .map(|req| req.outpoint)
+            .collect();
+        let expected: BTreeSet<OutPoint> = [&requests.deposits[0], &requests.deposits[2]]
+            .into_iter()
+            .map(|req| req.outpoint)
+            .collect();
+        assert_eq!(deposit_outpoints, expected);
+
+        // The transaction spends the signers' UTXO and the two remaining
+        // deposits, and the withdrawals are unaffected.
+        assert_eq!(utx.tx.input.len(), 3);
+        assert!(
+            utx.tx
+                .input
+                .iter()
+                .all(|tx_in| tx_in.previous_output != excluded_deposit)
+        );
+        assert_eq!(
+            utx.requests
+                .iter()
+                .filter_map(RequestRef::as_withdrawal)
+                .count(),
+            2
+        );
+
+        // Withdrawals can be excluded the same way.
+        let transactions = requests
+            .construct_transactions_excluding(&[], &[excluded_withdrawal])
+            .unwrap();
+        let utx = transactions.first().unwrap();
+        let withdrawal_ids: Vec<QualifiedRequestId> = utx
+            .requests
+            .iter()
+            .filter_map(RequestRef::as_withdrawal)
+            .map(WithdrawalRequest::qualified_id)
+            .collect();
+        assert_eq!(withdrawal_ids, vec![requests.withdrawals[1].qualified_id()]);
+        assert_eq!(
+            utx.requests
+                .iter()
+                .filter_map(RequestRef::as_deposit)
+                .count(),
+            3
+        );
+    }
+
     #[test]
-    fn returned_txs_match_fee_rate() {
-        // Each deposit and withdrawal has a max fee greater than the current market fee rate
+    fn construct_transactions_skips_deposits_over_max_fee() {
         let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
-        // Any old keypair will do here, we need it to construct the
-        // witness data of the right size.
-        let keypair = Keypair::new_global(&mut OsRng);
+        let fee_rate = 100.0;
+
+        // This deposit can pay for a transaction of its own, as estimated
+        // during validation, but its deposit script is much larger than
+        // usual, so its share of the fee in the actual transaction is
+        // over its max fee.
+        let minimum_fee = compute_transaction_fee(SOLO_DEPOSIT_TX_VSIZE, fee_rate, None);
+        let large_deposit = DepositRequest {
+            deposit_script: ScriptBuf::from_bytes(vec![OP_NOP.to_u8(); 1_000]),
+            ..create_deposit(4_000_000, minimum_fee, 0)
+        };
 
         let requests = SbtcRequests {
             deposits: vec![
-                create_deposit(12340, 100_000, 1 << 1),
-                create_deposit(56780, 100_000, 1 << 2),
-                create_deposit(90120, 100_000, (1 << 3) | (1 << 4)),
-                create_deposit(34560, 100_000, 1 << 5),
-                create_deposit(78900, 100_000, 0),
-            ],
-            withdrawals: vec![
-                create_withdrawal(10000, 100_000, 1 << 6),
-                create_withdrawal(20000, 100_000, 1 << 7),
-                create_withdrawal(30000, 100_000, 1 << 8),
-                create_withdrawal(40000, 100_000, (1 << 9) | (1 << 10)),
-                create_withdrawal(50000, 100_000, 0),
-                create_withdrawal(60000, 100_000, 0),
-                create_withdrawal(70000, 100_000, 0),
+                create_deposit(1_000_000, 100_000, 0),
+                create_deposit(2_000_000, 1_000, 0),
+                create_deposit(3_000_000, 100_000, 0),
+                large_deposit,
             ],
+            withdrawals: Vec::new(),
             signer_state: SignerBtcState {
                 utxo: SignerUtxo {
-                    outpoint: generate_outpoint(300_000, 0),
+                    outpoint: generate_outpoint(300_000_000, 0),
                     amount: 300_000_000,
                     public_key,
                 },
-                fee_rate: 25.0,
+                fee_rate,
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
             },
             num_signers: 10,
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
 
-        let mut transactions = requests.construct_transactions().unwrap();
-        more_asserts::assert_gt!(transactions.len(), 1);
+        // The second deposit cannot pay for its own input at this fee
+        // rate, so it never makes it past validation and is not reported
+        // as skipped.
+        let capped_deposit = &requests.deposits[1];
+        assert!(capped_deposit.max_fee < minimum_fee);
+        let large_deposit = &requests.deposits[3];
+        assert!(large_deposit.min_fee_share(fee_rate) > large_deposit.max_fee);
 
-        transactions.iter_mut().for_each(|utx| {
-            // The unsigned transaction has all witness data removed,
-            // so it should have a much smaller size than the "signed"
-            // version returned from UnsignedTransaction::new_transaction.
-            let unsigned_size = utx.tx.vsize();
-            testing::set_witness_data(utx, keypair);
-            let signed_vsize = utx.tx.vsize();
+        let (transactions, skipped) = requests
+            .construct_transactions_and_skipped(&[], &[])
+            .unwrap();
 
-            more_asserts::assert_lt!(unsigned_size, signed_vsize);
+        // The large deposit passed validation but was assessed too much
+        // once the transaction was constructed, so the package was
+        // rebuilt without it.
+        let expected_skip = SkippedDeposit {
+            outpoint: large_deposit.outpoint,
+            reason: SkipReason::FeeExceedsMaxFee,
+        };
+        assert_eq!(skipped.deposits, vec![expected_skip]);
+        assert!(skipped.withdrawals.is_empty());
 
-            let output_amounts: u64 = utx.output_amounts();
-            let input_amounts: u64 = utx.input_amounts();
+        assert_eq!(transactions.len(), 1);
+        let utx = transactions.first().unwrap();
+        let deposit_outpoints: BTreeSet<OutPoint> = utx
+            .requests
+            .iter()
+            .filter_map(RequestRef::as_deposit)
+            .map(|req| req.outpoint)
+            .collect();
+        let expected: BTreeSet<OutPoint> = [&requests.deposits[0], &requests.deposits[2]]
+            .into_iter()
+            .map(|req| req.outpoint)
+            .collect();
+        assert_eq!(deposit_outpoints, expected);
 
-            let reqs = utx.requests.iter().filter_map(RequestRef::as_withdrawal);
-            for (output, req) in utx.tx.output.iter().skip(2).zip(reqs) {
-                // One of the invariants is that the amount spent to the
-                // withdrawal recipient is the amount in the withdrawal
-                // request. The fees are already paid for separately.
-                assert_eq!(req.amount, output.value.to_sat());
-            }
+        // The swept deposits are assessed fees within their max fee.
+        assert!(utx.deposits_over_max_fee().is_empty());
 
-            more_asserts::assert_gt!(input_amounts, output_amounts);
-            more_asserts::assert_gt!(utx.requests.len(), 0);
+        // The skipped deposits are left out of the regular construction
+        // too.
+        let transactions = requests.construct_transactions().unwrap();
+        assert!(transactions.iter().all(|utx| {
+            utx.requests
+                .iter()
+                .filter_map(RequestRef::as_deposit)
+                .all(|req| ![capped_deposit, large_deposit].contains(&req))
+        }));
+    }
 
-            // The final fee rate should still be greater than the market fee rate
-            let fee_rate = (input_amounts - output_amounts) as f64 / signed_vsize as f64;
-            more_asserts::assert_le!(requests.signer_state.fee_rate, fee_rate);
-        });
+    #[test]
+    fn construct_transactions_reorders_packages_to_fund_withdrawals() {
+        // The signers' UTXO cannot cover the withdrawal on its own, and
+        // neither can it with any two of the deposits swept in. Each
+        // request ends up in its own transaction, so all of the deposits
+        // need to come before the withdrawal.
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let requests = SbtcRequests {
+            deposits: vec![
+                create_deposit(1_000_000, 100_000, 1 << 0),
+                create_deposit(2_000_000, 100_000, 1 << 1),
+                create_deposit(3_000_000, 100_000, 1 << 2),
+            ],
+            withdrawals: vec![create_withdrawal(5_500_000, 100_000, 1 << 3)],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(100_000, 0),
+                    amount: 100_000,
+                    public_key,
+                },
+                fee_rate: 10.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
+            },
+            num_signers: 10,
+            accept_threshold: 9,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
+        };
+
+        let transactions = requests.construct_transactions().unwrap();
+        assert_eq!(transactions.len(), 4);
+
+        for tx in transactions.iter() {
+            assert_eq!(tx.input_amounts(), tx.output_amounts() + tx.tx_fee);
+        }
+
+        let last_tx = transactions.last().unwrap();
+        assert_eq!(
+            last_tx
+                .requests
+                .iter()
+                .filter_map(RequestRef::as_withdrawal)
+                .count(),
+            1
+        );
     }
 
     #[test]
-    fn rbf_txs_have_greater_total_fee() {
-        // Each deposit and withdrawal has a max fee greater than the current market fee rate
+    fn construct_transactions_waits_for_min_batch() {
         let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
         let mut requests = SbtcRequests {
             deposits: vec![
-                create_deposit(12340, 100_000, 0),
-                create_deposit(56780, 100_000, 0),
-                create_deposit(90120, 100_000, 0),
-                create_deposit(34560, 100_000, 0),
-                create_deposit(78900, 100_000, 0),
-            ],
-            withdrawals: vec![
-                create_withdrawal(10000, 100_000, 0).wid(1),
-                create_withdrawal(20000, 100_000, 0).wid(1000),
+                create_deposit(1_000_000, 100_000, 0),
+                create_deposit(2_000_000, 100_000, 0),
             ],
+            withdrawals: Vec::new(),
             signer_state: SignerBtcState {
                 utxo: SignerUtxo {
                     outpoint: generate_outpoint(300_000, 0),
-                    amount: 300_000_000,
+                    amount: 300_000,
                     public_key,
                 },
-                fee_rate: 25.0,
+                fee_rate: 10.0,
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
             },
             num_signers: 10,
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: Some(MinBatch {
+                num_deposits: Some(3),
+                amount: None,
+            }),
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
 
-        // In the below code, we need to make sure that we take the _first_
-        // transaction in each package as that is the one that will be RBF'd.
+        // Only two deposits are pending, so nothing should be swept, and
+        // both deposits are reported as held back.
+        let (transactions, skipped) = requests
+            .construct_transactions_and_skipped(&[], &[])
+            .unwrap();
+        assert!(transactions.is_empty());
+        let reasons: Vec<SkipReason> = skipped.deposits.iter().map(|skip| skip.reason).collect();
+        assert_eq!(reasons, vec![SkipReason::BelowMinimumBatch; 2]);
 
-        let (old_fee_total, old_fee_rate) = {
-            let transactions = requests.construct_transactions().unwrap();
-            let utx = transactions.first().unwrap();
+        // Withdrawals are never held back, and the pending deposits go
+        // along with them.
+        requests.withdrawals = vec![create_withdrawal(10_000, 100_000, 0)];
+        let transactions = requests.construct_transactions().unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].requests.len(), 3);
+
+        // Once the third deposit is pending the deposits get swept.
+        requests.withdrawals = Vec::new();
+        requests
+            .deposits
+            .push(create_deposit(3_000_000, 100_000, 0));
+        let (transactions, skipped) = requests
+            .construct_transactions_and_skipped(&[], &[])
+            .unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].requests.len(), 3);
+        assert!(skipped.deposits.is_empty());
+    }
 
-            let output_amounts: u64 = utx.output_amounts();
-            let input_amounts: u64 = utx.input_amounts();
+    #[test]
+    fn construct_transactions_handles_withdrawals_to_signers() {
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let mut withdrawal = create_withdrawal(10_000, 100_000, 0);
+        withdrawal.script_pubkey = public_key.signers_script_pubkey().into();
 
-            more_asserts::assert_gt!(input_amounts, output_amounts);
-            let fee_total = input_amounts - output_amounts;
-            let fee_rate = fee_total as f64 / utx.tx.vsize() as f64;
-            (fee_total, fee_rate)
+        let mut requests = SbtcRequests {
+            deposits: vec![create_deposit(1_000_000, 100_000, 0)],
+            withdrawals: vec![withdrawal.clone(), create_withdrawal(20_000, 100_000, 0)],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(300_000, 0),
+                    amount: 300_000,
+                    public_key,
+                },
+                fee_rate: 10.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
+            },
+            num_signers: 10,
+            accept_threshold: 8,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
 
-        requests.signer_state.last_fees = Some(Fees {
-            total: old_fee_total,
-            rate: old_fee_rate,
-        });
+        // The withdrawal to the signers is left out, but the other
+        // requests are still serviced.
+        let transactions = requests.construct_transactions().unwrap();
+        assert_eq!(transactions.len(), 1);
+        let tx = &transactions[0];
+        assert_eq!(tx.requests.len(), 2);
+        assert!(
+            tx.requests
+                .iter()
+                .filter_map(RequestRef::as_withdrawal)
+                .all(|req| req.qualified_id() != withdrawal.qualified_id())
+        );
 
+        // When treated as change, the withdrawal is fulfilled with an
+        // output that is locked to the signers.
+        requests.withdrawals_to_signers = WithdrawalsToSignersPolicy::TreatAsChange;
         let transactions = requests.construct_transactions().unwrap();
-        let utx = transactions.first().unwrap();
+        assert_eq!(transactions.len(), 1);
+        let tx = &transactions[0];
+        assert_eq!(tx.requests.len(), 3);
 
-        let output_amounts: u64 = utx.output_amounts();
-        let input_amounts: u64 = utx.input_amounts();
+        let signers_script_pubkey = public_key.signers_script_pubkey();
+        let outputs_to_signers = tx
+            .tx
+            .output
+            .iter()
+            .filter(|output| output.script_pubkey == signers_script_pubkey)
+            .count();
+        assert_eq!(outputs_to_signers, 2);
+        assert!(tx.tx.output.iter().any(|output| {
+            output.script_pubkey == signers_script_pubkey && output.value.to_sat() == 10_000
+        }));
+    }
 
-        more_asserts::assert_gt!(input_amounts, output_amounts);
-        more_asserts::assert_gt!(input_amounts - output_amounts, old_fee_total);
-        more_asserts::assert_gt!(utx.requests.len(), 0);
+    #[test_case(MinBatch::default(), &[], true; "no thresholds")]
+    #[test_case(MinBatch { num_deposits: Some(2), amount: None }, &[1_000], false; "too few deposits")]
+    #[test_case(MinBatch { num_deposits: Some(2), amount: None }, &[1_000, 2_000], true; "enough deposits")]
+    #[test_case(MinBatch { num_deposits: None, amount: Some(5_000) }, &[1_000, 2_000], false; "too little amount")]
+    #[test_case(MinBatch { num_deposits: Some(5), amount: Some(3_000) }, &[1_000, 2_000], true; "either threshold")]
+    fn min_batch_is_met_by(min_batch: MinBatch, amounts: &[u64], expected: bool) {
+        let deposits: Vec<DepositRequest> = amounts
+            .iter()
+            .map(|amount| create_deposit(*amount, 0, 0))
+            .collect();
+        let requests: Vec<RequestRef> = deposits.iter().map(RequestRef::Deposit).collect();
 
-        // Since there are often both deposits and withdrawal, the
-        // following assertion checks that we capture the fees that
-        // depositors must pay.
-        assert_eq!(input_amounts, output_amounts + utx.tx_fee);
+        assert_eq!(min_batch.is_met_by(&requests), expected);
+    }
 
-        let state = &requests.signer_state;
-        let signed_vsize = UnsignedTransaction::new_transaction(&utx.requests, state)
-            .unwrap()
-            .vsize();
+    #[test_case(f64::NAN; "NaN fee rate")]
+    #[test_case(f64::INFINITY; "infinite fee rate")]
+    #[test_case(-1.0; "negative fee rate")]
+    #[test_case(0.0; "zero fee rate")]
+    fn construct_transactions_rejects_invalid_fee_rates(fee_rate: f64) {
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let requests = SbtcRequests {
+            deposits: vec![create_deposit(12340, 100_000, 0)],
+            withdrawals: vec![create_withdrawal(10000, 100_000, 0)],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(300_000, 0),
+                    amount: 300_000_000,
+                    public_key,
+                },
+                fee_rate,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
+            },
+            num_signers: 10,
+            accept_threshold: 8,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
+        };
 
-        // The unsigned transaction has all witness data removed,
-        // so it should have a much smaller size than the "signed"
-        // version returned from UnsignedTransaction::new_transaction.
-        more_asserts::assert_lt!(utx.tx.vsize(), signed_vsize);
-        // The final fee rate should still be greater than the market fee rate
-        let fee_rate = (input_amounts - output_amounts) as f64 / signed_vsize as f64;
-        more_asserts::assert_le!(requests.signer_state.fee_rate, fee_rate);
+        let result = requests.construct_transactions();
+        assert!(matches!(result, Err(Error::InvalidFeeRate(_))));
+    }
+
+    #[test]
+    fn construct_transactions_adds_anchor_output_for_cpfp() {
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let requests = SbtcRequests {
+            deposits: vec![create_deposit(123456, 100_000, 0)],
+            withdrawals: vec![create_withdrawal(10000, 100_000, 0).wid(1)],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(300_000, 0),
+                    amount: 300_000_000,
+                    public_key,
+                },
+                fee_rate: 1.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: true,
+            },
+            num_signers: 10,
+            accept_threshold: 8,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
+        };
+
+        let transactions = requests.construct_transactions().unwrap();
+        let parent = transactions.first().unwrap();
+
+        // The anchor output comes after the signers' outputs and the
+        // withdrawal output, and the transaction is still balanced.
+        assert_eq!(parent.tx.output.len(), 4);
+        let anchor_vout = parent.tx.output.len() - 1;
+        let anchor = &parent.tx.output[anchor_vout];
+        assert!(SignerBtcState::is_anchor_output(anchor));
+        assert_eq!(anchor.value, Amount::from_sat(ANCHOR_OUTPUT_AMOUNT));
+        assert_eq!(
+            parent.input_amounts(),
+            parent.output_amounts() + parent.tx_fee
+        );
+
+        // Now a child transaction spends the anchor output and gives it
+        // all to the miners.
+        let child = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint {
+                    txid: parent.tx.compute_txid(),
+                    vout: anchor_vout as u32,
+                },
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ZERO,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: Amount::ZERO,
+                script_pubkey: ScriptBuf::from_bytes(vec![OP_RETURN.to_u8()]),
+            }],
+        };
+
+        // The anchor output is keyless, so the child can spend it with an
+        // empty witness.
+        let mut child_bytes = Vec::new();
+        child.consensus_encode(&mut child_bytes).unwrap();
+        let anchor_script = anchor.script_pubkey.as_bytes();
+        let anchor_utxo = bitcoinconsensus::Utxo {
+            script_pubkey: anchor_script.as_ptr(),
+            script_pubkey_len: anchor_script.len() as u32,
+            value: ANCHOR_OUTPUT_AMOUNT as i64,
+        };
+        let flags = bitcoinconsensus::VERIFY_ALL_PRE_TAPROOT | bitcoinconsensus::VERIFY_TAPROOT;
+        bitcoinconsensus::verify_with_flags(
+            anchor_script,
+            ANCHOR_OUTPUT_AMOUNT,
+            &child_bytes,
+            Some(&[anchor_utxo]),
+            0,
+            flags,
+        )
+        .unwrap();
+
+        // The package of the sweep and the child pays a higher fee rate
+        // than the sweep alone.
+        let parent_fee_rate = parent.tx_fee as f64 / parent.tx_vsize as f64;
+        let package_fee = (parent.tx_fee + ANCHOR_OUTPUT_AMOUNT) as f64;
+        let package_vsize = (parent.tx_vsize as usize + child.vsize()) as f64;
+        more_asserts::assert_gt!(package_fee / package_vsize, parent_fee_rate);
     }
 
     #[test_case(2, false; "some deposits, single tx")]
@@ -2660,11 +4389,17 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
             },
             num_signers: 10,
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
         // If multiple_txs is specified, we add a withdrawal that will
         // cause the transaction to be split into two.
@@ -2691,9 +4426,9 @@ mod tests {
         let requests = SbtcRequests {
             deposits: Vec::new(),
             withdrawals: vec![
-                create_withdrawal(1000, 0, 0),
-                create_withdrawal(2000, 0, 0),
-                create_withdrawal(3000, 0, 0),
+                create_withdrawal(1000, 1000, 0),
+                create_withdrawal(2000, 1000, 0),
+                create_withdrawal(3000, 1000, 0),
             ],
             signer_state: SignerBtcState {
                 utxo: SignerUtxo {
@@ -2701,15 +4436,21 @@ mod tests {
                     amount: 3000,
                     public_key,
                 },
-                fee_rate: 0.0,
+                fee_rate: 1.0,
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
             },
             num_signers: 10,
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
 
         let transactions = requests.construct_transactions();
@@ -2763,11 +4504,17 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
             },
             num_signers: 10,
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
 
         let mut transactions = requests.construct_transactions().unwrap();
@@ -3066,11 +4813,17 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
             },
             num_signers: 11,
             accept_threshold: 6,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
 
         // Let's construct the unsigned transaction and check to see if we
@@ -3112,11 +4865,17 @@ mod tests {
                 public_key: generate_x_only_public_key(),
                 last_fees: None,
                 magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
             },
             accept_threshold: 127,
             num_signers: 128,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
 
         let transactions = requests.construct_transactions().unwrap();
@@ -3168,11 +4927,17 @@ mod tests {
                 public_key: generate_x_only_public_key(),
                 last_fees: None,
                 magic_bytes: [0; 2],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
             },
             accept_threshold: 10,
             num_signers: 14,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
 
         let mut transactions = requests.construct_transactions().unwrap();
@@ -3499,6 +5264,8 @@ mod tests {
                 script_pubkey: ScriptPubKey::from_bytes(vec![]),
                 amount: 0,
                 output_type,
+                magic_bytes: None,
+                op_return_version: None,
             });
             self
         }
@@ -3510,11 +5277,31 @@ mod tests {
                 script_pubkey: script.into(),
                 amount: 0,
                 output_type: TxOutputType::SignersOpReturn,
+                magic_bytes: None,
+                op_return_version: None,
             });
             self
         }
     }
 
+    #[test_case(&[b'T', b'3', 1, 4, 5], Some(([b'T', b'3'], 1)); "with data")]
+    #[test_case(&[b'X', b'2', 7], Some(([b'X', b'2'], 7)); "header only")]
+    #[test_case(&[b'T', b'3'], None; "too short")]
+    #[test_case(&[], None; "empty")]
+    fn test_parse_op_return_header(data: &[u8], expected: Option<([u8; 2], u8)>) {
+        let mut push_bytes = PushBytesBuf::new();
+        push_bytes.extend_from_slice(data).unwrap();
+        let script = ScriptBuf::new_op_return(push_bytes);
+
+        assert_eq!(parse_op_return_header(&script), expected);
+    }
+
+    #[test]
+    fn test_parse_op_return_header_not_op_return() {
+        let script = ScriptBuf::new_p2wpkh(&bitcoin::WPubkeyHash::from_byte_array([0; 20]));
+        assert_eq!(parse_op_return_header(&script), None);
+    }
+
     #[test_case(&TestTxOut::default(); "no outputs")]
     #[test_case(&TestTxOut::default()
         .output(TxOutputType::SignersOutput)
@@ -15,6 +15,7 @@ use crate::WITHDRAWAL_BLOCKS_EXPIRY;
 use crate::WITHDRAWAL_MIN_CONFIRMATIONS;
 use crate::bitcoin::utxo::FeeAssessment;
 use crate::bitcoin::utxo::SignerBtcState;
+use crate::bitcoin::utxo::tx_shuffle_seed;
 use crate::context::Context;
 use crate::context::SbtcLimits;
 use crate::error::Error;
@@ -130,6 +131,12 @@ impl BitcoinPreSignRequest {
             return Err(Error::PreSignInvalidFeeRate(self.fee_rate));
         }
 
+        if let Some(fee_rate) = self.rbf_target_fee_rate {
+            if !fee_rate.is_finite() || fee_rate <= 0.0 {
+                return Err(Error::PreSignInvalidFeeRate(fee_rate));
+            }
+        }
+
         Ok(())
     }
 
@@ -275,7 +282,9 @@ impl BitcoinPreSignRequest {
             utxo: signer_utxo,
             public_key: bitcoin::XOnlyPublicKey::from(btc_ctx.aggregate_key),
             last_fees: self.last_fees,
-            magic_bytes: [b'T', b'3'], //TODO(#472): Use the correct magic bytes.
+            magic_bytes: ctx.config().signer.network.magic_bytes(),
+            rbf_target_fee_rate: self.rbf_target_fee_rate,
+            anchor_output: ctx.config().signer.sweep_anchor_output,
         };
         let mut outputs = Vec::new();
 
@@ -334,6 +343,7 @@ impl BitcoinPreSignRequest {
             deposits,
             withdrawals,
             signer_state,
+            shuffle_seed: self.shuffle_seed,
         };
         let mut signer_state = signer_state;
         let tx = reports.create_transaction()?;
@@ -540,6 +550,9 @@ pub struct SbtcReports {
     /// Summary of the Signers' UTXO and information necessary for
     /// constructing their next UTXO.
     pub signer_state: SignerBtcState,
+    /// The seed used to shuffle the deposit inputs of the transaction, if
+    /// any.
+    pub shuffle_seed: Option<[u8; 32]>,
 }
 
 impl SbtcReports {
@@ -555,7 +568,10 @@ impl SbtcReports {
             .map(|(request, _)| RequestRef::Withdrawal(request));
 
         let state = &self.signer_state;
-        let requests = Requests::new(deposits.chain(withdrawals).collect());
+        let mut requests = Requests::new(deposits.chain(withdrawals).collect());
+        if let Some(seed) = self.shuffle_seed.as_ref() {
+            requests.shuffle(&tx_shuffle_seed(seed, &state.utxo.outpoint));
+        }
 
         UnsignedTransaction::new_stub(requests, state)
     }
@@ -1765,6 +1781,8 @@ mod tests {
             }],
             fee_rate: 1.0,
             last_fees: None,
+            shuffle_seed: None,
+            rbf_target_fee_rate: None,
         }, true; "unique-requests")]
     #[test_case(
         BitcoinPreSignRequest {
@@ -1794,6 +1812,8 @@ mod tests {
             }],
             fee_rate: 0.0,
             last_fees: None,
+            shuffle_seed: None,
+            rbf_target_fee_rate: None,
         }, false; "unique-requests-zero-fee-rate")]
     #[test_case(
         BitcoinPreSignRequest {
@@ -1823,6 +1843,8 @@ mod tests {
             }],
             fee_rate: -1.0,
             last_fees: None,
+            shuffle_seed: None,
+            rbf_target_fee_rate: None,
         }, false; "unique-requests-negative-fee-rate")]
     #[test_case(
         BitcoinPreSignRequest {
@@ -1852,6 +1874,8 @@ mod tests {
             }],
             fee_rate: 1.0,
             last_fees: None,
+            shuffle_seed: None,
+            rbf_target_fee_rate: None,
         }, false; "duplicate-deposits-in-same-tx")]
     #[test_case(
         BitcoinPreSignRequest {
@@ -1881,6 +1905,8 @@ mod tests {
             }],
             fee_rate: 1.0,
             last_fees: None,
+            shuffle_seed: None,
+            rbf_target_fee_rate: None,
         }, false; "duplicate-withdrawals-in-same-tx")]
     #[test_case(
         BitcoinPreSignRequest {
@@ -1912,6 +1938,8 @@ mod tests {
             ],
             fee_rate: 1.0,
             last_fees: None,
+            shuffle_seed: None,
+            rbf_target_fee_rate: None,
         }, false; "duplicate-withdrawal-request-ids-in-same-tx")]
     #[test_case(
         BitcoinPreSignRequest {
@@ -1950,12 +1978,16 @@ mod tests {
             ],
             fee_rate: 1.0,
             last_fees: None,
+            shuffle_seed: None,
+            rbf_target_fee_rate: None,
         }, false; "duplicate-requests-in-different-txs")]
     #[test_case(
         BitcoinPreSignRequest {
             request_package: Vec::new(),
             fee_rate: 1.0,
             last_fees: None,
+            shuffle_seed: None,
+            rbf_target_fee_rate: None,
         }, false; "empty-package_requests")]
     #[test_case(
         BitcoinPreSignRequest {
@@ -1971,6 +2003,8 @@ mod tests {
             ],
             fee_rate: 1.0,
             last_fees: None,
+            shuffle_seed: None,
+            rbf_target_fee_rate: None,
         }, false; "basically-empty-package_requests")]
     #[test_case(
         BitcoinPreSignRequest {
@@ -2006,7 +2040,51 @@ mod tests {
             ],
             fee_rate: 1.0,
             last_fees: None,
+            shuffle_seed: None,
+            rbf_target_fee_rate: None,
         }, false; "contains-empty-tx-requests")]
+    #[test_case(
+        BitcoinPreSignRequest {
+            request_package: vec![TxRequestIds {
+                deposits: vec![OutPoint {
+                    txid: Txid::from_byte_array([1; 32]),
+                    vout: 0,
+                }],
+                withdrawals: Vec::new(),
+            }],
+            fee_rate: 1.0,
+            last_fees: None,
+            shuffle_seed: None,
+            rbf_target_fee_rate: Some(2.0),
+        }, true; "positive-rbf-target-fee-rate")]
+    #[test_case(
+        BitcoinPreSignRequest {
+            request_package: vec![TxRequestIds {
+                deposits: vec![OutPoint {
+                    txid: Txid::from_byte_array([1; 32]),
+                    vout: 0,
+                }],
+                withdrawals: Vec::new(),
+            }],
+            fee_rate: 1.0,
+            last_fees: None,
+            shuffle_seed: None,
+            rbf_target_fee_rate: Some(0.0),
+        }, false; "zero-rbf-target-fee-rate")]
+    #[test_case(
+        BitcoinPreSignRequest {
+            request_package: vec![TxRequestIds {
+                deposits: vec![OutPoint {
+                    txid: Txid::from_byte_array([1; 32]),
+                    vout: 0,
+                }],
+                withdrawals: Vec::new(),
+            }],
+            fee_rate: 1.0,
+            last_fees: None,
+            shuffle_seed: None,
+            rbf_target_fee_rate: Some(f64::NAN),
+        }, false; "nan-rbf-target-fee-rate")]
     fn test_pre_validation(requests: BitcoinPreSignRequest, result: bool) {
         assert_eq!(requests.pre_validation().is_ok(), result);
     }
@@ -2108,6 +2186,7 @@ mod tests {
             None,
             None,
             Some(total_cap - sbtc_supply),
+            None,
         );
         // Create cache with test data
         let mut cache = ValidationCache::default();
@@ -18,19 +18,26 @@
 //! - Set aggregate key transactions
 
 use std::future::Future;
+use std::num::NonZeroUsize;
 use std::time::Duration;
 
+use crate::DEPOSIT_LOCKTIME_BLOCK_BUFFER;
+use crate::MAX_BITCOIN_HEADERS_PER_BATCH;
+use crate::SIGNER_SET_INFO_CACHE_MAX_AGE;
 use crate::bitcoin::BitcoinInteract;
 use crate::bitcoin::rpc::BitcoinBlockHeader;
+use crate::bitcoin::rpc::BitcoinBlockInfo;
 use crate::bitcoin::rpc::BitcoinTxInfo;
 use crate::bitcoin::utxo::TxDeconstructor as _;
+use crate::bitcoin::validation::DepositConfirmationStatus;
 use crate::context::Context;
 use crate::context::SbtcLimits;
 use crate::context::SignerEvent;
+use crate::emily_client::EmilyError;
 use crate::emily_client::EmilyInteract;
 use crate::error::Error;
 use crate::keys::PublicKey;
-use crate::keys::SignerScriptPubKey as _;
+use crate::keys::PublicKeyXOnly;
 use crate::metrics::BITCOIN_BLOCKCHAIN;
 use crate::metrics::Metrics;
 use crate::stacks::api::GetNakamotoStartHeight as _;
@@ -42,15 +49,23 @@ use crate::storage::DbWrite;
 use crate::storage::Transactable;
 use crate::storage::TransactionHandle;
 use crate::storage::model;
+use crate::storage::model::BitcoinBlockHeight;
 use crate::storage::model::EncryptedDkgShares;
 use bitcoin::Amount;
 use bitcoin::BlockHash;
 use bitcoin::ScriptBuf;
+use bitcoin::relative::LockTime;
+use emily_client::models::DepositStatus;
+use emily_client::models::DepositUpdate;
+use futures::FutureExt as _;
 use futures::stream::Stream;
 use futures::stream::StreamExt;
 use sbtc::deposits::CreateDepositRequest;
 use sbtc::deposits::DepositInfo;
+use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::collections::hash_map::Entry;
 
 /// Block observer
 #[derive(Debug)]
@@ -75,8 +90,62 @@ pub struct Deposit {
     pub block_hash: BlockHash,
 }
 
+impl Deposit {
+    /// Whether the lock time of the deposit is too close to elapsing for
+    /// the signers to sweep it, given the height of the block that
+    /// confirmed the deposit and the height of the bitcoin chain tip.
+    ///
+    /// The depositor can reclaim the funds in the block at the confirmed
+    /// height plus the lock time, and the signers stop sweeping a deposit
+    /// [`DEPOSIT_LOCKTIME_BLOCK_BUFFER`] blocks before then, so this
+    /// matches [`InputValidationResult::LockTimeExpiry`].
+    ///
+    /// [`InputValidationResult::LockTimeExpiry`]: crate::bitcoin::validation::InputValidationResult::LockTimeExpiry
+    pub fn is_past_sweep_window(
+        &self,
+        confirmed_height: BitcoinBlockHeight,
+        chain_tip_height: BitcoinBlockHeight,
+    ) -> bool {
+        match self.info.lock_time {
+            LockTime::Blocks(height) => {
+                let deposit_age = chain_tip_height.saturating_sub(confirmed_height);
+                let max_age = height
+                    .value()
+                    .saturating_sub(DEPOSIT_LOCKTIME_BLOCK_BUFFER)
+                    .into();
+                deposit_age >= max_age
+            }
+            // We do not sweep deposits with time based lock times, so
+            // there is nothing for the signers to expire.
+            LockTime::Time(_) => false,
+        }
+    }
+
+    /// Whether the depositor can reclaim the funds, given the height of
+    /// the block that confirmed the deposit and the height of the bitcoin
+    /// chain tip. This is when the deposit has expired.
+    pub fn is_reclaimable(
+        &self,
+        confirmed_height: BitcoinBlockHeight,
+        chain_tip_height: BitcoinBlockHeight,
+    ) -> bool {
+        match self.info.lock_time {
+            LockTime::Blocks(height) => {
+                let deposit_age = chain_tip_height.saturating_sub(confirmed_height);
+                deposit_age >= height.value().into()
+            }
+            LockTime::Time(_) => false,
+        }
+    }
+}
+
 impl DepositRequestValidator for CreateDepositRequest {
-    async fn validate<C>(&self, client: &C, is_mainnet: bool) -> Result<Option<Deposit>, Error>
+    async fn validate<C>(
+        &self,
+        client: &C,
+        is_mainnet: bool,
+        aggregate_key: Option<PublicKeyXOnly>,
+    ) -> Result<Option<Deposit>, Error>
     where
         C: BitcoinInteract,
     {
@@ -106,11 +175,20 @@ impl DepositRequestValidator for CreateDepositRequest {
         // info struct.
         tx_info.validate()?;
 
-        Ok(Some(Deposit {
-            info: self.validate_tx(&tx_info.tx, is_mainnet)?,
-            tx_info,
-            block_hash,
-        }))
+        let info = self.validate_tx(&tx_info.tx, is_mainnet)?;
+
+        if let Some(aggregate_key) = aggregate_key {
+            let signers_public_key = PublicKeyXOnly::from(info.signers_public_key);
+            if signers_public_key != aggregate_key {
+                return Err(Error::DepositWrongSignerKey(
+                    self.outpoint,
+                    signers_public_key,
+                    aggregate_key,
+                ));
+            }
+        }
+
+        Ok(Some(Deposit { info, tx_info, block_hash }))
     }
 }
 
@@ -122,10 +200,16 @@ pub trait DepositRequestValidator {
     /// This function fetches the transaction using the given client and
     /// checks that the transaction has been submitted. The transaction
     /// need not be confirmed.
+    ///
+    /// If an `aggregate_key` is given, then deposits that are locked to
+    /// any other public key are rejected with
+    /// [`Error::DepositWrongSignerKey`], since the signers holding that
+    /// key cannot sweep them.
     fn validate<C>(
         &self,
         client: &C,
         is_mainnet: bool,
+        aggregate_key: Option<PublicKeyXOnly>,
     ) -> impl Future<Output = Result<Option<Deposit>, Error>>
     where
         C: BitcoinInteract;
@@ -140,67 +224,198 @@ where
     #[tracing::instrument(skip_all, name = "block-observer")]
     pub async fn run(mut self) -> Result<(), Error> {
         let term = self.context.get_termination_handle();
+        let mut queue = VecDeque::new();
 
         loop {
             if term.shutdown_signalled() {
                 break;
             }
 
-            // Bitcoin blocks will generally arrive in ~10 minute intervals, so
-            // we don't need to be so aggressive in our timeout here.
-            let poll = tokio::time::timeout(Duration::from_millis(100), self.bitcoin_blocks.next());
-
-            match poll.await {
-                Ok(Some(Ok(block_hash))) => {
-                    tracing::info!("observed new bitcoin block from stream");
-                    metrics::counter!(
-                        Metrics::BlocksObservedTotal,
-                        "blockchain" => BITCOIN_BLOCKCHAIN,
-                    )
-                    .increment(1);
-
-                    if let Err(error) = self.process_bitcoin_blocks_until(block_hash).await {
-                        tracing::warn!(%error, %block_hash, "could not process bitcoin blocks");
+            self.enqueue_ready_block_hashes(&mut queue);
+
+            let block_hash = match queue.pop_front() {
+                Some(block_hash) => block_hash,
+                None => {
+                    // Bitcoin blocks will generally arrive in ~10 minute
+                    // intervals, so we don't need to be so aggressive in
+                    // our timeout here.
+                    let poll = tokio::time::timeout(
+                        Duration::from_millis(100),
+                        self.bitcoin_blocks.next(),
+                    );
+                    match poll.await {
+                        Ok(Some(Ok(block_hash))) => {
+                            self.context
+                                .state()
+                                .add_pending_block_hash(block_hash.into());
+                            block_hash
+                        }
+                        Ok(Some(Err(error))) => {
+                            tracing::warn!(%error, "error decoding new bitcoin block hash from stream");
+                            continue;
+                        }
+                        _ => continue,
                     }
+                }
+            };
 
-                    if let Err(error) = self.process_stacks_blocks().await {
-                        tracing::warn!(%error, "could not process stacks blocks");
-                    }
+            self.process_pending_block_hash(block_hash).await?;
+        }
 
-                    if let Err(error) = self.check_pending_dkg_shares(block_hash).await {
-                        tracing::warn!(%error, "could not check pending dkg shares");
-                        continue;
-                    }
+        tracing::info!("block observer has stopped");
 
-                    tracing::debug!("updating the signer state");
-                    if let Err(error) = self.update_signer_state(block_hash).await {
-                        tracing::warn!(%error, "could not update the signer state");
-                        continue;
-                    }
+        Ok(())
+    }
 
-                    tracing::info!("loading latest deposit requests from Emily");
-                    if let Err(error) = self.load_latest_deposit_requests().await {
-                        tracing::warn!(%error, "could not load latest deposit requests from Emily");
+    /// Move all block hashes that are immediately available on the block
+    /// hash stream into the given queue, marking them as pending in the
+    /// signer state. Block hashes that are already pending are skipped.
+    fn enqueue_ready_block_hashes(&mut self, queue: &mut VecDeque<BlockHash>) {
+        while let Some(Some(item)) = self.bitcoin_blocks.next().now_or_never() {
+            match item {
+                Ok(block_hash) => {
+                    if self
+                        .context
+                        .state()
+                        .add_pending_block_hash(block_hash.into())
+                    {
+                        queue.push_back(block_hash);
                     }
-
-                    self.context
-                        .signal(SignerEvent::BitcoinBlockObserved.into())?;
                 }
-                Ok(Some(Err(error))) => {
+                Err(error) => {
                     tracing::warn!(%error, "error decoding new bitcoin block hash from stream");
-                    continue;
                 }
-                _ => continue,
-            };
+            }
         }
-
-        tracing::info!("block observer has stopped");
-
-        Ok(())
     }
 }
 
 impl<C: Context, B> BlockObserver<C, B> {
+    /// Process a bitcoin block hash received over the block hash stream,
+    /// removing it from the signer state's pending block hashes once
+    /// done, regardless of the outcome.
+    async fn process_pending_block_hash(&self, block_hash: BlockHash) -> Result<(), Error> {
+        let result = self.process_new_block_hash(block_hash).await;
+        self.context
+            .state()
+            .remove_pending_block_hash(&block_hash.into());
+        result
+    }
+
+    /// Process a new bitcoin block hash received over the block hash
+    /// stream, updating the database and the signer state and notifying
+    /// the other event loops.
+    ///
+    /// A pass counts as a single storage error towards the storage error
+    /// shutdown threshold however many of its steps fail with one, and a
+    /// pass without any storage errors resets the count.
+    async fn process_new_block_hash(&self, block_hash: BlockHash) -> Result<(), Error> {
+        let mut storage_error = None;
+        let result = self
+            .process_new_block_hash_steps(block_hash, &mut storage_error)
+            .await;
+
+        match storage_error {
+            Some(error) => {
+                self.context.shutdown_on_storage_error(&error);
+            }
+            None => self.context.state().reset_storage_errors(),
+        }
+        result
+    }
+
+    /// Run the steps for processing a new bitcoin block hash, keeping the
+    /// first storage error encountered in the given `storage_error`.
+    async fn process_new_block_hash_steps(
+        &self,
+        block_hash: BlockHash,
+        storage_error: &mut Option<Error>,
+    ) -> Result<(), Error> {
+        tracing::info!("observed new bitcoin block from stream");
+        metrics::counter!(
+            Metrics::BlocksObservedTotal,
+            "blockchain" => BITCOIN_BLOCKCHAIN,
+        )
+        .increment(1);
+
+        let previous_tip = match self
+            .context
+            .get_storage()
+            .get_bitcoin_canonical_chain_tip_ref()
+            .await
+        {
+            Ok(previous_tip) => previous_tip,
+            Err(error) => {
+                tracing::warn!(%error, "could not fetch the bitcoin chain tip");
+                keep_storage_error(storage_error, error);
+                None
+            }
+        };
+
+        if let Err(error) = self.process_bitcoin_blocks_until(block_hash).await {
+            tracing::warn!(%error, %block_hash, "could not process bitcoin blocks");
+            keep_storage_error(storage_error, error);
+        }
+
+        if let Some(previous_tip) = previous_tip {
+            match self.find_reorg(&previous_tip, &block_hash.into()).await {
+                Ok(Some(event)) => self.context.signal(event.into())?,
+                Ok(None) => {}
+                Err(error) => {
+                    tracing::warn!(%error, "could not check for a bitcoin reorg");
+                    keep_storage_error(storage_error, error);
+                }
+            }
+        }
+
+        match self.process_stacks_blocks().await {
+            Ok(Some(event)) => self.context.signal(event.into())?,
+            Ok(None) => {}
+            Err(error) => {
+                tracing::warn!(%error, "could not process stacks blocks");
+                keep_storage_error(storage_error, error);
+            }
+        }
+
+        if let Err(error) = self.check_pending_dkg_shares(block_hash).await {
+            tracing::warn!(%error, "could not check pending dkg shares");
+            keep_storage_error(storage_error, error);
+            return Ok(());
+        }
+
+        if let Err(error) = self.prune_failed_dkg_shares(block_hash).await {
+            tracing::warn!(%error, "could not prune failed dkg shares");
+            keep_storage_error(storage_error, error);
+        }
+
+        if let Err(error) = self.prune_effective_limits(block_hash).await {
+            tracing::warn!(%error, "could not prune the effective sBTC limits");
+            keep_storage_error(storage_error, error);
+        }
+
+        tracing::debug!("updating the signer state");
+        if let Err(error) = self.update_signer_state(block_hash).await {
+            tracing::warn!(%error, "could not update the signer state");
+            keep_storage_error(storage_error, error);
+            return Ok(());
+        }
+
+        tracing::info!("loading latest deposit requests from Emily");
+        if let Err(error) = self.load_latest_deposit_requests().await {
+            match error.emily_error() {
+                Some(EmilyError::Unauthorized { .. }) => {
+                    tracing::error!(%error, "Emily rejected our credentials, check the API key");
+                }
+                _ => {
+                    tracing::warn!(%error, "could not load latest deposit requests from Emily");
+                }
+            }
+        }
+
+        self.context
+            .signal(SignerEvent::BitcoinBlockObserved.into())
+    }
+
     /// Fetch deposit requests from Emily and store the ones that pass
     /// validation into the database.
     #[tracing::instrument(skip_all)]
@@ -210,7 +425,11 @@ impl<C: Context, B> BlockObserver<C, B> {
     }
 
     /// Validate the given deposit requests and store the ones that pass
-    /// validation into the database.
+    /// validation into the database. Deposit requests whose lock time
+    /// elapses within [`DEPOSIT_LOCKTIME_BLOCK_BUFFER`] blocks of the
+    /// current bitcoin chain tip are not stored, since sweeping them would
+    /// race the depositor's reclaim. Of those, the ones that the depositor
+    /// can already reclaim are reported to Emily as expired.
     ///
     /// There are three types of errors that can happen during validation
     /// 1. The transaction fails primary validation. This means the deposit
@@ -225,12 +444,24 @@ impl<C: Context, B> BlockObserver<C, B> {
     pub async fn load_requests(&self, requests: &[CreateDepositRequest]) -> Result<(), Error> {
         let mut deposit_requests = Vec::new();
         let mut deposit_request_txs = Vec::new();
+        let mut expired_deposits = Vec::new();
         let bitcoin_client = self.context.get_bitcoin_client();
         let is_mainnet = self.context.config().signer.network.is_mainnet();
+        let chain_tip = self.context.state().bitcoin_chain_tip();
+        let chain_tip_height = chain_tip.block_height;
+        let context_window = self.context.config().signer.context_window;
+        let per_recipient_cap = self
+            .context
+            .state()
+            .get_current_limits()
+            .per_recipient_cap();
+        // The total amount of pending deposits for each recipient,
+        // including the deposits that we accept in this batch.
+        let mut pending_by_recipient: HashMap<model::StacksPrincipal, Amount> = HashMap::new();
 
         for request in requests {
             let deposit = request
-                .validate(&bitcoin_client, is_mainnet)
+                .validate(&bitcoin_client, is_mainnet, None)
                 .await
                 .inspect_err(|error| tracing::warn!(%error, "could not validate deposit request"));
 
@@ -242,12 +473,93 @@ impl<C: Context, B> BlockObserver<C, B> {
             self.process_bitcoin_blocks_until(deposit.block_hash)
                 .await?;
 
+            let confirmed_height = self
+                .context
+                .get_storage()
+                .get_bitcoin_block(&deposit.block_hash.into())
+                .await?
+                .map(|block| block.block_height);
+            let is_past_sweep_window = confirmed_height
+                .is_some_and(|height| deposit.is_past_sweep_window(height, chain_tip_height));
+            // The depositor can reclaim the funds soon, so sweeping the
+            // deposit would race the reclaim transaction. A deposit that
+            // has already been swept cannot be reclaimed though, so we
+            // leave it alone.
+            if is_past_sweep_window
+                && !self
+                    .is_deposit_swept(&deposit, &chain_tip.block_hash)
+                    .await?
+            {
+                tracing::warn!(
+                    outpoint = %deposit.info.outpoint,
+                    %chain_tip_height,
+                    "skipping deposit request whose lock time is about to elapse"
+                );
+                // We only tell Emily that the deposit has expired once
+                // the depositor can actually reclaim it.
+                let is_reclaimable = confirmed_height
+                    .is_some_and(|height| deposit.is_reclaimable(height, chain_tip_height));
+                if !is_reclaimable {
+                    continue;
+                }
+                expired_deposits.push(DepositUpdate {
+                    bitcoin_tx_output_index: deposit.info.outpoint.vout,
+                    bitcoin_txid: deposit.info.outpoint.txid.to_string(),
+                    status: DepositStatus::Expired,
+                    fulfillment: None,
+                    status_message: "The lock time of the deposit has elapsed".to_string(),
+                    reclaim_txid: None,
+                    replaced_by_tx: None,
+                });
+                continue;
+            }
+
             let tx = model::BitcoinTxRef {
                 txid: deposit.tx_info.compute_txid().into(),
                 block_hash: deposit.block_hash.into(),
             };
+            let deposit_request = model::DepositRequest::from(deposit);
+
+            // Deposits that we already know about have been counted
+            // towards the recipient's pending total, so we only check the
+            // per-recipient cap for new deposits.
+            let db = self.context.get_storage();
+            let is_known = db
+                .deposit_request_exists(&deposit_request.txid, deposit_request.output_index)
+                .await?;
+
+            if !is_known && per_recipient_cap < Amount::MAX_MONEY {
+                let pending = match pending_by_recipient.entry(deposit_request.recipient.clone()) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        let amount = db
+                            .get_pending_deposit_amount_for_recipient(
+                                entry.key(),
+                                &chain_tip.block_hash,
+                                context_window,
+                            )
+                            .await?;
+                        entry.insert(amount)
+                    }
+                };
+
+                let amount = Amount::from_sat(deposit_request.amount);
+                if *pending + amount > per_recipient_cap {
+                    tracing::warn!(
+                        txid = %deposit_request.txid,
+                        output_index = %deposit_request.output_index,
+                        recipient = %deposit_request.recipient,
+                        pending = %pending.to_sat(),
+                        %per_recipient_cap,
+                        "skipping deposit request that would exceed the per-recipient cap"
+                    );
+                    continue;
+                }
+                *pending += amount;
+            }
 
-            deposit_requests.push(model::DepositRequest::from(deposit));
+            tracing::debug!(%deposit_request, "storing deposit request");
+            deposit_requests.push(deposit_request);
             deposit_request_txs.push(tx);
         }
 
@@ -255,10 +567,43 @@ impl<C: Context, B> BlockObserver<C, B> {
         db.write_bitcoin_transactions(deposit_request_txs).await?;
         db.write_deposit_requests(deposit_requests).await?;
 
+        // Expired deposits will never be swept, so we let Emily know that
+        // it can stop handing them out as pending or accepted.
+        if !expired_deposits.is_empty() {
+            let emily_client = self.context.get_emily_client();
+            if let Err(error) = emily_client.update_deposits(expired_deposits).await {
+                tracing::warn!(%error, "could not mark expired deposit requests in Emily");
+            }
+        }
+
         tracing::debug!("finished processing deposit requests");
         Ok(())
     }
 
+    /// Whether the given deposit has been swept by a transaction confirmed
+    /// on the canonical bitcoin blockchain identified by the given chain
+    /// tip.
+    async fn is_deposit_swept(
+        &self,
+        deposit: &Deposit,
+        chain_tip: &model::BitcoinBlockHash,
+    ) -> Result<bool, Error> {
+        let signer_public_key = self.context.config().signer.public_key();
+        let report = self
+            .context
+            .get_storage()
+            .get_deposit_request_report(
+                chain_tip,
+                &deposit.info.outpoint.txid.into(),
+                deposit.info.outpoint.vout,
+                &signer_public_key,
+            )
+            .await?;
+
+        Ok(report
+            .is_some_and(|report| matches!(report.status, DepositConfirmationStatus::Spent(_))))
+    }
+
     /// Set the sbtc start height, if it has not been set already.
     async fn set_sbtc_bitcoin_start_height(&self) -> Result<(), Error> {
         if self.context.state().is_sbtc_bitcoin_start_height_set() {
@@ -290,27 +635,51 @@ impl<C: Context, B> BlockObserver<C, B> {
     ///    encounters a known block header or if the height of the block is
     ///    less than or equal to the `sbtc_bitcoin_start_height`.
     ///
+    /// If `max_batch` is set then at most that many headers are returned,
+    /// starting with the header at the lowest unknown height. Once those
+    /// blocks have been written to the database, the next call picks up
+    /// where this one left off.
+    ///
     /// If there are many unknown blocks then this function can take some
-    /// time. Since each header is 80 bytes, we should be able to fetch
-    /// headers for the entire bitcoin blockchain (~900k blocks at the time
-    /// of writing) into memory.
-    #[tracing::instrument(skip_all, fields(%block_hash))]
+    /// time, since it still needs to walk back to the lowest unknown
+    /// header, even if it only returns some of them.
+    ///
+    /// The header of the given block is fetched using the regular bitcoin
+    /// client, while the headers of any unknown ancestors are backfilled
+    /// using [`BlockObserver::get_backfill_block_header`].
     pub async fn next_headers_to_process(
         &self,
-        mut block_hash: BlockHash,
+        block_hash: BlockHash,
+        max_batch: Option<NonZeroUsize>,
     ) -> Result<Vec<BitcoinBlockHeader>, Error> {
+        let (headers, _) = self.unknown_headers(block_hash, max_batch).await?;
+        Ok(headers)
+    }
+
+    /// Walk back from the given block to the lowest unknown block,
+    /// returning at most `max_batch` of the lowest unknown headers,
+    /// ascending, along with the hashes of the remaining unknown blocks,
+    /// also ascending.
+    ///
+    /// The returned hashes are a cursor for the blocks that come after
+    /// the returned headers, so that callers can process them without
+    /// walking back again.
+    #[tracing::instrument(skip_all, fields(%block_hash))]
+    async fn unknown_headers(
+        &self,
+        mut block_hash: BlockHash,
+        max_batch: Option<NonZeroUsize>,
+    ) -> Result<(Vec<BitcoinBlockHeader>, Vec<BlockHash>), Error> {
         self.set_sbtc_bitcoin_start_height().await?;
 
         let start_height = self.context.state().get_sbtc_bitcoin_start_height();
-        let mut headers = std::collections::VecDeque::new();
+        let mut headers = VecDeque::new();
+        let mut remaining = VecDeque::new();
         let db = self.context.get_storage();
-        let bitcoin_client = self.context.get_bitcoin_client();
+        let tip_hash = block_hash;
 
         while !db.is_known_bitcoin_block_hash(&block_hash.into()).await? {
-            let Some(header) = bitcoin_client.get_block_header(&block_hash).await? else {
-                tracing::error!(%block_hash, "bitcoin-core does not know about block header");
-                return Err(Error::BitcoinCoreUnknownBlockHeader(block_hash));
-            };
+            let header = self.get_block_header(&block_hash, &tip_hash).await?;
 
             // We don't even try to write blocks to the database if the
             // height is less than the start height.
@@ -322,13 +691,45 @@ impl<C: Context, B> BlockObserver<C, B> {
             block_hash = header.previous_block_hash;
             headers.push_front(header);
 
+            // We are walking backwards, so the headers at the back have
+            // the greatest heights, and they get processed in a later
+            // batch. We only need to remember their hashes.
+            if max_batch.is_some_and(|max| headers.len() > max.get()) {
+                if let Some(header) = headers.pop_back() {
+                    remaining.push_front(header.hash);
+                }
+            }
+
             // We can write the block at the start height to the database.
             if at_start_height {
                 break;
             }
         }
 
-        Ok(headers.into())
+        Ok((headers.into(), remaining.into()))
+    }
+
+    /// Fetch the header of a block that we need to process on our way to
+    /// the given chain tip.
+    ///
+    /// The header of the chain tip is fetched using the regular bitcoin
+    /// client, while the headers of its ancestors are backfilled using
+    /// [`BlockObserver::get_backfill_block_header`].
+    async fn get_block_header(
+        &self,
+        block_hash: &BlockHash,
+        tip_hash: &BlockHash,
+    ) -> Result<BitcoinBlockHeader, Error> {
+        let header = if block_hash == tip_hash {
+            let bitcoin_client = self.context.get_bitcoin_client();
+            bitcoin_client.get_block_header(block_hash).await?
+        } else {
+            self.get_backfill_block_header(block_hash).await?
+        };
+        header.ok_or_else(|| {
+            tracing::error!(%block_hash, "bitcoin-core does not know about block header");
+            Error::BitcoinCoreUnknownBlockHeader(*block_hash)
+        })
     }
 
     /// Process bitcoin blocks until we get caught up to the given
@@ -346,27 +747,159 @@ impl<C: Context, B> BlockObserver<C, B> {
     ///
     /// This means that if we stop processing blocks midway though,
     /// subsequent calls to this function will properly pick up from where
-    /// we left off and update the database.
+    /// we left off and update the database. At most
+    /// [`MAX_BITCOIN_HEADERS_PER_BATCH`] headers are held in memory during
+    /// the walk back; the remaining blocks are tracked by their hashes and
+    /// their headers are fetched as they get processed, so we only walk
+    /// back once.
     async fn process_bitcoin_blocks_until(&self, block_hash: BlockHash) -> Result<(), Error> {
-        let block_headers = self.next_headers_to_process(block_hash).await?;
+        let (block_headers, remaining) = self
+            .unknown_headers(block_hash, Some(MAX_BITCOIN_HEADERS_PER_BATCH))
+            .await?;
 
         for block_header in block_headers {
-            self.process_bitcoin_block(block_header).await?;
+            let is_backfill = block_header.hash != block_hash;
+            self.process_bitcoin_block(block_header, is_backfill)
+                .await?;
+        }
+
+        for hash in remaining {
+            let block_header = self.get_block_header(&hash, &block_hash).await?;
+            self.process_bitcoin_block(block_header, hash != block_hash)
+                .await?;
         }
 
         Ok(())
     }
 
+    /// Fetch the header of a block that is being backfilled.
+    ///
+    /// The header is fetched using the backfill bitcoin client, falling
+    /// back to the regular bitcoin client if the backfill client fails or
+    /// does not know about the block, since it may lag behind.
+    async fn get_backfill_block_header(
+        &self,
+        block_hash: &BlockHash,
+    ) -> Result<Option<BitcoinBlockHeader>, Error> {
+        let backfill_client = self.context.get_backfill_bitcoin_client();
+        match backfill_client.get_block_header(block_hash).await {
+            Ok(Some(header)) => return Ok(Some(header)),
+            Ok(None) => {}
+            Err(error) => {
+                tracing::warn!(%error, %block_hash, "could not fetch block header from the backfill bitcoin client");
+            }
+        }
+        self.context
+            .get_bitcoin_client()
+            .get_block_header(block_hash)
+            .await
+    }
+
+    /// Fetch a block that is being backfilled.
+    ///
+    /// The block is fetched using the backfill bitcoin client, falling
+    /// back to the regular bitcoin client if the backfill client fails or
+    /// does not know about the block, since it may lag behind.
+    async fn get_backfill_block(
+        &self,
+        block_hash: &BlockHash,
+    ) -> Result<Option<BitcoinBlockInfo>, Error> {
+        let backfill_client = self.context.get_backfill_bitcoin_client();
+        match backfill_client.get_block(block_hash).await {
+            Ok(Some(block)) => return Ok(Some(block)),
+            Ok(None) => {}
+            Err(error) => {
+                tracing::warn!(%error, %block_hash, "could not fetch block from the backfill bitcoin client");
+            }
+        }
+        self.context
+            .get_bitcoin_client()
+            .get_block(block_hash)
+            .await
+    }
+
+    /// Check whether the block with the given hash orphaned the given
+    /// previous canonical chain tip, returning a
+    /// [`SignerEvent::BitcoinReorgDetected`] event if it did.
+    ///
+    /// This walks back from the block and the previous chain tip until
+    /// the two chains meet. The previous chain tip was not orphaned if it
+    /// is an ancestor of the block. This function assumes that the block
+    /// has already been processed, and returns `Ok(None)` if we do not
+    /// have enough blocks in the database to find the common ancestor.
+    async fn find_reorg(
+        &self,
+        previous_tip: &model::BitcoinBlockRef,
+        block_hash: &model::BitcoinBlockHash,
+    ) -> Result<Option<SignerEvent>, Error> {
+        let db = self.context.get_storage();
+
+        let Some(mut new_chain) = db.get_bitcoin_block(block_hash).await? else {
+            return Ok(None);
+        };
+        let Some(mut old_chain) = db.get_bitcoin_block(&previous_tip.block_hash).await? else {
+            return Ok(None);
+        };
+
+        while new_chain.block_hash != old_chain.block_hash {
+            let new_height = new_chain.block_height;
+            let old_height = old_chain.block_height;
+
+            if new_height >= old_height {
+                let Some(block) = db.get_bitcoin_block(&new_chain.parent_hash).await? else {
+                    return Ok(None);
+                };
+                new_chain = block;
+            }
+            if old_height >= new_height {
+                let Some(block) = db.get_bitcoin_block(&old_chain.parent_hash).await? else {
+                    return Ok(None);
+                };
+                old_chain = block;
+            }
+        }
+
+        if old_chain.block_hash == previous_tip.block_hash {
+            return Ok(None);
+        }
+
+        let common_ancestor = model::BitcoinBlockRef::from(old_chain);
+        let depth = previous_tip.block_height - common_ancestor.block_height;
+        tracing::warn!(
+            orphaned_tip = %previous_tip.block_hash,
+            common_ancestor = %common_ancestor.block_hash,
+            %depth,
+            "detected a bitcoin reorg"
+        );
+
+        Ok(Some(SignerEvent::BitcoinReorgDetected {
+            common_ancestor,
+            orphaned_tip: *previous_tip,
+            depth,
+        }))
+    }
+
     /// Write the bitcoin block and any transactions that spend to any of
     /// the signers `scriptPubKey`s to the database.
+    ///
+    /// Blocks that are being backfilled are fetched using
+    /// [`BlockObserver::get_backfill_block`], all others using the regular
+    /// bitcoin client.
     #[tracing::instrument(skip_all, fields(block_hash = %block_header.hash))]
-    async fn process_bitcoin_block(&self, block_header: BitcoinBlockHeader) -> Result<(), Error> {
-        let block = self
-            .context
-            .get_bitcoin_client()
-            .get_block(&block_header.hash)
-            .await?
-            .ok_or(Error::BitcoinCoreMissingBlock(block_header.hash))?;
+    async fn process_bitcoin_block(
+        &self,
+        block_header: BitcoinBlockHeader,
+        is_backfill: bool,
+    ) -> Result<(), Error> {
+        let block = if is_backfill {
+            self.get_backfill_block(&block_header.hash).await?
+        } else {
+            self.context
+                .get_bitcoin_client()
+                .get_block(&block_header.hash)
+                .await?
+        };
+        let block = block.ok_or(Error::BitcoinCoreMissingBlock(block_header.hash))?;
         let db_block = model::BitcoinBlock::from(&block);
 
         let storage = self.context.get_storage_mut();
@@ -374,8 +907,10 @@ impl<C: Context, B> BlockObserver<C, B> {
         // When a signer is not part of the bootstrap signing set but is
         // joining the set as a new signer, it will not have the signers
         // original scriptPubKey in its database, so it relies on the config
-        // to inform them of what it is.
-        let bootstrap_script_pubkey = self.context.config().signer.bootstrap_aggregate_key;
+        // to inform them of what it is. After a key rotation, the new
+        // scriptPubKey may not be in our database either, so we also
+        // include the ones from the sbtc-registry.
+        let signer_script_pubkeys = self.context.signer_script_set();
 
         // Begin a storage transaction.
         let storage_tx = storage.begin_transaction().await?;
@@ -385,9 +920,11 @@ impl<C: Context, B> BlockObserver<C, B> {
 
         // Extract the sBTC-related transactions from the block and write them
         // to the database (within the transaction).
+        let magic_bytes = self.context.config().signer.network.magic_bytes();
         extract_sbtc_transactions(
             &storage_tx,
-            bootstrap_script_pubkey,
+            &signer_script_pubkeys,
+            magic_bytes,
             block_header.hash,
             &block.transactions,
         )
@@ -401,8 +938,11 @@ impl<C: Context, B> BlockObserver<C, B> {
     }
 
     /// Process all recent stacks blocks.
+    ///
+    /// Returns a [`SignerEvent::StacksBlockObserved`] event for the
+    /// highest stacks block if it was not already in the database.
     #[tracing::instrument(skip_all)]
-    async fn process_stacks_blocks(&self) -> Result<(), Error> {
+    async fn process_stacks_blocks(&self) -> Result<Option<SignerEvent>, Error> {
         tracing::info!("processing stacks block");
         let stacks_client = self.context.get_stacks_client();
         let db = self.context.get_storage_mut();
@@ -421,15 +961,32 @@ impl<C: Context, B> BlockObserver<C, B> {
             .flat_map(TenureBlockHeaders::into_iter)
             .collect::<Vec<_>>();
 
+        let tip = headers.iter().max_by_key(|header| header.block_height);
+        let new_tip = match tip {
+            Some(tip) if !db.stacks_block_exists(*tip.block_hash).await? => Some(tip.clone()),
+            _ => None,
+        };
+
         db.write_stacks_block_headers(headers).await?;
 
         tracing::debug!("finished processing stacks block");
-        Ok(())
+        Ok(new_tip.map(|tip| SignerEvent::StacksBlockObserved {
+            block_hash: tip.block_hash,
+            height: tip.block_height,
+        }))
     }
 
     /// Update the sBTC peg limits from Emily
     async fn update_sbtc_limits(&self, chain_tip: BlockHash) -> Result<(), Error> {
-        let limits = self.context.get_emily_client().get_limits().await?;
+        // Operators may configure stricter limits locally, which Emily
+        // cannot relax.
+        let local_limits = self.context.config().signer.local_sbtc_limits();
+        let limits = self
+            .context
+            .get_emily_client()
+            .get_limits()
+            .await?
+            .merge(&local_limits);
         let sbtc_deployed = self.context.state().sbtc_contracts_deployed();
 
         let max_mintable = if limits.total_cap_exists() && sbtc_deployed {
@@ -464,7 +1021,16 @@ impl<C: Context, B> BlockObserver<C, B> {
             Some(rolling_limits.cap),
             Some(withdrawn_total),
             Some(max_mintable),
+            Some(limits.per_recipient_cap()),
         );
+
+        // Keep a record of the limits that apply to this chain tip, so
+        // that we can tell which limits any sweep was built under.
+        self.context
+            .get_storage_mut()
+            .write_effective_limits(&chain_tip.into(), &limits)
+            .await?;
+
         let signer_state = self.context.state();
         if limits == signer_state.get_current_limits() {
             tracing::trace!(%limits, "sBTC limits have not changed");
@@ -574,11 +1140,82 @@ impl<C: Context, B> BlockObserver<C, B> {
 
         Ok(())
     }
+
+    /// Deletes the DKG shares that have been marked as failed once they
+    /// have been around for longer than the retention window, so that
+    /// they do not accumulate forever.
+    async fn prune_failed_dkg_shares(&self, chain_tip: BlockHash) -> Result<(), Error> {
+        let db = self.context.get_storage_mut();
+
+        let chain_tip = db
+            .get_bitcoin_block(&chain_tip.into())
+            .await?
+            .ok_or(Error::NoChainTip)?;
+        let retention_window = self
+            .context
+            .config()
+            .signer
+            .dkg_failed_shares_retention_window;
+
+        let started_before = chain_tip
+            .block_height
+            .saturating_sub(retention_window as u64);
+
+        let num_pruned = db.prune_failed_dkg_shares(started_before).await?;
+        if num_pruned > 0 {
+            tracing::info!(
+                %num_pruned,
+                %started_before,
+                "deleted failed DKG shares outside of the retention window"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Deletes the recorded sBTC limits for bitcoin blocks that are
+    /// outside of the retention window, so that the history does not
+    /// grow forever.
+    async fn prune_effective_limits(&self, chain_tip: BlockHash) -> Result<(), Error> {
+        let db = self.context.get_storage_mut();
+
+        let chain_tip = db
+            .get_bitcoin_block(&chain_tip.into())
+            .await?
+            .ok_or(Error::NoChainTip)?;
+        let retention_window = self
+            .context
+            .config()
+            .signer
+            .effective_limits_retention_window;
+
+        let applied_before = chain_tip
+            .block_height
+            .saturating_sub(retention_window as u64);
+
+        let num_pruned = db.prune_effective_limits(applied_before).await?;
+        if num_pruned > 0 {
+            tracing::debug!(
+                %num_pruned,
+                %applied_before,
+                "deleted effective sBTC limits outside of the retention window"
+            );
+        }
+
+        Ok(())
+    }
 }
 
 /// Extract all BTC transactions from the block where one of the UTXOs
 /// can be spent by the signers.
 ///
+/// The given `candidate_script_pubkeys` are treated as signer
+/// scriptPubKeys in addition to the ones that we have in the database.
+/// During a key rotation both the old and the new scriptPubKeys of the
+/// signers can be live at once, and all of them are matched against. Any
+/// new scriptPubKey that the signers lock their UTXO with in this block
+/// is learned before the transactions are classified.
+///
 /// # Note
 ///
 /// When using the postgres storage, we need to make sure that this
@@ -586,111 +1223,139 @@ impl<C: Context, B> BlockObserver<C, B> {
 /// because of the foreign key constraints.
 pub async fn extract_sbtc_transactions<Storage>(
     db: &Storage,
-    bootstrap_aggregate_key: Option<PublicKey>,
+    candidate_script_pubkeys: &HashSet<ScriptBuf>,
+    magic_bytes: [u8; 2],
     block_hash: BlockHash,
     txs: &[BitcoinTxInfo],
 ) -> Result<(), Error>
 where
     Storage: DbRead + DbWrite,
 {
-    // Convert the bootstrap script public key to a `ScriptBuf` if it is
-    // provided. This is used to check if the transaction outputs are
-    // spent to the bootstrap signers' addresses.
-    let bootstrap_script_pubkey = bootstrap_aggregate_key.map(|key| key.signers_script_pubkey());
-
-    // Define a closure to extract the sBTC transactions from the given
-    // transactions and write them to the database.
-    let extract_fut = || async {
-        // We store all the scriptPubKeys associated with the signers'
-        // aggregate public key. Let's get the last years worth of them.
-        let signer_script_pubkeys: HashSet<ScriptBuf> = db
-            .get_signers_script_pubkeys()
-            .await?
-            .into_iter()
-            .map(ScriptBuf::from_bytes)
-            .chain(bootstrap_script_pubkey.clone())
-            .collect();
-
-        // Look through all the UTXOs in the given transaction slice and
-        // keep the transactions where a UTXO is locked with a
-        // `scriptPubKey` controlled by the signers.
-        let mut sbtc_txs = Vec::new();
-        for tx_info in txs {
-            let txid = tx_info.compute_txid();
-            tracing::trace!(%txid, "attempting to extract sbtc transaction");
-            if tx_info.tx.is_coinbase() {
-                continue;
+    // We store all the scriptPubKeys associated with the signers'
+    // aggregate public key. Let's get the last years worth of them.
+    let mut signer_script_pubkeys: HashSet<ScriptBuf> = db
+        .get_signers_script_pubkeys()
+        .await?
+        .into_iter()
+        .map(ScriptBuf::from_bytes)
+        .chain(candidate_script_pubkeys.iter().cloned())
+        .collect();
+
+    // The signers may have locked their UTXO with a scriptPubKey that we
+    // did not know about, and a transaction earlier in this block may pay
+    // to it too. So we learn the scriptPubKeys of the signers' outputs in
+    // this block before classifying any transaction. Transactions in a
+    // bitcoin block are ordered so that a transaction comes after the ones
+    // that it spends, so one pass finds all of them.
+    for tx_info in txs.iter().filter(|tx_info| !tx_info.tx.is_coinbase()) {
+        if tx_info.is_signer_created(&signer_script_pubkeys) {
+            if let Some(signers_output) = tx_info.tx.output.first() {
+                signer_script_pubkeys.insert(signers_output.script_pubkey.clone());
             }
+        }
+    }
 
-            // Bail if bitcoin-core doesn't return all the data that we
-            // care about for a non-coinbase transaction. This will happen
-            // if bitcoin core hasn't computed the undo data for the block
-            // with these transactions, of it there is a bug in bitcoin
-            // core.
-            tx_info.validate()?;
-
-            // If any of the outputs are spent to one of the signers'
-            // addresses, then we care about it
-            let outputs_spent_to_signers = tx_info
-                .tx
-                .output
-                .iter()
-                .any(|tx_out| signer_script_pubkeys.contains(&tx_out.script_pubkey));
-
-            // We might not know about the new scriptPubKey, but we are
-            // supposed to know about all existing scriptPubKeys, so we
-            // check the inputs as well.
-            let inputs_spent_by_signers = tx_info
-                .vin
-                .iter()
-                .filter_map(|vin| vin.prevout.as_ref())
-                .any(|prevout| signer_script_pubkeys.contains(&prevout.script_pubkey.script));
-
-            if !outputs_spent_to_signers && !inputs_spent_by_signers {
-                continue;
-            }
+    // Look through all the UTXOs in the given transaction slice and
+    // keep the transactions where a UTXO is locked with a
+    // `scriptPubKey` controlled by the signers.
+    let mut sbtc_txs = Vec::new();
+    for tx_info in txs {
+        let txid = tx_info.compute_txid();
+        tracing::trace!(%txid, "attempting to extract sbtc transaction");
+        if tx_info.tx.is_coinbase() {
+            continue;
+        }
+
+        // Bail if bitcoin-core doesn't return all the data that we
+        // care about for a non-coinbase transaction. This will happen
+        // if bitcoin core hasn't computed the undo data for the block
+        // with these transactions, of it there is a bug in bitcoin
+        // core.
+        tx_info.validate()?;
 
-            sbtc_txs.push(model::BitcoinTxRef {
+        // If any of the outputs are spent to one of the signers'
+        // addresses, then we care about it
+        let outputs_spent_to_signers = tx_info
+            .tx
+            .output
+            .iter()
+            .any(|tx_out| signer_script_pubkeys.contains(&tx_out.script_pubkey));
+
+        // We might not know about the new scriptPubKey, but we are
+        // supposed to know about all existing scriptPubKeys, so we
+        // check the inputs as well.
+        let inputs_spent_by_signers = tx_info
+            .vin
+            .iter()
+            .filter_map(|vin| vin.prevout.as_ref())
+            .any(|prevout| signer_script_pubkeys.contains(&prevout.script_pubkey.script));
+
+        if !outputs_spent_to_signers && !inputs_spent_by_signers {
+            continue;
+        }
+
+        if let Some(reason) = tx_info.anomalous_spend_reason(&signer_script_pubkeys) {
+            tracing::warn!(
+                %txid,
+                %block_hash,
+                %reason,
+                "transaction spends a signer output but was not created by the signers"
+            );
+            let spend = model::AnomalousSpend {
                 txid: txid.into(),
                 block_hash: block_hash.into(),
-            });
-
-            for prevout in tx_info.to_inputs(&signer_script_pubkeys) {
-                db.write_tx_prevout(&prevout).await?;
-                if prevout.prevout_type == model::TxPrevoutType::Deposit {
-                    metrics::counter!(
-                        Metrics::DepositsSweptTotal,
-                        "blockchain" => BITCOIN_BLOCKCHAIN,
-                    )
-                    .increment(1);
-                }
-            }
+                reason,
+            };
+            db.write_anomalous_spend(&spend).await?;
+        }
+
+        sbtc_txs.push(model::BitcoinTxRef {
+            txid: txid.into(),
+            block_hash: block_hash.into(),
+        });
 
-            let (tx_outputs, withdrawal_outputs) = tx_info.to_outputs(&signer_script_pubkeys)?;
-            for output in tx_outputs {
-                db.write_tx_output(&output).await?;
+        let prevouts = tx_info.to_inputs(&signer_script_pubkeys);
+        db.write_tx_prevouts(&prevouts).await?;
+        for prevout in prevouts {
+            if prevout.prevout_type == model::TxPrevoutType::Deposit {
+                metrics::counter!(
+                    Metrics::DepositsSweptTotal,
+                    "blockchain" => BITCOIN_BLOCKCHAIN,
+                )
+                .increment(1);
             }
-            for output in withdrawal_outputs {
-                db.write_withdrawal_tx_output(&output).await?;
+        }
+
+        let (mut tx_outputs, mut withdrawal_outputs) =
+            tx_info.to_outputs(&signer_script_pubkeys)?;
+        // An OP_RETURN header made for another network means that the
+        // withdrawal IDs encoded after it are not ones that we know about,
+        // so we reject them. We still record the outputs themselves so
+        // that we keep track of the signers' UTXO.
+        for output in tx_outputs.iter_mut() {
+            let Some(parsed_magic_bytes) = output.magic_bytes.as_deref() else {
+                continue;
+            };
+            if parsed_magic_bytes != magic_bytes {
+                tracing::warn!(
+                    %txid,
+                    output_index = output.output_index,
+                    magic_bytes = %hex::encode(parsed_magic_bytes),
+                    "rejecting the withdrawal outputs of a transaction with unexpected magic bytes"
+                );
+                output.magic_bytes = None;
+                output.op_return_version = None;
+                withdrawal_outputs.clear();
             }
         }
+        db.write_tx_outputs(&tx_outputs).await?;
+        for output in withdrawal_outputs {
+            db.write_withdrawal_tx_output(&output).await?;
+        }
+    }
 
-        // Write these transactions into storage.
-        db.write_bitcoin_transactions(sbtc_txs).await?;
-        Ok(())
-    };
-
-    // The first time, we get all sweep transactions with inputs that
-    // we know about. However, we could have locked the UTXO with a new
-    // scriptPubKey, and we have no way of knowing that ahead of time.
-    // The first pass over will populate the database with the new
-    // scriptPubKeys.
-    extract_fut().await?;
-
-    // This will catch cases where the signers have locked up their
-    // UTXO with a new scriptPubKey and there are a chain of
-    // transactions in the block.
-    extract_fut().await
+    // Write these transactions into storage.
+    db.write_bitcoin_transactions(sbtc_txs).await
 }
 
 /// Return the signing set that can make sBTC related contract calls along
@@ -701,6 +1366,24 @@ where
 /// the public key that is the result of a DKG run. If there are no
 /// rotate-keys transactions on the canonical stacks blockchain, then we
 /// return None.
+///
+/// If the aggregate key in the registry does not match the one in our
+/// latest verified DKG shares then we log a warning and increment a
+/// metric, but still return the registry value. This happens briefly
+/// during every key rotation, between the new shares being verified and
+/// the rotate-keys contract call being confirmed, so it cannot be an
+/// error here.
+///
+/// The signer set only changes after a rotate-keys contract call, so the
+/// info is cached in the signer state and returned until a new rotate-keys
+/// contract call is observed, or until the cache is invalidated with
+/// [`SignerState::invalidate_signer_set_info_cache`]. The cached value is
+/// also dropped if the bitcoin block that it was fetched at is no longer
+/// canonical, or if it is more than [`SIGNER_SET_INFO_CACHE_MAX_AGE`]
+/// blocks old, so that a reorg or a missed rotate-keys event cannot leave
+/// us with a stale signer set.
+///
+/// [`SignerState::invalidate_signer_set_info_cache`]: crate::context::SignerState::invalidate_signer_set_info_cache
 #[tracing::instrument(skip_all)]
 pub async fn get_signer_set_info<C>(ctx: &C) -> Result<Option<SignerSetInfo>, Error>
 where
@@ -708,30 +1391,127 @@ where
 {
     let stacks = ctx.get_stacks_client();
     let address = &ctx.config().signer.deployer;
+    let state = ctx.state();
     // If the sBTC contracts have not been deployed, then we don't have any
     // signer set info in the registry.
-    if !ctx.state().sbtc_contracts_deployed() {
+    if !state.sbtc_contracts_deployed() {
+        return Ok(None);
+    }
+
+    let db = ctx.get_storage();
+    let chain_tip = db.get_bitcoin_canonical_chain_tip_ref().await?;
+    let info = match cached_signer_set_info(ctx, chain_tip.as_ref()).await? {
+        Some(info) => info,
+        None => {
+            // We read this before making the request, so that if a
+            // rotate-keys event comes in while we wait then the cached
+            // value is stale.
+            let key_rotation_txid = state.latest_key_rotation_txid();
+
+            // This returns Ok(None) if API call returns a response with
+            // values that are only set when we first deploy the sBTC
+            // contracts.
+            let Some(info) = stacks.get_current_signer_set_info(address).await? else {
+                return Ok(None);
+            };
+            if let Some(chain_tip) = chain_tip {
+                state.cache_signer_set_info(key_rotation_txid, chain_tip, info.clone());
+            }
+            info
+        }
+    };
+
+    let latest_dkg_shares = db.get_latest_verified_dkg_shares().await?;
+    if let Err(error) = check_signer_set_info_consistency(&info, latest_dkg_shares.as_ref()) {
+        tracing::warn!(
+            %error,
+            "the sbtc-registry and our latest verified DKG shares disagree on the aggregate key"
+        );
+        metrics::counter!(Metrics::SignerSetInconsistenciesTotal).increment(1);
+    }
+
+    Ok(Some(info))
+}
+
+/// Keep the given error in `storage_error` if it is a storage error and
+/// no storage error has been kept yet.
+fn keep_storage_error(storage_error: &mut Option<Error>, error: Error) {
+    if storage_error.is_none() && error.is_storage_error() {
+        *storage_error = Some(error);
+    }
+}
+
+/// Return the cached signer set info if it is still usable at the given
+/// bitcoin chain tip.
+async fn cached_signer_set_info<C>(
+    ctx: &C,
+    chain_tip: Option<&model::BitcoinBlockRef>,
+) -> Result<Option<SignerSetInfo>, Error>
+where
+    C: Context,
+{
+    let (Some((fetched_at, info)), Some(chain_tip)) =
+        (ctx.state().cached_signer_set_info(), chain_tip)
+    else {
+        return Ok(None);
+    };
+
+    let age = *chain_tip
+        .block_height
+        .saturating_sub(fetched_at.block_height);
+    if age >= SIGNER_SET_INFO_CACHE_MAX_AGE {
+        return Ok(None);
+    }
+
+    let db = ctx.get_storage();
+    if !db
+        .in_canonical_bitcoin_blockchain(chain_tip, &fetched_at)
+        .await?
+    {
         return Ok(None);
     }
 
-    // This returns Ok(None) if API call returns a response with values
-    // that are only set when we first deploy the sBTC contracts.
-    stacks.get_current_signer_set_info(address).await
+    Ok(Some(info))
+}
+
+/// Check that the aggregate key in the sbtc-registry matches the aggregate
+/// key of the latest verified DKG shares, if we have any.
+pub fn check_signer_set_info_consistency(
+    info: &SignerSetInfo,
+    latest_dkg_shares: Option<&EncryptedDkgShares>,
+) -> Result<(), Error> {
+    match latest_dkg_shares {
+        Some(shares) if shares.aggregate_key != info.aggregate_key => {
+            Err(Error::SignerSetInfoInconsistency {
+                registry: info.aggregate_key,
+                dkg: shares.aggregate_key,
+            })
+        }
+        _ => Ok(()),
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::num::NonZeroU32;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
     use bitcoin::Amount;
     use bitcoin::BlockHash;
     use bitcoin::TxOut;
     use bitcoin::hashes::Hash as _;
     use fake::Dummy;
     use fake::Fake;
+    use metrics_exporter_prometheus::PrometheusBuilder;
     use model::BitcoinTxId;
     use model::ScriptPubKey;
+    use stacks_common::types::chainstate::StacksAddress;
     use test_log::test;
 
     use crate::bitcoin::rpc::GetTxResponse;
+    use crate::config::NetworkKind;
     use crate::context::SignerSignal;
     use crate::keys::PublicKey;
     use crate::keys::SignerScriptPubKey as _;
@@ -740,6 +1520,7 @@ mod tests {
     use crate::testing::block_observer::TestHarness;
     use crate::testing::context::*;
     use crate::testing::get_rng;
+    use crate::testing::storage::model::TestBitcoinTxInfo;
 
     use super::*;
 
@@ -790,6 +1571,396 @@ mod tests {
         handle.abort();
     }
 
+    /// Test that block hashes received over the block hash stream are
+    /// reported as pending until the block observer has processed them.
+    #[test(tokio::test)]
+    async fn pending_block_hashes_drain_once_processed() {
+        let mut rng = get_rng();
+        let storage = storage::memory::Store::new_shared();
+        let test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let min_height = test_harness.min_block_height();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .modify_settings(|settings| settings.signer.sbtc_bitcoin_start_height = min_height)
+            .build();
+
+        // There must be at least one signal receiver alive when the block observer
+        // later tries to send a signal, hence this line.
+        let _signal_rx = ctx.get_signal_receiver();
+
+        // Feed a few block hashes all at once, faster than the block
+        // observer can process them.
+        let block_hashes: Vec<BlockHash> = test_harness
+            .bitcoin_blocks()
+            .iter()
+            .rev()
+            .take(3)
+            .rev()
+            .map(|block| block.block_hash)
+            .collect();
+        let block_hash_stream = futures::stream::iter(block_hashes.clone())
+            .map(Ok::<_, Error>)
+            .chain(futures::stream::pending());
+
+        let mut block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: block_hash_stream,
+        };
+
+        assert!(ctx.pending_block_hashes().is_empty());
+
+        let mut queue = VecDeque::new();
+        block_observer.enqueue_ready_block_hashes(&mut queue);
+
+        let expected: Vec<model::BitcoinBlockHash> =
+            block_hashes.iter().copied().map(Into::into).collect();
+        assert_eq!(ctx.pending_block_hashes(), expected);
+
+        let mut remaining = expected.len();
+        while let Some(block_hash) = queue.pop_front() {
+            block_observer
+                .process_pending_block_hash(block_hash)
+                .await
+                .unwrap();
+            remaining -= 1;
+            assert_eq!(ctx.pending_block_hashes().len(), remaining);
+            assert!(!ctx.pending_block_hashes().contains(&block_hash.into()));
+        }
+
+        assert!(ctx.pending_block_hashes().is_empty());
+    }
+
+    /// Return a Postgres store whose connection pool has been closed, so
+    /// that every storage operation fails.
+    async fn closed_pg_store() -> storage::postgres::PgStore {
+        let url = format!("{}/postgres", crate::testing::storage::DATABASE_URL_BASE);
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy(&url)
+            .unwrap();
+        pool.close().await;
+        storage::postgres::PgStore::from(pool)
+    }
+
+    /// Test that the block observer signals a shutdown, and stops, once it
+    /// has seen the configured number of consecutive storage errors.
+    #[test(tokio::test)]
+    async fn block_observer_shuts_down_after_persistent_storage_errors() {
+        let mut rng = get_rng();
+        let test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let min_height = test_harness.min_block_height();
+        let ctx = TestContext::builder()
+            .with_storage(closed_pg_store().await)
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .modify_settings(|settings| {
+                settings.signer.sbtc_bitcoin_start_height = min_height;
+                settings.signer.storage_error_shutdown_threshold = NonZeroU32::new(10);
+            })
+            .build();
+
+        let _signal_rx = ctx.get_signal_receiver();
+        let term = ctx.get_termination_handle();
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: test_harness.spawn_block_hash_stream(),
+        };
+
+        assert!(!term.shutdown_signalled());
+
+        // The block observer only returns once a shutdown has been
+        // signalled, which here can only come from the storage errors.
+        tokio::time::timeout(Duration::from_secs(5), block_observer.run())
+            .await
+            .expect("block observer did not stop on storage errors")
+            .unwrap();
+
+        assert!(term.shutdown_signalled());
+    }
+
+    /// Test that a block that fails in several of its processing steps
+    /// only counts as one storage error towards the shutdown threshold.
+    #[test(tokio::test)]
+    async fn block_observer_counts_storage_errors_once_per_block() {
+        let mut rng = get_rng();
+        let test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let min_height = test_harness.min_block_height();
+        let ctx = TestContext::builder()
+            .with_storage(closed_pg_store().await)
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .modify_settings(|settings| {
+                settings.signer.sbtc_bitcoin_start_height = min_height;
+                settings.signer.storage_error_shutdown_threshold = NonZeroU32::new(2);
+            })
+            .build();
+
+        let _signal_rx = ctx.get_signal_receiver();
+        let term = ctx.get_termination_handle();
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: (),
+        };
+        let mut block_hashes = test_harness
+            .bitcoin_blocks()
+            .iter()
+            .map(|block| block.block_hash);
+
+        let block_hash = block_hashes.next().unwrap();
+        block_observer
+            .process_pending_block_hash(block_hash)
+            .await
+            .unwrap();
+        assert!(!term.shutdown_signalled());
+
+        let block_hash = block_hashes.next().unwrap();
+        block_observer
+            .process_pending_block_hash(block_hash)
+            .await
+            .unwrap();
+        assert!(term.shutdown_signalled());
+    }
+
+    /// Test that the block observer keeps going on storage errors when no
+    /// storage error shutdown threshold is configured.
+    #[test(tokio::test)]
+    async fn block_observer_ignores_storage_errors_without_threshold() {
+        let mut rng = get_rng();
+        let test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let min_height = test_harness.min_block_height();
+        let ctx = TestContext::builder()
+            .with_storage(closed_pg_store().await)
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .modify_settings(|settings| settings.signer.sbtc_bitcoin_start_height = min_height)
+            .build();
+
+        let _signal_rx = ctx.get_signal_receiver();
+        assert!(
+            ctx.config()
+                .signer
+                .storage_error_shutdown_threshold
+                .is_none()
+        );
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: (),
+        };
+
+        for block in test_harness.bitcoin_blocks() {
+            block_observer
+                .process_pending_block_hash(block.block_hash)
+                .await
+                .unwrap();
+        }
+
+        assert!(!ctx.get_termination_handle().shutdown_signalled());
+    }
+
+    /// Test that the ancestors of a new block are fetched from the
+    /// backfill bitcoin client when one is configured, while the new block
+    /// itself is fetched from the regular bitcoin client. The regular
+    /// bitcoin client here only knows about the new block, so backfilling
+    /// fails unless it goes through the backfill bitcoin client.
+    #[test(tokio::test)]
+    async fn backfill_uses_the_backfill_bitcoin_client() {
+        let mut rng = get_rng();
+        let storage = storage::memory::Store::new_shared();
+        let backfill = TestHarness::generate(&mut rng, 20, 0..5);
+        let min_height = backfill.min_block_height();
+        let chain_tip = backfill
+            .bitcoin_blocks()
+            .iter()
+            .max_by_key(|block| block.height)
+            .unwrap()
+            .block_hash;
+
+        let mut primary = backfill.clone();
+        primary.retain_bitcoin_blocks(|block| block.block_hash == chain_tip);
+
+        let mut ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(primary.clone())
+            .with_emily_client(primary.clone())
+            .with_bitcoin_client(primary.clone())
+            .modify_settings(|settings| settings.signer.sbtc_bitcoin_start_height = min_height)
+            .build();
+        ctx.inner.set_backfill_bitcoin_client(backfill.clone());
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+        };
+
+        block_observer
+            .process_bitcoin_blocks_until(chain_tip)
+            .await
+            .unwrap();
+
+        for block in backfill.bitcoin_blocks() {
+            let persisted = storage
+                .get_bitcoin_block(&block.block_hash.into())
+                .await
+                .expect("storage error")
+                .expect("block wasn't persisted");
+
+            assert_eq!(persisted.block_hash, block.block_hash.into())
+        }
+    }
+
+    /// Test that backfilling falls back to the regular bitcoin client for
+    /// blocks that the backfill bitcoin client does not know about.
+    #[test(tokio::test)]
+    async fn backfill_falls_back_to_the_bitcoin_client() {
+        let mut rng = get_rng();
+        let storage = storage::memory::Store::new_shared();
+        let primary = TestHarness::generate(&mut rng, 20, 0..5);
+        let backfill = TestHarness::generate(&mut rng, 5, 0..5);
+        let min_height = primary.min_block_height();
+        let mut ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(primary.clone())
+            .with_emily_client(primary.clone())
+            .with_bitcoin_client(primary.clone())
+            .modify_settings(|settings| settings.signer.sbtc_bitcoin_start_height = min_height)
+            .build();
+        ctx.inner.set_backfill_bitcoin_client(backfill.clone());
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+        };
+
+        let chain_tip = primary
+            .bitcoin_blocks()
+            .iter()
+            .max_by_key(|block| block.height)
+            .unwrap()
+            .block_hash;
+        block_observer
+            .process_bitcoin_blocks_until(chain_tip)
+            .await
+            .unwrap();
+
+        for block in primary.bitcoin_blocks() {
+            let persisted = storage
+                .get_bitcoin_block(&block.block_hash.into())
+                .await
+                .expect("storage error")
+                .expect("block wasn't persisted");
+
+            assert_eq!(persisted.block_hash, block.block_hash.into())
+        }
+    }
+
+    /// Test that a capped walk back returns the lowest unknown headers
+    /// along with a cursor for the rest of the unknown blocks, so that
+    /// they can be processed without walking back again.
+    #[test(tokio::test)]
+    async fn unknown_headers_returns_a_cursor_past_the_batch() {
+        let mut rng = get_rng();
+        let storage = storage::memory::Store::new_shared();
+        let test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let min_height = test_harness.min_block_height();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .modify_settings(|settings| settings.signer.sbtc_bitcoin_start_height = min_height)
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+        };
+
+        let mut blocks = test_harness.bitcoin_blocks().to_vec();
+        blocks.sort_by_key(|block| block.height);
+        let chain_tip = blocks.last().unwrap().block_hash;
+
+        let max_batch = NonZeroUsize::new(3);
+        let (headers, remaining) = block_observer
+            .unknown_headers(chain_tip, max_batch)
+            .await
+            .unwrap();
+
+        let header_hashes: Vec<BlockHash> = headers.iter().map(|header| header.hash).collect();
+        let expected: Vec<BlockHash> = blocks.iter().map(|block| block.block_hash).collect();
+        assert_eq!(header_hashes, expected[..3]);
+        assert_eq!(remaining, expected[3..]);
+
+        // Processing everything leaves nothing unknown behind.
+        block_observer
+            .process_bitcoin_blocks_until(chain_tip)
+            .await
+            .unwrap();
+        let (headers, remaining) = block_observer
+            .unknown_headers(chain_tip, max_batch)
+            .await
+            .unwrap();
+        assert!(headers.is_empty());
+        assert!(remaining.is_empty());
+    }
+
+    /// Test that the block observer signals the new stacks chain tip after
+    /// processing a bitcoin block that brought in new stacks blocks.
+    #[test(tokio::test)]
+    async fn should_signal_new_stacks_blocks() {
+        let mut rng = get_rng();
+        let storage = storage::memory::Store::new_shared();
+        let test_harness = TestHarness::generate(&mut rng, 20, 1..5);
+        let min_height = test_harness.min_block_height();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .modify_settings(|settings| settings.signer.sbtc_bitcoin_start_height = min_height)
+            .build();
+
+        // There must be at least one signal receiver alive when the block observer
+        // later tries to send a signal, hence this line.
+        let _signal_rx = ctx.get_signal_receiver();
+        let block_hash_stream = test_harness.spawn_block_hash_stream();
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: block_hash_stream,
+        };
+
+        let (tip_id, tip_block, _) = test_harness.stacks_blocks().last().unwrap();
+        let expected_hash: model::StacksBlockHash = (*tip_id).into();
+        let expected_height: model::StacksBlockHeight = tip_block.header.chain_length.into();
+
+        let handle = tokio::spawn(block_observer.run());
+        ctx.wait_for_signal(Duration::from_secs(3), |signal| {
+            matches!(
+                signal,
+                SignerSignal::Event(SignerEvent::StacksBlockObserved { block_hash, height })
+                    if *block_hash == expected_hash && *height == expected_height
+            )
+        })
+        .await
+        .expect("block observer did not signal the new stacks blocks");
+
+        let persisted = storage
+            .get_stacks_block(&expected_hash)
+            .await
+            .expect("storage error");
+        assert!(persisted.is_some());
+
+        handle.abort();
+    }
+
     /// Test that `BlockObserver::load_latest_deposit_requests` takes
     /// deposits from emily, validates them and only keeps the ones that
     /// pass validation and have been confirmed.
@@ -895,28 +2066,161 @@ mod tests {
             .modify_settings(|settings| settings.signer.sbtc_bitcoin_start_height = min_height)
             .build();
 
-        let block_observer = BlockObserver {
-            context: ctx,
-            bitcoin_blocks: (),
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+        };
+
+        {
+            let db = storage.lock().await;
+            assert_eq!(db.deposit_requests.len(), 0);
+        }
+
+        block_observer.load_latest_deposit_requests().await.unwrap();
+        // Only the transaction from tx_setup0 was valid. Note that, since
+        // we are not using a real block hash stored in the database. Our
+        // DbRead function won't actually find it. And in prod we won't
+        // actually store the deposit request transaction.
+        let deposit = {
+            let db = storage.lock().await;
+            assert_eq!(db.deposit_requests.len(), 1);
+            db.deposit_requests.values().next().cloned().unwrap()
+        };
+
+        assert_eq!(deposit.outpoint(), req0.outpoint);
+    }
+
+    /// Test that `BlockObserver::load_requests` stops persisting a
+    /// deposit request exactly when its lock time comes within
+    /// [`DEPOSIT_LOCKTIME_BLOCK_BUFFER`] blocks of elapsing.
+    #[test_case::test_case(6, true; "one block before the buffer")]
+    #[test_case::test_case(7, false; "at the buffer")]
+    #[tokio::test]
+    async fn load_requests_skips_deposits_at_the_buffer_boundary(
+        chain_tip_offset: u64,
+        is_stored: bool,
+    ) {
+        let mut rng = get_rng();
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let confirming_block = test_harness.bitcoin_blocks().first().unwrap();
+        let (confirming_block_hash, confirmed_height) =
+            (confirming_block.block_hash, confirming_block.height);
+
+        // The depositor can reclaim the funds 10 blocks after the deposit
+        // is confirmed, so the signers stop sweeping it 7 blocks after.
+        let lock_time = 10;
+        assert_eq!(lock_time - DEPOSIT_LOCKTIME_BLOCK_BUFFER as u32, 7);
+        let tx_setup = sbtc::testing::deposits::tx_setup(lock_time, 32000, &[500_000]);
+        let request = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint {
+                txid: tx_setup.tx.compute_txid(),
+                vout: 0,
+            },
+            deposit_script: tx_setup.deposits.first().unwrap().deposit_script(),
+            reclaim_script: tx_setup.reclaims.first().unwrap().reclaim_script(),
+        };
+        let get_tx_resp = GetTxResponse {
+            tx: tx_setup.tx.clone(),
+            block_hash: Some(confirming_block_hash),
+            confirmations: None,
+            block_time: None,
+        };
+        test_harness.add_deposits(&[(get_tx_resp.tx.compute_txid(), get_tx_resp)]);
+        let min_height = test_harness.min_block_height();
+
+        let storage = storage::memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .modify_settings(|settings| settings.signer.sbtc_bitcoin_start_height = min_height)
+            .build();
+
+        ctx.state().set_bitcoin_chain_tip(model::BitcoinBlockRef {
+            block_hash: fake::Faker.fake_with_rng(&mut rng),
+            block_height: confirmed_height + chain_tip_offset,
+        });
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+        };
+        block_observer.load_requests(&[request]).await.unwrap();
+
+        let db = storage.lock().await;
+        assert_eq!(db.deposit_requests.len(), usize::from(is_stored));
+    }
+
+    #[test_case::test_case(100, 150, false; "lock time has not elapsed")]
+    #[test_case::test_case(100, 246, false; "lock time elapses after the buffer")]
+    #[test_case::test_case(100, 247, true; "lock time elapses within the buffer")]
+    #[test_case::test_case(100, 250, true; "lock time elapses at the chain tip")]
+    #[test_case::test_case(100, 400, true; "lock time elapsed long ago")]
+    fn deposit_leaves_sweep_window_before_lock_time(
+        confirmed_height: u64,
+        chain_tip_height: u64,
+        is_expired: bool,
+    ) {
+        let lock_time = 150;
+        let tx_setup = sbtc::testing::deposits::tx_setup(lock_time, 32000, &[500_000]);
+        let request = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint {
+                txid: tx_setup.tx.compute_txid(),
+                vout: 0,
+            },
+            deposit_script: tx_setup.deposits.first().unwrap().deposit_script(),
+            reclaim_script: tx_setup.reclaims.first().unwrap().reclaim_script(),
+        };
+        let deposit = Deposit {
+            info: request.validate_tx(&tx_setup.tx, false).unwrap(),
+            tx_info: BitcoinTxInfo {
+                fee: None,
+                tx: tx_setup.tx,
+                vin: Vec::new(),
+            },
+            block_hash: BlockHash::all_zeros(),
         };
 
-        {
-            let db = storage.lock().await;
-            assert_eq!(db.deposit_requests.len(), 0);
-        }
+        assert_eq!(
+            deposit.is_past_sweep_window(confirmed_height.into(), chain_tip_height.into()),
+            is_expired
+        );
+    }
 
-        block_observer.load_latest_deposit_requests().await.unwrap();
-        // Only the transaction from tx_setup0 was valid. Note that, since
-        // we are not using a real block hash stored in the database. Our
-        // DbRead function won't actually find it. And in prod we won't
-        // actually store the deposit request transaction.
-        let deposit = {
-            let db = storage.lock().await;
-            assert_eq!(db.deposit_requests.len(), 1);
-            db.deposit_requests.values().next().cloned().unwrap()
+    #[test_case::test_case(100, 247, false; "within the buffer")]
+    #[test_case::test_case(100, 249, false; "one block before the lock time elapses")]
+    #[test_case::test_case(100, 250, true; "lock time elapses at the chain tip")]
+    #[test_case::test_case(100, 400, true; "lock time elapsed long ago")]
+    fn deposit_is_reclaimable_once_lock_time_elapses(
+        confirmed_height: u64,
+        chain_tip_height: u64,
+        is_reclaimable: bool,
+    ) {
+        let lock_time = 150;
+        let tx_setup = sbtc::testing::deposits::tx_setup(lock_time, 32000, &[500_000]);
+        let request = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint {
+                txid: tx_setup.tx.compute_txid(),
+                vout: 0,
+            },
+            deposit_script: tx_setup.deposits.first().unwrap().deposit_script(),
+            reclaim_script: tx_setup.reclaims.first().unwrap().reclaim_script(),
+        };
+        let deposit = Deposit {
+            info: request.validate_tx(&tx_setup.tx, false).unwrap(),
+            tx_info: BitcoinTxInfo {
+                fee: None,
+                tx: tx_setup.tx,
+                vin: Vec::new(),
+            },
+            block_hash: BlockHash::all_zeros(),
         };
 
-        assert_eq!(deposit.outpoint(), req0.outpoint);
+        assert_eq!(
+            deposit.is_reclaimable(confirmed_height.into(), chain_tip_height.into()),
+            is_reclaimable
+        );
     }
 
     /// Test that `BlockObserver::extract_deposit_requests` after
@@ -999,6 +2303,83 @@ mod tests {
         );
     }
 
+    /// Test that `BlockObserver::load_latest_deposit_requests` skips
+    /// deposit requests that would push the total amount of pending
+    /// deposits for a recipient over the per-recipient cap.
+    #[tokio::test]
+    async fn load_requests_enforces_per_recipient_cap() {
+        let mut rng = get_rng();
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+
+        let block_hash = test_harness
+            .bitcoin_blocks()
+            .first()
+            .map(|block| block.block_hash);
+        let amount = 500_000;
+
+        // We create two deposits for the same recipient in separate
+        // transactions. Each of them is under the cap, but together they
+        // are over it.
+        let mut deposit_requests = Vec::new();
+        for _ in 0..2 {
+            let recipient = StacksAddress::burn_address(false);
+            let tx_setup =
+                sbtc::testing::deposits::tx_setup_with_recipient(150, 32000, &[amount], recipient);
+            let txid = tx_setup.tx.compute_txid();
+            let get_tx_resp = GetTxResponse {
+                tx: tx_setup.tx.clone(),
+                block_hash,
+                confirmations: None,
+                block_time: None,
+            };
+            let request = CreateDepositRequest {
+                outpoint: bitcoin::OutPoint { txid, vout: 0 },
+                deposit_script: tx_setup.deposits.first().unwrap().deposit_script(),
+                reclaim_script: tx_setup.reclaims.first().unwrap().reclaim_script(),
+            };
+
+            test_harness.add_deposit(txid, get_tx_resp);
+            test_harness.add_pending_deposit(request.clone());
+            deposit_requests.push(request);
+        }
+
+        let min_height = test_harness.min_block_height();
+        let storage = storage::memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .modify_settings(|settings| settings.signer.sbtc_bitcoin_start_height = min_height)
+            .build();
+
+        let limits = SbtcLimits::new(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(Amount::from_sat(amount + amount / 2)),
+        );
+        ctx.state().update_current_limits(limits);
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+        };
+
+        block_observer.load_latest_deposit_requests().await.unwrap();
+
+        // Only the first deposit fits under the cap.
+        let storage = storage.lock().await;
+        assert_eq!(storage.deposit_requests.len(), 1);
+        let deposit = storage.deposit_requests.values().next().unwrap();
+        assert_eq!(deposit.outpoint(), deposit_requests[0].outpoint);
+    }
+
     /// Test that `BlockObserver::extract_sbtc_transactions` takes the
     /// stored signer `scriptPubKey`s and stores all transactions from a
     /// bitcoin block that match one of those `scriptPubkey`s.
@@ -1020,11 +2401,11 @@ mod tests {
         // 4. We try "extracting" a block with two transactions where one
         //    of them spends to the signers. The one transaction should be
         //    stored in our storage.
-        let signers_script_pubkey: ScriptPubKey = fake::Faker.fake_with_rng(&mut rng);
+        let aggregate_key = PublicKey::dummy_with_rng(&fake::Faker, &mut rng);
+        let signers_script_pubkey: ScriptPubKey = aggregate_key.signers_script_pubkey().into();
 
         // We start by storing our `scriptPubKey`.
         let storage = storage::memory::Store::new_shared();
-        let aggregate_key = PublicKey::dummy_with_rng(&fake::Faker, &mut rng);
         let shares = model::EncryptedDkgShares {
             aggregate_key,
             tweaked_aggregate_key: aggregate_key.signers_tweaked_pubkey().unwrap(),
@@ -1073,9 +2454,15 @@ mod tests {
         // First we try extracting the transactions from a block that does
         // not contain any transactions spent to the signers
         let txs = [tx_setup1.tx.fake_with_rng(&mut rng)];
-        extract_sbtc_transactions(&storage, None, block_hash, &txs)
-            .await
-            .unwrap();
+        extract_sbtc_transactions(
+            &storage,
+            &HashSet::new(),
+            NetworkKind::Regtest.magic_bytes(),
+            block_hash,
+            &txs,
+        )
+        .await
+        .unwrap();
 
         // We need to change the scope so that the mutex guard is dropped.
         {
@@ -1095,9 +2482,15 @@ mod tests {
             tx_setup0.tx.fake_with_rng(&mut rng),
             tx_setup1.tx.fake_with_rng(&mut rng),
         ];
-        extract_sbtc_transactions(&storage, None, block_hash, &txs)
-            .await
-            .unwrap();
+        extract_sbtc_transactions(
+            &storage,
+            &HashSet::new(),
+            NetworkKind::Regtest.magic_bytes(),
+            block_hash,
+            &txs,
+        )
+        .await
+        .unwrap();
 
         let store = storage.lock().await;
         let stored_transactions = store.bitcoin_block_to_transactions.get(&block_hash.into());
@@ -1109,4 +2502,529 @@ mod tests {
         assert_eq!(tx_ids.len(), 1);
         assert!(tx_ids.contains(&expected_tx_id));
     }
+
+    /// Test that `extract_sbtc_transactions` records a transaction that
+    /// spends the signers' UTXO but lacks the `OP_RETURN` output that the
+    /// signers always include.
+    #[tokio::test]
+    async fn anomalous_signer_spends_get_recorded() {
+        let mut rng = get_rng();
+        let block_hash = BlockHash::from_byte_array([1u8; 32]);
+        let aggregate_key = PublicKey::dummy_with_rng(&fake::Faker, &mut rng);
+        let signers_script_pubkey: ScriptPubKey = aggregate_key.signers_script_pubkey().into();
+
+        let storage = storage::memory::Store::new_shared();
+        let shares = model::EncryptedDkgShares {
+            aggregate_key,
+            tweaked_aggregate_key: aggregate_key.signers_tweaked_pubkey().unwrap(),
+            script_pubkey: signers_script_pubkey.clone(),
+            encrypted_private_shares: Vec::new(),
+            public_shares: Vec::new(),
+            signer_set_public_keys: vec![aggregate_key],
+            signature_share_threshold: 1,
+            dkg_shares_status: DkgSharesStatus::Unverified,
+            started_at_bitcoin_block_hash: block_hash.into(),
+            started_at_bitcoin_block_height: 1u64.into(),
+        };
+        storage.write_encrypted_dkg_shares(&shares).await.unwrap();
+
+        // This transaction spends the signers' output as its first input,
+        // but it only has one output, so there is no `OP_RETURN`.
+        let mut tx_setup = sbtc::testing::deposits::tx_setup(0, 0, &[100]);
+        let other_script_pubkey: ScriptPubKey = fake::Faker.fake_with_rng(&mut rng);
+        tx_setup.tx.output = vec![TxOut {
+            value: Amount::ONE_BTC,
+            script_pubkey: other_script_pubkey.into(),
+        }];
+        let txid = tx_setup.tx.compute_txid();
+
+        let mut tx_info: BitcoinTxInfo = tx_setup.tx.fake_with_rng(&mut rng);
+        let prevout = tx_info.vin[0].prevout.as_mut().unwrap();
+        prevout.script_pubkey.script = signers_script_pubkey.into();
+
+        extract_sbtc_transactions(
+            &storage,
+            &HashSet::new(),
+            NetworkKind::Regtest.magic_bytes(),
+            block_hash,
+            &[tx_info],
+        )
+        .await
+        .unwrap();
+
+        let store = storage.lock().await;
+        assert_eq!(store.anomalous_spends.len(), 1);
+
+        let spend = store
+            .anomalous_spends
+            .get(&(txid.into(), block_hash.into()))
+            .unwrap();
+        assert_eq!(
+            spend.reason,
+            model::AnomalousSpendReason::MissingSignersOpReturn
+        );
+    }
+
+    /// Test that `extract_sbtc_transactions` rejects the withdrawal
+    /// outputs of a sweep whose OP_RETURN header was made for another
+    /// network, while still recording the signers' outputs.
+    #[tokio::test]
+    async fn mismatched_magic_bytes_are_rejected() {
+        let mut rng = get_rng();
+        let block_hash = BlockHash::from_byte_array([1u8; 32]);
+        let aggregate_key = PublicKey::dummy_with_rng(&fake::Faker, &mut rng);
+        let signers_script_pubkey = aggregate_key.signers_script_pubkey();
+        let recipient: ScriptPubKey = fake::Faker.fake_with_rng(&mut rng);
+
+        let sweep = TestBitcoinTxInfo::sweep_builder()
+            .signer_input(
+                TestBitcoinTxInfo::random_prevout(&mut rng),
+                TxOut {
+                    value: Amount::from_sat(100_000),
+                    script_pubkey: signers_script_pubkey.clone(),
+                },
+            )
+            .withdrawal_output(recipient.into(), Amount::from_sat(20_000))
+            .magic_bytes(NetworkKind::Testnet.magic_bytes())
+            .build();
+        let txid: BitcoinTxId = sweep.tx.compute_txid().into();
+
+        let mut tx_info: BitcoinTxInfo = sweep.tx.fake_with_rng(&mut rng);
+        let prevout = tx_info.vin[0].prevout.as_mut().unwrap();
+        prevout.script_pubkey.script = signers_script_pubkey.clone();
+
+        // The in-memory store does not support writing withdrawal outputs,
+        // so this would panic if they were not rejected.
+        let storage = storage::memory::Store::new_shared();
+        extract_sbtc_transactions(
+            &storage,
+            &HashSet::from([signers_script_pubkey]),
+            NetworkKind::Regtest.magic_bytes(),
+            block_hash,
+            &[tx_info],
+        )
+        .await
+        .unwrap();
+
+        let store = storage.lock().await;
+        let outputs = store.bitcoin_outputs.get(&txid).unwrap();
+        let output_types: Vec<_> = outputs.iter().map(|output| output.output_type).collect();
+        assert_eq!(
+            output_types,
+            [
+                model::TxOutputType::SignersOutput,
+                model::TxOutputType::SignersOpReturn,
+                model::TxOutputType::Withdrawal,
+            ]
+        );
+        assert!(outputs[1].magic_bytes.is_none());
+        assert!(outputs[1].op_return_version.is_none());
+    }
+
+    /// Test that a donation to a scriptPubKey that the signers only start
+    /// using in a later transaction of the same block is still detected.
+    #[tokio::test]
+    async fn donations_to_keys_learned_later_in_the_block_are_detected() {
+        let mut rng = get_rng();
+        let block_hash = BlockHash::from_byte_array([1u8; 32]);
+        let storage = storage::memory::Store::new_shared();
+
+        let old_script_pubkey =
+            PublicKey::dummy_with_rng(&fake::Faker, &mut rng).signers_script_pubkey();
+        let new_script_pubkey =
+            PublicKey::dummy_with_rng(&fake::Faker, &mut rng).signers_script_pubkey();
+
+        // This transaction was not created by the signers, and it sends
+        // funds to the new scriptPubKey before the signers use it.
+        let mut donation = sbtc::testing::deposits::tx_setup(0, 0, &[100]);
+        donation.tx.output = vec![TxOut {
+            value: Amount::from_sat(10_000),
+            script_pubkey: new_script_pubkey.clone(),
+        }];
+        let donation_txid: BitcoinTxId = donation.tx.compute_txid().into();
+        let donation_info: BitcoinTxInfo = donation.tx.fake_with_rng(&mut rng);
+
+        // The sweep spends the UTXO locked by the old scriptPubKey and
+        // locks the new signers' UTXO with the new scriptPubKey.
+        let mut sweep = TestBitcoinTxInfo::sweep_builder()
+            .signer_input(
+                TestBitcoinTxInfo::random_prevout(&mut rng),
+                TxOut {
+                    value: Amount::from_sat(100_000),
+                    script_pubkey: old_script_pubkey.clone(),
+                },
+            )
+            .build();
+        sweep.tx.output[0].script_pubkey = new_script_pubkey;
+        let mut sweep_info: BitcoinTxInfo = sweep.tx.fake_with_rng(&mut rng);
+        let prevout = sweep_info.vin[0].prevout.as_mut().unwrap();
+        prevout.script_pubkey.script = old_script_pubkey.clone();
+
+        extract_sbtc_transactions(
+            &storage,
+            &HashSet::from([old_script_pubkey]),
+            NetworkKind::Regtest.magic_bytes(),
+            block_hash,
+            &[donation_info, sweep_info],
+        )
+        .await
+        .unwrap();
+
+        let store = storage.lock().await;
+        let outputs = store.bitcoin_outputs.get(&donation_txid).unwrap();
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].output_type, model::TxOutputType::Donation);
+    }
+
+    /// Test that donations to the scriptPubKey of a rotated aggregate key
+    /// are detected once the key rotation is applied to the signer state,
+    /// even though we have no DKG shares for the new key.
+    #[tokio::test]
+    async fn donations_to_rotated_key_are_detected() {
+        let mut rng = get_rng();
+        let block_hash = BlockHash::from_byte_array([1u8; 32]);
+        let storage = storage::memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_mocked_clients()
+            .build();
+
+        let info: SignerSetInfo = fake::Faker.fake_with_rng(&mut rng);
+        let new_script_pubkey = info.aggregate_key.signers_script_pubkey();
+
+        // This transaction was not created by the signers, but it sends
+        // funds to the scriptPubKey of the new aggregate key.
+        let mut tx_setup = sbtc::testing::deposits::tx_setup(0, 0, &[100]);
+        tx_setup.tx.output = vec![TxOut {
+            value: Amount::from_sat(10_000),
+            script_pubkey: new_script_pubkey.clone(),
+        }];
+        let txid: BitcoinTxId = tx_setup.tx.compute_txid().into();
+        let tx_info: BitcoinTxInfo = tx_setup.tx.fake_with_rng(&mut rng);
+
+        // Before the rotation we do not know about the new key.
+        assert!(!ctx.signer_script_set().contains(&new_script_pubkey));
+        extract_sbtc_transactions(
+            &storage,
+            &ctx.signer_script_set(),
+            NetworkKind::Regtest.magic_bytes(),
+            block_hash,
+            &[tx_info.clone()],
+        )
+        .await
+        .unwrap();
+        assert!(!storage.lock().await.bitcoin_outputs.contains_key(&txid));
+
+        // Now the key rotation is applied and the donation is detected.
+        ctx.state().update_registry_signer_set_info(info);
+        assert!(ctx.signer_script_set().contains(&new_script_pubkey));
+        extract_sbtc_transactions(
+            &storage,
+            &ctx.signer_script_set(),
+            NetworkKind::Regtest.magic_bytes(),
+            block_hash,
+            &[tx_info],
+        )
+        .await
+        .unwrap();
+
+        let store = storage.lock().await;
+        let outputs = store.bitcoin_outputs.get(&txid).unwrap();
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].output_type, model::TxOutputType::Donation);
+    }
+
+    /// Test that failed DKG shares are deleted once they fall outside of
+    /// the retention window, while verified and unverified shares are
+    /// left alone.
+    #[tokio::test]
+    async fn failed_dkg_shares_are_pruned_after_retention_window() {
+        let mut rng = get_rng();
+        let retention_window = 5;
+        let storage = storage::memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_mocked_clients()
+            .modify_settings(|settings| {
+                settings.signer.dkg_failed_shares_retention_window = retention_window
+            })
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+        };
+
+        let mut chain_tip: model::BitcoinBlock = fake::Faker.fake_with_rng(&mut rng);
+        chain_tip.block_height = 100u64.into();
+        storage.write_bitcoin_block(&chain_tip).await.unwrap();
+
+        // The stale shares started well before the retention window,
+        // while the recent failed shares started within it.
+        let mut keys = Vec::new();
+        for (status, started_at) in [
+            (DkgSharesStatus::Failed, 90u64),
+            (DkgSharesStatus::Verified, 90),
+            (DkgSharesStatus::Unverified, 90),
+            (DkgSharesStatus::Failed, 97),
+        ] {
+            let mut shares: EncryptedDkgShares = fake::Faker.fake_with_rng(&mut rng);
+            shares.dkg_shares_status = status;
+            shares.started_at_bitcoin_block_height = started_at.into();
+            storage.write_encrypted_dkg_shares(&shares).await.unwrap();
+            keys.push(PublicKeyXOnly::from(shares.aggregate_key));
+        }
+        let [
+            stale_failed,
+            stale_verified,
+            stale_unverified,
+            recent_failed,
+        ] = keys[..]
+        else {
+            unreachable!("we wrote four DKG shares");
+        };
+
+        let block_hash = chain_tip.block_hash.into();
+        block_observer
+            .prune_failed_dkg_shares(block_hash)
+            .await
+            .unwrap();
+
+        {
+            let store = storage.lock().await;
+            assert!(!store.encrypted_dkg_shares.contains_key(&stale_failed));
+            assert!(store.encrypted_dkg_shares.contains_key(&stale_verified));
+            assert!(store.encrypted_dkg_shares.contains_key(&stale_unverified));
+            assert!(store.encrypted_dkg_shares.contains_key(&recent_failed));
+        }
+
+        // After enough blocks the recent failed shares are pruned too.
+        let mut next_tip: model::BitcoinBlock = fake::Faker.fake_with_rng(&mut rng);
+        next_tip.block_height = 103u64.into();
+        next_tip.parent_hash = chain_tip.block_hash;
+        storage.write_bitcoin_block(&next_tip).await.unwrap();
+
+        let block_hash = next_tip.block_hash.into();
+        block_observer
+            .prune_failed_dkg_shares(block_hash)
+            .await
+            .unwrap();
+
+        let store = storage.lock().await;
+        assert!(!store.encrypted_dkg_shares.contains_key(&recent_failed));
+        assert!(store.encrypted_dkg_shares.contains_key(&stale_verified));
+        assert!(store.encrypted_dkg_shares.contains_key(&stale_unverified));
+        assert_eq!(store.encrypted_dkg_shares.len(), 2);
+    }
+
+    /// Test that the recorded sBTC limits are deleted once their bitcoin
+    /// block falls outside of the retention window.
+    #[tokio::test]
+    async fn effective_limits_are_pruned_after_retention_window() {
+        let mut rng = get_rng();
+        let storage = storage::memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_mocked_clients()
+            .modify_settings(|settings| settings.signer.effective_limits_retention_window = 2)
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+        };
+
+        // Record limits for a chain of four blocks at heights 100 to 103.
+        let mut blocks: Vec<model::BitcoinBlock> = Vec::new();
+        for height in 100u64..104 {
+            let mut block: model::BitcoinBlock = fake::Faker.fake_with_rng(&mut rng);
+            block.block_height = height.into();
+            if let Some(parent) = blocks.last() {
+                block.parent_hash = parent.block_hash;
+            }
+            storage.write_bitcoin_block(&block).await.unwrap();
+            storage
+                .write_effective_limits(&block.block_hash, &SbtcLimits::unlimited())
+                .await
+                .unwrap();
+            blocks.push(block);
+        }
+
+        let chain_tip = blocks.last().unwrap().block_hash;
+        block_observer
+            .prune_effective_limits(chain_tip.into())
+            .await
+            .unwrap();
+
+        // Only the limits for the blocks within two blocks of the chain
+        // tip are kept, most recent first.
+        let history = storage.get_effective_limits_history().await.unwrap();
+        let kept: Vec<_> = history
+            .iter()
+            .map(|entry| entry.bitcoin_chain_tip)
+            .collect();
+        assert_eq!(
+            kept,
+            vec![
+                blocks[3].block_hash,
+                blocks[2].block_hash,
+                blocks[1].block_hash
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn signer_set_info_inconsistency_is_reported() {
+        let mut rng = get_rng();
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        // The registry says the aggregate key is one thing while our
+        // latest verified DKG shares say it is another.
+        let info: SignerSetInfo = fake::Faker.fake_with_rng(&mut rng);
+        let mut shares: EncryptedDkgShares = fake::Faker.fake_with_rng(&mut rng);
+        shares.dkg_shares_status = DkgSharesStatus::Verified;
+        assert_ne!(info.aggregate_key, shares.aggregate_key);
+
+        ctx.get_storage_mut()
+            .write_encrypted_dkg_shares(&shares)
+            .await
+            .unwrap();
+
+        let info2 = info.clone();
+        ctx.with_stacks_client(|client| {
+            client
+                .expect_get_current_signer_set_info()
+                .returning(move |_| Box::pin(std::future::ready(Ok(Some(info2.clone())))));
+        })
+        .await;
+
+        let error = check_signer_set_info_consistency(&info, Some(&shares)).unwrap_err();
+        match error {
+            Error::SignerSetInfoInconsistency { registry, dkg } => {
+                assert_eq!(registry, info.aggregate_key);
+                assert_eq!(dkg, shares.aggregate_key);
+            }
+            error => panic!("unexpected error: {error}"),
+        }
+
+        // The inconsistency is reported but the registry value is still
+        // what gets returned. We record metrics locally so that we can
+        // check that the inconsistency was counted.
+        ctx.state().set_sbtc_contracts_deployed();
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let metrics_handle = recorder.handle();
+        let fetched = {
+            let _guard = metrics::set_default_local_recorder(&recorder);
+            get_signer_set_info(&ctx).await.unwrap()
+        };
+        assert_eq!(fetched, Some(info.clone()));
+
+        let rendered = metrics_handle.render();
+        assert!(rendered.contains("signer_set_inconsistencies_total 1"));
+
+        // Once the keys agree there is nothing to report.
+        shares.aggregate_key = info.aggregate_key;
+        assert!(check_signer_set_info_consistency(&info, Some(&shares)).is_ok());
+        assert!(check_signer_set_info_consistency(&info, None).is_ok());
+    }
+
+    #[tokio::test]
+    async fn signer_set_info_is_cached_until_key_rotation() {
+        let mut rng = get_rng();
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let info: SignerSetInfo = fake::Faker.fake_with_rng(&mut rng);
+        let info2 = info.clone();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls2 = calls.clone();
+        ctx.with_stacks_client(|client| {
+            client
+                .expect_get_current_signer_set_info()
+                .returning(move |_| {
+                    calls2.fetch_add(1, Ordering::SeqCst);
+                    Box::pin(std::future::ready(Ok(Some(info2.clone()))))
+                });
+        })
+        .await;
+
+        // Nothing gets fetched or cached before the contracts are
+        // deployed.
+        assert!(get_signer_set_info(&ctx).await.unwrap().is_none());
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        assert!(ctx.state().cached_signer_set_info().is_none());
+
+        // The cached value is tied to the bitcoin chain tip at the time
+        // that it was fetched.
+        let storage = ctx.get_storage_mut();
+        let mut chain_tip: model::BitcoinBlock = fake::Faker.fake_with_rng(&mut rng);
+        chain_tip.block_height = 100u64.into();
+        storage.write_bitcoin_block(&chain_tip).await.unwrap();
+
+        // On a cold cache we go to the stacks node, and afterward we use
+        // the cached value.
+        ctx.state().set_sbtc_contracts_deployed();
+        let fetched = get_signer_set_info(&ctx).await.unwrap();
+        assert_eq!(fetched, Some(info.clone()));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let fetched = get_signer_set_info(&ctx).await.unwrap();
+        assert_eq!(fetched, Some(info.clone()));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // A new rotate-keys event means that the cached value is stale.
+        let txid: model::StacksTxId = fake::Faker.fake_with_rng(&mut rng);
+        ctx.state().set_latest_key_rotation_txid(txid);
+        assert!(ctx.state().cached_signer_set_info().is_none());
+
+        let fetched = get_signer_set_info(&ctx).await.unwrap();
+        assert_eq!(fetched, Some(info.clone()));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        get_signer_set_info(&ctx).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // We can also force the next call to go to the stacks node.
+        ctx.state().invalidate_signer_set_info_cache();
+        let fetched = get_signer_set_info(&ctx).await.unwrap();
+        assert_eq!(fetched, Some(info.clone()));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // A reorg that orphans the block that the cached value was
+        // fetched at means that the cached value is stale.
+        let mut fork_tip: model::BitcoinBlock = fake::Faker.fake_with_rng(&mut rng);
+        fork_tip.block_height = 101u64.into();
+        storage.write_bitcoin_block(&fork_tip).await.unwrap();
+
+        let fetched = get_signer_set_info(&ctx).await.unwrap();
+        assert_eq!(fetched, Some(info.clone()));
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        get_signer_set_info(&ctx).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        // Blocks extending the chain keep the cached value until it
+        // becomes too old, in case we missed a rotate-keys event.
+        let mut next_tip: model::BitcoinBlock = fake::Faker.fake_with_rng(&mut rng);
+        next_tip.block_height = fork_tip.block_height + SIGNER_SET_INFO_CACHE_MAX_AGE - 1;
+        next_tip.parent_hash = fork_tip.block_hash;
+        storage.write_bitcoin_block(&next_tip).await.unwrap();
+
+        get_signer_set_info(&ctx).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        let mut old_tip: model::BitcoinBlock = fake::Faker.fake_with_rng(&mut rng);
+        old_tip.block_height = next_tip.block_height + 1;
+        old_tip.parent_hash = next_tip.block_hash;
+        storage.write_bitcoin_block(&old_tip).await.unwrap();
+
+        let fetched = get_signer_set_info(&ctx).await.unwrap();
+        assert_eq!(fetched, Some(info));
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+    }
 }
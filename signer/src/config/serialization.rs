@@ -33,6 +33,17 @@ where
         .map_err(serde::de::Error::custom)
 }
 
+/// A deserializer for an optional url::Url. Does not support deserializing a
+/// list, only a single URL.
+pub fn url_deserializer_option<'de, D>(deserializer: D) -> Result<Option<url::Url>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| s.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
 /// A deserializer for the std::time::Duration type.
 /// Serde includes a default deserializer, but it expects a struct.
 pub fn duration_seconds_deserializer<'de, D>(
@@ -37,6 +37,25 @@ pub enum SignerEvent {
     P2P(P2PEvent),
     /// Signals that a block observer event has occurred.
     BitcoinBlockObserved,
+    /// Signals that the block observer has observed a bitcoin block that
+    /// does not build on the canonical chain tip it had stored before.
+    BitcoinReorgDetected {
+        /// The most recent block that is on both the orphaned chain and
+        /// the new canonical chain.
+        common_ancestor: crate::storage::model::BitcoinBlockRef,
+        /// The canonical chain tip before the reorg.
+        orphaned_tip: crate::storage::model::BitcoinBlockRef,
+        /// The number of blocks on the orphaned chain after the common
+        /// ancestor.
+        depth: u64,
+    },
+    /// Signals that the block observer has stored new stacks blocks.
+    StacksBlockObserved {
+        /// The block ID of the highest of the new stacks blocks.
+        block_hash: crate::storage::model::StacksBlockHash,
+        /// The height of the highest of the new stacks blocks.
+        height: crate::storage::model::StacksBlockHeight,
+    },
     /// A Request decider event has occurred.
     RequestDecider(RequestDeciderEvent),
     /// Transaction signer events
@@ -5,18 +5,27 @@ mod signer_context;
 mod signer_state;
 mod termination;
 
+use std::collections::HashSet;
+
+use bitcoin::ScriptBuf;
 use tokio::sync::broadcast::error::RecvError;
 use tokio_stream::wrappers::ReceiverStream;
 
 use crate::SIGNER_CHANNEL_CAPACITY;
 use crate::bitcoin::BitcoinInteract;
+use crate::config::ConfigSummary;
 use crate::config::Settings;
 use crate::emily_client::EmilyInteract;
 use crate::error::Error;
+use crate::keys::PublicKey;
+use crate::keys::SignerScriptPubKey as _;
 use crate::stacks::api::StacksInteract;
 use crate::storage::DbRead;
 use crate::storage::DbWrite;
 use crate::storage::Transactable;
+use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::BitcoinBlockRef;
+use crate::transaction_coordinator::given_key_is_coordinator;
 
 pub use messaging::*;
 pub use signer_context::SignerContext;
@@ -46,11 +55,105 @@ pub trait Context: Clone + Sync + Send {
     ) -> impl DbRead + DbWrite + Transactable + Clone + Sync + Send + 'static;
     /// Get a handle to a Bitcoin client.
     fn get_bitcoin_client(&self) -> impl BitcoinInteract + Clone + 'static;
+    /// Get a handle to the Bitcoin client used for backfilling blocks.
+    /// This is the regular Bitcoin client unless a dedicated backfill
+    /// client has been configured.
+    fn get_backfill_bitcoin_client(&self) -> impl BitcoinInteract + Clone + 'static {
+        self.get_bitcoin_client()
+    }
     /// Get a handler to the Stacks client.
     fn get_stacks_client(&self) -> impl StacksInteract + Clone + 'static;
     /// Get a handle to an Emily client.
     fn get_emily_client(&self) -> impl EmilyInteract + Clone + 'static;
 
+    /// Get a snapshot of the settings that affect how the signer processes
+    /// requests, for diagnostics.
+    fn effective_config_summary(&self) -> ConfigSummary {
+        ConfigSummary::from(self.config())
+    }
+
+    /// Get the scriptPubKeys that the signers use, or have used, to lock
+    /// their UTXOs, as far as the context knows.
+    ///
+    /// This is the set of scriptPubKeys of the aggregate keys observed in
+    /// the sbtc-registry, along with the bootstrap aggregate key from the
+    /// config, if one is set. The set grows whenever a key rotation is
+    /// applied to the signer state.
+    fn signer_script_set(&self) -> HashSet<ScriptBuf> {
+        let mut script_pubkeys = self.state().signer_script_set();
+        script_pubkeys.extend(
+            self.config()
+                .signer
+                .bootstrap_aggregate_key
+                .map(|key| key.signers_script_pubkey()),
+        );
+        script_pubkeys
+    }
+
+    /// Get the hashes of the bitcoin blocks that the block observer has
+    /// received over the block hash stream but has not finished
+    /// processing yet, oldest first.
+    fn pending_block_hashes(&self) -> Vec<BitcoinBlockHash> {
+        self.state().pending_block_hashes()
+    }
+
+    /// Check whether this signer is the elected coordinator for the given
+    /// bitcoin chain tip.
+    ///
+    /// The coordinator is elected from the bootstrap signing set using the
+    /// hash of the chain tip, so every signer with the same signing set
+    /// and chain tip agrees on exactly one coordinator. This is the same
+    /// election used for both DKG and sweep coordination.
+    fn is_coordinator(&self, chain_tip: &BitcoinBlockRef) -> Result<bool, Error> {
+        let signer_set = &self.config().signer.bootstrap_signing_set;
+        if signer_set.is_empty() {
+            return Ok(false);
+        }
+
+        let public_key = PublicKey::from_private_key(&self.config().signer.private_key);
+        Ok(given_key_is_coordinator(
+            public_key,
+            &chain_tip.block_hash,
+            signer_set,
+        ))
+    }
+
+    /// Apply the storage error shutdown policy to the given error.
+    ///
+    /// Storage errors are counted, and once the number of consecutive
+    /// storage errors reaches the configured threshold a shutdown is
+    /// signalled so that a process supervisor can restart the signer.
+    /// Other errors are ignored. Returns whether a shutdown was signalled.
+    ///
+    /// The count is of failed passes rather than failed storage calls: a
+    /// caller that makes several storage calls for one unit of work, like
+    /// the block observer handling a new block, should call this at most
+    /// once for that unit of work, and call
+    /// [`SignerState::reset_storage_errors`] once a unit of work completes
+    /// without any storage errors.
+    fn shutdown_on_storage_error(&self, error: &Error) -> bool {
+        if !error.is_storage_error() {
+            return false;
+        }
+
+        let num_errors = self.state().record_storage_error();
+        let Some(threshold) = self.config().signer.storage_error_shutdown_threshold else {
+            return false;
+        };
+
+        if num_errors < threshold.get() {
+            return false;
+        }
+
+        tracing::error!(
+            %error,
+            %num_errors,
+            "too many consecutive storage errors, signalling shutdown"
+        );
+        self.get_termination_handle().signal_shutdown();
+        true
+    }
+
     /// Create a new signal stream containing signer messages from:
     /// 1. The signer network, as defined by the given network object
     ///    implementing [`MessageTransfer`].
@@ -33,6 +33,9 @@ pub struct SignerContext<S, BC, ST, EM> {
     storage: S,
     /// Handle to a Bitcoin-RPC fallback-client.
     bitcoin_client: BC,
+    /// Handle to a Bitcoin-RPC client that is used for backfilling blocks,
+    /// if one has been configured.
+    backfill_bitcoin_client: Option<BC>,
     // TODO: Additional clients to be added in future PRs. We may want
     // to break the clients out into a separate struct to keep the field
     // count down.
@@ -58,10 +61,18 @@ where
     /// based on the provided types.
     pub fn init(config: Settings, db: S) -> Result<Self, Error> {
         let bc = BC::try_from(&config.bitcoin.rpc_endpoints)?;
+        let backfill_bc = config
+            .bitcoin
+            .backfill_rpc_endpoint
+            .as_ref()
+            .map(|url| BC::try_from(std::slice::from_ref(url)))
+            .transpose()?;
         let st = ST::try_from(&config)?;
         let em = EM::try_from(&config.emily)?;
 
-        Ok(Self::new(config, db, bc, st, em))
+        let mut context = Self::new(config, db, bc, st, em);
+        context.backfill_bitcoin_client = backfill_bc;
+        Ok(context)
     }
 }
 
@@ -97,6 +108,7 @@ where
             term_tx,
             storage: db,
             bitcoin_client,
+            backfill_bitcoin_client: None,
             stacks_client,
             emily_client,
         }
@@ -158,6 +170,12 @@ where
         self.bitcoin_client.clone()
     }
 
+    fn get_backfill_bitcoin_client(&self) -> impl BitcoinInteract + Clone + 'static {
+        self.backfill_bitcoin_client
+            .clone()
+            .unwrap_or_else(|| self.bitcoin_client.clone())
+    }
+
     fn get_stacks_client(&self) -> impl StacksInteract + Clone + 'static {
         self.stacks_client.clone()
     }
@@ -174,6 +192,11 @@ impl<Storage, Bitcoin, Stacks, Emily> SignerContext<Storage, Bitcoin, Stacks, Em
         &mut self.config
     }
 
+    /// Set the Bitcoin client that is used for backfilling blocks.
+    pub fn set_backfill_bitcoin_client(&mut self, client: Bitcoin) {
+        self.backfill_bitcoin_client = Some(client);
+    }
+
     /// Resets the termination signal for this context.
     ///
     /// This sets the underlying termination state to `false`, allowing
@@ -192,18 +215,74 @@ impl<Storage, Bitcoin, Stacks, Emily> SignerContext<Storage, Bitcoin, Stacks, Em
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeSet;
+    use std::num::NonZeroU16;
     use std::sync::{
         Arc,
         atomic::{AtomicU8, Ordering},
     };
 
+    use fake::{Fake as _, Faker};
     use tokio::sync::Notify;
 
     use crate::{
         context::{Context as _, SignerEvent, SignerSignal},
-        testing::context::*,
+        keys::{PrivateKey, PublicKey},
+        storage::model::BitcoinBlockRef,
+        testing::{context::*, get_rng},
     };
 
+    #[tokio::test]
+    async fn is_coordinator_elects_exactly_one_signer() {
+        let mut rng = get_rng();
+        let mut context = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let private_keys: Vec<PrivateKey> = std::iter::repeat_with(|| PrivateKey::new(&mut rng))
+            .take(7)
+            .collect();
+        let signer_set: BTreeSet<PublicKey> = private_keys
+            .iter()
+            .map(PublicKey::from_private_key)
+            .collect();
+        context.config_mut().signer.bootstrap_signing_set = signer_set;
+
+        let chain_tip: BitcoinBlockRef = Faker.fake_with_rng(&mut rng);
+
+        let mut num_coordinators = 0;
+        for private_key in private_keys {
+            context.config_mut().signer.private_key = private_key;
+            if context.is_coordinator(&chain_tip).unwrap() {
+                num_coordinators += 1;
+            }
+        }
+
+        assert_eq!(num_coordinators, 1);
+    }
+
+    #[tokio::test]
+    async fn effective_config_summary_reflects_settings() {
+        let mut context = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let summary = context.effective_config_summary();
+        assert_eq!(summary.max_deposits_per_bitcoin_tx, 25);
+
+        context.config_mut().signer.max_deposits_per_bitcoin_tx = NonZeroU16::new(7).unwrap();
+
+        let summary = context.effective_config_summary();
+        assert_eq!(summary.max_deposits_per_bitcoin_tx, 7);
+        assert_eq!(summary.network, context.config().signer.network);
+        assert_eq!(
+            summary.context_window,
+            context.config().signer.context_window
+        );
+    }
+
     /// This test shows that cloning a context and signalling on the original
     /// context will also signal on the cloned context. But it also demonstrates
     /// that there can be timing issues (particularly in tests) when signalling
@@ -3,18 +3,21 @@
 use std::collections::BTreeSet;
 use std::sync::{
     RwLock,
-    atomic::{AtomicBool, AtomicU64, Ordering},
+    atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
 };
 
 use bitcoin::Amount;
+use bitcoin::ScriptBuf;
 use hashbrown::HashSet;
 use libp2p::PeerId;
 
 use crate::keys::PublicKey;
+use crate::keys::SignerScriptPubKey as _;
 use crate::stacks::api::SignerSetInfo;
 use crate::storage::model::BitcoinBlockHash;
 use crate::storage::model::BitcoinBlockHeight;
 use crate::storage::model::BitcoinBlockRef;
+use crate::storage::model::StacksTxId;
 
 /// A struct for holding internal signer state. This struct is served by
 /// the [`SignerContext`] and can be used to cache global state instead of
@@ -24,12 +27,28 @@ pub struct SignerState {
     current_signer_set: SignerSet,
     current_limits: RwLock<SbtcLimits>,
     registry_signing_set_info: RwLock<Option<SignerSetInfo>>,
+    // The scriptPubKeys of the aggregate keys in the signer set info
+    // that we have observed in the sbtc-registry.
+    signer_script_set: RwLock<std::collections::HashSet<ScriptBuf>>,
+    // The txid of the latest rotate-keys contract call that we have
+    // observed, if any have been observed since the signer started.
+    latest_key_rotation_txid: RwLock<Option<StacksTxId>>,
+    // The signer set info last fetched from the sbtc-registry, along with
+    // the latest rotate-keys txid at the time that it was fetched.
+    signer_set_info_cache: RwLock<Option<CachedSignerSetInfo>>,
     sbtc_contracts_deployed: AtomicBool,
     sbtc_bitcoin_start_height: AtomicU64,
     is_sbtc_bitcoin_start_height_set: AtomicBool,
     // The current bitcoin chain tip. This gets updated at the end of the
     // block observer's duties when it observes a new bitcoin block.
     bitcoin_chain_tip: RwLock<BitcoinBlockRef>,
+    // The number of storage errors encountered since the last successful
+    // storage operation.
+    consecutive_storage_errors: AtomicU32,
+    // The hashes of the bitcoin blocks that the block observer has
+    // received but not yet finished processing, in the order that they
+    // were received.
+    pending_block_hashes: RwLock<Vec<BitcoinBlockHash>>,
 }
 
 impl SignerState {
@@ -47,7 +66,16 @@ impl SignerState {
     }
 
     /// Replace the current signer set info with the given input.
+    ///
+    /// This also adds the scriptPubKey of the aggregate key in the given
+    /// info to the signer script set. The scriptPubKeys of earlier
+    /// aggregate keys are kept, since UTXOs locked by them may still be
+    /// spent after a key rotation.
     pub fn update_registry_signer_set_info(&self, info: SignerSetInfo) {
+        self.signer_script_set
+            .write()
+            .expect("BUG: Failed to acquire write lock of signer script set")
+            .insert(info.aggregate_key.signers_script_pubkey());
         self.registry_signing_set_info
             .write()
             .expect("BUG: Failed to acquire write lock of signer set info")
@@ -65,6 +93,79 @@ impl SignerState {
             .cloned()
     }
 
+    /// Return the scriptPubKeys of the aggregate keys that have been
+    /// observed in the sbtc-registry.
+    pub fn signer_script_set(&self) -> std::collections::HashSet<ScriptBuf> {
+        self.signer_script_set
+            .read()
+            .expect("BUG: Failed to acquire read lock of signer script set")
+            .clone()
+    }
+
+    /// Get the txid of the latest rotate-keys contract call observed by
+    /// this signer.
+    pub fn latest_key_rotation_txid(&self) -> Option<StacksTxId> {
+        *self
+            .latest_key_rotation_txid
+            .read()
+            .expect("BUG: Failed to acquire read lock")
+    }
+
+    /// Set the txid of the latest rotate-keys contract call observed by
+    /// this signer. Any cached signer set info that was fetched before
+    /// this call is no longer returned by
+    /// [`SignerState::cached_signer_set_info`].
+    pub fn set_latest_key_rotation_txid(&self, txid: StacksTxId) {
+        self.latest_key_rotation_txid
+            .write()
+            .expect("BUG: Failed to acquire write lock")
+            .replace(txid);
+    }
+
+    /// Return the cached signer set info, along with the bitcoin chain tip
+    /// at the time that it was fetched, if it was fetched after the latest
+    /// observed rotate-keys contract call.
+    pub fn cached_signer_set_info(&self) -> Option<(BitcoinBlockRef, SignerSetInfo)> {
+        let latest_txid = self.latest_key_rotation_txid();
+        self.signer_set_info_cache
+            .read()
+            .expect("BUG: Failed to acquire read lock")
+            .as_ref()
+            .filter(|cached| cached.key_rotation_txid == latest_txid)
+            .map(|cached| (cached.chain_tip, cached.info.clone()))
+    }
+
+    /// Cache the given signer set info. The `key_rotation_txid` should be
+    /// the value of [`SignerState::latest_key_rotation_txid`] from before
+    /// the info was fetched, and `chain_tip` the canonical bitcoin chain
+    /// tip when it was fetched.
+    pub fn cache_signer_set_info(
+        &self,
+        key_rotation_txid: Option<StacksTxId>,
+        chain_tip: BitcoinBlockRef,
+        info: SignerSetInfo,
+    ) {
+        let cached = CachedSignerSetInfo {
+            key_rotation_txid,
+            chain_tip,
+            info,
+        };
+        self.signer_set_info_cache
+            .write()
+            .expect("BUG: Failed to acquire write lock")
+            .replace(cached);
+    }
+
+    /// Drop any cached signer set info, forcing the next call to
+    /// [`get_signer_set_info`](crate::block_observer::get_signer_set_info)
+    /// to fetch it from the stacks node.
+    pub fn invalidate_signer_set_info_cache(&self) {
+        self.signer_set_info_cache
+            .write()
+            .expect("BUG: Failed to acquire write lock")
+            .take();
+    }
+
     /// Get the current bitcoin chain tip.
     pub fn bitcoin_chain_tip(&self) -> BitcoinBlockRef {
         self.bitcoin_chain_tip
@@ -129,6 +230,52 @@ impl SignerState {
     pub fn is_sbtc_bitcoin_start_height_set(&self) -> bool {
         self.is_sbtc_bitcoin_start_height_set.load(Ordering::SeqCst)
     }
+
+    /// Record a storage error, returning the number of consecutive
+    /// storage errors including this one.
+    pub fn record_storage_error(&self) -> u32 {
+        self.consecutive_storage_errors
+            .fetch_add(1, Ordering::SeqCst)
+            .saturating_add(1)
+    }
+
+    /// Reset the number of consecutive storage errors.
+    pub fn reset_storage_errors(&self) {
+        self.consecutive_storage_errors.store(0, Ordering::SeqCst);
+    }
+
+    /// Get the hashes of the bitcoin blocks that have been received by the
+    /// block observer but have not been processed yet, in the order that
+    /// they were received.
+    pub fn pending_block_hashes(&self) -> Vec<BitcoinBlockHash> {
+        self.pending_block_hashes
+            .read()
+            .expect("BUG: Failed to acquire read lock of pending block hashes")
+            .clone()
+    }
+
+    /// Mark the given bitcoin block hash as pending processing. Returns
+    /// false if the block hash was already pending.
+    pub fn add_pending_block_hash(&self, block_hash: BitcoinBlockHash) -> bool {
+        let mut pending = self
+            .pending_block_hashes
+            .write()
+            .expect("BUG: Failed to acquire write lock of pending block hashes");
+        if pending.contains(&block_hash) {
+            return false;
+        }
+        pending.push(block_hash);
+        true
+    }
+
+    /// Remove the given bitcoin block hash from the set of block hashes
+    /// pending processing.
+    pub fn remove_pending_block_hash(&self, block_hash: &BitcoinBlockHash) {
+        self.pending_block_hashes
+            .write()
+            .expect("BUG: Failed to acquire write lock of pending block hashes")
+            .retain(|pending| pending != block_hash);
+    }
 }
 
 impl Default for SignerState {
@@ -137,9 +284,14 @@ impl Default for SignerState {
             current_signer_set: Default::default(),
             current_limits: RwLock::new(SbtcLimits::zero()),
             registry_signing_set_info: RwLock::new(None),
+            signer_script_set: RwLock::new(Default::default()),
+            latest_key_rotation_txid: RwLock::new(None),
+            signer_set_info_cache: RwLock::new(None),
             sbtc_contracts_deployed: Default::default(),
             sbtc_bitcoin_start_height: Default::default(),
             is_sbtc_bitcoin_start_height_set: Default::default(),
+            consecutive_storage_errors: Default::default(),
+            pending_block_hashes: RwLock::new(Vec::new()),
             // The block hash here is often used as the parent block hash
             // of the genesis block on bitcoin.
             bitcoin_chain_tip: RwLock::new(BitcoinBlockRef {
@@ -150,8 +302,18 @@ impl Default for SignerState {
     }
 }
 
+/// Signer set info fetched from the sbtc-registry, keyed on the latest
+/// rotate-keys contract call and the bitcoin chain tip at the time that it
+/// was fetched.
+#[derive(Debug, Clone)]
+struct CachedSignerSetInfo {
+    key_rotation_txid: Option<StacksTxId>,
+    chain_tip: BitcoinBlockRef,
+    info: SignerSetInfo,
+}
+
 /// Represents the current sBTC limits.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct SbtcLimits {
     /// Represents the total cap for all pegged-in BTC/sBTC.
     total_cap: Option<Amount>,
@@ -170,6 +332,9 @@ pub struct SbtcLimits {
     withdrawn_total: Option<u64>,
     /// Represents the maximum amount of sBTC that can currently be minted.
     max_mintable_cap: Option<Amount>,
+    /// Represents the maximum total amount of pending deposits allowed for
+    /// a single stacks recipient.
+    per_recipient_cap: Option<Amount>,
 }
 
 /// A struct containing the two parameters that define the rolling
@@ -211,14 +376,15 @@ impl std::fmt::Display for SbtcLimits {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "[total cap: {:?}, per-deposit min: {:?}, per-deposit cap: {:?}, per-withdrawal cap: {:?}, max-mintable cap: {:?}, rolling-withdrawal blocks: {:?}, rolling-withdrawal cap: {:?}]",
+            "[total cap: {:?}, per-deposit min: {:?}, per-deposit cap: {:?}, per-withdrawal cap: {:?}, max-mintable cap: {:?}, rolling-withdrawal blocks: {:?}, rolling-withdrawal cap: {:?}, per-recipient cap: {:?}]",
             self.total_cap,
             self.per_deposit_minimum,
             self.per_deposit_cap,
             self.per_withdrawal_cap,
             self.max_mintable_cap,
             self.rolling_withdrawal_blocks,
-            self.rolling_withdrawal_cap
+            self.rolling_withdrawal_cap,
+            self.per_recipient_cap
         )
     }
 }
@@ -235,6 +401,7 @@ impl SbtcLimits {
         rolling_withdrawal_cap: Option<u64>,
         withdrawn_total: Option<u64>,
         max_mintable_cap: Option<Amount>,
+        per_recipient_cap: Option<Amount>,
     ) -> Self {
         Self {
             total_cap,
@@ -245,6 +412,7 @@ impl SbtcLimits {
             rolling_withdrawal_cap,
             withdrawn_total,
             max_mintable_cap,
+            per_recipient_cap,
         }
     }
 
@@ -259,6 +427,7 @@ impl SbtcLimits {
             rolling_withdrawal_cap: Some(0),
             withdrawn_total: Some(u64::MAX),
             max_mintable_cap: Some(Amount::ZERO),
+            per_recipient_cap: Some(Amount::ZERO),
         }
     }
 
@@ -287,11 +456,70 @@ impl SbtcLimits {
         self.per_withdrawal_cap.unwrap_or(Amount::MAX_MONEY)
     }
 
+    /// Get the maximum amount of BTC that a single sweep transaction may
+    /// bring in through deposits.
+    ///
+    /// No single transaction may sweep in more than the total cap for all
+    /// pegged-in BTC, so that is what we use here.
+    pub fn per_tx_cap(&self) -> Amount {
+        self.total_cap()
+    }
+
     /// Get the maximum amount of sBTC that can currently be minted.
     pub fn max_mintable_cap(&self) -> Amount {
         self.max_mintable_cap.unwrap_or(Amount::MAX_MONEY)
     }
 
+    /// Get the maximum total amount of pending deposits allowed for a
+    /// single stacks recipient.
+    pub fn per_recipient_cap(&self) -> Amount {
+        self.per_recipient_cap.unwrap_or(Amount::MAX_MONEY)
+    }
+
+    /// Combine these limits with the `other` limits, taking the more
+    /// restrictive value of each limit. A limit that is not set places no
+    /// restriction, so the value from the other side is used.
+    ///
+    /// For caps and the rolling withdrawal cap the smaller value is more
+    /// restrictive. For the per-deposit minimum, the rolling withdrawal
+    /// window and the amount withdrawn within it, the larger value is.
+    pub fn merge(&self, other: &SbtcLimits) -> SbtcLimits {
+        fn stricter<T: Ord>(a: Option<T>, b: Option<T>, pick: fn(T, T) -> T) -> Option<T> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(pick(a, b)),
+                (a, b) => a.or(b),
+            }
+        }
+
+        SbtcLimits {
+            total_cap: stricter(self.total_cap, other.total_cap, Ord::min),
+            per_deposit_minimum: stricter(
+                self.per_deposit_minimum,
+                other.per_deposit_minimum,
+                Ord::max,
+            ),
+            per_deposit_cap: stricter(self.per_deposit_cap, other.per_deposit_cap, Ord::min),
+            per_withdrawal_cap: stricter(
+                self.per_withdrawal_cap,
+                other.per_withdrawal_cap,
+                Ord::min,
+            ),
+            rolling_withdrawal_blocks: stricter(
+                self.rolling_withdrawal_blocks,
+                other.rolling_withdrawal_blocks,
+                Ord::max,
+            ),
+            rolling_withdrawal_cap: stricter(
+                self.rolling_withdrawal_cap,
+                other.rolling_withdrawal_cap,
+                Ord::min,
+            ),
+            withdrawn_total: stricter(self.withdrawn_total, other.withdrawn_total, Ord::max),
+            max_mintable_cap: stricter(self.max_mintable_cap, other.max_mintable_cap, Ord::min),
+            per_recipient_cap: stricter(self.per_recipient_cap, other.per_recipient_cap, Ord::min),
+        }
+    }
+
     /// Get the rolling withdrawal limits.
     pub fn rolling_withdrawal_limits(&self) -> RollingWithdrawalLimits {
         let withdrawn_total = self.withdrawn_total.unwrap_or(0);
@@ -324,6 +552,7 @@ impl SbtcLimits {
             rolling_withdrawal_cap: Some(u64::MAX),
             max_mintable_cap: Some(Amount::MAX_MONEY),
             withdrawn_total: Some(0),
+            per_recipient_cap: Some(Amount::MAX_MONEY),
         }
     }
 
@@ -339,6 +568,7 @@ impl SbtcLimits {
             rolling_withdrawal_cap: None,
             max_mintable_cap: None,
             withdrawn_total: None,
+            per_recipient_cap: None,
         }
     }
 
@@ -353,6 +583,7 @@ impl SbtcLimits {
             rolling_withdrawal_cap: None,
             max_mintable_cap: None,
             withdrawn_total: None,
+            per_recipient_cap: None,
         }
     }
 
@@ -367,6 +598,7 @@ impl SbtcLimits {
             rolling_withdrawal_cap: Some(rolling.cap),
             max_mintable_cap: None,
             withdrawn_total: Some(rolling.withdrawn_total),
+            per_recipient_cap: None,
         }
     }
 }
@@ -568,4 +800,74 @@ mod tests {
         signer_set.remove_signer(&public_key);
         assert!(!signer_set.is_allowed_peer(&public_key.into()));
     }
+
+    #[test]
+    fn merge_limits_with_one_side_unset() {
+        use super::*;
+
+        let remote = SbtcLimits::new_per_deposit(10_000, 100_000);
+        let local = SbtcLimits::new_per_withdrawal(50_000);
+
+        let merged = remote.merge(&local);
+        assert_eq!(merged.per_deposit_minimum(), Amount::from_sat(10_000));
+        assert_eq!(merged.per_deposit_cap(), Amount::from_sat(100_000));
+        assert_eq!(merged.per_withdrawal_cap(), Amount::from_sat(50_000));
+        assert!(!merged.total_cap_exists());
+        assert_eq!(merged, local.merge(&remote));
+    }
+
+    #[test]
+    fn merge_limits_takes_the_more_restrictive_values() {
+        use super::*;
+
+        let remote = SbtcLimits::new_per_deposit(10_000, 100_000);
+        let local = SbtcLimits::new_per_deposit(20_000, 80_000);
+
+        let merged = remote.merge(&local);
+        assert_eq!(merged.per_deposit_minimum(), Amount::from_sat(20_000));
+        assert_eq!(merged.per_deposit_cap(), Amount::from_sat(80_000));
+
+        let remote = SbtcLimits::new(
+            Some(Amount::from_sat(1_000_000)),
+            None,
+            None,
+            None,
+            Some(100),
+            Some(500_000),
+            Some(1_000),
+            None,
+            Some(Amount::from_sat(300_000)),
+        );
+        let local = SbtcLimits::new(
+            Some(Amount::from_sat(2_000_000)),
+            None,
+            None,
+            None,
+            Some(144),
+            Some(400_000),
+            Some(0),
+            None,
+            Some(Amount::from_sat(200_000)),
+        );
+
+        let merged = remote.merge(&local);
+        assert_eq!(merged.total_cap(), Amount::from_sat(1_000_000));
+        assert_eq!(merged.per_recipient_cap(), Amount::from_sat(200_000));
+
+        let rolling = merged.rolling_withdrawal_limits();
+        assert_eq!(rolling.blocks, 144);
+        assert_eq!(rolling.cap, 400_000);
+        assert_eq!(rolling.withdrawn_total, 1_000);
+    }
+
+    #[test]
+    fn merge_limits_with_equal_values_is_unchanged() {
+        use super::*;
+
+        let limits = SbtcLimits::new_per_deposit(10_000, 100_000);
+        assert_eq!(limits.merge(&limits), limits);
+
+        let limits = SbtcLimits::zero();
+        assert_eq!(limits.merge(&limits), limits);
+    }
 }
@@ -1,5 +1,6 @@
 //! Emily API client module
 
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration;
 use std::time::Instant;
@@ -8,7 +9,7 @@ use bitcoin::Amount;
 use bitcoin::OutPoint;
 use bitcoin::ScriptBuf;
 use bitcoin::Txid;
-use emily_client::apis::Error as EmilyError;
+use emily_client::apis::Error as EmilyApiError;
 use emily_client::apis::ResponseContent;
 use emily_client::apis::configuration::ApiKey;
 use emily_client::apis::configuration::Configuration as EmilyApiConfig;
@@ -17,12 +18,21 @@ use emily_client::apis::limits_api;
 use emily_client::apis::withdrawal_api;
 use emily_client::models::DepositInfo;
 use emily_client::models::DepositUpdate;
+use emily_client::models::GetDepositsResponse;
+use emily_client::models::GetWithdrawalsResponse;
 use emily_client::models::UpdateDepositsRequestBody;
 use emily_client::models::UpdateDepositsResponse;
 use emily_client::models::UpdateWithdrawalsRequestBody;
 use emily_client::models::UpdateWithdrawalsResponse;
+use emily_client::models::WithdrawalInfo;
 use emily_client::models::WithdrawalUpdate;
 use emily_client::models::{DepositStatus, WithdrawalStatus};
+use futures::Stream;
+use futures::StreamExt as _;
+use futures::TryFutureExt as _;
+use futures::TryStreamExt as _;
+use rand::Rng as _;
+use rand::rngs::OsRng;
 use sbtc::deposits::CreateDepositRequest;
 use url::Url;
 
@@ -47,23 +57,147 @@ pub enum EmilyClientError {
 
     /// An error occurred while getting a deposit request
     #[error("error getting a deposit: {0}")]
-    GetDeposit(EmilyError<deposit_api::GetDepositError>),
+    GetDeposit(EmilyApiError<deposit_api::GetDepositError>),
 
     /// An error occurred while getting deposits
     #[error("error getting deposits: {0}")]
-    GetDeposits(EmilyError<deposit_api::GetDepositsError>),
+    GetDeposits(EmilyApiError<deposit_api::GetDepositsError>),
 
     /// An error occurred while updating deposits
     #[error("error updating deposits: {0}")]
-    UpdateDeposits(EmilyError<deposit_api::UpdateDepositsSignerError>),
+    UpdateDeposits(EmilyApiError<deposit_api::UpdateDepositsSignerError>),
+
+    /// An error occurred while getting withdrawals
+    #[error("error getting withdrawals: {0}")]
+    GetWithdrawals(EmilyApiError<withdrawal_api::GetWithdrawalsError>),
 
     /// An error occurred while updating withdrawals
     #[error("error updating withdrawals: {0}")]
-    UpdateWithdrawals(EmilyError<withdrawal_api::UpdateWithdrawalsSignerError>),
+    UpdateWithdrawals(EmilyApiError<withdrawal_api::UpdateWithdrawalsSignerError>),
 
     /// An error occurred while getting limits
     #[error("error getting limits: {0}")]
-    GetLimits(EmilyError<limits_api::GetLimitsError>),
+    GetLimits(EmilyApiError<limits_api::GetLimitsError>),
+
+    /// Could not read a TLS certificate or key file
+    #[error("could not read TLS file {}", .0.display())]
+    ReadTlsFile(PathBuf, #[source] std::io::Error),
+
+    /// The TLS certificates or keys could not be used to build an HTTP
+    /// client
+    #[error("invalid TLS configuration: {0}")]
+    TlsConfig(#[source] emily_reqwest::Error),
+}
+
+impl EmilyClientError {
+    /// Classify the failed request to Emily behind this error by how Emily
+    /// responded, if at all.
+    ///
+    /// Returns `None` if this error is not from a request to Emily, or if
+    /// Emily responded successfully but with a body that we could not
+    /// parse.
+    pub fn response_error(&self) -> Option<EmilyError> {
+        match self {
+            EmilyClientError::GetDeposit(error) => EmilyError::from_api_error(error),
+            EmilyClientError::GetDeposits(error) => EmilyError::from_api_error(error),
+            EmilyClientError::UpdateDeposits(error) => EmilyError::from_api_error(error),
+            EmilyClientError::GetWithdrawals(error) => EmilyError::from_api_error(error),
+            EmilyClientError::UpdateWithdrawals(error) => EmilyError::from_api_error(error),
+            EmilyClientError::GetLimits(error) => EmilyError::from_api_error(error),
+            EmilyClientError::InvalidUrlScheme(_)
+            | EmilyClientError::InvalidUrlHostRequired(_)
+            | EmilyClientError::ReadTlsFile(_, _)
+            | EmilyClientError::TlsConfig(_) => None,
+        }
+    }
+}
+
+/// A failed request to Emily, classified by how Emily responded.
+///
+/// This allows telling apart, say, a misconfigured API key from Emily
+/// being down, which call for different handling and alerting.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EmilyError {
+    /// Emily rejected the request with a 4xx status code other than the
+    /// ones covered by the variants below.
+    #[error("Emily rejected the request with status {status}: {body}")]
+    BadRequest {
+        /// The status code of the response.
+        status: emily_reqwest::StatusCode,
+        /// The body of the response.
+        body: String,
+    },
+    /// Emily rejected the request with a 401 or 403 status code, which
+    /// usually means that the API key is missing or wrong.
+    #[error("Emily rejected our credentials with status {status}: {body}")]
+    Unauthorized {
+        /// The status code of the response.
+        status: emily_reqwest::StatusCode,
+        /// The body of the response.
+        body: String,
+    },
+    /// Emily responded with a 404 status code.
+    #[error("Emily could not find the resource, status {status}: {body}")]
+    NotFound {
+        /// The status code of the response.
+        status: emily_reqwest::StatusCode,
+        /// The body of the response.
+        body: String,
+    },
+    /// Emily failed to handle the request and responded with a 5xx status
+    /// code.
+    #[error("Emily failed with status {status}: {body}")]
+    ServerError {
+        /// The status code of the response.
+        status: emily_reqwest::StatusCode,
+        /// The body of the response.
+        body: String,
+    },
+    /// We could not get a response from Emily, because it could not be
+    /// reached, the connection failed or the request timed out. This
+    /// holds a description of the failure.
+    #[error("could not reach Emily: {0}")]
+    Transport(String),
+}
+
+impl EmilyError {
+    /// Classify an error from the generated Emily client.
+    ///
+    /// Returns `None` if Emily responded successfully but with a body that
+    /// we could not parse, for local I/O errors, and for any other error
+    /// that did not come from connecting to, or waiting on, Emily, such
+    /// as a request that could not be built.
+    pub fn from_api_error<T>(error: &EmilyApiError<T>) -> Option<Self> {
+        match error {
+            EmilyApiError::Reqwest(error) => match error.status() {
+                Some(status) => Some(Self::from_status(status, String::new())),
+                None if error.is_connect() || error.is_timeout() || error.is_request() => {
+                    Some(Self::Transport(error.to_string()))
+                }
+                None => None,
+            },
+            EmilyApiError::ResponseError(content) => {
+                Some(Self::from_status(content.status, content.content.clone()))
+            }
+            EmilyApiError::Serde(_) | EmilyApiError::Io(_) => None,
+        }
+    }
+
+    /// Classify a response from Emily with an unsuccessful status code.
+    fn from_status(status: emily_reqwest::StatusCode, body: String) -> Self {
+        match status.as_u16() {
+            401 | 403 => Self::Unauthorized { status, body },
+            404 => Self::NotFound { status, body },
+            _ if status.is_server_error() => Self::ServerError { status, body },
+            _ => Self::BadRequest { status, body },
+        }
+    }
+
+    /// Whether the request may succeed if it is retried, which is the case
+    /// when Emily failed to handle it or could not be reached.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::ServerError { .. } | Self::Transport(_))
+    }
 }
 
 /// Trait describing the interactions with Emily API.
@@ -87,6 +221,12 @@ pub trait EmilyInteract: Sync + Send {
         status: DepositStatus,
     ) -> impl std::future::Future<Output = Result<Vec<CreateDepositRequest>, Error>> + Send;
 
+    /// Get withdrawals with a specific status from Emily.
+    fn get_withdrawals_with_status(
+        &self,
+        status: WithdrawalStatus,
+    ) -> impl std::future::Future<Output = Result<Vec<WithdrawalInfo>, Error>> + Send;
+
     /// Update accepted deposits after their sweep bitcoin transaction has been
     /// confirmed (but before being finalized -- the stacks transaction minting
     /// sBTC has not been confirmed yet).
@@ -119,6 +259,94 @@ pub trait EmilyInteract: Sync + Send {
     fn get_limits(&self) -> impl std::future::Future<Output = Result<SbtcLimits, Error>> + Send;
 }
 
+/// The identity that the Emily client presents to Emily when mutual TLS
+/// is required.
+#[derive(Clone)]
+pub enum EmilyClientIdentity {
+    /// A PEM encoded certificate chain and a PEM encoded PKCS#8 private
+    /// key.
+    Pem {
+        /// The PEM encoded certificate chain.
+        cert: Vec<u8>,
+        /// The PEM encoded PKCS#8 private key.
+        key: Vec<u8>,
+    },
+    /// A DER encoded PKCS#12 archive with the certificate chain and
+    /// private key.
+    Pkcs12 {
+        /// The DER encoded PKCS#12 archive.
+        der: Vec<u8>,
+        /// The password used to decrypt the archive.
+        password: String,
+    },
+}
+
+/// TLS settings for connecting to Emily.
+#[derive(Clone, Default)]
+pub struct EmilyTlsConfig {
+    /// A PEM encoded CA certificate to trust when verifying the
+    /// certificate of Emily, in addition to the built-in root
+    /// certificates.
+    pub root_ca: Option<Vec<u8>>,
+    /// The identity to present to Emily for mutual TLS.
+    pub client_identity: Option<EmilyClientIdentity>,
+}
+
+impl EmilyTlsConfig {
+    /// Whether these settings differ from the defaults of the HTTP client.
+    pub fn is_empty(&self) -> bool {
+        self.root_ca.is_none() && self.client_identity.is_none()
+    }
+}
+
+impl TryFrom<&EmilyClientConfig> for EmilyTlsConfig {
+    type Error = Error;
+
+    fn try_from(config: &EmilyClientConfig) -> Result<Self, Self::Error> {
+        let read = |path: &PathBuf| {
+            std::fs::read(path).map_err(|err| EmilyClientError::ReadTlsFile(path.clone(), err))
+        };
+
+        let root_ca = config.tls_ca_cert.as_ref().map(read).transpose()?;
+
+        let client_identity = match (&config.tls_client_cert, &config.tls_client_key) {
+            (Some(cert), Some(key)) => Some(EmilyClientIdentity::Pem {
+                cert: read(cert)?,
+                key: read(key)?,
+            }),
+            _ => match &config.tls_client_pkcs12 {
+                Some(path) => Some(EmilyClientIdentity::Pkcs12 {
+                    der: read(path)?,
+                    password: config
+                        .tls_client_pkcs12_password
+                        .clone()
+                        .unwrap_or_default(),
+                }),
+                None => None,
+            },
+        };
+
+        Ok(Self { root_ca, client_identity })
+    }
+}
+
+/// The default maximum size, in bytes, of a response body that we accept
+/// from Emily. Emily caps its responses at 1 MB, so this leaves plenty of
+/// headroom.
+pub const DEFAULT_MAX_RESPONSE_SIZE: u64 = 2 * 1024 * 1024;
+
+/// The default maximum number of items that we accept in a single page of
+/// results from Emily.
+pub const DEFAULT_MAX_PAGE_ITEMS: u64 = 10_000;
+
+/// The default number of attempts made for an idempotent request to Emily
+/// that fails with a transient error.
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// The default delay before the first retry of a request to Emily. The
+/// delay doubles with each subsequent retry.
+pub const DEFAULT_RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
+
 /// Emily API client.
 #[derive(Clone)]
 pub struct EmilyClient {
@@ -128,6 +356,26 @@ pub struct EmilyClient {
     /// Regardless of the page_size setting, responses are always capped at 1 MB total size.
     /// If None, only the 1 MB cap applies.
     page_size: Option<u32>,
+    /// The maximum size, in bytes, of a response body that we are willing
+    /// to read. Larger responses are rejected without being buffered.
+    max_response_size: u64,
+    /// The maximum number of items that we accept in a single page.
+    max_page_items: u64,
+    /// The maximum number of deposits that we fetch for a single status,
+    /// across all pages. If None, all deposits are fetched.
+    max_deposits: Option<u64>,
+    /// The number of attempts made for idempotent requests that fail with
+    /// a transient error.
+    max_attempts: u32,
+    /// The delay before the first retry of a failed request.
+    retry_base_delay: Duration,
+    /// The root certificates, besides the built-in ones, that the HTTP
+    /// client in `config` trusts. An HTTP client cannot be changed once
+    /// it is built, so we keep these around for building its successor.
+    root_certificates: Vec<emily_reqwest::Certificate>,
+    /// The identity that the HTTP client in `config` presents to Emily,
+    /// kept for the same reason as `root_certificates`.
+    client_identity: Option<emily_reqwest::Identity>,
 }
 
 impl EmilyClient {
@@ -166,6 +414,12 @@ impl EmilyClient {
         // the above checks. We just don't want the base_path below to contain
         // the api key.
         let _ = url.set_username("");
+        // Emily may be served under a path prefix, like `/sbtc/api`, and
+        // the generated client appends endpoint paths directly to the base
+        // path, so anything after the path would end up in the middle of
+        // the request URL.
+        url.set_query(None);
+        url.set_fragment(None);
 
         let mut config = EmilyApiConfig::new();
         // Url::parse defaults `path` to `/` even if the parsed url was without the trailing `/`
@@ -180,9 +434,365 @@ impl EmilyClient {
             // This limitation exists because Emily needs to pass the parameter
             // to DynamoDB's as a i32.
             page_size: page_size.map(|size| size as u32),
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            max_page_items: DEFAULT_MAX_PAGE_ITEMS,
+            max_deposits: None,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            retry_base_delay: DEFAULT_RETRY_BASE_DELAY,
+            root_certificates: Vec::new(),
+            client_identity: None,
+        })
+    }
+
+    /// Use the given limits on the size of the responses from Emily.
+    ///
+    /// Responses with a body larger than `max_response_size` bytes, or
+    /// pages with more than `max_page_items` items, are rejected with
+    /// [`Error::EmilyResponseTooLarge`] and [`Error::EmilyPageTooLarge`]
+    /// respectively.
+    pub fn with_response_limits(mut self, max_response_size: u64, max_page_items: u64) -> Self {
+        self.max_response_size = max_response_size;
+        self.max_page_items = max_page_items;
+        self
+    }
+
+    /// Fetch at most `max_deposits` deposits from Emily for a single
+    /// status, or all of them if `None`. Deposits beyond this limit are
+    /// never fetched.
+    pub fn with_max_deposits(mut self, max_deposits: Option<u64>) -> Self {
+        self.max_deposits = max_deposits;
+        self
+    }
+
+    /// Make up to `max_attempts` attempts for idempotent requests that
+    /// fail with an [`EmilyError`] that is retryable.
+    ///
+    /// The delay before the n-th retry is drawn uniformly between half and
+    /// all of `base_delay * 2^(n - 1)`. Setting `max_attempts` to one
+    /// disables retries.
+    pub fn with_retries(mut self, max_attempts: u32, base_delay: Duration) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self.retry_base_delay = base_delay;
+        self
+    }
+
+    /// Use the given TLS settings for connections to Emily.
+    ///
+    /// The given root CA certificate is trusted in addition to the ones
+    /// that the client already trusts, including those from earlier calls
+    /// to this function, and the given client identity, if any, replaces
+    /// the one that the client presents.
+    pub fn with_tls(mut self, tls: &EmilyTlsConfig) -> Result<Self, Error> {
+        if let Some(root_ca) = &tls.root_ca {
+            let certificate = emily_reqwest::Certificate::from_pem(root_ca)
+                .map_err(EmilyClientError::TlsConfig)?;
+            self.root_certificates.push(certificate);
+        }
+
+        if let Some(client_identity) = &tls.client_identity {
+            let identity = match client_identity {
+                EmilyClientIdentity::Pem { cert, key } => {
+                    emily_reqwest::Identity::from_pkcs8_pem(cert, key)
+                }
+                EmilyClientIdentity::Pkcs12 { der, password } => {
+                    emily_reqwest::Identity::from_pkcs12_der(der, password)
+                }
+            };
+            self.client_identity = Some(identity.map_err(EmilyClientError::TlsConfig)?);
+        }
+
+        self.config.client = self.build_http_client()?;
+        Ok(self)
+    }
+
+    /// Build an HTTP client with all the TLS settings of this client.
+    ///
+    /// Apart from the TLS settings, the HTTP client is configured the same
+    /// way as the default one of the generated Emily client.
+    fn build_http_client(&self) -> Result<emily_reqwest::Client, Error> {
+        let builder = self
+            .root_certificates
+            .iter()
+            .cloned()
+            .fold(emily_reqwest::Client::builder(), |builder, certificate| {
+                builder.add_root_certificate(certificate)
+            });
+
+        let builder = match self.client_identity.clone() {
+            Some(identity) => builder.identity(identity),
+            None => builder,
+        };
+
+        let client = builder.build().map_err(EmilyClientError::TlsConfig)?;
+        Ok(client)
+    }
+
+    /// Run the given request, retrying it with exponential backoff and
+    /// jitter while it fails with a transient error.
+    ///
+    /// This must only be used for idempotent requests.
+    async fn retry_transient<T, F, Fut>(&self, mut request: F) -> Result<T, Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T, Error>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match request().await {
+                Err(error) if attempt < self.max_attempts && is_transient(&error) => {
+                    let max_delay = self
+                        .retry_base_delay
+                        .saturating_mul(2u32.saturating_pow(attempt - 1));
+                    let delay = OsRng.gen_range(max_delay / 2..=max_delay);
+                    tracing::warn!(
+                        %error,
+                        attempt,
+                        ?delay,
+                        "transient error from Emily, retrying request"
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Fetch a single page of deposits with the given status, retrying on
+    /// transient errors.
+    async fn get_deposits_page(
+        &self,
+        status: DepositStatus,
+        next_token: Option<&str>,
+    ) -> Result<GetDepositsResponse, Error> {
+        self.retry_transient(move || self.fetch_deposits_page(status, next_token))
+            .await
+    }
+
+    /// Fetch a single page of deposits with the given status.
+    async fn fetch_deposits_page(
+        &self,
+        status: DepositStatus,
+        next_token: Option<&str>,
+    ) -> Result<GetDepositsResponse, Error> {
+        let page: GetDepositsResponse = self
+            .get_page("deposit", status.to_string(), next_token)
+            .await
+            .map_err(|error| error.into_error(EmilyClientError::GetDeposits))?;
+
+        if page.deposits.len() as u64 > self.max_page_items {
+            return Err(Error::EmilyPageTooLarge(self.max_page_items));
+        }
+
+        Ok(page)
+    }
+
+    /// Fetch a single page of withdrawals with the given status.
+    async fn fetch_withdrawals_page(
+        &self,
+        status: WithdrawalStatus,
+        next_token: Option<&str>,
+    ) -> Result<GetWithdrawalsResponse, Error> {
+        let page: GetWithdrawalsResponse = self
+            .get_page("withdrawal", status.to_string(), next_token)
+            .await
+            .map_err(|error| error.into_error(EmilyClientError::GetWithdrawals))?;
+
+        if page.withdrawals.len() as u64 > self.max_page_items {
+            return Err(Error::EmilyPageTooLarge(self.max_page_items));
+        }
+
+        Ok(page)
+    }
+
+    /// Fetch a page of results with the given status from one of the
+    /// paginated list endpoints of Emily, which all take the same query
+    /// parameters.
+    ///
+    /// The generated client buffers the whole response body before
+    /// looking at it, so the request is sent here instead and the body is
+    /// read incrementally, which rejects an oversized response as soon as
+    /// it crosses `max_response_size`.
+    async fn get_page<T, E>(
+        &self,
+        endpoint: &str,
+        status: String,
+        next_token: Option<&str>,
+    ) -> Result<T, PageError<E>>
+    where
+        T: serde::de::DeserializeOwned,
+        E: serde::de::DeserializeOwned,
+    {
+        let uri = format!("{}/{endpoint}", self.config.base_path);
+        let mut request = self.config.client.get(uri).query(&[("status", status)]);
+        if let Some(next_token) = next_token {
+            request = request.query(&[("nextToken", next_token)]);
+        }
+        if let Some(page_size) = self.page_size {
+            request = request.query(&[("pageSize", page_size.to_string())]);
+        }
+        if let Some(user_agent) = &self.config.user_agent {
+            request = request.header(emily_reqwest::header::USER_AGENT, user_agent.clone());
+        }
+
+        let transport_error = |error| PageError::Api(EmilyApiError::Reqwest(error));
+        let mut response = request.send().await.map_err(transport_error)?;
+
+        if response
+            .content_length()
+            .is_some_and(|length| length > self.max_response_size)
+        {
+            return Err(PageError::TooLarge(self.max_response_size));
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(transport_error)? {
+            if (body.len() + chunk.len()) as u64 > self.max_response_size {
+                return Err(PageError::TooLarge(self.max_response_size));
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        let response_status = response.status();
+        if response_status.is_client_error() || response_status.is_server_error() {
+            let content = String::from_utf8_lossy(&body).into_owned();
+            return Err(PageError::Api(EmilyApiError::ResponseError(
+                ResponseContent {
+                    status: response_status,
+                    entity: serde_json::from_str(&content).ok(),
+                    content,
+                },
+            )));
+        }
+
+        serde_json::from_slice(&body).map_err(|error| PageError::Api(EmilyApiError::Serde(error)))
+    }
+
+    /// Stream all deposits with the given status, transparently following
+    /// `next_token` from one page of results to the next.
+    ///
+    /// Pages are only fetched as the stream is polled, so the full result
+    /// set is never held in memory at once. The stream ends after
+    /// `max_deposits` deposits, if set, or once the pagination timeout
+    /// has elapsed, with a warning in both cases. Deposits that cannot be
+    /// parsed are logged and skipped, while a failure to fetch a page is
+    /// returned as the last item of the stream.
+    pub fn get_deposits_paginated(
+        &self,
+        status: DepositStatus,
+    ) -> impl Stream<Item = Result<CreateDepositRequest, Error>> + Send + '_ {
+        self.paginate(
+            "deposits",
+            self.max_deposits,
+            move |next_token| async move {
+                let page = self
+                    .get_deposits_page(status, next_token.as_deref())
+                    .await?;
+                let deposits = page
+                    .deposits
+                    .iter()
+                    .filter_map(|deposit| {
+                        Self::parse_deposit(deposit)
+                            .inspect_err(|error| {
+                                tracing::warn!(
+                                    "Skipping corrupted deposit (txid: {}): {:?}",
+                                    deposit.bitcoin_txid,
+                                    error
+                                )
+                            })
+                            .ok()
+                    })
+                    .collect();
+
+                Ok((deposits, page.next_token.flatten()))
+            },
+        )
+    }
+
+    /// Stream all withdrawals with the given status, transparently
+    /// following `next_token` from one page of results to the next.
+    ///
+    /// Pages are only fetched as the stream is polled. The stream ends
+    /// once the pagination timeout has elapsed, with a warning, while a
+    /// failure to fetch a page is returned as the last item of the stream.
+    pub fn get_withdrawals_paginated(
+        &self,
+        status: WithdrawalStatus,
+    ) -> impl Stream<Item = Result<WithdrawalInfo, Error>> + Send + '_ {
+        self.paginate("withdrawals", None, move |next_token| async move {
+            let page = self
+                .fetch_withdrawals_page(status, next_token.as_deref())
+                .await?;
+            Ok((page.withdrawals, page.next_token.flatten()))
         })
     }
 
+    /// Stream the items of a paginated Emily endpoint, where `fetch_page`
+    /// fetches the page of items identified by the given token along with
+    /// the token of the next page, if there is one.
+    ///
+    /// The stream ends after `max_items` items, if set, or once the
+    /// pagination timeout has elapsed, and a failure to fetch a page is
+    /// returned as the last item of the stream.
+    fn paginate<'a, T, F, Fut>(
+        &'a self,
+        kind: &'static str,
+        max_items: Option<u64>,
+        fetch_page: F,
+    ) -> impl Stream<Item = Result<T, Error>> + Send + 'a
+    where
+        T: Send + 'a,
+        F: Fn(Option<String>) -> Fut + Send + 'a,
+        Fut: Future<Output = Result<(Vec<T>, Option<String>), Error>> + Send + 'a,
+    {
+        let start_time = Instant::now();
+        // The state is the token for the next page along with the number
+        // of items streamed so far, and `None` once we are done.
+        let initial_state: Option<(Option<String>, u64)> = Some((None, 0));
+
+        let pages = futures::stream::try_unfold(initial_state, move |state| {
+            // Futures do nothing until polled, so creating this one is
+            // free if we stop before fetching the page.
+            let page = state.as_ref().map(|(token, _)| fetch_page(token.clone()));
+            async move {
+                let (Some((next_token, num_items)), Some(page)) = (state, page) else {
+                    return Ok(None);
+                };
+                if next_token.is_some() && start_time.elapsed() > self.pagination_timeout {
+                    tracing::warn!(
+                        "timeout fetching {kind}, breaking at page {:?}, fetched {} {kind}",
+                        next_token,
+                        num_items
+                    );
+                    return Ok(None);
+                }
+
+                let (mut items, next_token) = page.await?;
+                let num_items = num_items + items.len() as u64;
+
+                if let Some(max_items) = max_items {
+                    let over_limit = num_items > max_items;
+                    if over_limit || (num_items == max_items && next_token.is_some()) {
+                        tracing::warn!(
+                            max_items,
+                            "reached the maximum number of {kind}, ignoring the rest"
+                        );
+                        let remaining = max_items - (num_items - items.len() as u64);
+                        items.truncate(usize::try_from(remaining).unwrap_or(usize::MAX));
+                        return Ok(Some((items, None)));
+                    }
+                }
+
+                let next_state = next_token.map(|token| (Some(token), num_items));
+                Ok::<_, Error>(Some((items, next_state)))
+            }
+        });
+
+        pages
+            .map_ok(|items| futures::stream::iter(items.into_iter().map(Ok)))
+            .try_flatten()
+    }
+
     fn parse_deposit(deposit: &DepositInfo) -> Result<CreateDepositRequest, Error> {
         Ok(CreateDepositRequest {
             outpoint: OutPoint {
@@ -197,6 +807,40 @@ impl EmilyClient {
     }
 }
 
+/// An error fetching a page of results from Emily.
+enum PageError<E> {
+    /// The request failed or Emily responded with an error.
+    Api(EmilyApiError<E>),
+    /// The response body exceeds the given limit, in bytes.
+    TooLarge(u64),
+}
+
+impl<E> PageError<E> {
+    /// Convert this into an [`Error`], using the given variant of
+    /// [`EmilyClientError`] for errors from the API.
+    fn into_error(self, api_error: fn(EmilyApiError<E>) -> EmilyClientError) -> Error {
+        match self {
+            PageError::Api(error) => Error::EmilyApi(api_error(error)),
+            PageError::TooLarge(max_response_size) => {
+                Error::EmilyResponseTooLarge(max_response_size)
+            }
+        }
+    }
+}
+
+/// Whether the error is from an idempotent request to Emily that may
+/// succeed if retried.
+fn is_transient(error: &Error) -> bool {
+    match error {
+        Error::EmilyApi(
+            error @ (EmilyClientError::GetDeposits(_) | EmilyClientError::GetLimits(_)),
+        ) => error
+            .response_error()
+            .is_some_and(|error| error.is_retryable()),
+        _ => false,
+    }
+}
+
 impl EmilyInteract for EmilyClient {
     async fn get_deposit(
         &self,
@@ -210,7 +854,7 @@ impl EmilyInteract for EmilyClient {
 
         let deposit = match resp {
             Ok(deposit) => deposit,
-            Err(EmilyError::ResponseError(ResponseContent { status, .. }))
+            Err(EmilyApiError::ResponseError(ResponseContent { status, .. }))
                 if status.as_u16() == 404 =>
             {
                 return Ok(None);
@@ -262,57 +906,28 @@ impl EmilyInteract for EmilyClient {
         status: DepositStatus,
     ) -> Result<Vec<CreateDepositRequest>, Error> {
         let mut all_deposits = Vec::new();
-        let mut next_token: Option<String> = None;
-        let start_time = Instant::now();
-        loop {
-            let resp = match deposit_api::get_deposits(
-                &self.config,
-                status,
-                next_token.as_deref(),
-                self.page_size,
-            )
-            .await
-            {
-                Ok(resp) => resp,
-                Err(e) => {
-                    if all_deposits.is_empty() {
-                        return Err(Error::EmilyApi(EmilyClientError::GetDeposits(e)));
-                    }
-                    tracing::warn!("failed to fetch page of deposits: {:?}", e);
+        let mut deposits = std::pin::pin!(self.get_deposits_paginated(status));
+        while let Some(deposit) = deposits.next().await {
+            match deposit {
+                Ok(deposit) => all_deposits.push(deposit),
+                Err(error) if all_deposits.is_empty() => return Err(error),
+                Err(error) => {
+                    tracing::warn!("failed to fetch page of deposits: {:?}", error);
                     break;
                 }
-            };
-            // Convert each DepositInfo to our CreateDepositRequest
-            for deposit in resp.deposits.iter() {
-                match Self::parse_deposit(deposit) {
-                    Ok(req) => all_deposits.push(req),
-                    Err(e) => tracing::warn!(
-                        "Skipping corrupted deposit (txid: {}): {:?}",
-                        deposit.bitcoin_txid,
-                        e
-                    ),
-                }
-            }
-
-            // If more pages exist, loop again; otherwise stop
-            match resp.next_token.flatten() {
-                Some(token) => next_token = Some(token),
-                None => break,
-            }
-
-            if start_time.elapsed() > self.pagination_timeout {
-                tracing::warn!(
-                    "timeout fetching deposits, breaking at page {:?}, fetched {} deposits",
-                    next_token,
-                    all_deposits.len()
-                );
-                break;
             }
         }
 
         Ok(all_deposits)
     }
 
+    async fn get_withdrawals_with_status(
+        &self,
+        status: WithdrawalStatus,
+    ) -> Result<Vec<WithdrawalInfo>, Error> {
+        self.get_withdrawals_paginated(status).try_collect().await
+    }
+
     async fn update_deposits(
         &self,
         update_deposits: Vec<DepositUpdate>,
@@ -365,6 +980,7 @@ impl EmilyInteract for EmilyClient {
                 status: DepositStatus::Accepted,
                 fulfillment: None,
                 status_message: "".to_string(),
+                reclaim_txid: None,
                 replaced_by_tx: None,
             })
             .collect();
@@ -390,10 +1006,12 @@ impl EmilyInteract for EmilyClient {
     }
 
     async fn get_limits(&self) -> Result<SbtcLimits, Error> {
-        let limits = limits_api::get_limits(&self.config)
-            .await
-            .map_err(EmilyClientError::GetLimits)
-            .map_err(Error::EmilyApi)?;
+        let limits = self
+            .retry_transient(move || {
+                limits_api::get_limits(&self.config)
+                    .map_err(|error| Error::EmilyApi(EmilyClientError::GetLimits(error)))
+            })
+            .await?;
 
         let total_cap = limits.peg_cap.flatten().map(Amount::from_sat);
         let per_deposit_minimum = limits.per_deposit_minimum.flatten().map(Amount::from_sat);
@@ -414,6 +1032,7 @@ impl EmilyInteract for EmilyClient {
             rolling_withdrawal_cap,
             None,
             None,
+            None,
         ))
     }
 }
@@ -440,6 +1059,14 @@ impl EmilyInteract for ApiFallbackClient<EmilyClient> {
             .await
     }
 
+    async fn get_withdrawals_with_status(
+        &self,
+        status: WithdrawalStatus,
+    ) -> Result<Vec<WithdrawalInfo>, Error> {
+        self.exec(|client, _| client.get_withdrawals_with_status(status))
+            .await
+    }
+
     async fn update_deposits(
         &self,
         update_deposits: Vec<DepositUpdate>,
@@ -481,10 +1108,21 @@ impl TryFrom<&EmilyClientConfig> for ApiFallbackClient<EmilyClient> {
     type Error = Error;
 
     fn try_from(config: &EmilyClientConfig) -> Result<Self, Self::Error> {
+        let tls = EmilyTlsConfig::try_from(config)?;
         let clients = config
             .endpoints
             .iter()
-            .map(|url| EmilyClient::try_new(url, config.pagination_timeout, None))
+            .map(|url| {
+                let client = EmilyClient::try_new(url, config.pagination_timeout, None)?
+                    .with_response_limits(config.max_response_size, config.max_page_items)
+                    .with_max_deposits(config.max_deposits)
+                    .with_retries(config.max_attempts, config.retry_base_delay);
+                if tls.is_empty() {
+                    Ok(client)
+                } else {
+                    client.with_tls(&tls)
+                }
+            })
             .collect::<Result<Vec<_>, _>>()?;
 
         Self::new(clients).map_err(Into::into)
@@ -493,6 +1131,8 @@ impl TryFrom<&EmilyClientConfig> for ApiFallbackClient<EmilyClient> {
 
 #[cfg(test)]
 mod tests {
+    use std::net::Ipv4Addr;
+
     use super::*;
 
     #[test]
@@ -516,4 +1156,520 @@ mod tests {
         assert_eq!(client.config.base_path, "http://localhost:8080");
         assert!(client.config.api_key.is_none());
     }
+
+    #[test]
+    fn try_from_url_with_path_prefix() {
+        // Arrange.
+        let url = Url::parse("http://test_key@localhost:8080/sbtc/api/?foo=bar#baz").unwrap();
+        // Act.
+        let client = EmilyClient::try_new(&url, Duration::from_secs(1), None).unwrap();
+        // Assert.
+        assert_eq!(client.config.base_path, "http://localhost:8080/sbtc/api");
+        assert_eq!(client.config.api_key.unwrap().key, "test_key");
+    }
+
+    #[tokio::test]
+    async fn get_deposits_uses_path_prefix() {
+        let mut server = mockito::Server::new_async().await;
+        let url = Url::parse(&format!("{}/sbtc/api/", server.url())).unwrap();
+        let client = EmilyClient::try_new(&url, Duration::from_secs(1), None).unwrap();
+
+        let body = serde_json::json!({ "deposits": [], "nextToken": null }).to_string();
+        // We fetch both pending and accepted deposits.
+        let mock = server
+            .mock("GET", "/sbtc/api/deposit")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let deposits = client.get_deposits().await.unwrap();
+        assert!(deposits.is_empty());
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn get_deposits_rejects_oversized_responses() {
+        let mut server = mockito::Server::new_async().await;
+        let url = Url::parse(&server.url()).unwrap();
+
+        let deposit = serde_json::json!({
+            "amount": 100_000,
+            "bitcoinTxOutputIndex": 0,
+            "bitcoinTxid": "0000000000000000000000000000000000000000000000000000000000000000",
+            "depositScript": "",
+            "lastUpdateBlockHash": "",
+            "lastUpdateHeight": 0,
+            "recipient": "",
+            "reclaimScript": "",
+            "status": "pending",
+        });
+        let deposits = vec![deposit; 10];
+        let body = serde_json::json!({ "deposits": deposits, "nextToken": null }).to_string();
+        let _mock = server
+            .mock("GET", "/deposit")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(&body)
+            .create_async()
+            .await;
+
+        // The body is larger than the maximum response size.
+        let client = EmilyClient::try_new(&url, Duration::from_secs(1), None)
+            .unwrap()
+            .with_response_limits(body.len() as u64 - 1, DEFAULT_MAX_PAGE_ITEMS);
+        let result = client
+            .get_deposits_with_status(DepositStatus::Pending)
+            .await;
+        assert!(matches!(result, Err(Error::EmilyResponseTooLarge(_))));
+
+        // The page has more items than allowed.
+        let client = EmilyClient::try_new(&url, Duration::from_secs(1), None)
+            .unwrap()
+            .with_response_limits(DEFAULT_MAX_RESPONSE_SIZE, 9);
+        let result = client
+            .get_deposits_with_status(DepositStatus::Pending)
+            .await;
+        assert!(matches!(result, Err(Error::EmilyPageTooLarge(9))));
+
+        // Right at the limits the page is accepted.
+        let client = EmilyClient::try_new(&url, Duration::from_secs(1), None)
+            .unwrap()
+            .with_response_limits(body.len() as u64, 10);
+        let deposits = client
+            .get_deposits_with_status(DepositStatus::Pending)
+            .await
+            .unwrap();
+        assert_eq!(deposits.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn get_deposits_retries_transient_errors() {
+        let mut server = mockito::Server::new_async().await;
+        let url = Url::parse(&server.url()).unwrap();
+
+        let body = serde_json::json!({ "deposits": [], "nextToken": null }).to_string();
+        let unavailable = server
+            .mock("GET", "/deposit")
+            .match_query(mockito::Matcher::Any)
+            .with_status(503)
+            .expect(2)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("GET", "/deposit")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(&body)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = EmilyClient::try_new(&url, Duration::from_secs(1), None)
+            .unwrap()
+            .with_retries(3, Duration::from_millis(1));
+        let deposits = client
+            .get_deposits_with_status(DepositStatus::Pending)
+            .await
+            .unwrap();
+        assert!(deposits.is_empty());
+
+        unavailable.assert_async().await;
+        ok.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn get_deposits_does_not_retry_client_errors() {
+        let mut server = mockito::Server::new_async().await;
+        let url = Url::parse(&server.url()).unwrap();
+
+        let bad_request = server
+            .mock("GET", "/deposit")
+            .match_query(mockito::Matcher::Any)
+            .with_status(400)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = EmilyClient::try_new(&url, Duration::from_secs(1), None)
+            .unwrap()
+            .with_retries(3, Duration::from_millis(1));
+        let result = client
+            .get_deposits_with_status(DepositStatus::Pending)
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::EmilyApi(EmilyClientError::GetDeposits(_)))
+        ));
+
+        bad_request.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn get_limits_errors_are_classified_by_status() {
+        let cases: [(usize, fn(&EmilyError) -> bool); 5] = [
+            (400, |e| matches!(e, EmilyError::BadRequest { .. })),
+            (401, |e| matches!(e, EmilyError::Unauthorized { .. })),
+            (403, |e| matches!(e, EmilyError::Unauthorized { .. })),
+            (404, |e| matches!(e, EmilyError::NotFound { .. })),
+            (503, |e| matches!(e, EmilyError::ServerError { .. })),
+        ];
+
+        for (status, expected) in cases {
+            let mut server = mockito::Server::new_async().await;
+            let url = Url::parse(&server.url()).unwrap();
+
+            let _mock = server
+                .mock("GET", "/limits")
+                .with_status(status)
+                .with_body("nope")
+                .create_async()
+                .await;
+
+            let client = EmilyClient::try_new(&url, Duration::from_secs(1), None)
+                .unwrap()
+                .with_retries(1, Duration::ZERO);
+            let error = client.get_limits().await.unwrap_err();
+
+            let emily_error = error.emily_error().unwrap();
+            assert!(expected(&emily_error), "{status}: {emily_error:?}");
+            assert_eq!(emily_error.is_retryable(), status >= 500);
+
+            let (EmilyError::BadRequest { status: actual, body }
+            | EmilyError::Unauthorized { status: actual, body }
+            | EmilyError::NotFound { status: actual, body }
+            | EmilyError::ServerError { status: actual, body }) = emily_error
+            else {
+                panic!("expected an error with a status code");
+            };
+            assert_eq!(actual.as_u16() as usize, status);
+            assert_eq!(body, "nope");
+        }
+    }
+
+    #[tokio::test]
+    async fn unreachable_emily_is_a_transport_error() {
+        // Bind to a port and drop the listener so that nothing is
+        // listening on it.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let url = Url::parse(&format!("http://127.0.0.1:{port}")).unwrap();
+        let client = EmilyClient::try_new(&url, Duration::from_secs(1), None)
+            .unwrap()
+            .with_retries(1, Duration::ZERO);
+        let error = client.get_limits().await.unwrap_err();
+
+        let emily_error = error.emily_error().unwrap();
+        assert!(matches!(emily_error, EmilyError::Transport(_)));
+        assert!(emily_error.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn request_builder_errors_are_not_transport_errors() {
+        let error = emily_reqwest::Client::new()
+            .get("not a url")
+            .send()
+            .await
+            .unwrap_err();
+        assert!(error.is_builder());
+
+        let error = EmilyApiError::<()>::Reqwest(error);
+        assert_eq!(EmilyError::from_api_error(&error), None);
+    }
+
+    #[tokio::test]
+    async fn get_deposits_paginated_follows_next_token_up_to_cap() {
+        let mut server = mockito::Server::new_async().await;
+        let url = Url::parse(&server.url()).unwrap();
+
+        let deposit = serde_json::json!({
+            "amount": 100_000,
+            "bitcoinTxOutputIndex": 0,
+            "bitcoinTxid": "0000000000000000000000000000000000000000000000000000000000000000",
+            "depositScript": "",
+            "lastUpdateBlockHash": "",
+            "lastUpdateHeight": 0,
+            "recipient": "",
+            "reclaimScript": "",
+            "status": "pending",
+        });
+        let page1 = serde_json::json!({ "deposits": vec![&deposit; 3], "nextToken": "page-2" });
+        let page2 = serde_json::json!({ "deposits": vec![&deposit; 3], "nextToken": null });
+        let _mock1 = server
+            .mock("GET", "/deposit")
+            .match_query(mockito::Matcher::Regex("^status=pending$".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(page1.to_string())
+            .create_async()
+            .await;
+        let _mock2 = server
+            .mock("GET", "/deposit")
+            .match_query(mockito::Matcher::Regex("nextToken=page-2".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(page2.to_string())
+            .create_async()
+            .await;
+
+        // Without a cap we get the deposits from both pages.
+        let client = EmilyClient::try_new(&url, Duration::from_secs(1), None).unwrap();
+        let deposits: Vec<_> = client
+            .get_deposits_paginated(DepositStatus::Pending)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(deposits.len(), 6);
+
+        // With a cap that falls within the second page, the stream stops
+        // exactly at the cap.
+        let client = client.with_max_deposits(Some(4));
+        let deposits: Vec<_> = client
+            .get_deposits_paginated(DepositStatus::Pending)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(deposits.len(), 4);
+
+        let deposits = client
+            .get_deposits_with_status(DepositStatus::Pending)
+            .await
+            .unwrap();
+        assert_eq!(deposits.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn get_withdrawals_with_status_follows_next_token() {
+        let mut server = mockito::Server::new_async().await;
+        let url = Url::parse(&server.url()).unwrap();
+
+        let withdrawal = serde_json::json!({
+            "amount": 100_000,
+            "lastUpdateBlockHash": "",
+            "lastUpdateHeight": 0,
+            "recipient": "",
+            "requestId": 1,
+            "sender": "",
+            "stacksBlockHash": "",
+            "stacksBlockHeight": 0,
+            "status": "accepted",
+            "txid": "",
+        });
+        let page1 =
+            serde_json::json!({ "withdrawals": vec![&withdrawal; 2], "nextToken": "page-2" });
+        let page2 = serde_json::json!({ "withdrawals": vec![&withdrawal; 3], "nextToken": null });
+        let _mock1 = server
+            .mock("GET", "/withdrawal")
+            .match_query(mockito::Matcher::Regex("^status=accepted$".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(page1.to_string())
+            .create_async()
+            .await;
+        let _mock2 = server
+            .mock("GET", "/withdrawal")
+            .match_query(mockito::Matcher::Regex("nextToken=page-2".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(page2.to_string())
+            .create_async()
+            .await;
+
+        let client = EmilyClient::try_new(&url, Duration::from_secs(1), None).unwrap();
+        let withdrawals = client
+            .get_withdrawals_with_status(WithdrawalStatus::Accepted)
+            .await
+            .unwrap();
+        assert_eq!(withdrawals.len(), 5);
+    }
+
+    /// Generate a self-signed CA certificate.
+    fn generate_ca() -> rcgen::Certificate {
+        let mut params = rcgen::CertificateParams::new(Vec::new());
+        params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+        rcgen::Certificate::from_params(params).unwrap()
+    }
+
+    /// Generate a certificate for 127.0.0.1 signed by the given CA.
+    /// Returns the PEM encoded certificate and PKCS#8 private key.
+    fn generate_signed_cert(ca: &rcgen::Certificate) -> (String, String) {
+        let mut params = rcgen::CertificateParams::new(Vec::new());
+        params.subject_alt_names = vec![rcgen::SanType::IpAddress(Ipv4Addr::LOCALHOST.into())];
+        let cert = rcgen::Certificate::from_params(params).unwrap();
+        let cert_pem = cert.serialize_pem_with_signer(ca).unwrap();
+        (cert_pem, cert.serialize_private_key_pem())
+    }
+
+    /// Start a TLS server on 127.0.0.1, using a certificate signed by the
+    /// given CA, that responds to every request with the same limits. If
+    /// `client_ca` is given then the server requires clients to present
+    /// a certificate signed by it. Returns the port that the server is
+    /// listening on.
+    async fn spawn_tls_limits_server(
+        ca: &rcgen::Certificate,
+        client_ca: Option<&rcgen::Certificate>,
+    ) -> u16 {
+        use openssl::pkey::PKey;
+        use openssl::ssl::Ssl;
+        use openssl::ssl::SslAcceptor;
+        use openssl::ssl::SslMethod;
+        use openssl::ssl::SslVerifyMode;
+        use openssl::x509::X509;
+        use tokio::io::AsyncReadExt as _;
+        use tokio::io::AsyncWriteExt as _;
+
+        let (cert_pem, key_pem) = generate_signed_cert(ca);
+
+        let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls_server()).unwrap();
+        let certificate = X509::from_pem(cert_pem.as_bytes()).unwrap();
+        let private_key = PKey::private_key_from_pem(key_pem.as_bytes()).unwrap();
+        builder.set_certificate(&certificate).unwrap();
+        builder.set_private_key(&private_key).unwrap();
+
+        if let Some(client_ca) = client_ca {
+            let client_ca_pem = client_ca.serialize_pem().unwrap();
+            let client_ca = X509::from_pem(client_ca_pem.as_bytes()).unwrap();
+            builder.cert_store_mut().add_cert(client_ca).unwrap();
+            builder.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+        }
+        let acceptor = builder.build();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let ssl = Ssl::new(acceptor.context()).unwrap();
+                tokio::spawn(async move {
+                    let mut stream = tokio_openssl::SslStream::new(ssl, stream).unwrap();
+                    // Clients that do not trust our CA, or that do not
+                    // present a certificate when we require one, fail the
+                    // handshake.
+                    if std::pin::Pin::new(&mut stream).accept().await.is_err() {
+                        return;
+                    }
+                    let mut buf = [0u8; 4096];
+                    let _ = stream.read(&mut buf).await;
+
+                    let body = r#"{"pegCap":1000,"accountCaps":{}}"#;
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+                        content-length: {}\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                    let _ = stream.shutdown().await;
+                });
+            }
+        });
+
+        port
+    }
+
+    #[tokio::test]
+    async fn custom_root_ca_is_trusted() {
+        let ca = generate_ca();
+        let port = spawn_tls_limits_server(&ca, None).await;
+        let url = Url::parse(&format!("https://127.0.0.1:{port}")).unwrap();
+
+        let tls = EmilyTlsConfig {
+            root_ca: Some(ca.serialize_pem().unwrap().into_bytes()),
+            client_identity: None,
+        };
+        let client = EmilyClient::try_new(&url, Duration::from_secs(1), None)
+            .unwrap()
+            .with_tls(&tls)
+            .unwrap();
+
+        let limits = client.get_limits().await.unwrap();
+        assert_eq!(limits.total_cap(), Amount::from_sat(1000));
+
+        // A client that trusts a different CA must refuse the connection.
+        let other_ca = generate_ca();
+        let tls = EmilyTlsConfig {
+            root_ca: Some(other_ca.serialize_pem().unwrap().into_bytes()),
+            client_identity: None,
+        };
+        let client = EmilyClient::try_new(&url, Duration::from_secs(1), None)
+            .unwrap()
+            .with_tls(&tls)
+            .unwrap();
+
+        let result = client.get_limits().await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn client_certificate_is_presented_when_required() {
+        let ca = generate_ca();
+        let client_ca = generate_ca();
+        let port = spawn_tls_limits_server(&ca, Some(&client_ca)).await;
+        let url = Url::parse(&format!("https://127.0.0.1:{port}")).unwrap();
+
+        let root_ca = EmilyTlsConfig {
+            root_ca: Some(ca.serialize_pem().unwrap().into_bytes()),
+            client_identity: None,
+        };
+        let identity_signed_by = |ca: &rcgen::Certificate| {
+            let (cert, key) = generate_signed_cert(ca);
+            EmilyTlsConfig {
+                root_ca: None,
+                client_identity: Some(EmilyClientIdentity::Pem {
+                    cert: cert.into_bytes(),
+                    key: key.into_bytes(),
+                }),
+            }
+        };
+
+        // Trusting the server is not enough, the server also wants to
+        // know who we are.
+        let client = EmilyClient::try_new(&url, Duration::from_secs(1), None)
+            .unwrap()
+            .with_tls(&root_ca)
+            .unwrap();
+        assert!(client.get_limits().await.is_err());
+
+        // An identity that the server does not trust is rejected too.
+        let client = EmilyClient::try_new(&url, Duration::from_secs(1), None)
+            .unwrap()
+            .with_tls(&root_ca)
+            .unwrap()
+            .with_tls(&identity_signed_by(&ca))
+            .unwrap();
+        assert!(client.get_limits().await.is_err());
+
+        // Adding the identity keeps the root CA from the earlier call, so
+        // the client both trusts the server and is trusted by it.
+        let client = EmilyClient::try_new(&url, Duration::from_secs(1), None)
+            .unwrap()
+            .with_tls(&root_ca)
+            .unwrap()
+            .with_tls(&identity_signed_by(&client_ca))
+            .unwrap();
+
+        let limits = client.get_limits().await.unwrap();
+        assert_eq!(limits.total_cap(), Amount::from_sat(1000));
+    }
+
+    #[test]
+    fn invalid_root_ca_is_rejected() {
+        let url = Url::parse("https://localhost:8080").unwrap();
+        let tls = EmilyTlsConfig {
+            root_ca: Some(b"not a certificate".to_vec()),
+            client_identity: None,
+        };
+        let result = EmilyClient::try_new(&url, Duration::from_secs(1), None)
+            .unwrap()
+            .with_tls(&tls);
+
+        assert!(result.is_err());
+    }
 }
@@ -7,8 +7,10 @@ use blockstack_lib::types::chainstate::StacksBlockId;
 use crate::bitcoin::validation::WithdrawalCapContext;
 use crate::blocklist_client::BlocklistClientError;
 use crate::codec;
+use crate::config::NetworkKind;
 use crate::dkg;
 use crate::emily_client::EmilyClientError;
+use crate::emily_client::EmilyError;
 use crate::keys::PublicKey;
 use crate::keys::PublicKeyXOnly;
 use crate::stacks::contracts::DepositValidationError;
@@ -16,6 +18,7 @@ use crate::stacks::contracts::RotateKeysValidationError;
 use crate::stacks::contracts::WithdrawalAcceptValidationError;
 use crate::stacks::contracts::WithdrawalRejectValidationError;
 use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::QualifiedRequestId;
 use crate::storage::model::SigHash;
 use crate::transaction_signer::StacksSignRequestId;
 use crate::wsts_state_machine::StateMachineId;
@@ -146,6 +149,16 @@ pub enum Error {
     #[error("emily API error: {0}")]
     EmilyApi(#[from] EmilyClientError),
 
+    /// The Emily API returned a response body larger than the configured
+    /// limit, in bytes.
+    #[error("emily API response exceeds the limit of {0} bytes")]
+    EmilyResponseTooLarge(u64),
+
+    /// The Emily API returned a page with more items than the configured
+    /// limit.
+    #[error("emily API page exceeds the limit of {0} items")]
+    EmilyPageTooLarge(u64),
+
     /// An error occurred while communicating with the blocklist client
     #[error("blocklist client error: {0}")]
     BlocklistClient(#[from] BlocklistClientError),
@@ -160,6 +173,16 @@ pub enum Error {
     #[error("bitcoin-core getblockheader RPC error for hash {1}: {0}")]
     BitcoinCoreGetBlockHeader(#[source] bitcoincore_rpc::Error, bitcoin::BlockHash),
 
+    /// Bitcoin-core did not report the block as part of its best chain
+    /// within the allotted time.
+    #[error("timed-out waiting for bitcoin block {0} to be part of the best chain")]
+    BitcoinCoreWaitForBlockTimeout(bitcoin::BlockHash),
+
+    /// Bitcoin-core did not report the transaction as having the required
+    /// number of confirmations within the allotted time.
+    #[error("timed-out waiting for bitcoin transaction {0} to have {1} confirmations")]
+    BitcoinCoreWaitForTxConfirmationTimeout(bitcoin::Txid, u32),
+
     /// Bitcoin block header is unknown to bitcoin-core. This is only
     /// triggered if bitcoin-core does not know about the block hash.
     #[error("Unknown block hash response from bitcoin-core getblockheader RPC call: {0}")]
@@ -169,6 +192,28 @@ pub enum Error {
     #[error("failed to retrieve the raw transaction for txid {1} from bitcoin-core. {0}")]
     BitcoinCoreGetTransaction(#[source] bitcoincore_rpc::Error, bitcoin::Txid),
 
+    /// The aggregate key in the sbtc-registry contract does not match the
+    /// aggregate key of the latest verified DKG shares.
+    #[error(
+        "the registry aggregate key {registry} does not match the latest verified DKG aggregate key {dkg}"
+    )]
+    SignerSetInfoInconsistency {
+        /// The aggregate key in the sbtc-registry contract.
+        registry: PublicKey,
+        /// The aggregate key of the latest verified DKG shares.
+        dkg: PublicKey,
+    },
+
+    /// The bitcoin network that bitcoin-core is running on does not match
+    /// the network in the signer's configuration.
+    #[error("the configured network {configured} does not match the bitcoin-core chain {reported}")]
+    BitcoinNetworkMismatch {
+        /// The network in the signer's configuration.
+        configured: NetworkKind,
+        /// The chain that bitcoin-core reported.
+        reported: bitcoin::Network,
+    },
+
     /// Error when creating an RPC client to bitcoin-core
     #[error("could not create RPC client to {1}: {0}")]
     BitcoinCoreRpcClient(#[source] bitcoincore_rpc::Error, String),
@@ -330,10 +375,19 @@ pub enum Error {
     #[error("deposit validation error: {0}")]
     DepositValidation(#[from] Box<DepositValidationError>),
 
+    /// The deposit is locked to a public key other than the signers'
+    /// current aggregate key.
+    #[error("deposit {0} is locked to {1} instead of the current aggregate key {2}")]
+    DepositWrongSignerKey(bitcoin::OutPoint, PublicKeyXOnly, PublicKeyXOnly),
+
     /// An error when serializing an object to JSON
     #[error("JSON serialization error: {0}")]
     JsonSerialize(#[source] serde_json::Error),
 
+    /// An error when deserializing an object from JSON
+    #[error("JSON deserialization error: {0}")]
+    JsonDeserialize(#[source] serde_json::Error),
+
     /// Could not parse the path part of a URL
     #[error("failed to construct a valid URL from {1} and {2}: {0}")]
     PathJoin(#[source] url::ParseError, url::Url, Cow<'static, str>),
@@ -547,6 +601,16 @@ pub enum Error {
     #[error("missing dkg shares for the given aggregate key: {0}")]
     MissingDkgShares(crate::keys::PublicKeyXOnly),
 
+    /// The signature share threshold of some DKG shares is zero or larger
+    /// than the number of signers that took part in DKG.
+    #[error("invalid dkg shares threshold {0} for a signer set of size {1}")]
+    InvalidDkgSharesThreshold(u16, usize),
+
+    /// The tweaked aggregate key or `scriptPubKey` of some DKG shares is
+    /// not the one derived from their aggregate key.
+    #[error("dkg shares tweaked key or scriptPubKey does not match the aggregate key: {0}")]
+    DkgSharesAggregateKeyMismatch(PublicKey),
+
     /// Missing public key
     #[error("missing public key")]
     MissingPublicKey,
@@ -745,11 +809,16 @@ pub enum Error {
     #[error("the UnsignedTransaction must contain deposit or withdrawal requests")]
     BitcoinNoRequests,
 
-    /// Indicates that the BitcoinPreSignRequest object contains a fee rate
-    /// that is less than or equal to zero.
-    #[error("the fee rate in the BitcoinPreSignRequest object is not greater than zero: {0}")]
+    /// Indicates that the BitcoinPreSignRequest object contains a fee rate,
+    /// or an RBF target fee rate, that is not greater than zero.
+    #[error("a fee rate in the BitcoinPreSignRequest object is not greater than zero: {0}")]
     PreSignInvalidFeeRate(f64),
 
+    /// Indicates that the fee rate in the signers' bitcoin state is NaN,
+    /// infinite, or not greater than zero.
+    #[error("the fee rate used to construct a bitcoin transaction is invalid: {0}")]
+    InvalidFeeRate(f64),
+
     /// Error when deposit requests would exceed sBTC supply cap
     #[error(
         "total deposit amount ({total_amount} sats) would exceed sBTC supply cap (current max mintable is {max_mintable} sats)"
@@ -775,6 +844,12 @@ pub enum Error {
             amounts = .0.amounts, cap = .0.cap, cap_blocks = .0.cap_blocks, withdrawn_total = .0.withdrawn_total)]
     ExceedsWithdrawalCap(WithdrawalCapContext),
 
+    /// Indicates that a withdrawal request was written again with a
+    /// different amount, recipient, or max fee than the one already
+    /// stored under the same id.
+    #[error("withdrawal request {0} was re-written with different immutable fields")]
+    WithdrawalRequestMutation(QualifiedRequestId),
+
     /// An error was raised by the in-memory database.
     #[cfg(any(test, feature = "testing"))]
     #[error("In-memory database error: {0}")]
@@ -804,4 +879,26 @@ impl Error {
     pub fn wsts_coordinator(err: wsts::state_machine::coordinator::Error) -> Self {
         Error::WstsCoordinator(Box::new(err))
     }
+
+    /// Whether this error came from communicating with the database.
+    pub fn is_storage_error(&self) -> bool {
+        matches!(
+            self,
+            Error::SqlxQuery(_)
+                | Error::SqlxConnect(_)
+                | Error::SqlxBeginTransaction(_)
+                | Error::SqlxCommitTransaction(_)
+                | Error::SqlxRollbackTransaction(_)
+                | Error::SqlxAcquireConnection(_)
+        )
+    }
+
+    /// How Emily responded to the failed request behind this error, if
+    /// this error came from a request to Emily.
+    pub fn emily_error(&self) -> Option<EmilyError> {
+        match self {
+            Error::EmilyApi(error) => error.response_error(),
+            _ => None,
+        }
+    }
 }
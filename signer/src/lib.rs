@@ -23,6 +23,7 @@ pub mod message;
 pub mod metrics;
 pub mod network;
 pub mod proto;
+pub mod reconciliation;
 pub mod request_decider;
 pub mod signature;
 pub mod stacks;
@@ -75,6 +76,19 @@ pub const SIGNER_CHANNEL_CAPACITY: usize = 1024;
 /// for the signers UTXO.
 pub const MAX_REORG_BLOCK_COUNT: u64 = 10;
 
+/// The maximum number of bitcoin block headers that the block observer
+/// processes in one batch when catching up to the bitcoin chain tip.
+/// This bounds the number of headers held in memory after the signer has
+/// been offline for a long time.
+pub const MAX_BITCOIN_HEADERS_PER_BATCH: std::num::NonZeroUsize =
+    std::num::NonZeroUsize::new(1000).unwrap();
+
+/// The maximum number of bitcoin blocks that cached signer set info is
+/// used for before it is fetched again from the stacks node. This bounds
+/// how long the signer can act on a stale signer set if it misses a
+/// rotate-keys event.
+pub const SIGNER_SET_INFO_CACHE_MAX_AGE: u64 = 6;
+
 /// The maximum number of sweep transactions that the signers can confirm
 /// per block.
 ///
@@ -87,6 +101,10 @@ pub const MAX_REORG_BLOCK_COUNT: u64 = 10;
 /// <https://github.com/bitcoin/bitcoin/blob/v25.0/src/policy/policy.h#L58-L59>
 pub const MAX_MEMPOOL_PACKAGE_TX_COUNT: u64 = 25;
 
+/// The default maximum number of requests that are made to a stacks node
+/// at the same time when checking a batch of requests.
+pub const DEFAULT_STACKS_MAX_CONCURRENT_REQUESTS: u16 = 10;
+
 /// The default maximum number of deposit inputs per bitcoin transaction.
 ///
 /// The default here is chosen so that there is a ~50% chance that the
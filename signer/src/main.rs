@@ -116,6 +116,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         tracing::error!(%err, "failed to initialize the signer context");
     })?;
 
+    let summary = context.effective_config_summary();
+    tracing::info!(?summary, "effective signer configuration");
+
+    // Make sure that bitcoin-core is on the network that we are configured
+    // for, otherwise we would be operating on the wrong chain.
+    let network = context.config().signer.network;
+    signer::bitcoin::ensure_network_matches(&context.get_bitcoin_client(), network)
+        .await
+        .inspect_err(|err| {
+            tracing::error!(%err, "failed to verify the bitcoin network of bitcoin-core");
+        })?;
+
     // TODO: We should first check "another source of truth" for the current
     // signing set, and only assume we are bootstrapping if that source is
     // empty.
@@ -231,6 +231,14 @@ pub struct BitcoinPreSignRequest {
     /// The total fee amount and the fee rate for the last transaction that
     /// used this UTXO as an input.
     pub last_fees: Option<Fees>,
+    /// The seed used to shuffle the deposit inputs of each transaction in
+    /// the package, if they were shuffled. See
+    /// [`SbtcRequests::shuffle_seed`](crate::bitcoin::utxo::SbtcRequests::shuffle_seed).
+    pub shuffle_seed: Option<[u8; 32]>,
+    /// The fee rate, in sats per vbyte, that the transactions replacing
+    /// the ones that last used the signers' UTXO target, if any. See
+    /// [`SignerBtcState::rbf_target_fee_rate`](crate::bitcoin::utxo::SignerBtcState::rbf_target_fee_rate).
+    pub rbf_target_fee_rate: Option<f64>,
 }
 
 /// An acknowledgment of a [`BitcoinPreSignRequest`].
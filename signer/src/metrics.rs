@@ -52,6 +52,9 @@ pub enum Metrics {
     ValidationDurationSeconds,
     /// The number of peers connected in the p2p network.
     PeersConnected,
+    /// The total number of times the aggregate key in the sbtc-registry
+    /// did not match the aggregate key of our latest verified DKG shares.
+    SignerSetInconsistenciesTotal,
 }
 
 impl From<Metrics> for metrics::KeyName {
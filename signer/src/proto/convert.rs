@@ -1232,6 +1232,8 @@ impl From<BitcoinPreSignRequest> for proto::BitcoinPreSignRequest {
                 .collect(),
             fee_rate: value.fee_rate,
             last_fees: value.last_fees.map(|v| v.into()),
+            shuffle_seed: value.shuffle_seed.map(proto::Uint256::from),
+            rbf_target_fee_rate: value.rbf_target_fee_rate,
         }
     }
 }
@@ -1247,6 +1249,8 @@ impl TryFrom<proto::BitcoinPreSignRequest> for BitcoinPreSignRequest {
                 .collect::<Result<Vec<_>, _>>()?,
             fee_rate: value.fee_rate,
             last_fees: value.last_fees.map(|v| v.into()),
+            shuffle_seed: value.shuffle_seed.map(<[u8; 32]>::from),
+            rbf_target_fee_rate: value.rbf_target_fee_rate,
         })
     }
 }
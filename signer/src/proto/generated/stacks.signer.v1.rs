@@ -69,9 +69,7 @@ pub struct StacksTransactionSignature {
     pub txid: ::core::option::Option<super::super::StacksTxid>,
     /// A recoverable ECDSA signature over the transaction.
     #[prost(message, optional, tag = "2")]
-    pub signature: ::core::option::Option<
-        super::super::super::crypto::RecoverableSignature,
-    >,
+    pub signature: ::core::option::Option<super::super::super::crypto::RecoverableSignature>,
 }
 /// Represents a request to sign a Stacks transaction.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -145,9 +143,7 @@ pub struct CompleteDeposit {
     /// The block hash of the bitcoin block that contains a sweep
     /// transaction with the above `outpoint` as one of its inputs.
     #[prost(message, optional, tag = "6")]
-    pub sweep_block_hash: ::core::option::Option<
-        super::super::super::bitcoin::BitcoinBlockHash,
-    >,
+    pub sweep_block_hash: ::core::option::Option<super::super::super::bitcoin::BitcoinBlockHash>,
     /// The block height associated with the above bitcoin block hash.
     #[prost(uint64, tag = "7")]
     pub sweep_block_height: u64,
@@ -182,9 +178,7 @@ pub struct AcceptWithdrawal {
     /// The block hash of the bitcoin block that contains a sweep
     /// transaction with the above `outpoint` as one of its outputs.
     #[prost(message, optional, tag = "6")]
-    pub sweep_block_hash: ::core::option::Option<
-        super::super::super::bitcoin::BitcoinBlockHash,
-    >,
+    pub sweep_block_hash: ::core::option::Option<super::super::super::bitcoin::BitcoinBlockHash>,
     /// The block height associated with the above bitcoin block hash.
     #[prost(uint64, tag = "7")]
     pub sweep_block_height: u64,
@@ -280,9 +274,7 @@ impl SmartContract {
 pub struct SignerMessage {
     /// / The bitcoin chain tip defining the signers view of the blockchain at the time the message was created
     #[prost(message, optional, tag = "1")]
-    pub bitcoin_chain_tip: ::core::option::Option<
-        super::super::super::bitcoin::BitcoinBlockHash,
-    >,
+    pub bitcoin_chain_tip: ::core::option::Option<super::super::super::bitcoin::BitcoinBlockHash>,
     /// The message payload
     #[prost(oneof = "signer_message::Payload", tags = "2, 3, 4, 5, 8, 10, 11")]
     pub payload: ::core::option::Option<signer_message::Payload>,
@@ -334,9 +326,7 @@ pub mod wsts_message {
         DkgBegin(super::super::super::super::crypto::wsts::DkgBegin),
         /// Send DKG public shares
         #[prost(message, tag = "3")]
-        SignerDkgPublicShares(
-            super::super::super::super::crypto::wsts::SignerDkgPublicShares,
-        ),
+        SignerDkgPublicShares(super::super::super::super::crypto::wsts::SignerDkgPublicShares),
         /// Tell signers to send DKG private shares
         #[prost(message, tag = "4")]
         DkgPrivateBegin(super::super::super::super::crypto::wsts::DkgPrivateBegin),
@@ -357,14 +347,10 @@ pub mod wsts_message {
         NonceResponse(super::super::super::super::crypto::wsts::NonceResponse),
         /// Tell signers to construct signature shares
         #[prost(message, tag = "10")]
-        SignatureShareRequest(
-            super::super::super::super::crypto::wsts::SignatureShareRequest,
-        ),
+        SignatureShareRequest(super::super::super::super::crypto::wsts::SignatureShareRequest),
         /// Tell coordinator signature shares
         #[prost(message, tag = "11")]
-        SignatureShareResponse(
-            super::super::super::super::crypto::wsts::SignatureShareResponse,
-        ),
+        SignatureShareResponse(super::super::super::super::crypto::wsts::SignatureShareResponse),
     }
     #[derive(Clone, Copy, PartialEq, ::prost::Oneof)]
     pub enum Id {
@@ -392,9 +378,7 @@ pub struct Signed {
     pub signature: ::core::option::Option<super::super::super::crypto::EcdsaSignature>,
     /// The public key of the signer that generated the signature.
     #[prost(message, optional, tag = "2")]
-    pub signer_public_key: ::core::option::Option<
-        super::super::super::crypto::PublicKey,
-    >,
+    pub signer_public_key: ::core::option::Option<super::super::super::crypto::PublicKey>,
     /// The signed structure.
     #[prost(message, optional, tag = "3")]
     pub signer_message: ::core::option::Option<SignerMessage>,
@@ -415,6 +399,14 @@ pub struct BitcoinPreSignRequest {
     /// used this UTXO as an input.
     #[prost(message, optional, tag = "3")]
     pub last_fees: ::core::option::Option<Fees>,
+    /// The seed used to shuffle the deposit inputs of each transaction in
+    /// the package, if they were shuffled.
+    #[prost(message, optional, tag = "4")]
+    pub shuffle_seed: ::core::option::Option<super::super::super::crypto::Uint256>,
+    /// The fee rate, in sats per vbyte, that the transactions replacing the
+    /// ones that last used the signers' UTXO target, if any.
+    #[prost(double, optional, tag = "5")]
+    pub rbf_target_fee_rate: ::core::option::Option<f64>,
 }
 /// Represents an acknowledgment of a BitcoinPreSignRequest.
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
@@ -0,0 +1,142 @@
+//! Module for reconciling the state of requests in Emily with the state
+//! of requests in the signer's database.
+//!
+//! Emily is updated by the signers on a best-effort basis, so it can fall
+//! behind or get ahead of what the signers have actually done. The
+//! functions here report where the two disagree so that operators can
+//! investigate and correct Emily.
+
+use emily_client::models::WithdrawalInfo;
+use emily_client::models::WithdrawalStatus;
+use hex::FromHex as _;
+
+use crate::emily_client::EmilyInteract;
+use crate::error::Error;
+use crate::storage::DbRead;
+use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::BitcoinTxRef;
+use crate::storage::model::QualifiedRequestId;
+use crate::storage::model::StacksBlockHash;
+use crate::storage::model::StacksTxId;
+
+/// The withdrawals where Emily and the signer's database disagree about
+/// whether the funds have been swept out.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WithdrawalReconciliationReport {
+    /// Withdrawals that Emily considers pending but that have been swept
+    /// out by a transaction confirmed on the canonical bitcoin
+    /// blockchain, along with that sweep transaction.
+    pub swept_but_pending_in_emily: Vec<(QualifiedRequestId, BitcoinTxRef)>,
+    /// Withdrawals that Emily considers accepted but where the signer has
+    /// no record of a sweep transaction confirmed on the canonical bitcoin
+    /// blockchain. This is expected for a short while after the signers
+    /// broadcast a sweep transaction, so only withdrawals that stay here
+    /// across a few bitcoin blocks need investigating.
+    pub accepted_in_emily_but_not_swept: Vec<QualifiedRequestId>,
+    /// Withdrawals that Emily considers confirmed but where the signer
+    /// has no record of a sweep transaction confirmed on the canonical
+    /// bitcoin blockchain.
+    pub confirmed_in_emily_but_not_swept: Vec<QualifiedRequestId>,
+}
+
+impl WithdrawalReconciliationReport {
+    /// Whether Emily and the signer agree on the state of all
+    /// withdrawals.
+    pub fn is_empty(&self) -> bool {
+        self.swept_but_pending_in_emily.is_empty()
+            && self.accepted_in_emily_but_not_swept.is_empty()
+            && self.confirmed_in_emily_but_not_swept.is_empty()
+    }
+}
+
+/// Compare the withdrawals that Emily considers pending, accepted or
+/// confirmed with the withdrawals that have been swept out on the canonical bitcoin
+/// blockchain according to the signer's database.
+///
+/// Withdrawals in Emily that cannot be parsed into a
+/// [`QualifiedRequestId`] are logged and skipped.
+pub async fn reconcile_withdrawals<E, D>(
+    emily: &E,
+    db: &D,
+) -> Result<WithdrawalReconciliationReport, Error>
+where
+    E: EmilyInteract,
+    D: DbRead,
+{
+    let chain_tip = db
+        .get_bitcoin_canonical_chain_tip()
+        .await?
+        .ok_or(Error::NoChainTip)?;
+
+    let mut report = WithdrawalReconciliationReport::default();
+
+    let pending = emily
+        .get_withdrawals_with_status(WithdrawalStatus::Pending)
+        .await?;
+    for id in pending.iter().filter_map(parse_withdrawal_id) {
+        if let Some(sweep) = db.get_withdrawal_sweep_info(&chain_tip, &id).await? {
+            report.swept_but_pending_in_emily.push((id, sweep));
+        }
+    }
+
+    report.accepted_in_emily_but_not_swept =
+        unswept_withdrawals(emily, db, &chain_tip, WithdrawalStatus::Accepted).await?;
+    report.confirmed_in_emily_but_not_swept =
+        unswept_withdrawals(emily, db, &chain_tip, WithdrawalStatus::Confirmed).await?;
+
+    Ok(report)
+}
+
+/// Return the withdrawals that have the given status in Emily but have not
+/// been swept out on the bitcoin blockchain identified by the chain tip.
+async fn unswept_withdrawals<E, D>(
+    emily: &E,
+    db: &D,
+    chain_tip: &BitcoinBlockHash,
+    status: WithdrawalStatus,
+) -> Result<Vec<QualifiedRequestId>, Error>
+where
+    E: EmilyInteract,
+    D: DbRead,
+{
+    let withdrawals = emily.get_withdrawals_with_status(status).await?;
+
+    let mut unswept = Vec::new();
+    for id in withdrawals.iter().filter_map(parse_withdrawal_id) {
+        if db
+            .get_withdrawal_sweep_info(chain_tip, &id)
+            .await?
+            .is_none()
+        {
+            unswept.push(id);
+        }
+    }
+
+    Ok(unswept)
+}
+
+/// Parse the identifier of a withdrawal returned by Emily, logging and
+/// returning `None` if it is malformed.
+fn parse_withdrawal_id(withdrawal: &WithdrawalInfo) -> Option<QualifiedRequestId> {
+    let parse = || -> Result<QualifiedRequestId, Error> {
+        let txid = <[u8; 32]>::from_hex(&withdrawal.txid).map_err(Error::DecodeHexBytes)?;
+        let block_hash =
+            <[u8; 32]>::from_hex(&withdrawal.stacks_block_hash).map_err(Error::DecodeHexBytes)?;
+
+        Ok(QualifiedRequestId {
+            request_id: withdrawal.request_id,
+            txid: StacksTxId::from(txid),
+            block_hash: StacksBlockHash::from(block_hash),
+        })
+    };
+
+    parse()
+        .inspect_err(|error| {
+            tracing::warn!(
+                %error,
+                request_id = withdrawal.request_id,
+                "skipping malformed withdrawal from Emily"
+            )
+        })
+        .ok()
+}
@@ -3,6 +3,7 @@
 use std::borrow::Cow;
 use std::collections::BTreeSet;
 use std::future::Future;
+use std::num::NonZeroU16;
 use std::time::Duration;
 
 use bitcoin::Amount;
@@ -34,6 +35,7 @@ use clarity::vm::types::OptionalData;
 use clarity::vm::types::TupleData;
 use clarity::vm::types::{BuffData, ListData, SequenceData};
 use clarity::vm::{ClarityName, ContractName, Value};
+use futures::StreamExt as _;
 use reqwest::StatusCode;
 use reqwest::header::CONTENT_LENGTH;
 use reqwest::header::CONTENT_TYPE;
@@ -46,6 +48,7 @@ use crate::keys::PublicKey;
 use crate::storage::DbRead;
 use crate::storage::model::BitcoinBlockHash;
 use crate::storage::model::BitcoinBlockHeight;
+use crate::storage::model::BitcoinTxId;
 use crate::storage::model::StacksBlock;
 use crate::storage::model::StacksBlockHeight;
 use crate::storage::model::ToLittleEndianOrder as _;
@@ -188,6 +191,38 @@ pub trait StacksInteract: Send + Sync {
         outpoint: &OutPoint,
     ) -> impl Future<Output = Result<bool, Error>> + Send;
 
+    /// Retrieve whether sBTC has been minted for each of the given deposit
+    /// requests, identified by their outpoints.
+    ///
+    /// The checks are made using [`StacksInteract::is_deposit_completed`],
+    /// with at most `max_concurrent_requests` of them in flight at once,
+    /// and the returned vector is in the same order as the given
+    /// identifiers.
+    fn is_deposits_completed(
+        &self,
+        contract_principal: &StacksAddress,
+        ids: &[(BitcoinTxId, u32)],
+        max_concurrent_requests: NonZeroU16,
+    ) -> impl Future<Output = Result<Vec<bool>, Error>> + Send {
+        async move {
+            let mut completed = vec![false; ids.len()];
+            let mut checks = futures::stream::iter(ids.iter().enumerate())
+                .map(|(index, (txid, vout))| async move {
+                    let outpoint = OutPoint::new(**txid, *vout);
+                    self.is_deposit_completed(contract_principal, &outpoint)
+                        .await
+                        .map(|is_completed| (index, is_completed))
+                })
+                .buffer_unordered(usize::from(max_concurrent_requests.get()));
+
+            while let Some(check) = checks.next().await {
+                let (index, is_completed) = check?;
+                completed[index] = is_completed;
+            }
+            Ok(completed)
+        }
+    }
+
     /// Retrieve a boolean value from the stacks node indicating whether
     /// the withdrawal request has a response transaction either accepting
     /// or rejecting the request.
@@ -2355,6 +2390,65 @@ mod tests {
         mock.assert();
     }
 
+    #[tokio::test]
+    async fn is_deposits_completed_preserves_order() {
+        let txid = BitcoinTxId::from([1; 32]);
+        let completed = [true, false, false, true, false];
+
+        let mut stacks_node_server = mockito::Server::new_async().await;
+        let mocks: Vec<_> = completed
+            .iter()
+            .enumerate()
+            .map(|(vout, is_completed)| {
+                // Only completed deposits have an entry in the map.
+                let data = is_completed.then(|| Box::new(Value::Bool(true)));
+                let clarity_value = Value::Optional(OptionalData { data });
+                let json_response = serde_json::json!({
+                    "okay": true,
+                    "result": format!("0x{}", clarity_value.serialize_to_hex().unwrap()),
+                });
+
+                let txid_arg = Value::Sequence(SequenceData::Buffer(BuffData {
+                    data: txid.to_le_bytes().to_vec(),
+                }));
+                let arguments = [
+                    txid_arg.serialize_to_hex().unwrap(),
+                    Value::UInt(vout as u128).serialize_to_hex().unwrap(),
+                ];
+                stacks_node_server
+                    .mock("POST", "/v2/contracts/call-read/ST000000000000000000002AMW42H/sbtc-registry/get-deposit-status?tip=latest")
+                    .match_body(mockito::Matcher::PartialJson(
+                        serde_json::json!({ "arguments": arguments }),
+                    ))
+                    .with_status(200)
+                    .with_header("content-type", "application/json")
+                    .with_body(serde_json::to_string(&json_response).unwrap())
+                    .expect(1)
+                    .create()
+            })
+            .collect();
+
+        let client =
+            StacksClient::new(url::Url::parse(stacks_node_server.url().as_str()).unwrap()).unwrap();
+
+        let ids: Vec<(BitcoinTxId, u32)> = (0..completed.len() as u32)
+            .map(|vout| (txid, vout))
+            .collect();
+        let response = client
+            .is_deposits_completed(
+                &StacksAddress::burn_address(false),
+                &ids,
+                NonZeroU16::new(2).unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response, completed);
+        for mock in mocks {
+            mock.assert();
+        }
+    }
+
     #[test_case(Some(true); "accepted-withdrawal")]
     #[test_case(Some(false); "rejected-withdrawal")]
     #[test_case(None; "incomplete-withdrawal")]
@@ -34,7 +34,9 @@ use crate::keys::PrivateKey;
 use crate::keys::PublicKey;
 use crate::signature::RecoverableEcdsaSignature as _;
 use crate::signature::SighashDigest as _;
+use crate::stacks::api::SignerSetInfo;
 use crate::stacks::contracts::AsTxPayload;
+use crate::stacks::contracts::RotateKeysV1;
 
 /// Stacks multisig addresses are Hash160 hashes of bitcoin Scripts (more
 /// or less). The enum value below defines which Script will be used to
@@ -265,6 +267,42 @@ impl SignerWallet {
             signatures_required: self.signatures_required,
         }
     }
+
+    /// Create the unsigned rotate-keys contract call transaction that
+    /// updates the signer set in the sbtc-registry to the given one.
+    ///
+    /// The transaction is sent from this wallet, so it needs to be signed
+    /// by the current signers before it can be broadcast, and it uses up
+    /// the wallet's next nonce.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the new signer set does not define a valid
+    /// multi-sig wallet, see [`SignerWallet::new`] for the conditions.
+    pub fn rotate_keys_tx(
+        &self,
+        deployer: StacksAddress,
+        new_set: &SignerSetInfo,
+        tx_fee: u64,
+    ) -> Result<StacksTransaction, Error> {
+        // The signers would not be able to sign anything after the
+        // rotation if the new set does not make a valid wallet.
+        SignerWallet::new(
+            &new_set.signer_set,
+            new_set.signatures_required,
+            self.network_kind,
+            0,
+        )?;
+
+        let contract_call = RotateKeysV1 {
+            new_keys: new_set.signer_set.clone(),
+            aggregate_key: new_set.aggregate_key,
+            deployer,
+            signatures_required: new_set.signatures_required,
+        };
+
+        Ok(MultisigTx::new_tx(&contract_call, self, tx_fee).tx)
+    }
 }
 
 /// A helper struct for properly signing a transaction for the signers'
@@ -434,6 +472,7 @@ where
 mod tests {
     use blockstack_lib::chainstate::stacks::TransactionPayload;
     use blockstack_lib::clarity::vm::Value as ClarityValue;
+    use blockstack_lib::clarity::vm::types::SequenceData;
     use fake::Fake;
     use rand::rngs::OsRng;
     use rand::seq::SliceRandom;
@@ -561,6 +600,67 @@ mod tests {
         tx.verify().unwrap();
     }
 
+    /// The rotate-keys transaction created by the wallet calls the
+    /// rotate-keys function with the details of the new signer set.
+    #[test]
+    fn rotate_keys_tx_calls_rotate_keys_with_new_set() {
+        let mut rng = get_rng();
+        let network = NetworkKind::Testnet;
+
+        let public_keys: Vec<PublicKey> =
+            std::iter::repeat_with(|| fake::Faker.fake_with_rng(&mut rng))
+                .take(5)
+                .collect();
+        let wallet = SignerWallet::new(&public_keys, 3, network, 0).unwrap();
+
+        let new_signer_set: BTreeSet<PublicKey> =
+            std::iter::repeat_with(|| fake::Faker.fake_with_rng(&mut rng))
+                .take(7)
+                .collect();
+        let new_set = SignerSetInfo {
+            aggregate_key: PublicKey::combine_keys(&new_signer_set).unwrap(),
+            signer_set: new_signer_set,
+            signatures_required: 5,
+        };
+        let deployer = StacksAddress::burn_address(false);
+
+        let tx = wallet.rotate_keys_tx(deployer, &new_set, TX_FEE).unwrap();
+
+        // The transaction uses the wallet's nonce.
+        assert_eq!(tx.get_origin_nonce(), 0);
+        assert_eq!(wallet.get_nonce(), 1);
+
+        let TransactionPayload::ContractCall(call) = tx.payload else {
+            panic!("expected a contract call payload");
+        };
+        assert_eq!(call.address, deployer);
+        assert_eq!(call.contract_name.to_string(), RotateKeysV1::CONTRACT_NAME);
+        assert_eq!(call.function_name.to_string(), RotateKeysV1::FUNCTION_NAME);
+
+        let aggregate_key = new_set.aggregate_key.serialize().to_vec();
+        let [
+            _,
+            ClarityValue::Sequence(SequenceData::Buffer(key)),
+            threshold,
+        ] = call.function_args.as_slice()
+        else {
+            panic!("unexpected contract call arguments");
+        };
+        assert_eq!(key.data, aggregate_key);
+        assert_eq!(threshold, &ClarityValue::UInt(5));
+
+        // A new signer set that does not make a valid wallet is rejected.
+        let invalid_set = SignerSetInfo {
+            signatures_required: 8,
+            ..new_set
+        };
+        assert!(
+            wallet
+                .rotate_keys_tx(deployer, &invalid_set, TX_FEE)
+                .is_err()
+        );
+    }
+
     /// If one of the signers signs a digest with the wrong key, then we
     /// will reject it. We also reject the case where they sign the wrong
     /// digest with a "correct" key.
@@ -677,6 +777,7 @@ mod tests {
             num_withdraw_requests_per_block: 0,
             num_signers_per_request: 0,
             consecutive_blocks: false,
+            fork_points: Vec::new(),
         };
         let test_data = TestData::generate(&mut rng, &[], &test_params);
         test_data.write_to(&db).await;
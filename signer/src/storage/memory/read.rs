@@ -27,6 +27,25 @@ impl DbRead for SharedStore {
         Ok(self.lock().await.bitcoin_blocks.get(block_hash).cloned())
     }
 
+    async fn get_bitcoin_block_with_canonicality(
+        &self,
+        block_hash: &model::BitcoinBlockHash,
+        chain_tip: &model::BitcoinBlockHash,
+    ) -> Result<Option<(model::BitcoinBlock, bool)>, Error> {
+        let store = self.lock().await;
+        let bitcoin_blocks = &store.bitcoin_blocks;
+        let Some(block) = bitcoin_blocks.get(block_hash) else {
+            return Ok(None);
+        };
+
+        let first = bitcoin_blocks.get(chain_tip);
+        let is_canonical = std::iter::successors(first, |tip| bitcoin_blocks.get(&tip.parent_hash))
+            .take_while(|tip| tip.block_height >= block.block_height)
+            .any(|tip| &tip.block_hash == block_hash);
+
+        Ok(Some((block.clone(), is_canonical)))
+    }
+
     async fn get_stacks_block(
         &self,
         block_hash: &model::StacksBlockHash,
@@ -34,6 +53,28 @@ impl DbRead for SharedStore {
         Ok(self.lock().await.stacks_blocks.get(block_hash).cloned())
     }
 
+    async fn get_stacks_blocks_by_bitcoin_height(
+        &self,
+        height: BitcoinBlockHeight,
+    ) -> Result<Vec<model::StacksBlock>, Error> {
+        let store = self.lock().await;
+
+        let mut blocks = store
+            .stacks_blocks
+            .values()
+            .filter(|block| {
+                store
+                    .bitcoin_blocks
+                    .get(&block.bitcoin_anchor)
+                    .is_some_and(|anchor| anchor.block_height == height)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        blocks.sort_by_key(|block| (block.block_height, block.block_hash));
+        Ok(blocks)
+    }
+
     async fn get_bitcoin_canonical_chain_tip(
         &self,
     ) -> Result<Option<model::BitcoinBlockHash>, Error> {
@@ -153,6 +194,205 @@ impl DbRead for SharedStore {
             .collect())
     }
 
+    async fn get_unswept_deposit_value(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+        signatures_required: u16,
+    ) -> Result<bitcoin::Amount, Error> {
+        let deposits = self
+            .get_pending_accepted_deposit_requests(chain_tip, context_window, signatures_required)
+            .await?;
+
+        let store = self.lock().await;
+
+        // Get all canonical blocks in the context window, and the
+        // outpoints spent by transactions in them.
+        let canonical_bitcoin_blocks =
+            store.canonical_bitcoin_block_hashes(chain_tip, context_window);
+        let spent_outpoints = store.spent_outpoints(&canonical_bitcoin_blocks);
+
+        let total = deposits
+            .iter()
+            .filter(|deposit| !spent_outpoints.contains(&(deposit.txid, deposit.output_index)))
+            .map(|deposit| deposit.amount)
+            .sum();
+
+        Ok(bitcoin::Amount::from_sat(total))
+    }
+
+    async fn get_deposit_requests(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+    ) -> Result<Vec<model::DepositRequest>, Error> {
+        let store = self.lock().await;
+        let deposit_requests = store.get_deposit_requests(chain_tip, context_window);
+
+        // Get all canonical blocks in the context window.
+        let canonical_bitcoin_blocks = std::iter::successors(Some(chain_tip), |block_hash| {
+            store
+                .bitcoin_blocks
+                .get(block_hash)
+                .map(|block| &block.parent_hash)
+        })
+        .take(context_window as usize)
+        .collect::<HashSet<_>>();
+
+        Ok(deposit_requests
+            .into_iter()
+            .map(|mut deposit_request| {
+                deposit_request.confirmed_at_height = store
+                    .bitcoin_transactions_to_blocks
+                    .get(&deposit_request.txid)
+                    .unwrap_or(&Vec::new())
+                    .iter()
+                    .filter(|block_hash| canonical_bitcoin_blocks.contains(block_hash))
+                    .filter_map(|block_hash| store.bitcoin_blocks.get(block_hash))
+                    .map(|block| block.block_height)
+                    .next();
+                deposit_request
+            })
+            .collect())
+    }
+
+    async fn get_deposit_requests_by_recipient(
+        &self,
+        recipient: &model::StacksPrincipal,
+        chain_tip: &model::BitcoinBlockHash,
+        limit: u16,
+    ) -> Result<Vec<model::DepositRequest>, Error> {
+        let store = self.lock().await;
+
+        // Get the heights of all canonical blocks.
+        let canonical_bitcoin_blocks =
+            std::iter::successors(store.bitcoin_blocks.get(chain_tip), |block| {
+                store.bitcoin_blocks.get(&block.parent_hash)
+            })
+            .map(|block| (block.block_hash, block.block_height))
+            .collect::<HashMap<_, _>>();
+
+        let mut deposit_requests: Vec<model::DepositRequest> = store
+            .deposit_requests
+            .values()
+            .filter(|req| &req.recipient == recipient)
+            .filter_map(|req| {
+                let confirmed_at_height = store
+                    .bitcoin_transactions_to_blocks
+                    .get(&req.txid)?
+                    .iter()
+                    .find_map(|block_hash| canonical_bitcoin_blocks.get(block_hash).copied())?;
+
+                let mut req = req.clone();
+                req.confirmed_at_height = Some(confirmed_at_height);
+                Some(req)
+            })
+            .collect();
+
+        deposit_requests.sort_by(|a, b| {
+            b.confirmed_at_height
+                .cmp(&a.confirmed_at_height)
+                .then_with(|| a.txid.cmp(&b.txid))
+                .then_with(|| a.output_index.cmp(&b.output_index))
+        });
+        deposit_requests.truncate(limit as usize);
+
+        Ok(deposit_requests)
+    }
+
+    async fn get_pending_deposit_amount_for_recipient(
+        &self,
+        recipient: &model::StacksPrincipal,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+    ) -> Result<bitcoin::Amount, Error> {
+        let store = self.lock().await;
+
+        // Get all canonical blocks in the context window, and the
+        // outpoints spent by transactions in them.
+        let canonical_bitcoin_blocks =
+            store.canonical_bitcoin_block_hashes(chain_tip, context_window);
+        let spent_outpoints = store.spent_outpoints(&canonical_bitcoin_blocks);
+
+        let total = store
+            .deposit_requests
+            .values()
+            .filter(|req| &req.recipient == recipient)
+            .filter(|req| {
+                store
+                    .bitcoin_transactions_to_blocks
+                    .get(&req.txid)
+                    .is_some_and(|blocks| {
+                        blocks
+                            .iter()
+                            .any(|block_hash| canonical_bitcoin_blocks.contains(block_hash))
+                    })
+            })
+            .filter(|req| !spent_outpoints.contains(&(req.txid, req.output_index)))
+            .map(|req| req.amount)
+            .sum();
+
+        Ok(bitcoin::Amount::from_sat(total))
+    }
+
+    async fn get_accepted_deposit_requests(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        accept_threshold: u32,
+        limit: u16,
+    ) -> Result<Vec<model::DepositRequest>, Error> {
+        let store = self.lock().await;
+
+        // Get the heights of all canonical blocks.
+        let canonical_bitcoin_blocks =
+            std::iter::successors(store.bitcoin_blocks.get(chain_tip), |block| {
+                store.bitcoin_blocks.get(&block.parent_hash)
+            })
+            .map(|block| (block.block_hash, block.block_height))
+            .collect::<HashMap<_, _>>();
+
+        let threshold = accept_threshold as usize;
+
+        let mut deposit_requests: Vec<model::DepositRequest> = store
+            .deposit_requests
+            .values()
+            .filter(|req| {
+                let num_accepts = store
+                    .deposit_request_to_signers
+                    .get(&(req.txid, req.output_index))
+                    .map(|signers| {
+                        signers
+                            .iter()
+                            .filter(|signer| signer.can_accept && signer.can_sign)
+                            .count()
+                    })
+                    .unwrap_or_default();
+                num_accepts >= threshold
+            })
+            .filter_map(|req| {
+                let confirmed_at_height = store
+                    .bitcoin_transactions_to_blocks
+                    .get(&req.txid)?
+                    .iter()
+                    .find_map(|block_hash| canonical_bitcoin_blocks.get(block_hash).copied())?;
+
+                let mut req = req.clone();
+                req.confirmed_at_height = Some(confirmed_at_height);
+                Some(req)
+            })
+            .collect();
+
+        deposit_requests.sort_by(|a, b| {
+            b.confirmed_at_height
+                .cmp(&a.confirmed_at_height)
+                .then_with(|| a.txid.cmp(&b.txid))
+                .then_with(|| a.output_index.cmp(&b.output_index))
+        });
+        deposit_requests.truncate(limit as usize);
+
+        Ok(deposit_requests)
+    }
+
     async fn get_deposit_request_report(
         &self,
         _chain_tip: &model::BitcoinBlockHash,
@@ -343,6 +583,21 @@ impl DbRead for SharedStore {
             .map(|(_, shares)| shares.clone()))
     }
 
+    async fn get_verified_dkg_shares_for_key(
+        &self,
+        aggregate_key: &PublicKey,
+    ) -> Result<Option<model::EncryptedDkgShares>, Error> {
+        Ok(self
+            .lock()
+            .await
+            .encrypted_dkg_shares
+            .get(&PublicKeyXOnly::from(aggregate_key))
+            .map(|(_, shares)| shares)
+            .filter(|shares| &shares.aggregate_key == aggregate_key)
+            .filter(|shares| shares.dkg_shares_status == DkgSharesStatus::Verified)
+            .cloned())
+    }
+
     async fn get_encrypted_dkg_shares_count(&self) -> Result<u32, Error> {
         Ok(self
             .lock()
@@ -455,6 +710,64 @@ impl DbRead for SharedStore {
         get_utxo(&aggregate_key, sbtc_txs)
     }
 
+    async fn get_signer_utxos(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        max_count: u16,
+    ) -> Result<Vec<SignerUtxo>, Error> {
+        let store = self.lock().await;
+        let bitcoin_blocks = &store.bitcoin_blocks;
+        let first = bitcoin_blocks.get(chain_tip);
+
+        let context_window = 1000;
+        let txids: Vec<&model::BitcoinTxId> =
+            std::iter::successors(first, |block| bitcoin_blocks.get(&block.parent_hash))
+                .take(context_window)
+                .filter_map(|block| store.bitcoin_block_to_transactions.get(&block.block_hash))
+                .flatten()
+                .collect();
+
+        let spent: HashSet<(model::BitcoinTxId, u32)> = txids
+            .iter()
+            .filter_map(|txid| store.bitcoin_prevouts.get(*txid))
+            .flatten()
+            .filter(|prevout| prevout.prevout_type == model::TxPrevoutType::SignersInput)
+            .map(|prevout| (prevout.prevout_txid, prevout.prevout_output_index))
+            .collect();
+
+        let aggregate_keys: HashMap<&model::ScriptPubKey, PublicKey> = store
+            .encrypted_dkg_shares
+            .values()
+            .map(|(_, shares)| (&shares.script_pubkey, shares.aggregate_key))
+            .collect();
+
+        let mut utxos: Vec<SignerUtxo> = txids
+            .iter()
+            .filter_map(|txid| store.bitcoin_outputs.get(*txid))
+            .flatten()
+            .filter(|output| {
+                matches!(
+                    output.output_type,
+                    model::TxOutputType::SignersOutput | model::TxOutputType::Donation
+                )
+            })
+            .filter(|output| !spent.contains(&(output.txid, output.output_index)))
+            .filter_map(|output| {
+                let aggregate_key = aggregate_keys.get(&output.script_pubkey)?;
+                Some(SignerUtxo {
+                    outpoint: bitcoin::OutPoint::new(output.txid.into(), output.output_index),
+                    amount: output.amount,
+                    public_key: (*aggregate_key).into(),
+                })
+            })
+            .collect();
+
+        utxos.sort_by(|a, b| b.amount.cmp(&a.amount).then(a.outpoint.cmp(&b.outpoint)));
+        utxos.dedup();
+        utxos.truncate(max_count as usize);
+        Ok(utxos)
+    }
+
     async fn get_deposit_request_signer_votes(
         &self,
         txid: &model::BitcoinTxId,
@@ -648,6 +961,87 @@ impl DbRead for SharedStore {
         unimplemented!("can only be tested using integration tests for now.");
     }
 
+    async fn get_withdrawal_sweep_info(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        id: &model::QualifiedRequestId,
+    ) -> Result<Option<model::BitcoinTxRef>, Error> {
+        let store = self.lock().await;
+
+        let Some(output) = store
+            .bitcoin_withdrawal_outputs
+            .get(&(id.request_id, id.block_hash))
+        else {
+            return Ok(None);
+        };
+        let Some(block_hashes) = store
+            .bitcoin_transactions_to_blocks
+            .get(&output.bitcoin_txid)
+        else {
+            return Ok(None);
+        };
+
+        let sweep_block = std::iter::successors(store.bitcoin_blocks.get(chain_tip), |block| {
+            store.bitcoin_blocks.get(&block.parent_hash)
+        })
+        .find(|block| block_hashes.contains(&block.block_hash));
+
+        Ok(sweep_block.map(|block| model::BitcoinTxRef {
+            txid: output.bitcoin_txid,
+            block_hash: block.block_hash,
+        }))
+    }
+
+    async fn get_sweep_transactions_in_range(
+        &self,
+        from: BitcoinBlockHeight,
+        to: BitcoinBlockHeight,
+    ) -> Result<Vec<model::SweepTx>, Error> {
+        let store = self.lock().await;
+
+        let Some(chain_tip) = store
+            .bitcoin_blocks
+            .values()
+            .max_by_key(|block| (block.block_height, block.block_hash))
+        else {
+            return Ok(Vec::new());
+        };
+
+        let is_sweep = |txid: &model::BitcoinTxId| {
+            store.bitcoin_prevouts.get(txid).is_some_and(|prevouts| {
+                prevouts
+                    .iter()
+                    .any(|prevout| prevout.prevout_type == model::TxPrevoutType::SignersInput)
+            })
+        };
+
+        let mut sweeps = Vec::new();
+        let blocks = std::iter::successors(Some(chain_tip), |block| {
+            store.bitcoin_blocks.get(&block.parent_hash)
+        });
+        for block in blocks.take_while(|block| block.block_height >= from) {
+            if block.block_height > to {
+                continue;
+            }
+            let Some(txids) = store.bitcoin_block_to_transactions.get(&block.block_hash) else {
+                continue;
+            };
+            let block_sweeps =
+                txids
+                    .iter()
+                    .filter(|txid| is_sweep(txid))
+                    .map(|txid| model::SweepTx {
+                        txid: *txid,
+                        block_hash: block.block_hash,
+                        block_height: block.block_height,
+                    });
+            sweeps.extend(block_sweeps);
+        }
+
+        sweeps.sort_by_key(|sweep| (sweep.block_height, sweep.txid));
+        Ok(sweeps)
+    }
+
     async fn get_deposit_request(
         &self,
         txid: &model::BitcoinTxId,
@@ -673,6 +1067,31 @@ impl DbRead for SharedStore {
             .map(|s| (s.will_sign, s.aggregate_key)))
     }
 
+    async fn get_effective_limits_history(&self) -> Result<Vec<model::EffectiveSbtcLimits>, Error> {
+        let store = self.lock().await;
+
+        let mut history = store
+            .effective_sbtc_limits
+            .iter()
+            .filter_map(|(block_hash, limits)| {
+                let block = store.bitcoin_blocks.get(block_hash)?;
+                let entry = model::EffectiveSbtcLimits {
+                    bitcoin_chain_tip: *block_hash,
+                    limits: limits.clone(),
+                };
+                Some((block.block_height, entry))
+            })
+            .collect::<Vec<_>>();
+
+        history.sort_by(|(height1, entry1), (height2, entry2)| {
+            height2
+                .cmp(height1)
+                .then_with(|| entry1.bitcoin_chain_tip.cmp(&entry2.bitcoin_chain_tip))
+        });
+
+        Ok(history.into_iter().map(|(_, entry)| entry).collect())
+    }
+
     // The postgres implementation uses a timestamp to figure out when a
     // decision was inserted into the database. The in memory database
     // does not have such a timestamp, so we use the Stacks block's
@@ -782,6 +1201,16 @@ impl DbRead for InMemoryTransaction {
         self.store.get_bitcoin_block(block_hash).await
     }
 
+    async fn get_bitcoin_block_with_canonicality(
+        &self,
+        block_hash: &model::BitcoinBlockHash,
+        chain_tip: &model::BitcoinBlockHash,
+    ) -> Result<Option<(model::BitcoinBlock, bool)>, Error> {
+        self.store
+            .get_bitcoin_block_with_canonicality(block_hash, chain_tip)
+            .await
+    }
+
     async fn get_stacks_block(
         &self,
         block_hash: &model::StacksBlockHash,
@@ -789,6 +1218,13 @@ impl DbRead for InMemoryTransaction {
         self.store.get_stacks_block(block_hash).await
     }
 
+    async fn get_stacks_blocks_by_bitcoin_height(
+        &self,
+        height: BitcoinBlockHeight,
+    ) -> Result<Vec<model::StacksBlock>, Error> {
+        self.store.get_stacks_blocks_by_bitcoin_height(height).await
+    }
+
     async fn get_bitcoin_canonical_chain_tip(
         &self,
     ) -> Result<Option<model::BitcoinBlockHash>, Error> {
@@ -830,6 +1266,60 @@ impl DbRead for InMemoryTransaction {
             .await
     }
 
+    async fn get_unswept_deposit_value(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+        signatures_required: u16,
+    ) -> Result<bitcoin::Amount, Error> {
+        self.store
+            .get_unswept_deposit_value(chain_tip, context_window, signatures_required)
+            .await
+    }
+
+    async fn get_deposit_requests(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+    ) -> Result<Vec<model::DepositRequest>, Error> {
+        self.store
+            .get_deposit_requests(chain_tip, context_window)
+            .await
+    }
+
+    async fn get_deposit_requests_by_recipient(
+        &self,
+        recipient: &model::StacksPrincipal,
+        chain_tip: &model::BitcoinBlockHash,
+        limit: u16,
+    ) -> Result<Vec<model::DepositRequest>, Error> {
+        self.store
+            .get_deposit_requests_by_recipient(recipient, chain_tip, limit)
+            .await
+    }
+
+    async fn get_pending_deposit_amount_for_recipient(
+        &self,
+        recipient: &model::StacksPrincipal,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+    ) -> Result<bitcoin::Amount, Error> {
+        self.store
+            .get_pending_deposit_amount_for_recipient(recipient, chain_tip, context_window)
+            .await
+    }
+
+    async fn get_accepted_deposit_requests(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        accept_threshold: u32,
+        limit: u16,
+    ) -> Result<Vec<model::DepositRequest>, Error> {
+        self.store
+            .get_accepted_deposit_requests(chain_tip, accept_threshold, limit)
+            .await
+    }
+
     async fn deposit_request_exists(
         &self,
         txid: &model::BitcoinTxId,
@@ -999,6 +1489,15 @@ impl DbRead for InMemoryTransaction {
         self.store.get_latest_verified_dkg_shares().await
     }
 
+    async fn get_verified_dkg_shares_for_key(
+        &self,
+        aggregate_key: &PublicKey,
+    ) -> Result<Option<model::EncryptedDkgShares>, Error> {
+        self.store
+            .get_verified_dkg_shares_for_key(aggregate_key)
+            .await
+    }
+
     async fn get_encrypted_dkg_shares_count(&self) -> Result<u32, Error> {
         self.store.get_encrypted_dkg_shares_count().await
     }
@@ -1033,6 +1532,14 @@ impl DbRead for InMemoryTransaction {
         self.store.get_signer_utxo(chain_tip).await
     }
 
+    async fn get_signer_utxos(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        max_count: u16,
+    ) -> Result<Vec<SignerUtxo>, Error> {
+        self.store.get_signer_utxos(chain_tip, max_count).await
+    }
+
     async fn get_deposit_request_signer_votes(
         &self,
         txid: &model::BitcoinTxId,
@@ -1116,6 +1623,22 @@ impl DbRead for InMemoryTransaction {
             .await
     }
 
+    async fn get_withdrawal_sweep_info(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        id: &model::QualifiedRequestId,
+    ) -> Result<Option<model::BitcoinTxRef>, Error> {
+        self.store.get_withdrawal_sweep_info(chain_tip, id).await
+    }
+
+    async fn get_sweep_transactions_in_range(
+        &self,
+        from: BitcoinBlockHeight,
+        to: BitcoinBlockHeight,
+    ) -> Result<Vec<model::SweepTx>, Error> {
+        self.store.get_sweep_transactions_in_range(from, to).await
+    }
+
     async fn get_deposit_request(
         &self,
         txid: &model::BitcoinTxId,
@@ -1130,4 +1653,8 @@ impl DbRead for InMemoryTransaction {
     ) -> Result<Option<(bool, PublicKeyXOnly)>, Error> {
         self.store.will_sign_bitcoin_tx_sighash(sighash).await
     }
+
+    async fn get_effective_limits_history(&self) -> Result<Vec<model::EffectiveSbtcLimits>, Error> {
+        self.store.get_effective_limits_history().await
+    }
 }
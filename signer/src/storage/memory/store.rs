@@ -4,12 +4,14 @@ use bitcoin::OutPoint;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use time::OffsetDateTime;
 use tokio::sync::Mutex;
 
 use crate::bitcoin::utxo::SignerUtxo;
+use crate::context::SbtcLimits;
 use crate::error::Error;
 use crate::keys::PublicKey;
 use crate::keys::PublicKeyXOnly;
@@ -21,7 +23,6 @@ use crate::storage::model::WithdrawalAcceptEvent;
 use crate::storage::model::WithdrawalRejectEvent;
 
 use crate::storage::TransactionHandle;
-use crate::storage::util::get_utxo;
 
 use super::MemoryStoreError;
 
@@ -115,6 +116,14 @@ pub struct Store {
     /// Bitcoin withdrawal outputs
     pub bitcoin_withdrawal_outputs:
         HashMap<(u64, model::StacksBlockHash), model::BitcoinWithdrawalOutput>,
+
+    /// Transactions spending signer outputs that do not look like they
+    /// were created by the signers.
+    pub anomalous_spends:
+        HashMap<(model::BitcoinTxId, model::BitcoinBlockHash), model::AnomalousSpend>,
+
+    /// The sBTC limits in effect at a given bitcoin chain tip.
+    pub effective_sbtc_limits: HashMap<model::BitcoinBlockHash, SbtcLimits>,
 }
 
 impl Store {
@@ -197,45 +206,56 @@ impl Store {
         let bitcoin_blocks = &self.bitcoin_blocks;
         let first = bitcoin_blocks.get(chain_tip);
 
-        // Traverse the canonical chain backwards and find the first block containing relevant tx(s)
-        let sbtc_txs = std::iter::successors(first, |block| bitcoin_blocks.get(&block.parent_hash))
+        // Traverse the canonical chain backwards and pick the largest
+        // donation locked by the signers, breaking ties by outpoint.
+        let donation = std::iter::successors(first, |block| bitcoin_blocks.get(&block.parent_hash))
             .take(context_window as usize)
-            .filter_map(|block| {
-                let txs = self.bitcoin_block_to_transactions.get(&block.block_hash)?;
-
-                let mut sbtc_txs = txs
-                    .iter()
-                    .filter_map(|txid| {
-                        let outputs = self.bitcoin_outputs.get(txid)?;
-
-                        outputs
-                            .iter()
-                            .any(|output| output.output_type == model::TxOutputType::Donation)
-                            .then_some(outputs.first()?.txid)
-                            .and_then(|txid| self.reconstruct_transaction(&txid))
-                    })
-                    .filter(|tx| {
-                        tx.output
-                            .first()
-                            .is_some_and(|out| out.script_pubkey == script_pubkey)
-                    })
-                    .peekable();
-
-                if sbtc_txs.peek().is_some() {
-                    Some(sbtc_txs.collect::<Vec<_>>())
-                } else {
-                    None
-                }
+            .filter_map(|block| self.bitcoin_block_to_transactions.get(&block.block_hash))
+            .flatten()
+            .filter_map(|txid| self.bitcoin_outputs.get(txid))
+            .flatten()
+            .filter(|output| output.output_type == model::TxOutputType::Donation)
+            .filter(|output| *output.script_pubkey == script_pubkey)
+            .map(|output| SignerUtxo {
+                outpoint: OutPoint::new(output.txid.into(), output.output_index),
+                amount: output.amount,
+                public_key: bitcoin::XOnlyPublicKey::from(aggregate_key),
             })
-            .next();
+            .max_by_key(|utxo| (utxo.amount, std::cmp::Reverse(utxo.outpoint)));
 
-        // `sbtc_txs` contains all the txs in the highest canonical block where the first
-        // output is spendable by script_pubkey
-        let Some(sbtc_txs) = sbtc_txs else {
-            return Ok(None);
-        };
+        Ok(donation)
+    }
 
-        get_utxo(aggregate_key, sbtc_txs)
+    /// Get the hashes of the blocks on the blockchain identified by the
+    /// chain tip within the context window.
+    pub fn canonical_bitcoin_block_hashes<'a>(
+        &'a self,
+        chain_tip: &'a model::BitcoinBlockHash,
+        context_window: u16,
+    ) -> HashSet<&'a model::BitcoinBlockHash> {
+        std::iter::successors(Some(chain_tip), |block_hash| {
+            self.bitcoin_blocks
+                .get(block_hash)
+                .map(|block| &block.parent_hash)
+        })
+        .take(context_window as usize)
+        .collect()
+    }
+
+    /// Get the outpoints spent by transactions confirmed in the given
+    /// bitcoin blocks.
+    pub fn spent_outpoints(
+        &self,
+        block_hashes: &HashSet<&model::BitcoinBlockHash>,
+    ) -> HashSet<(model::BitcoinTxId, u32)> {
+        block_hashes
+            .iter()
+            .filter_map(|block_hash| self.bitcoin_block_to_transactions.get(*block_hash))
+            .flatten()
+            .filter_map(|txid| self.bitcoin_prevouts.get(txid))
+            .flatten()
+            .map(|prevout| (prevout.prevout_txid, prevout.prevout_output_index))
+            .collect()
     }
 
     /// Get all deposit requests that are on the blockchain identified by
@@ -1,8 +1,12 @@
 use crate::error::Error;
 use crate::storage::memory::MemoryStoreError;
 use crate::storage::memory::store::Store;
+use crate::storage::model::{BitcoinTxRef, BitcoinWithdrawalOutput, WithdrawalRequest};
 use crate::storage::{DbRead, DbWrite, Transactable, TransactionHandle};
 use crate::testing::blocks::{BitcoinChain, StacksChain};
+use crate::testing::get_rng;
+
+use fake::{Fake, Faker};
 
 use assert_matches::assert_matches;
 use test_log::test;
@@ -259,3 +263,62 @@ async fn test_in_memory_transaction_optimistic_concurrency_violation_with_direct
         ))
     );
 }
+
+#[tokio::test]
+async fn test_in_memory_get_withdrawal_sweep_info_follows_the_chain_tip() -> Result<(), Error> {
+    let shared_store = Store::new_shared();
+    let mut rng = get_rng();
+
+    let bitcoin_chain = BitcoinChain::default();
+    let btc_1 = bitcoin_chain.first_block();
+    let btc_2a = btc_1.new_child();
+    let btc_2b = btc_1.new_child();
+    let btc_3a = btc_2a.new_child();
+    for block in [btc_1, &btc_2a, &btc_2b, &btc_3a] {
+        shared_store.write_bitcoin_block(block).await?;
+    }
+
+    let request: WithdrawalRequest = Faker.fake_with_rng(&mut rng);
+    let id = request.qualified_id();
+    assert!(
+        shared_store
+            .get_withdrawal_sweep_info(&btc_3a.block_hash, &id)
+            .await?
+            .is_none()
+    );
+
+    // The withdrawal gets swept out in a transaction confirmed in one of
+    // the forks.
+    let output = BitcoinWithdrawalOutput {
+        request_id: request.request_id,
+        stacks_txid: request.txid,
+        stacks_block_hash: request.block_hash,
+        bitcoin_chain_tip: btc_2a.block_hash,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    let sweep = BitcoinTxRef {
+        txid: output.bitcoin_txid,
+        block_hash: btc_2a.block_hash,
+    };
+    shared_store.write_bitcoin_transaction(&sweep).await?;
+    shared_store
+        .write_bitcoin_withdrawals_outputs(&[output])
+        .await?;
+
+    // The sweep is found from the block that confirmed it and from its
+    // descendants, but not from the other fork or from before it.
+    for chain_tip in [&btc_2a, &btc_3a] {
+        let sweep_info = shared_store
+            .get_withdrawal_sweep_info(&chain_tip.block_hash, &id)
+            .await?;
+        assert_eq!(sweep_info, Some(sweep));
+    }
+    for chain_tip in [btc_1, &btc_2b] {
+        let sweep_info = shared_store
+            .get_withdrawal_sweep_info(&chain_tip.block_hash, &id)
+            .await?;
+        assert!(sweep_info.is_none());
+    }
+
+    Ok(())
+}
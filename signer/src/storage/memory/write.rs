@@ -1,4 +1,5 @@
 use crate::{
+    context::SbtcLimits,
     error::Error,
     keys::PublicKeyXOnly,
     storage::{
@@ -95,6 +96,18 @@ impl DbWrite for SharedStore {
 
         let pk = (withdraw_request.request_id, withdraw_request.block_hash);
 
+        if let Some(existing) = store.withdrawal_requests.get(&pk) {
+            if existing.amount != withdraw_request.amount
+                || existing.recipient != withdraw_request.recipient
+                || existing.max_fee != withdraw_request.max_fee
+            {
+                return Err(Error::WithdrawalRequestMutation(
+                    withdraw_request.qualified_id(),
+                ));
+            }
+            return Ok(());
+        }
+
         store
             .stacks_block_to_withdrawal_requests
             .entry(pk.1)
@@ -193,6 +206,8 @@ impl DbWrite for SharedStore {
         &self,
         shares: &model::EncryptedDkgShares,
     ) -> Result<(), Error> {
+        shares.validate()?;
+
         let mut store = self.lock().await;
         store.version += 1;
 
@@ -295,6 +310,60 @@ impl DbWrite for SharedStore {
         Ok(())
     }
 
+    async fn write_tx_outputs(&self, outputs: &[model::TxOutput]) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        for output in outputs {
+            let txid_outputs = store.bitcoin_outputs.entry(output.txid).or_default();
+            if !txid_outputs.contains(output) {
+                txid_outputs.push(output.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn write_tx_prevouts(&self, prevouts: &[model::TxPrevout]) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        for prevout in prevouts {
+            let txid_prevouts = store.bitcoin_prevouts.entry(prevout.txid).or_default();
+            if !txid_prevouts.contains(prevout) {
+                txid_prevouts.push(prevout.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn write_anomalous_spend(&self, spend: &model::AnomalousSpend) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store
+            .anomalous_spends
+            .insert((spend.txid, spend.block_hash), spend.clone());
+
+        Ok(())
+    }
+
+    async fn write_effective_limits(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        limits: &SbtcLimits,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        store
+            .effective_sbtc_limits
+            .insert(*chain_tip, limits.clone());
+
+        Ok(())
+    }
+
     async fn write_bitcoin_withdrawals_outputs(
         &self,
         withdrawal_outputs: &[model::BitcoinWithdrawalOutput],
@@ -357,6 +426,48 @@ impl DbWrite for SharedStore {
         }
         Ok(false)
     }
+
+    async fn prune_failed_dkg_shares(
+        &self,
+        started_before: model::BitcoinBlockHeight,
+    ) -> Result<u64, Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        let num_shares = store.encrypted_dkg_shares.len();
+        store.encrypted_dkg_shares.retain(|_, (_, shares)| {
+            shares.dkg_shares_status != DkgSharesStatus::Failed
+                || shares.started_at_bitcoin_block_height >= started_before
+        });
+
+        Ok((num_shares - store.encrypted_dkg_shares.len()) as u64)
+    }
+
+    async fn prune_effective_limits(
+        &self,
+        applied_before: model::BitcoinBlockHeight,
+    ) -> Result<u64, Error> {
+        let mut store = self.lock().await;
+        store.version += 1;
+
+        let num_limits = store.effective_sbtc_limits.len();
+        let stale_blocks: Vec<_> = store
+            .effective_sbtc_limits
+            .keys()
+            .filter(|block_hash| {
+                store
+                    .bitcoin_blocks
+                    .get(block_hash)
+                    .is_some_and(|block| block.block_height < applied_before)
+            })
+            .copied()
+            .collect();
+        for block_hash in stale_blocks {
+            store.effective_sbtc_limits.remove(&block_hash);
+        }
+
+        Ok((num_limits - store.effective_sbtc_limits.len()) as u64)
+    }
 }
 
 impl DbWrite for InMemoryTransaction {
@@ -473,6 +584,26 @@ impl DbWrite for InMemoryTransaction {
         self.store.write_tx_prevout(prevout).await
     }
 
+    async fn write_tx_outputs(&self, outputs: &[model::TxOutput]) -> Result<(), Error> {
+        self.store.write_tx_outputs(outputs).await
+    }
+
+    async fn write_tx_prevouts(&self, prevouts: &[model::TxPrevout]) -> Result<(), Error> {
+        self.store.write_tx_prevouts(prevouts).await
+    }
+
+    async fn write_anomalous_spend(&self, spend: &model::AnomalousSpend) -> Result<(), Error> {
+        self.store.write_anomalous_spend(spend).await
+    }
+
+    async fn write_effective_limits(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        limits: &SbtcLimits,
+    ) -> Result<(), Error> {
+        self.store.write_effective_limits(chain_tip, limits).await
+    }
+
     async fn write_bitcoin_txs_sighashes(
         &self,
         sighashes: &[model::BitcoinTxSigHash],
@@ -502,4 +633,18 @@ impl DbWrite for InMemoryTransaction {
     {
         self.store.verify_dkg_shares(aggregate_key).await
     }
+
+    async fn prune_failed_dkg_shares(
+        &self,
+        started_before: model::BitcoinBlockHeight,
+    ) -> Result<u64, Error> {
+        self.store.prune_failed_dkg_shares(started_before).await
+    }
+
+    async fn prune_effective_limits(
+        &self,
+        applied_before: model::BitcoinBlockHeight,
+    ) -> Result<u64, Error> {
+        self.store.prune_effective_limits(applied_before).await
+    }
 }
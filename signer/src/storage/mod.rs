@@ -21,6 +21,7 @@ use blockstack_lib::types::chainstate::StacksBlockId;
 use crate::bitcoin::utxo::SignerUtxo;
 use crate::bitcoin::validation::DepositRequestReport;
 use crate::bitcoin::validation::WithdrawalRequestReport;
+use crate::context::SbtcLimits;
 use crate::error::Error;
 use crate::keys::PublicKey;
 use crate::keys::PublicKeyXOnly;
@@ -59,12 +60,33 @@ pub trait DbRead {
         block_hash: &model::BitcoinBlockHash,
     ) -> impl Future<Output = Result<Option<model::BitcoinBlock>, Error>> + Send;
 
+    /// Get the bitcoin block with the given block hash along with whether
+    /// it is on the canonical bitcoin blockchain identified by the given
+    /// `chain_tip`. Returns `None` if the block is unknown.
+    fn get_bitcoin_block_with_canonicality(
+        &self,
+        block_hash: &model::BitcoinBlockHash,
+        chain_tip: &model::BitcoinBlockHash,
+    ) -> impl Future<Output = Result<Option<(model::BitcoinBlock, bool)>, Error>> + Send;
+
     /// Get the stacks block with the given block hash.
     fn get_stacks_block(
         &self,
         block_hash: &model::StacksBlockHash,
     ) -> impl Future<Output = Result<Option<model::StacksBlock>, Error>> + Send;
 
+    /// Get the stacks blocks anchored to bitcoin blocks at the given
+    /// height.
+    ///
+    /// There may be more than one bitcoin block at a given height, so the
+    /// returned stacks blocks may be anchored to bitcoin blocks on
+    /// different forks. The blocks are ordered by their stacks block
+    /// height.
+    fn get_stacks_blocks_by_bitcoin_height(
+        &self,
+        height: BitcoinBlockHeight,
+    ) -> impl Future<Output = Result<Vec<model::StacksBlock>, Error>> + Send;
+
     /// Get the bitcoin canonical chain tip.
     fn get_bitcoin_canonical_chain_tip(
         &self,
@@ -77,6 +99,13 @@ pub trait DbRead {
 
     /// Get the stacks chain tip, defined as the highest stacks block
     /// confirmed by the bitcoin chain tip.
+    ///
+    /// The stacks blocks anchored to any of the ancestors of the bitcoin
+    /// chain tip are considered, so this still returns a stacks block
+    /// when no stacks block is anchored to the bitcoin chain tip itself.
+    /// Returns `None` if no stacks block is anchored to the bitcoin chain
+    /// tip or any of its ancestors, which can happen early in a fresh
+    /// chain.
     fn get_stacks_chain_tip(
         &self,
         bitcoin_chain_tip: &model::BitcoinBlockHash,
@@ -107,6 +136,71 @@ pub trait DbRead {
         signatures_required: u16,
     ) -> impl Future<Output = Result<Vec<model::DepositRequest>, Error>> + Send;
 
+    /// Get the total value of the deposit requests that have been
+    /// confirmed on the canonical bitcoin blockchain identified by the
+    /// given chain tip, have been accepted by at least
+    /// `signatures_required` signers, and have not been swept yet.
+    fn get_unswept_deposit_value(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+        signatures_required: u16,
+    ) -> impl Future<Output = Result<bitcoin::Amount, Error>> + Send;
+
+    /// Get the deposit requests that have been confirmed on the bitcoin
+    /// blockchain identified by the given chain tip, looking back at most
+    /// `context_window` blocks.
+    ///
+    /// Each returned deposit request has its `confirmed_at_height` set to
+    /// the height of the block that confirmed the deposit transaction.
+    fn get_deposit_requests(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+    ) -> impl Future<Output = Result<Vec<model::DepositRequest>, Error>> + Send;
+
+    /// Get at most `limit` deposit requests for the given stacks
+    /// recipient that have been confirmed on the bitcoin blockchain
+    /// identified by the given chain tip, most recently confirmed first.
+    ///
+    /// Each returned deposit request has its `confirmed_at_height` set to
+    /// the height of the block that confirmed the deposit transaction.
+    fn get_deposit_requests_by_recipient(
+        &self,
+        recipient: &model::StacksPrincipal,
+        chain_tip: &model::BitcoinBlockHash,
+        limit: u16,
+    ) -> impl Future<Output = Result<Vec<model::DepositRequest>, Error>> + Send;
+
+    /// Get the total amount of the deposit requests for the given stacks
+    /// recipient that have been confirmed on the bitcoin blockchain
+    /// identified by the given chain tip, looking back at most
+    /// `context_window` blocks, and that have not been swept yet.
+    fn get_pending_deposit_amount_for_recipient(
+        &self,
+        recipient: &model::StacksPrincipal,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+    ) -> impl Future<Output = Result<bitcoin::Amount, Error>> + Send;
+
+    /// Get at most `limit` deposit requests that have been confirmed on
+    /// the bitcoin blockchain identified by the given chain tip and that
+    /// have been accepted by at least `accept_threshold` signers, most
+    /// recently confirmed first.
+    ///
+    /// Deposit requests without any votes count as accepted by zero
+    /// signers, so an `accept_threshold` of zero returns every confirmed
+    /// deposit request.
+    ///
+    /// Each returned deposit request has its `confirmed_at_height` set to
+    /// the height of the block that confirmed the deposit transaction.
+    fn get_accepted_deposit_requests(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        accept_threshold: u32,
+        limit: u16,
+    ) -> impl Future<Output = Result<Vec<model::DepositRequest>, Error>> + Send;
+
     /// Check whether we have a record of the deposit request in our
     /// database.
     fn deposit_request_exists(
@@ -306,6 +400,13 @@ pub trait DbRead {
         &self,
     ) -> impl Future<Output = Result<Option<model::EncryptedDkgShares>, Error>> + Send;
 
+    /// Return the DKG shares for the given aggregate key if they have
+    /// passed verification, and return None otherwise.
+    fn get_verified_dkg_shares_for_key(
+        &self,
+        aggregate_key: &PublicKey,
+    ) -> impl Future<Output = Result<Option<model::EncryptedDkgShares>, Error>> + Send;
+
     /// Returns the number of non-failed DKG shares entries in the database.
     fn get_encrypted_dkg_shares_count(&self) -> impl Future<Output = Result<u32, Error>> + Send;
 
@@ -343,11 +444,35 @@ pub trait DbRead {
     ///    transaction within the same block to satisfy points 1-3, but if
     ///    the signers have one or more transactions within a block,
     ///    exactly one output satisfying points 1-2 will be unspent.
+    ///
+    /// There can be more than one unspent signer-controlled output on
+    /// the blockchain identified by the chain tip, for example when
+    /// someone donates to the signers. In that case the canonical signer
+    /// UTXO is:
+    /// 1. The unspent output of the sweep transaction in the most recent
+    ///    block, if the signers have swept funds before.
+    /// 2. Otherwise, the unspent donation with the largest amount.
     fn get_signer_utxo(
         &self,
         chain_tip: &model::BitcoinBlockHash,
     ) -> impl Future<Output = Result<Option<SignerUtxo>, Error>> + Send;
 
+    /// Get up to `max_count` of the signers' unspent outputs, largest
+    /// amount first.
+    ///
+    /// These are the signers' outputs and donation outputs that are in
+    /// blocks on the canonical bitcoin blockchain identified by the given
+    /// chain tip, are locked by a key from one of the signers' DKG shares,
+    /// and are not spent by a signers' input on that blockchain. Under
+    /// normal conditions the first one is the UTXO returned by
+    /// [`DbRead::get_signer_utxo`], while the others are small outputs,
+    /// like donations.
+    fn get_signer_utxos(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        max_count: u16,
+    ) -> impl Future<Output = Result<Vec<SignerUtxo>, Error>> + Send;
+
     /// For the given outpoint and aggregate key, get the list all signer
     /// votes in the signer set.
     fn get_deposit_request_signer_votes(
@@ -439,6 +564,32 @@ pub trait DbRead {
         context_window: u16,
     ) -> impl Future<Output = Result<Vec<model::SweptWithdrawalRequest>, Error>> + Send;
 
+    /// Fetch the bitcoin transaction that swept out the funds for the
+    /// given withdrawal request, along with the block hash that confirmed
+    /// it.
+    ///
+    /// `None` is returned if we do not have a record of the withdrawal
+    /// request, or if no transaction sweeping out the funds has been
+    /// confirmed on the bitcoin blockchain identified by the given chain
+    /// tip.
+    fn get_withdrawal_sweep_info(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        id: &model::QualifiedRequestId,
+    ) -> impl Future<Output = Result<Option<model::BitcoinTxRef>, Error>> + Send;
+
+    /// Get the sweep transactions confirmed in bitcoin blocks on the
+    /// canonical bitcoin blockchain with heights from `from` to `to`,
+    /// inclusive.
+    ///
+    /// A sweep transaction is one that spends an output controlled by the
+    /// signers. The returned transactions are ordered by block height.
+    fn get_sweep_transactions_in_range(
+        &self,
+        from: BitcoinBlockHeight,
+        to: BitcoinBlockHeight,
+    ) -> impl Future<Output = Result<Vec<model::SweepTx>, Error>> + Send;
+
     /// Get the deposit request given the transaction id and output index.
     fn get_deposit_request(
         &self,
@@ -451,6 +602,12 @@ pub trait DbRead {
         &self,
         sighash: &model::SigHash,
     ) -> impl Future<Output = Result<Option<(bool, PublicKeyXOnly)>, Error>> + Send;
+
+    /// Get all recorded sBTC limits, ordered by the height of the bitcoin
+    /// block that they were applied to, most recent first.
+    fn get_effective_limits_history(
+        &self,
+    ) -> impl Future<Output = Result<Vec<model::EffectiveSbtcLimits>, Error>> + Send;
 }
 
 /// Represents the ability to write data to the signer storage.
@@ -480,6 +637,10 @@ pub trait DbWrite {
     ) -> impl Future<Output = Result<(), Error>> + Send;
 
     /// Write a withdrawal request.
+    ///
+    /// Writing the same withdrawal request more than once is a no-op, but
+    /// an [`Error::WithdrawalRequestMutation`] is returned if the amount,
+    /// recipient, or max fee differ from the ones already stored.
     fn write_withdrawal_request(
         &self,
         request: &model::WithdrawalRequest,
@@ -563,6 +724,37 @@ pub trait DbWrite {
         prevout: &model::TxPrevout,
     ) -> impl Future<Output = Result<(), Error>> + Send;
 
+    /// Write the bitcoin transaction outputs to the database in a single
+    /// batch. Outputs that have already been written are ignored.
+    fn write_tx_outputs(
+        &self,
+        outputs: &[model::TxOutput],
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Write the bitcoin transaction inputs to the database in a single
+    /// batch. Inputs that have already been written are ignored.
+    fn write_tx_prevouts(
+        &self,
+        prevouts: &[model::TxPrevout],
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Write a record of a transaction that spends an output controlled
+    /// by the signers but does not look like a transaction that the
+    /// signers created.
+    fn write_anomalous_spend(
+        &self,
+        spend: &model::AnomalousSpend,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record the sBTC limits that were in effect when the signers
+    /// processed the given bitcoin chain tip. Writing limits for a chain
+    /// tip that already has limits recorded replaces them.
+    fn write_effective_limits(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        limits: &SbtcLimits,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
     /// Write the bitcoin transactions sighashes to the database.
     fn write_bitcoin_txs_sighashes(
         &self,
@@ -596,4 +788,23 @@ pub trait DbWrite {
     ) -> impl Future<Output = Result<bool, Error>> + Send
     where
         X: Into<PublicKeyXOnly> + Send;
+
+    /// Deletes the stored DKG shares that have been marked as failed and
+    /// whose DKG run started at a bitcoin block height below the given
+    /// height. Verified and unverified shares are never deleted.
+    ///
+    /// Returns the number of deleted shares.
+    fn prune_failed_dkg_shares(
+        &self,
+        started_before: BitcoinBlockHeight,
+    ) -> impl Future<Output = Result<u64, Error>> + Send;
+
+    /// Deletes the recorded sBTC limits for bitcoin blocks with a height
+    /// below the given height.
+    ///
+    /// Returns the number of deleted records.
+    fn prune_effective_limits(
+        &self,
+        applied_before: BitcoinBlockHeight,
+    ) -> impl Future<Output = Result<u64, Error>> + Send;
 }
@@ -21,9 +21,13 @@ use crate::bitcoin::rpc::BitcoinBlockInfo;
 use crate::bitcoin::validation::InputValidationResult;
 use crate::bitcoin::validation::WithdrawalValidationResult;
 use crate::block_observer::Deposit;
+use crate::codec::Decode as _;
+use crate::context::SbtcLimits;
 use crate::error::Error;
+use crate::keys::PrivateKey;
 use crate::keys::PublicKey;
 use crate::keys::PublicKeyXOnly;
+use crate::keys::SignerScriptPubKey as _;
 use crate::stacks::api::SignerSetInfo;
 
 /// A bitcoin transaction output (TXO) relevant for the sBTC signers.
@@ -52,6 +56,14 @@ pub struct TxOutput {
     pub amount: u64,
     /// The type of output.
     pub output_type: TxOutputType,
+    /// The magic bytes parsed from the data of the signers' `OP_RETURN`
+    /// output. This is `None` for all other outputs.
+    #[sqlx(default)]
+    pub magic_bytes: Option<Bytes>,
+    /// The sBTC `OP_RETURN` format version parsed from the data of the
+    /// signers' `OP_RETURN` output. This is `None` for all other outputs.
+    #[sqlx(default)]
+    pub op_return_version: Option<i16>,
 }
 
 /// A bitcoin transaction output (TXO) related to a withdrawal.
@@ -97,6 +109,43 @@ pub struct TxPrevout {
     pub prevout_type: TxPrevoutType,
 }
 
+/// A sweep transaction, one that spends an output controlled by the
+/// signers, along with the bitcoin block that confirmed it.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct SweepTx {
+    /// The ID of the sweep transaction.
+    pub txid: BitcoinTxId,
+    /// The hash of the bitcoin block that confirmed the sweep transaction.
+    pub block_hash: BitcoinBlockHash,
+    /// The height of the bitcoin block that confirmed the sweep
+    /// transaction.
+    pub block_height: BitcoinBlockHeight,
+}
+
+/// A transaction that spends an output controlled by the signers but
+/// does not follow the layout of a transaction created by the signers.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct AnomalousSpend {
+    /// The ID of the transaction spending the signers' output.
+    pub txid: BitcoinTxId,
+    /// The hash of the bitcoin block that included the transaction.
+    pub block_hash: BitcoinBlockHash,
+    /// Why the transaction was deemed anomalous.
+    pub reason: AnomalousSpendReason,
+}
+
+/// The sBTC limits that were in effect when the signers processed a
+/// bitcoin block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EffectiveSbtcLimits {
+    /// The bitcoin chain tip that the limits were applied to.
+    pub bitcoin_chain_tip: BitcoinBlockHash,
+    /// The sBTC limits in effect at the chain tip.
+    pub limits: SbtcLimits,
+}
+
 /// Bitcoin block.
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
 #[cfg_attr(feature = "testing", derive(fake::Dummy))]
@@ -221,6 +270,13 @@ pub struct DepositRequest {
         dummy(faker = "crate::testing::dummy::BitcoinAddresses(1..5)")
     )]
     pub sender_script_pub_keys: Vec<ScriptPubKey>,
+    /// The height of the bitcoin block that confirmed the deposit
+    /// transaction. This is not stored with the deposit request and is
+    /// only populated by queries that join on the confirming block, such
+    /// as [`DbRead::get_deposit_requests`](crate::storage::DbRead::get_deposit_requests).
+    #[sqlx(default)]
+    #[cfg_attr(feature = "testing", dummy(default))]
+    pub confirmed_at_height: Option<BitcoinBlockHeight>,
 }
 
 impl From<Deposit> for DepositRequest {
@@ -246,6 +302,7 @@ impl From<Deposit> for DepositRequest {
             lock_time: deposit.info.lock_time.to_consensus_u32(),
             signers_public_key: deposit.info.signers_public_key.into(),
             sender_script_pub_keys: sender_script_pub_keys.into_iter().collect(),
+            confirmed_at_height: None,
         }
     }
 }
@@ -260,6 +317,19 @@ impl DepositRequest {
     }
 }
 
+/// A concise, single line summary of the deposit request for logging.
+impl std::fmt::Display for DepositRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "deposit {} amount={} recipient={}",
+            self.outpoint(),
+            self.amount,
+            self.recipient
+        )
+    }
+}
+
 /// A signer acknowledging a deposit request.
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
 #[cfg_attr(feature = "testing", derive(fake::Dummy))]
@@ -342,6 +412,20 @@ impl WithdrawalRequest {
     }
 }
 
+/// A concise, single line summary of the withdrawal request for logging.
+impl std::fmt::Display for WithdrawalRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "withdrawal {} txid={} amount={} recipient={}",
+            self.request_id,
+            self.txid,
+            self.amount,
+            self.recipient.to_hex_string()
+        )
+    }
+}
+
 /// A signer acknowledging a withdrawal request.
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
 #[cfg_attr(feature = "testing", derive(fake::Dummy))]
@@ -490,7 +574,6 @@ impl SweptWithdrawalRequest {
 /// This struct represents the output of a successful run of distributed
 /// key generation (DKG) that was run by a set of signers.
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
-#[cfg_attr(feature = "testing", derive(fake::Dummy))]
 pub struct EncryptedDkgShares {
     /// The aggregate key for these shares
     pub aggregate_key: PublicKey,
@@ -528,6 +611,50 @@ impl EncryptedDkgShares {
     pub fn signer_set_public_keys(&self) -> BTreeSet<PublicKey> {
         self.signer_set_public_keys.iter().copied().collect()
     }
+
+    /// Check that these shares are internally consistent.
+    ///
+    /// The signature share threshold must be non-zero and no larger than
+    /// the number of signers that participated in DKG, and the tweaked
+    /// aggregate key and `scriptPubKey` must be the ones derived from the
+    /// aggregate key.
+    ///
+    /// Note that the aggregate key is not checked against the combination
+    /// of the `signer_set_public_keys`. With WSTS, the aggregate key is
+    /// the sum of the constant terms of the polynomials that the signers
+    /// commit to during DKG, which are unrelated to their long-term
+    /// public keys.
+    pub fn validate(&self) -> Result<(), Error> {
+        let num_signers = self.signer_set_public_keys.len();
+        let threshold = self.signature_share_threshold;
+        if threshold == 0 || usize::from(threshold) > num_signers {
+            return Err(Error::InvalidDkgSharesThreshold(threshold, num_signers));
+        }
+
+        if self.tweaked_aggregate_key != self.aggregate_key.signers_tweaked_pubkey()? {
+            return Err(Error::DkgSharesAggregateKeyMismatch(self.aggregate_key));
+        }
+
+        if *self.script_pubkey != self.aggregate_key.signers_script_pubkey() {
+            return Err(Error::DkgSharesAggregateKeyMismatch(self.aggregate_key));
+        }
+
+        Ok(())
+    }
+
+    /// Decrypt the private DKG shares using the given private key and
+    /// decode them into the saved WSTS signer state.
+    ///
+    /// This fails if the private key is not the one that was used to
+    /// encrypt the shares, or if the decrypted bytes are not a valid
+    /// encoding of a [`wsts::traits::SignerState`].
+    pub fn decrypt(&self, private_key: &PrivateKey) -> Result<wsts::traits::SignerState, Error> {
+        let decrypted =
+            wsts::util::decrypt(&private_key.to_bytes(), &self.encrypted_private_shares)
+                .map_err(|_| Error::Encryption)?;
+
+        Ok(wsts::traits::SignerState::decode(decrypted.as_slice())?)
+    }
 }
 
 impl From<EncryptedDkgShares> for SignerSetInfo {
@@ -679,6 +806,23 @@ pub enum TxPrevoutType {
     Deposit,
 }
 
+/// The reasons why a transaction spending an output controlled by the
+/// signers may be considered anomalous.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type, strum::Display)]
+#[sqlx(type_name = "anomalous_spend_reason", rename_all = "snake_case")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[strum(serialize_all = "snake_case")]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub enum AnomalousSpendReason {
+    /// An output controlled by the signers was spent, but not as the
+    /// first input of the transaction, which is where the signers always
+    /// place their UTXO.
+    SignersInputNotFirst,
+    /// The first input spends an output controlled by the signers, but
+    /// the second output is not an `OP_RETURN` output.
+    MissingSignersOpReturn,
+}
+
 /// An identifier for a withdrawal request, comprised of the Stacks
 /// transaction ID, the Stacks block ID that included the transaction, and
 /// the request-id generated by the clarity contract for the withdrawal
@@ -1610,10 +1754,35 @@ mod tests {
 
     use sbtc::events::FromLittleEndianOrder;
 
+    use crate::codec::Encode as _;
+    use crate::testing::dummy::Unit;
     use crate::testing::get_rng;
 
     use super::*;
 
+    #[test]
+    fn deposit_request_display_is_a_summary() {
+        let deposit: DepositRequest = fake::Faker.fake_with_rng(&mut get_rng());
+        let summary = deposit.to_string();
+
+        assert!(summary.contains(&deposit.outpoint().to_string()));
+        assert!(summary.contains(&format!("amount={}", deposit.amount)));
+        assert!(summary.contains(&deposit.recipient.to_string()));
+        assert_eq!(summary.lines().count(), 1);
+    }
+
+    #[test]
+    fn withdrawal_request_display_is_a_summary() {
+        let withdrawal: WithdrawalRequest = fake::Faker.fake_with_rng(&mut get_rng());
+        let summary = withdrawal.to_string();
+
+        assert!(summary.contains(&format!("withdrawal {}", withdrawal.request_id)));
+        assert!(summary.contains(&withdrawal.txid.to_string()));
+        assert!(summary.contains(&format!("amount={}", withdrawal.amount)));
+        assert!(summary.contains(&withdrawal.recipient.to_hex_string()));
+        assert_eq!(summary.lines().count(), 1);
+    }
+
     #[test]
     fn conversion_bitcoin_header_hashes() {
         let mut rng = get_rng();
@@ -1643,4 +1812,29 @@ mod tests {
 
         assert_eq!(block_hash, round_trip);
     }
+
+    #[test]
+    fn encrypted_dkg_shares_decrypt_round_trip() {
+        let mut rng = get_rng();
+
+        let private_key = PrivateKey::new(&mut rng);
+        let signer_state: wsts::traits::SignerState = Unit.fake_with_rng(&mut rng);
+
+        let encoded = signer_state.encode_to_vec();
+        let encrypted_private_shares =
+            wsts::util::encrypt(&private_key.to_bytes(), &encoded, &mut rng).unwrap();
+
+        let shares = EncryptedDkgShares {
+            encrypted_private_shares,
+            ..fake::Faker.fake_with_rng(&mut rng)
+        };
+
+        let decrypted = shares.decrypt(&private_key).unwrap();
+        assert_eq!(decrypted, signer_state);
+
+        // Some other private key cannot decrypt the shares.
+        let other_private_key = PrivateKey::new(&mut rng);
+        let result = shares.decrypt(&other_private_key);
+        assert!(matches!(result, Err(Error::Encryption)));
+    }
 }
@@ -2,6 +2,8 @@ use std::collections::BTreeSet;
 
 use bitcoin::OutPoint;
 use clarity::types::chainstate::StacksBlockId;
+use futures::Stream;
+use futures::TryStreamExt as _;
 
 use crate::{
     DEPOSIT_LOCKTIME_BLOCK_BUFFER, MAX_MEMPOOL_PACKAGE_TX_COUNT, MAX_REORG_BLOCK_COUNT,
@@ -105,6 +107,36 @@ impl From<PgSignerUtxo> for SignerUtxo {
         }
     }
 }
+
+/// The query for the deposit requests confirmed on the bitcoin blockchain
+/// identified by a chain tip (`$1`) and a context window (`$2`), most
+/// recently confirmed first. It is shared between
+/// [`PgRead::get_deposit_requests`] and [`PgRead::stream_deposit_requests`].
+const DEPOSIT_REQUESTS_QUERY: &str = r#"
+    SELECT
+        deposit_requests.txid
+      , deposit_requests.output_index
+      , deposit_requests.spend_script
+      , deposit_requests.reclaim_script
+      , deposit_requests.reclaim_script_hash
+      , deposit_requests.recipient
+      , deposit_requests.amount
+      , deposit_requests.max_fee
+      , deposit_requests.lock_time
+      , deposit_requests.signers_public_key
+      , deposit_requests.sender_script_pub_keys
+      , blocks.block_height AS confirmed_at_height
+    FROM bitcoin_blockchain_of($1, $2) AS blocks
+    JOIN sbtc_signer.bitcoin_transactions AS transactions
+      ON transactions.block_hash = blocks.block_hash
+    JOIN sbtc_signer.deposit_requests AS deposit_requests
+      ON deposit_requests.txid = transactions.txid
+    ORDER BY
+        blocks.block_height DESC
+      , deposit_requests.txid
+      , deposit_requests.output_index
+"#;
+
 /// Read-accessors to the Postgres database.
 pub struct PgRead;
 
@@ -143,6 +175,12 @@ impl PgRead {
         .map_err(Error::SqlxQuery)
     }
 
+    /// Return the canonical unspent signers' output of the given type.
+    ///
+    /// Outputs of sweep transactions are ordered by the height of the
+    /// block that confirmed them, most recent first, while donations are
+    /// ordered by amount, largest first. Any remaining ties are broken by
+    /// amount and then by outpoint, so that the result is deterministic.
     async fn get_utxo<'e, E>(
         executor: &'e mut E,
         chain_tip: &model::BitcoinBlockHash,
@@ -155,7 +193,7 @@ impl PgRead {
         let pg_utxo = sqlx::query_as::<_, PgSignerUtxo>(
             r#"
             WITH bitcoin_blockchain AS (
-                SELECT block_hash
+                SELECT block_hash, block_height
                 FROM bitcoin_blockchain_until($1, $2)
             ),
             confirmed_sweeps AS (
@@ -181,7 +219,13 @@ impl PgRead {
               AND cs.prevout_output_index = bo.output_index
             WHERE cs.prevout_txid IS NULL
               AND bo.output_type = $3
-            ORDER BY bo.amount DESC
+            ORDER BY
+                CASE WHEN bo.output_type = 'signers_output'
+                     THEN bb.block_height
+                END DESC NULLS LAST
+              , bo.amount DESC
+              , bo.txid ASC
+              , bo.output_index ASC
             LIMIT 1;
             "#,
         )
@@ -699,6 +743,81 @@ impl PgRead {
         .map_err(Error::SqlxQuery)
     }
 
+    pub async fn get_bitcoin_block_with_canonicality<'e, E>(
+        executor: &'e mut E,
+        block_hash: &model::BitcoinBlockHash,
+        chain_tip: &model::BitcoinBlockHash,
+    ) -> Result<Option<(model::BitcoinBlock, bool)>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        // We walk back from the chain tip until we reach the height of the
+        // block in question. If the block is unknown then the height
+        // subquery is NULL, the recursion stops, and no rows are returned.
+        let row = sqlx::query_as::<
+            _,
+            (
+                model::BitcoinBlockHash,
+                BitcoinBlockHeight,
+                model::BitcoinBlockHash,
+                bool,
+            ),
+        >(
+            r#"
+            WITH RECURSIVE canonical_chain AS (
+                SELECT
+                    block_hash
+                  , block_height
+                  , parent_hash
+                FROM sbtc_signer.bitcoin_blocks
+                WHERE block_hash = $2
+
+                UNION ALL
+
+                SELECT
+                    parent.block_hash
+                  , parent.block_height
+                  , parent.parent_hash
+                FROM sbtc_signer.bitcoin_blocks AS parent
+                JOIN canonical_chain AS child
+                  ON parent.block_hash = child.parent_hash
+                WHERE child.block_height > (
+                    SELECT block_height
+                    FROM sbtc_signer.bitcoin_blocks
+                    WHERE block_hash = $1
+                )
+            )
+            SELECT
+                bb.block_hash
+              , bb.block_height
+              , bb.parent_hash
+              , EXISTS (
+                    SELECT TRUE
+                    FROM canonical_chain AS cc
+                    WHERE cc.block_hash = bb.block_hash
+                ) AS is_canonical
+            FROM sbtc_signer.bitcoin_blocks AS bb
+            WHERE bb.block_hash = $1;
+            "#,
+        )
+        .bind(block_hash)
+        .bind(chain_tip)
+        .fetch_optional(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(
+            row.map(|(block_hash, block_height, parent_hash, is_canonical)| {
+                let block = model::BitcoinBlock {
+                    block_hash,
+                    block_height,
+                    parent_hash,
+                };
+                (block, is_canonical)
+            }),
+        )
+    }
+
     pub async fn get_stacks_block<'e, E>(
         executor: &'e mut E,
         block_hash: &model::StacksBlockHash,
@@ -721,6 +840,31 @@ impl PgRead {
         .map_err(Error::SqlxQuery)
     }
 
+    async fn get_stacks_blocks_by_bitcoin_height<'e, E>(
+        executor: &'e mut E,
+        height: BitcoinBlockHeight,
+    ) -> Result<Vec<model::StacksBlock>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::StacksBlock>(
+            "SELECT
+                sb.block_hash
+              , sb.block_height
+              , sb.parent_hash
+              , sb.bitcoin_anchor
+            FROM sbtc_signer.stacks_blocks AS sb
+            JOIN sbtc_signer.bitcoin_blocks AS bb
+              ON bb.block_hash = sb.bitcoin_anchor
+            WHERE bb.block_height = $1
+            ORDER BY sb.block_height ASC, sb.block_hash ASC;",
+        )
+        .bind(i64::try_from(height).map_err(Error::ConversionDatabaseInt)?)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
     pub async fn get_bitcoin_canonical_chain_tip<'e, E>(
         executor: &'e mut E,
     ) -> Result<Option<model::BitcoinBlockHash>, Error>
@@ -870,6 +1014,248 @@ impl PgRead {
         .map_err(Error::SqlxQuery)
     }
 
+    pub async fn get_deposit_requests<'e, E>(
+        executor: &'e mut E,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+    ) -> Result<Vec<model::DepositRequest>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::DepositRequest>(DEPOSIT_REQUESTS_QUERY)
+            .bind(chain_tip)
+            .bind(i32::from(context_window))
+            .fetch_all(executor)
+            .await
+            .map_err(Error::SqlxQuery)
+    }
+
+    /// Stream the deposit requests confirmed on the bitcoin blockchain
+    /// identified by the given chain tip and context window, most recently
+    /// confirmed first.
+    ///
+    /// Rows are fetched from the database as the stream is polled, so the
+    /// deposit requests are never all held in memory at once.
+    pub fn stream_deposit_requests<'e, E>(
+        executor: E,
+        chain_tip: model::BitcoinBlockHash,
+        context_window: u16,
+    ) -> impl Stream<Item = Result<model::DepositRequest, Error>> + Send + 'e
+    where
+        E: sqlx::PgExecutor<'e> + 'e,
+    {
+        sqlx::query_as::<_, model::DepositRequest>(DEPOSIT_REQUESTS_QUERY)
+            .bind(chain_tip)
+            .bind(i32::from(context_window))
+            .fetch(executor)
+            .map_err(Error::SqlxQuery)
+    }
+
+    pub async fn get_deposit_requests_by_recipient<'e, E>(
+        executor: &'e mut E,
+        recipient: &model::StacksPrincipal,
+        chain_tip: &model::BitcoinBlockHash,
+        limit: u16,
+    ) -> Result<Vec<model::DepositRequest>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        // We only need to walk the blockchain back to the lowest block
+        // that confirmed a deposit for the recipient. If there are no such
+        // deposits then the minimum height is NULL and we stop at the
+        // chain tip.
+        sqlx::query_as::<_, model::DepositRequest>(
+            r#"
+            WITH recipient_deposits AS (
+                SELECT
+                    deposit_requests.txid
+                  , deposit_requests.output_index
+                  , deposit_requests.spend_script
+                  , deposit_requests.reclaim_script
+                  , deposit_requests.reclaim_script_hash
+                  , deposit_requests.recipient
+                  , deposit_requests.amount
+                  , deposit_requests.max_fee
+                  , deposit_requests.lock_time
+                  , deposit_requests.signers_public_key
+                  , deposit_requests.sender_script_pub_keys
+                  , transactions.block_hash
+                FROM sbtc_signer.deposit_requests AS deposit_requests
+                JOIN sbtc_signer.bitcoin_transactions AS transactions
+                  ON transactions.txid = deposit_requests.txid
+                WHERE deposit_requests.recipient = $2
+            )
+            , min_block_height AS (
+                SELECT MIN(blocks.block_height) AS block_height
+                FROM recipient_deposits
+                JOIN sbtc_signer.bitcoin_blocks AS blocks
+                  ON blocks.block_hash = recipient_deposits.block_hash
+            )
+            SELECT
+                recipient_deposits.txid
+              , recipient_deposits.output_index
+              , recipient_deposits.spend_script
+              , recipient_deposits.reclaim_script
+              , recipient_deposits.reclaim_script_hash
+              , recipient_deposits.recipient
+              , recipient_deposits.amount
+              , recipient_deposits.max_fee
+              , recipient_deposits.lock_time
+              , recipient_deposits.signers_public_key
+              , recipient_deposits.sender_script_pub_keys
+              , blocks.block_height AS confirmed_at_height
+            FROM recipient_deposits
+            JOIN sbtc_signer.bitcoin_blockchain_until(
+                $1,
+                (SELECT block_height FROM min_block_height)
+            ) AS blocks
+              ON blocks.block_hash = recipient_deposits.block_hash
+            ORDER BY
+                blocks.block_height DESC
+              , recipient_deposits.txid
+              , recipient_deposits.output_index
+            LIMIT $3
+            "#,
+        )
+        .bind(chain_tip)
+        .bind(recipient)
+        .bind(i32::from(limit))
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    pub async fn get_pending_deposit_amount_for_recipient<'e, E>(
+        executor: &'e mut E,
+        recipient: &model::StacksPrincipal,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+    ) -> Result<bitcoin::Amount, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let total_amount = sqlx::query_scalar::<_, Option<i64>>(
+            r#"
+            WITH transactions_in_window AS (
+                SELECT transactions.txid
+                FROM bitcoin_blockchain_of($1, $2) AS blocks_in_window
+                JOIN sbtc_signer.bitcoin_transactions AS transactions
+                  ON transactions.block_hash = blocks_in_window.block_hash
+            )
+            SELECT SUM(deposit_requests.amount)::BIGINT
+            FROM sbtc_signer.deposit_requests AS deposit_requests
+            WHERE deposit_requests.recipient = $3
+              AND EXISTS (
+                SELECT 1
+                FROM transactions_in_window
+                WHERE transactions_in_window.txid = deposit_requests.txid
+              )
+              AND NOT EXISTS (
+                SELECT 1
+                FROM sbtc_signer.bitcoin_tx_inputs AS bti
+                JOIN transactions_in_window
+                  ON transactions_in_window.txid = bti.txid
+                WHERE bti.prevout_txid = deposit_requests.txid
+                  AND bti.prevout_output_index = deposit_requests.output_index
+              )
+            "#,
+        )
+        .bind(chain_tip)
+        .bind(i32::from(context_window))
+        .bind(recipient)
+        .fetch_one(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        // Amounts are always positive in the database, so this conversion
+        // is always fine.
+        let total = u64::try_from(total_amount.unwrap_or(0)).map_err(|_| Error::TypeConversion)?;
+        Ok(bitcoin::Amount::from_sat(total))
+    }
+
+    pub async fn get_accepted_deposit_requests<'e, E>(
+        executor: &'e mut E,
+        chain_tip: &model::BitcoinBlockHash,
+        accept_threshold: u32,
+        limit: u16,
+    ) -> Result<Vec<model::DepositRequest>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        // Like with the deposits for a recipient, we only need to walk the
+        // blockchain back to the lowest block that confirmed an accepted
+        // deposit.
+        sqlx::query_as::<_, model::DepositRequest>(
+            r#"
+            WITH accepted_deposits AS (
+                SELECT
+                    deposit_requests.txid
+                  , deposit_requests.output_index
+                  , deposit_requests.spend_script
+                  , deposit_requests.reclaim_script
+                  , deposit_requests.reclaim_script_hash
+                  , deposit_requests.recipient
+                  , deposit_requests.amount
+                  , deposit_requests.max_fee
+                  , deposit_requests.lock_time
+                  , deposit_requests.signers_public_key
+                  , deposit_requests.sender_script_pub_keys
+                FROM sbtc_signer.deposit_requests AS deposit_requests
+                LEFT JOIN sbtc_signer.deposit_signers AS signers
+                  USING (txid, output_index)
+                GROUP BY deposit_requests.txid, deposit_requests.output_index
+                HAVING COUNT(signers.txid) FILTER (
+                    WHERE signers.can_accept AND signers.can_sign
+                ) >= $2
+            )
+            , accepted_deposit_blocks AS (
+                SELECT
+                    accepted_deposits.*
+                  , transactions.block_hash
+                FROM accepted_deposits
+                JOIN sbtc_signer.bitcoin_transactions AS transactions
+                  ON transactions.txid = accepted_deposits.txid
+            )
+            , min_block_height AS (
+                SELECT MIN(blocks.block_height) AS block_height
+                FROM accepted_deposit_blocks
+                JOIN sbtc_signer.bitcoin_blocks AS blocks
+                  ON blocks.block_hash = accepted_deposit_blocks.block_hash
+            )
+            SELECT
+                accepted_deposit_blocks.txid
+              , accepted_deposit_blocks.output_index
+              , accepted_deposit_blocks.spend_script
+              , accepted_deposit_blocks.reclaim_script
+              , accepted_deposit_blocks.reclaim_script_hash
+              , accepted_deposit_blocks.recipient
+              , accepted_deposit_blocks.amount
+              , accepted_deposit_blocks.max_fee
+              , accepted_deposit_blocks.lock_time
+              , accepted_deposit_blocks.signers_public_key
+              , accepted_deposit_blocks.sender_script_pub_keys
+              , blocks.block_height AS confirmed_at_height
+            FROM accepted_deposit_blocks
+            JOIN sbtc_signer.bitcoin_blockchain_until(
+                $1,
+                (SELECT block_height FROM min_block_height)
+            ) AS blocks
+              ON blocks.block_hash = accepted_deposit_blocks.block_hash
+            ORDER BY
+                blocks.block_height DESC
+              , accepted_deposit_blocks.txid
+              , accepted_deposit_blocks.output_index
+            LIMIT $3
+            "#,
+        )
+        .bind(chain_tip)
+        .bind(i64::from(accept_threshold))
+        .bind(i32::from(limit))
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
     pub async fn get_pending_accepted_deposit_requests<'e, E>(
         executor: &'e mut E,
         chain_tip: &model::BitcoinBlockHash,
@@ -958,6 +1344,28 @@ impl PgRead {
         .map_err(Error::SqlxQuery)
     }
 
+    pub async fn get_unswept_deposit_value<'e, E>(
+        executor: &'e mut E,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+        threshold: u16,
+    ) -> Result<bitcoin::Amount, Error>
+    where
+        E: 'static,
+        for<'c> &'c mut E: sqlx::PgExecutor<'c>,
+    {
+        let deposits = Self::get_pending_accepted_deposit_requests(
+            executor,
+            chain_tip,
+            context_window,
+            threshold,
+        )
+        .await?;
+
+        let total = deposits.iter().map(|deposit| deposit.amount).sum();
+        Ok(bitcoin::Amount::from_sat(total))
+    }
+
     pub async fn get_deposit_request_signer_votes<'e, E>(
         executor: &'e mut E,
         txid: &model::BitcoinTxId,
@@ -1770,6 +2178,37 @@ impl PgRead {
         .map_err(Error::SqlxQuery)
     }
 
+    async fn get_verified_dkg_shares_for_key<'e, E>(
+        executor: &'e mut E,
+        aggregate_key: &PublicKey,
+    ) -> Result<Option<model::EncryptedDkgShares>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::EncryptedDkgShares>(
+            r#"
+            SELECT
+                aggregate_key
+              , tweaked_aggregate_key
+              , script_pubkey
+              , encrypted_private_shares
+              , public_shares
+              , signer_set_public_keys
+              , signature_share_threshold
+              , dkg_shares_status
+              , started_at_bitcoin_block_hash
+              , started_at_bitcoin_block_height
+            FROM sbtc_signer.dkg_shares
+            WHERE aggregate_key = $1
+              AND dkg_shares_status = 'verified';
+            "#,
+        )
+        .bind(aggregate_key)
+        .fetch_optional(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
     /// Returns the number of non-failed rows in the `dkg_shares` table.
     async fn get_encrypted_dkg_shares_count<'e, E>(executor: &'e mut E) -> Result<u32, Error>
     where
@@ -1967,6 +2406,68 @@ impl PgRead {
         }
     }
 
+    async fn get_signer_utxos<'e, E>(
+        executor: &'e mut E,
+        chain_tip: &model::BitcoinBlockHash,
+        max_count: u16,
+    ) -> Result<Vec<SignerUtxo>, Error>
+    where
+        E: 'static,
+        for<'c> &'c mut E: sqlx::PgExecutor<'c>,
+    {
+        // The signers' UTXOs are either outputs of our sweep transactions
+        // or donations, so we only need to look at blocks at or after the
+        // earliest of those.
+        let min_utxo_height = Self::minimum_utxo_height(executor).await?;
+        let min_donation_height = Self::minimum_donation_txo_height(executor).await?;
+        let Some(min_block_height) = min_utxo_height.into_iter().chain(min_donation_height).min()
+        else {
+            return Ok(Vec::new());
+        };
+
+        let pg_utxos = sqlx::query_as::<_, PgSignerUtxo>(
+            r#"
+            WITH bitcoin_blockchain AS (
+                SELECT block_hash
+                FROM bitcoin_blockchain_until($1, $2)
+            ),
+            confirmed_sweeps AS (
+                SELECT
+                    prevout_txid
+                  , prevout_output_index
+                FROM sbtc_signer.bitcoin_tx_inputs
+                JOIN sbtc_signer.bitcoin_transactions AS bt USING (txid)
+                JOIN bitcoin_blockchain AS bb USING (block_hash)
+                WHERE prevout_type = 'signers_input'
+            )
+            SELECT
+                bo.txid
+              , bo.output_index
+              , bo.amount
+              , ds.aggregate_key
+            FROM sbtc_signer.bitcoin_tx_outputs AS bo
+            JOIN sbtc_signer.bitcoin_transactions AS bt USING (txid)
+            JOIN bitcoin_blockchain AS bb USING (block_hash)
+            JOIN sbtc_signer.dkg_shares AS ds USING (script_pubkey)
+            LEFT JOIN confirmed_sweeps AS cs
+              ON cs.prevout_txid = bo.txid
+              AND cs.prevout_output_index = bo.output_index
+            WHERE cs.prevout_txid IS NULL
+              AND bo.output_type IN ('signers_output', 'donation')
+            ORDER BY bo.amount DESC, bo.txid, bo.output_index
+            LIMIT $3;
+            "#,
+        )
+        .bind(chain_tip)
+        .bind(i64::try_from(min_block_height).map_err(Error::ConversionDatabaseInt)?)
+        .bind(i32::from(max_count))
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(pg_utxos.into_iter().map(SignerUtxo::from).collect())
+    }
+
     async fn is_known_bitcoin_block_hash<'e, E>(
         executor: &'e mut E,
         block_hash: &model::BitcoinBlockHash,
@@ -2385,6 +2886,47 @@ impl PgRead {
         .map_err(Error::SqlxQuery)
     }
 
+    async fn get_sweep_transactions_in_range<'e, E>(
+        executor: &'e mut E,
+        from: BitcoinBlockHeight,
+        to: BitcoinBlockHeight,
+    ) -> Result<Vec<model::SweepTx>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query_as::<_, model::SweepTx>(
+            r#"
+            WITH canonical_chain_tip AS (
+                SELECT block_hash
+                FROM sbtc_signer.bitcoin_blocks
+                ORDER BY block_height DESC, block_hash DESC
+                LIMIT 1
+            ),
+            bitcoin_blockchain AS (
+                SELECT
+                    block_hash
+                  , block_height
+                FROM bitcoin_blockchain_until((SELECT block_hash FROM canonical_chain_tip), $1)
+                WHERE block_height BETWEEN $1 AND $2
+            )
+            SELECT DISTINCT
+                bt.txid
+              , bb.block_hash
+              , bb.block_height
+            FROM sbtc_signer.bitcoin_tx_inputs AS bi
+            JOIN sbtc_signer.bitcoin_transactions AS bt USING (txid)
+            JOIN bitcoin_blockchain AS bb USING (block_hash)
+            WHERE bi.prevout_type = 'signers_input'
+            ORDER BY bb.block_height ASC, bt.txid ASC
+            "#,
+        )
+        .bind(i64::try_from(from).map_err(Error::ConversionDatabaseInt)?)
+        .bind(i64::try_from(to).map_err(Error::ConversionDatabaseInt)?)
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
     async fn get_deposit_request<'e, E>(
         executor: &'e mut E,
         txid: &model::BitcoinTxId,
@@ -2440,6 +2982,37 @@ impl PgRead {
         .map_err(Error::SqlxQuery)
     }
 
+    async fn get_effective_limits_history<'e, E>(
+        executor: &'e mut E,
+    ) -> Result<Vec<model::EffectiveSbtcLimits>, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let rows = sqlx::query_as::<_, (model::BitcoinBlockHash, String)>(
+            r#"
+            SELECT
+                esl.block_hash
+              , esl.limits::TEXT
+            FROM sbtc_signer.effective_sbtc_limits AS esl
+            JOIN sbtc_signer.bitcoin_blocks AS bb
+              ON bb.block_hash = esl.block_hash
+            ORDER BY bb.block_height DESC, esl.block_hash
+            "#,
+        )
+        .fetch_all(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        rows.into_iter()
+            .map(|(bitcoin_chain_tip, limits)| {
+                Ok(model::EffectiveSbtcLimits {
+                    bitcoin_chain_tip,
+                    limits: serde_json::from_str(&limits).map_err(Error::JsonDeserialize)?,
+                })
+            })
+            .collect()
+    }
+
     async fn get_withdrawal_signer_decisions<'e, E>(
         executor: &'e mut E,
         chain_tip: &model::BitcoinBlockHash,
@@ -2524,6 +3097,19 @@ impl DbRead for PgStore {
         PgRead::get_bitcoin_block(self.get_connection().await?.as_mut(), block_hash).await
     }
 
+    async fn get_bitcoin_block_with_canonicality(
+        &self,
+        block_hash: &model::BitcoinBlockHash,
+        chain_tip: &model::BitcoinBlockHash,
+    ) -> Result<Option<(model::BitcoinBlock, bool)>, Error> {
+        PgRead::get_bitcoin_block_with_canonicality(
+            self.get_connection().await?.as_mut(),
+            block_hash,
+            chain_tip,
+        )
+        .await
+    }
+
     async fn get_stacks_block(
         &self,
         block_hash: &model::StacksBlockHash,
@@ -2531,6 +3117,14 @@ impl DbRead for PgStore {
         PgRead::get_stacks_block(self.get_connection().await?.as_mut(), block_hash).await
     }
 
+    async fn get_stacks_blocks_by_bitcoin_height(
+        &self,
+        height: BitcoinBlockHeight,
+    ) -> Result<Vec<model::StacksBlock>, Error> {
+        PgRead::get_stacks_blocks_by_bitcoin_height(self.get_connection().await?.as_mut(), height)
+            .await
+    }
+
     async fn get_bitcoin_canonical_chain_tip(
         &self,
     ) -> Result<Option<model::BitcoinBlockHash>, Error> {
@@ -2580,6 +3174,79 @@ impl DbRead for PgStore {
         .await
     }
 
+    async fn get_unswept_deposit_value(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+        signatures_required: u16,
+    ) -> Result<bitcoin::Amount, Error> {
+        PgRead::get_unswept_deposit_value(
+            self.get_connection().await?.as_mut(),
+            chain_tip,
+            context_window,
+            signatures_required,
+        )
+        .await
+    }
+
+    async fn get_deposit_requests(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+    ) -> Result<Vec<model::DepositRequest>, Error> {
+        PgRead::get_deposit_requests(
+            self.get_connection().await?.as_mut(),
+            chain_tip,
+            context_window,
+        )
+        .await
+    }
+
+    async fn get_deposit_requests_by_recipient(
+        &self,
+        recipient: &model::StacksPrincipal,
+        chain_tip: &model::BitcoinBlockHash,
+        limit: u16,
+    ) -> Result<Vec<model::DepositRequest>, Error> {
+        PgRead::get_deposit_requests_by_recipient(
+            self.get_connection().await?.as_mut(),
+            recipient,
+            chain_tip,
+            limit,
+        )
+        .await
+    }
+
+    async fn get_pending_deposit_amount_for_recipient(
+        &self,
+        recipient: &model::StacksPrincipal,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+    ) -> Result<bitcoin::Amount, Error> {
+        PgRead::get_pending_deposit_amount_for_recipient(
+            self.get_connection().await?.as_mut(),
+            recipient,
+            chain_tip,
+            context_window,
+        )
+        .await
+    }
+
+    async fn get_accepted_deposit_requests(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        accept_threshold: u32,
+        limit: u16,
+    ) -> Result<Vec<model::DepositRequest>, Error> {
+        PgRead::get_accepted_deposit_requests(
+            self.get_connection().await?.as_mut(),
+            chain_tip,
+            accept_threshold,
+            limit,
+        )
+        .await
+    }
+
     async fn get_deposit_request_signer_votes(
         &self,
         txid: &model::BitcoinTxId,
@@ -2779,6 +3446,17 @@ impl DbRead for PgStore {
         PgRead::get_latest_verified_dkg_shares(self.get_connection().await?.as_mut()).await
     }
 
+    async fn get_verified_dkg_shares_for_key(
+        &self,
+        aggregate_key: &PublicKey,
+    ) -> Result<Option<model::EncryptedDkgShares>, Error> {
+        PgRead::get_verified_dkg_shares_for_key(
+            self.get_connection().await?.as_mut(),
+            aggregate_key,
+        )
+        .await
+    }
+
     async fn get_encrypted_dkg_shares_count(&self) -> Result<u32, Error> {
         PgRead::get_encrypted_dkg_shares_count(self.get_connection().await?.as_mut()).await
     }
@@ -2819,6 +3497,14 @@ impl DbRead for PgStore {
         PgRead::get_signer_utxo(self.get_connection().await?.as_mut(), chain_tip).await
     }
 
+    async fn get_signer_utxos(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        max_count: u16,
+    ) -> Result<Vec<SignerUtxo>, Error> {
+        PgRead::get_signer_utxos(self.get_connection().await?.as_mut(), chain_tip, max_count).await
+    }
+
     async fn is_known_bitcoin_block_hash(
         &self,
         block_hash: &model::BitcoinBlockHash,
@@ -2893,6 +3579,24 @@ impl DbRead for PgStore {
         .await
     }
 
+    async fn get_withdrawal_sweep_info(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        id: &model::QualifiedRequestId,
+    ) -> Result<Option<model::BitcoinTxRef>, Error> {
+        PgRead::get_withdrawal_sweep_info(self.get_connection().await?.as_mut(), chain_tip, id)
+            .await
+    }
+
+    async fn get_sweep_transactions_in_range(
+        &self,
+        from: BitcoinBlockHeight,
+        to: BitcoinBlockHeight,
+    ) -> Result<Vec<model::SweepTx>, Error> {
+        PgRead::get_sweep_transactions_in_range(self.get_connection().await?.as_mut(), from, to)
+            .await
+    }
+
     async fn get_deposit_request(
         &self,
         txid: &model::BitcoinTxId,
@@ -2908,6 +3612,10 @@ impl DbRead for PgStore {
         PgRead::will_sign_bitcoin_tx_sighash(self.get_connection().await?.as_mut(), sighash).await
     }
 
+    async fn get_effective_limits_history(&self) -> Result<Vec<model::EffectiveSbtcLimits>, Error> {
+        PgRead::get_effective_limits_history(self.get_connection().await?.as_mut()).await
+    }
+
     async fn get_withdrawal_signer_decisions(
         &self,
         chain_tip: &model::BitcoinBlockHash,
@@ -2948,6 +3656,15 @@ impl DbRead for PgTransaction<'_> {
         PgRead::get_bitcoin_block(tx.as_mut(), block_hash).await
     }
 
+    async fn get_bitcoin_block_with_canonicality(
+        &self,
+        block_hash: &model::BitcoinBlockHash,
+        chain_tip: &model::BitcoinBlockHash,
+    ) -> Result<Option<(model::BitcoinBlock, bool)>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_bitcoin_block_with_canonicality(tx.as_mut(), block_hash, chain_tip).await
+    }
+
     async fn get_stacks_block(
         &self,
         block_hash: &model::StacksBlockHash,
@@ -2955,6 +3672,13 @@ impl DbRead for PgTransaction<'_> {
         PgRead::get_stacks_block(self.tx.lock().await.as_mut(), block_hash).await
     }
 
+    async fn get_stacks_blocks_by_bitcoin_height(
+        &self,
+        height: BitcoinBlockHeight,
+    ) -> Result<Vec<model::StacksBlock>, Error> {
+        PgRead::get_stacks_blocks_by_bitcoin_height(self.tx.lock().await.as_mut(), height).await
+    }
+
     async fn get_bitcoin_canonical_chain_tip(
         &self,
     ) -> Result<Option<model::BitcoinBlockHash>, Error> {
@@ -3008,6 +3732,69 @@ impl DbRead for PgTransaction<'_> {
         .await
     }
 
+    async fn get_unswept_deposit_value(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+        signatures_required: u16,
+    ) -> Result<bitcoin::Amount, Error> {
+        PgRead::get_unswept_deposit_value(
+            self.tx.lock().await.as_mut(),
+            chain_tip,
+            context_window,
+            signatures_required,
+        )
+        .await
+    }
+
+    async fn get_deposit_requests(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+    ) -> Result<Vec<model::DepositRequest>, Error> {
+        PgRead::get_deposit_requests(self.tx.lock().await.as_mut(), chain_tip, context_window).await
+    }
+
+    async fn get_deposit_requests_by_recipient(
+        &self,
+        recipient: &model::StacksPrincipal,
+        chain_tip: &model::BitcoinBlockHash,
+        limit: u16,
+    ) -> Result<Vec<model::DepositRequest>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_deposit_requests_by_recipient(tx.as_mut(), recipient, chain_tip, limit).await
+    }
+
+    async fn get_pending_deposit_amount_for_recipient(
+        &self,
+        recipient: &model::StacksPrincipal,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+    ) -> Result<bitcoin::Amount, Error> {
+        PgRead::get_pending_deposit_amount_for_recipient(
+            self.tx.lock().await.as_mut(),
+            recipient,
+            chain_tip,
+            context_window,
+        )
+        .await
+    }
+
+    async fn get_accepted_deposit_requests(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        accept_threshold: u32,
+        limit: u16,
+    ) -> Result<Vec<model::DepositRequest>, Error> {
+        PgRead::get_accepted_deposit_requests(
+            self.tx.lock().await.as_mut(),
+            chain_tip,
+            accept_threshold,
+            limit,
+        )
+        .await
+    }
+
     async fn deposit_request_exists(
         &self,
         txid: &model::BitcoinTxId,
@@ -3204,6 +3991,13 @@ impl DbRead for PgTransaction<'_> {
         PgRead::get_latest_verified_dkg_shares(tx.as_mut()).await
     }
 
+    async fn get_verified_dkg_shares_for_key(
+        &self,
+        aggregate_key: &PublicKey,
+    ) -> Result<Option<model::EncryptedDkgShares>, Error> {
+        PgRead::get_verified_dkg_shares_for_key(self.tx.lock().await.as_mut(), aggregate_key).await
+    }
+
     async fn get_encrypted_dkg_shares_count(&self) -> Result<u32, Error> {
         let mut tx = self.tx.lock().await;
         PgRead::get_encrypted_dkg_shares_count(tx.as_mut()).await
@@ -3247,6 +4041,14 @@ impl DbRead for PgTransaction<'_> {
         PgRead::get_signer_utxo(self.tx.lock().await.as_mut(), chain_tip).await
     }
 
+    async fn get_signer_utxos(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        max_count: u16,
+    ) -> Result<Vec<SignerUtxo>, Error> {
+        PgRead::get_signer_utxos(self.tx.lock().await.as_mut(), chain_tip, max_count).await
+    }
+
     async fn get_deposit_request_signer_votes(
         &self,
         txid: &model::BitcoinTxId,
@@ -3342,6 +4144,22 @@ impl DbRead for PgTransaction<'_> {
         .await
     }
 
+    async fn get_withdrawal_sweep_info(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        id: &model::QualifiedRequestId,
+    ) -> Result<Option<model::BitcoinTxRef>, Error> {
+        PgRead::get_withdrawal_sweep_info(self.tx.lock().await.as_mut(), chain_tip, id).await
+    }
+
+    async fn get_sweep_transactions_in_range(
+        &self,
+        from: BitcoinBlockHeight,
+        to: BitcoinBlockHeight,
+    ) -> Result<Vec<model::SweepTx>, Error> {
+        PgRead::get_sweep_transactions_in_range(self.tx.lock().await.as_mut(), from, to).await
+    }
+
     async fn get_deposit_request(
         &self,
         txid: &model::BitcoinTxId,
@@ -3358,4 +4176,9 @@ impl DbRead for PgTransaction<'_> {
         let mut tx = self.tx.lock().await;
         PgRead::will_sign_bitcoin_tx_sighash(tx.as_mut(), sighash).await
     }
+
+    async fn get_effective_limits_history(&self) -> Result<Vec<model::EffectiveSbtcLimits>, Error> {
+        let mut tx = self.tx.lock().await;
+        PgRead::get_effective_limits_history(tx.as_mut()).await
+    }
 }
@@ -1,7 +1,9 @@
+use crate::storage::model::{BitcoinBlockHash, DepositRequest};
 #[cfg(any(test, feature = "testing"))]
 use crate::storage::model::{StacksBlockHash, StacksBlockHeight};
 use crate::storage::{Transactable, TransactionHandle};
 use crate::{error::Error, storage::postgres::PGSQL_MIGRATIONS};
+use futures::Stream;
 use sqlx::Executor;
 use sqlx::pool::PoolConnection;
 use sqlx::{PgExecutor, postgres::PgPoolOptions};
@@ -168,6 +170,26 @@ impl PgStore {
         self.0.acquire().await.map_err(Error::SqlxAcquireConnection)
     }
 
+    /// Stream the deposit requests confirmed on the bitcoin blockchain
+    /// identified by the given chain tip and context window, most recently
+    /// confirmed first.
+    ///
+    /// This returns the same deposit requests as
+    /// [`DbRead::get_deposit_requests`], but fetches them from the
+    /// database as the stream is polled instead of buffering them, so
+    /// that very large sets of deposit requests can be processed
+    /// incrementally. The stream holds a connection from the pool until
+    /// it is dropped.
+    ///
+    /// [`DbRead::get_deposit_requests`]: crate::storage::DbRead::get_deposit_requests
+    pub fn stream_deposit_requests(
+        &self,
+        chain_tip: &BitcoinBlockHash,
+        context_window: u16,
+    ) -> impl Stream<Item = Result<DepositRequest, Error>> + Send + '_ {
+        super::read::PgRead::stream_deposit_requests(&self.0, *chain_tip, context_window)
+    }
+
     /// Check whether the given block hash is a part of the stacks
     /// blockchain identified by the given chain-tip. Used by tests which use
     /// a `PgStore` directly. Included here due to `PgRead` being an internal
@@ -1,5 +1,6 @@
 use super::{PgStore, PgTransaction};
 use crate::{
+    context::SbtcLimits,
     error::Error,
     keys::PublicKeyXOnly,
     storage::{
@@ -231,19 +232,32 @@ impl PgWrite {
     where
         &'e mut E: sqlx::PgExecutor<'e>,
     {
-        sqlx::query(
-            "INSERT INTO sbtc_signer.withdrawal_requests
-              ( request_id
-              , txid
-              , block_hash
-              , recipient
-              , amount
-              , max_fee
-              , sender_address
-              , bitcoin_block_height
-              )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            ON CONFLICT DO NOTHING",
+        // The `existing` CTE sees the table as it was before the insert,
+        // so it only returns a row if the request had already been
+        // written.
+        let existing = sqlx::query_as::<_, (model::ScriptPubKey, i64, i64)>(
+            "WITH existing AS (
+                SELECT recipient, amount, max_fee
+                FROM sbtc_signer.withdrawal_requests
+                WHERE request_id = $1
+                  AND block_hash = $3
+            ),
+            inserted AS (
+                INSERT INTO sbtc_signer.withdrawal_requests
+                  ( request_id
+                  , txid
+                  , block_hash
+                  , recipient
+                  , amount
+                  , max_fee
+                  , sender_address
+                  , bitcoin_block_height
+                  )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ON CONFLICT DO NOTHING
+            )
+            SELECT recipient, amount, max_fee
+            FROM existing",
         )
         .bind(i64::try_from(request.request_id).map_err(Error::ConversionDatabaseInt)?)
         .bind(request.txid)
@@ -253,10 +267,21 @@ impl PgWrite {
         .bind(i64::try_from(request.max_fee).map_err(Error::ConversionDatabaseInt)?)
         .bind(&request.sender_address)
         .bind(i64::try_from(request.bitcoin_block_height).map_err(Error::ConversionDatabaseInt)?)
-        .execute(executor)
+        .fetch_optional(executor)
         .await
         .map_err(Error::SqlxQuery)?;
 
+        if let Some((recipient, amount, max_fee)) = existing {
+            let amount = u64::try_from(amount).map_err(Error::ConversionDatabaseInt)?;
+            let max_fee = u64::try_from(max_fee).map_err(Error::ConversionDatabaseInt)?;
+            if recipient != request.recipient
+                || amount != request.amount
+                || max_fee != request.max_fee
+            {
+                return Err(Error::WithdrawalRequestMutation(request.qualified_id()));
+            }
+        }
+
         Ok(())
     }
 
@@ -461,6 +486,8 @@ impl PgWrite {
     where
         &'e mut E: sqlx::PgExecutor<'e>,
     {
+        shares.validate()?;
+
         let started_at_bitcoin_block_height = i64::try_from(shares.started_at_bitcoin_block_height)
             .map_err(Error::ConversionDatabaseInt)?;
 
@@ -650,8 +677,10 @@ impl PgWrite {
               , amount
               , script_pubkey
               , output_type
+              , magic_bytes
+              , op_return_version
             )
-            VALUES ($1, $2, $3, $4, $5)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             ON CONFLICT DO NOTHING;
             "#,
         )
@@ -660,6 +689,8 @@ impl PgWrite {
         .bind(i64::try_from(output.amount).map_err(Error::ConversionDatabaseInt)?)
         .bind(&output.script_pubkey)
         .bind(output.output_type)
+        .bind(&output.magic_bytes)
+        .bind(output.op_return_version)
         .execute(executor)
         .await
         .map_err(Error::SqlxQuery)?;
@@ -729,6 +760,217 @@ impl PgWrite {
         Ok(())
     }
 
+    async fn write_tx_outputs<'e, E>(
+        executor: &'e mut E,
+        outputs: &[model::TxOutput],
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        if outputs.is_empty() {
+            return Ok(());
+        }
+
+        let mut txid = Vec::with_capacity(outputs.len());
+        let mut output_index = Vec::with_capacity(outputs.len());
+        let mut amount = Vec::with_capacity(outputs.len());
+        let mut script_pubkey = Vec::with_capacity(outputs.len());
+        let mut output_type = Vec::with_capacity(outputs.len());
+        let mut magic_bytes = Vec::with_capacity(outputs.len());
+        let mut op_return_version = Vec::with_capacity(outputs.len());
+
+        for output in outputs {
+            txid.push(output.txid);
+            output_index
+                .push(i32::try_from(output.output_index).map_err(Error::ConversionDatabaseInt)?);
+            amount.push(i64::try_from(output.amount).map_err(Error::ConversionDatabaseInt)?);
+            script_pubkey.push(output.script_pubkey.clone());
+            output_type.push(output.output_type);
+            magic_bytes.push(output.magic_bytes.clone());
+            op_return_version.push(output.op_return_version);
+        }
+
+        sqlx::query(
+            r#"
+            WITH tx_ids         AS (SELECT ROW_NUMBER() OVER (), txid FROM UNNEST($1::BYTEA[]) AS txid)
+            , output_index      AS (SELECT ROW_NUMBER() OVER (), output_index FROM UNNEST($2::INTEGER[]) AS output_index)
+            , amount            AS (SELECT ROW_NUMBER() OVER (), amount FROM UNNEST($3::BIGINT[]) AS amount)
+            , script_pubkey     AS (SELECT ROW_NUMBER() OVER (), script_pubkey FROM UNNEST($4::BYTEA[]) AS script_pubkey)
+            , output_type       AS (SELECT ROW_NUMBER() OVER (), output_type FROM UNNEST($5::sbtc_signer.output_type[]) AS output_type)
+            , magic_bytes       AS (SELECT ROW_NUMBER() OVER (), magic_bytes FROM UNNEST($6::BYTEA[]) AS magic_bytes)
+            , op_return_version AS (SELECT ROW_NUMBER() OVER (), op_return_version FROM UNNEST($7::SMALLINT[]) AS op_return_version)
+            INSERT INTO sbtc_signer.bitcoin_tx_outputs (
+                txid
+              , output_index
+              , amount
+              , script_pubkey
+              , output_type
+              , magic_bytes
+              , op_return_version
+            )
+            SELECT
+                txid
+              , output_index
+              , amount
+              , script_pubkey
+              , output_type
+              , magic_bytes
+              , op_return_version
+            FROM tx_ids
+            JOIN output_index USING (row_number)
+            JOIN amount USING (row_number)
+            JOIN script_pubkey USING (row_number)
+            JOIN output_type USING (row_number)
+            JOIN magic_bytes USING (row_number)
+            JOIN op_return_version USING (row_number)
+            ON CONFLICT DO NOTHING"#,
+        )
+        .bind(txid)
+        .bind(output_index)
+        .bind(amount)
+        .bind(script_pubkey)
+        .bind(output_type)
+        .bind(magic_bytes)
+        .bind(op_return_version)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_tx_prevouts<'e, E>(
+        executor: &'e mut E,
+        prevouts: &[model::TxPrevout],
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        if prevouts.is_empty() {
+            return Ok(());
+        }
+
+        let mut txid = Vec::with_capacity(prevouts.len());
+        let mut prevout_txid = Vec::with_capacity(prevouts.len());
+        let mut prevout_output_index = Vec::with_capacity(prevouts.len());
+        let mut amount = Vec::with_capacity(prevouts.len());
+        let mut script_pubkey = Vec::with_capacity(prevouts.len());
+        let mut prevout_type = Vec::with_capacity(prevouts.len());
+
+        for prevout in prevouts {
+            txid.push(prevout.txid);
+            prevout_txid.push(prevout.prevout_txid);
+            prevout_output_index.push(
+                i32::try_from(prevout.prevout_output_index)
+                    .map_err(Error::ConversionDatabaseInt)?,
+            );
+            amount.push(i64::try_from(prevout.amount).map_err(Error::ConversionDatabaseInt)?);
+            script_pubkey.push(prevout.script_pubkey.clone());
+            prevout_type.push(prevout.prevout_type);
+        }
+
+        sqlx::query(
+            r#"
+            WITH tx_ids             AS (SELECT ROW_NUMBER() OVER (), txid FROM UNNEST($1::BYTEA[]) AS txid)
+            , prevout_txid          AS (SELECT ROW_NUMBER() OVER (), prevout_txid FROM UNNEST($2::BYTEA[]) AS prevout_txid)
+            , prevout_output_index  AS (SELECT ROW_NUMBER() OVER (), prevout_output_index FROM UNNEST($3::INTEGER[]) AS prevout_output_index)
+            , amount                AS (SELECT ROW_NUMBER() OVER (), amount FROM UNNEST($4::BIGINT[]) AS amount)
+            , script_pubkey         AS (SELECT ROW_NUMBER() OVER (), script_pubkey FROM UNNEST($5::BYTEA[]) AS script_pubkey)
+            , prevout_type          AS (SELECT ROW_NUMBER() OVER (), prevout_type FROM UNNEST($6::sbtc_signer.prevout_type[]) AS prevout_type)
+            INSERT INTO sbtc_signer.bitcoin_tx_inputs (
+                txid
+              , prevout_txid
+              , prevout_output_index
+              , amount
+              , script_pubkey
+              , prevout_type
+            )
+            SELECT
+                txid
+              , prevout_txid
+              , prevout_output_index
+              , amount
+              , script_pubkey
+              , prevout_type
+            FROM tx_ids
+            JOIN prevout_txid USING (row_number)
+            JOIN prevout_output_index USING (row_number)
+            JOIN amount USING (row_number)
+            JOIN script_pubkey USING (row_number)
+            JOIN prevout_type USING (row_number)
+            ON CONFLICT DO NOTHING"#,
+        )
+        .bind(txid)
+        .bind(prevout_txid)
+        .bind(prevout_output_index)
+        .bind(amount)
+        .bind(script_pubkey)
+        .bind(prevout_type)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_anomalous_spend<'e, E>(
+        executor: &'e mut E,
+        spend: &model::AnomalousSpend,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO anomalous_spends (
+                txid
+              , block_hash
+              , reason
+            )
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING;
+            "#,
+        )
+        .bind(spend.txid)
+        .bind(spend.block_hash)
+        .bind(spend.reason)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_effective_limits<'e, E>(
+        executor: &'e mut E,
+        chain_tip: &model::BitcoinBlockHash,
+        limits: &SbtcLimits,
+    ) -> Result<(), Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let limits = serde_json::to_string(limits).map_err(Error::JsonSerialize)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.effective_sbtc_limits (
+                block_hash
+              , limits
+            )
+            VALUES ($1, $2::JSONB)
+            ON CONFLICT (block_hash) DO UPDATE
+            SET limits = EXCLUDED.limits;
+            "#,
+        )
+        .bind(chain_tip)
+        .bind(limits)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
     async fn write_bitcoin_txs_sighashes<'e, E>(
         executor: &'e mut E,
         sighashes: &[model::BitcoinTxSigHash],
@@ -966,6 +1208,53 @@ impl PgWrite {
         .map(|res| res.rows_affected() > 0)
         .map_err(Error::SqlxQuery)
     }
+
+    async fn prune_failed_dkg_shares<'e, E>(
+        executor: &'e mut E,
+        started_before: model::BitcoinBlockHeight,
+    ) -> Result<u64, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let started_before = i64::try_from(started_before).map_err(Error::ConversionDatabaseInt)?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM sbtc_signer.dkg_shares
+            WHERE dkg_shares_status = 'failed'
+              AND started_at_bitcoin_block_height < $1;
+            "#,
+        )
+        .bind(started_before)
+        .execute(executor)
+        .await
+        .map(|res| res.rows_affected())
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn prune_effective_limits<'e, E>(
+        executor: &'e mut E,
+        applied_before: model::BitcoinBlockHeight,
+    ) -> Result<u64, Error>
+    where
+        &'e mut E: sqlx::PgExecutor<'e>,
+    {
+        let applied_before = i64::try_from(applied_before).map_err(Error::ConversionDatabaseInt)?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM sbtc_signer.effective_sbtc_limits AS esl
+            USING sbtc_signer.bitcoin_blocks AS bb
+            WHERE bb.block_hash = esl.block_hash
+              AND bb.block_height < $1;
+            "#,
+        )
+        .bind(applied_before)
+        .execute(executor)
+        .await
+        .map(|res| res.rows_affected())
+        .map_err(Error::SqlxQuery)
+    }
 }
 
 impl DbWrite for PgStore {
@@ -1082,6 +1371,27 @@ impl DbWrite for PgStore {
         PgWrite::write_tx_prevout(self.get_connection().await?.as_mut(), prevout).await
     }
 
+    async fn write_tx_outputs(&self, outputs: &[model::TxOutput]) -> Result<(), Error> {
+        PgWrite::write_tx_outputs(self.get_connection().await?.as_mut(), outputs).await
+    }
+
+    async fn write_tx_prevouts(&self, prevouts: &[model::TxPrevout]) -> Result<(), Error> {
+        PgWrite::write_tx_prevouts(self.get_connection().await?.as_mut(), prevouts).await
+    }
+
+    async fn write_anomalous_spend(&self, spend: &model::AnomalousSpend) -> Result<(), Error> {
+        PgWrite::write_anomalous_spend(self.get_connection().await?.as_mut(), spend).await
+    }
+
+    async fn write_effective_limits(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        limits: &SbtcLimits,
+    ) -> Result<(), Error> {
+        PgWrite::write_effective_limits(self.get_connection().await?.as_mut(), chain_tip, limits)
+            .await
+    }
+
     async fn write_bitcoin_txs_sighashes(
         &self,
         sighashes: &[model::BitcoinTxSigHash],
@@ -1113,6 +1423,21 @@ impl DbWrite for PgStore {
     {
         PgWrite::verify_dkg_shares(self.get_connection().await?.as_mut(), aggregate_key).await
     }
+
+    async fn prune_failed_dkg_shares(
+        &self,
+        started_before: model::BitcoinBlockHeight,
+    ) -> Result<u64, Error> {
+        PgWrite::prune_failed_dkg_shares(self.get_connection().await?.as_mut(), started_before)
+            .await
+    }
+
+    async fn prune_effective_limits(
+        &self,
+        applied_before: model::BitcoinBlockHeight,
+    ) -> Result<u64, Error> {
+        PgWrite::prune_effective_limits(self.get_connection().await?.as_mut(), applied_before).await
+    }
 }
 
 impl DbWrite for PgTransaction<'_> {
@@ -1245,6 +1570,30 @@ impl DbWrite for PgTransaction<'_> {
         PgWrite::write_tx_prevout(tx.as_mut(), prevout).await
     }
 
+    async fn write_tx_outputs(&self, outputs: &[model::TxOutput]) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_tx_outputs(tx.as_mut(), outputs).await
+    }
+
+    async fn write_tx_prevouts(&self, prevouts: &[model::TxPrevout]) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_tx_prevouts(tx.as_mut(), prevouts).await
+    }
+
+    async fn write_anomalous_spend(&self, spend: &model::AnomalousSpend) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_anomalous_spend(tx.as_mut(), spend).await
+    }
+
+    async fn write_effective_limits(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        limits: &SbtcLimits,
+    ) -> Result<(), Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::write_effective_limits(tx.as_mut(), chain_tip, limits).await
+    }
+
     async fn write_bitcoin_txs_sighashes(
         &self,
         sighashes: &[model::BitcoinTxSigHash],
@@ -1276,4 +1625,20 @@ impl DbWrite for PgTransaction<'_> {
         let mut tx = self.tx.lock().await;
         PgWrite::verify_dkg_shares(tx.as_mut(), aggregate_key).await
     }
+
+    async fn prune_failed_dkg_shares(
+        &self,
+        started_before: model::BitcoinBlockHeight,
+    ) -> Result<u64, Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::prune_failed_dkg_shares(tx.as_mut(), started_before).await
+    }
+
+    async fn prune_effective_limits(
+        &self,
+        applied_before: model::BitcoinBlockHeight,
+    ) -> Result<u64, Error> {
+        let mut tx = self.tx.lock().await;
+        PgWrite::prune_effective_limits(tx.as_mut(), applied_before).await
+    }
 }
@@ -24,6 +24,7 @@ use clarity::types::chainstate::BurnchainHeaderHash;
 use clarity::types::chainstate::SortitionId;
 use clarity::vm::costs::ExecutionCost;
 use emily_client::models::DepositStatus;
+use emily_client::models::WithdrawalStatus;
 use rand::seq::IteratorRandom;
 use sbtc::deposits::CreateDepositRequest;
 
@@ -72,6 +73,15 @@ impl TestHarness {
         &self.bitcoin_blocks
     }
 
+    /// Keep only the bitcoin blocks for which the given predicate returns
+    /// true.
+    pub fn retain_bitcoin_blocks<F>(&mut self, f: F)
+    where
+        F: FnMut(&BitcoinBlockInfo) -> bool,
+    {
+        self.bitcoin_blocks.retain(f);
+    }
+
     /// The minimum block height amount blocks in this blockchain
     pub fn min_block_height(&self) -> Option<BitcoinBlockHeight> {
         self.bitcoin_blocks.iter().map(|block| block.height).min()
@@ -498,6 +508,13 @@ impl EmilyInteract for TestHarness {
         }
     }
 
+    async fn get_withdrawals_with_status(
+        &self,
+        _status: WithdrawalStatus,
+    ) -> Result<Vec<emily_client::models::WithdrawalInfo>, Error> {
+        Ok(Vec::new())
+    }
+
     async fn update_deposits(
         &self,
         _update_deposits: Vec<emily_client::models::DepositUpdate>,
@@ -15,6 +15,7 @@ use blockstack_lib::{
 };
 use clarity::types::chainstate::{StacksAddress, StacksBlockId};
 use emily_client::models::DepositStatus;
+use emily_client::models::WithdrawalStatus;
 use tokio::sync::{Mutex, broadcast};
 use tokio::time::error::Elapsed;
 
@@ -294,6 +295,10 @@ where
         self.inner.get_bitcoin_client()
     }
 
+    fn get_backfill_bitcoin_client(&self) -> impl BitcoinInteract + Clone + 'static {
+        self.inner.get_backfill_bitcoin_client()
+    }
+
     fn get_stacks_client(&self) -> impl StacksInteract + Clone + 'static {
         self.inner.get_stacks_client()
     }
@@ -569,6 +574,17 @@ impl EmilyInteract for WrappedMockEmilyInteract {
             .await
     }
 
+    async fn get_withdrawals_with_status(
+        &self,
+        status: WithdrawalStatus,
+    ) -> Result<Vec<emily_client::models::WithdrawalInfo>, Error> {
+        self.inner
+            .lock()
+            .await
+            .get_withdrawals_with_status(status)
+            .await
+    }
+
     async fn update_deposits(
         &self,
         update_deposits: Vec<emily_client::models::DepositUpdate>,
@@ -540,6 +540,41 @@ impl fake::Dummy<SignerSetConfig> for EncryptedDkgShares {
     }
 }
 
+/// Randomly generated DKG shares that pass
+/// [`EncryptedDkgShares::validate`], so that they can be written to the
+/// database.
+impl fake::Dummy<fake::Faker> for EncryptedDkgShares {
+    fn dummy_with_rng<R: Rng + ?Sized>(config: &fake::Faker, rng: &mut R) -> Self {
+        let aggregate_key: PublicKey = config.fake_with_rng(rng);
+        aggregate_key.fake_with_rng(rng)
+    }
+}
+
+/// Randomly generated DKG shares for the given aggregate key that pass
+/// [`EncryptedDkgShares::validate`].
+impl fake::Dummy<PublicKey> for EncryptedDkgShares {
+    fn dummy_with_rng<R: Rng + ?Sized>(aggregate_key: &PublicKey, rng: &mut R) -> Self {
+        let num_keys = rng.gen_range(1..=7);
+        let signer_set_public_keys: Vec<PublicKey> =
+            std::iter::repeat_with(|| Faker.fake_with_rng(rng))
+                .take(num_keys)
+                .collect();
+
+        EncryptedDkgShares {
+            aggregate_key: *aggregate_key,
+            tweaked_aggregate_key: aggregate_key.signers_tweaked_pubkey().unwrap(),
+            script_pubkey: aggregate_key.signers_script_pubkey().into(),
+            encrypted_private_shares: Faker.fake_with_rng(rng),
+            public_shares: Faker.fake_with_rng(rng),
+            signer_set_public_keys,
+            signature_share_threshold: rng.gen_range(1..=num_keys as u16),
+            dkg_shares_status: Faker.fake_with_rng(rng),
+            started_at_bitcoin_block_hash: Faker.fake_with_rng(rng),
+            started_at_bitcoin_block_height: Faker.fake_with_rng::<u32, _>(rng).into(),
+        }
+    }
+}
+
 impl fake::Dummy<&[PublicKey]> for SignerBtcState {
     fn dummy_with_rng<R: Rng + ?Sized>(signer_set_public_keys: &&[PublicKey], rng: &mut R) -> Self {
         let aggregate_key = PublicKey::combine_keys(*signer_set_public_keys).unwrap();
@@ -549,6 +584,8 @@ impl fake::Dummy<&[PublicKey]> for SignerBtcState {
             fee_rate: Faker.fake_with_rng(rng),
             last_fees: Faker.fake_with_rng(rng),
             magic_bytes: [1, 2],
+            rbf_target_fee_rate: None,
+            anchor_output: false,
             public_key: aggregate_key_x_only,
             utxo: SignerUtxo {
                 amount: Faker.fake_with_rng(rng),
@@ -770,6 +807,8 @@ impl fake::Dummy<fake::Faker> for BitcoinPreSignRequest {
             request_package: fake::vec![TxRequestIds; 0..20],
             fee_rate: config.fake_with_rng(rng),
             last_fees: config.fake_with_rng(rng),
+            shuffle_seed: config.fake_with_rng(rng),
+            rbf_target_fee_rate: config.fake_with_rng(rng),
         }
     }
 }
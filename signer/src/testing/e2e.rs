@@ -0,0 +1,234 @@
+//! Helpers for driving the signers' bitcoin flows end-to-end on regtest.
+
+use std::collections::HashSet;
+
+use bitcoin::AddressType;
+use bitcoin::Amount;
+use bitcoin::OutPoint;
+use bitcoin::ScriptBuf;
+use bitcoin::Sequence;
+use bitcoin::Transaction;
+use bitcoin::TxIn;
+use bitcoin::TxOut;
+use bitcoin::Txid;
+use bitcoin::Witness;
+use bitcoin::absolute::LockTime;
+use bitcoin::transaction::Version;
+use bitcoincore_rpc::RpcApi as _;
+use bitvec::array::BitArray;
+use clarity::vm::types::PrincipalData;
+use sbtc::deposits::CreateDepositRequest;
+use sbtc::deposits::DepositScriptInputs;
+use sbtc::deposits::ReclaimScriptInputs;
+use sbtc::testing::regtest;
+use sbtc::testing::regtest::AsUtxo as _;
+use sbtc::testing::regtest::Faucet;
+use sbtc::testing::regtest::Recipient;
+use stacks_common::types::chainstate::StacksAddress;
+
+use crate::DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX;
+use crate::bitcoin::BitcoinInteract as _;
+use crate::bitcoin::utxo::DepositRequest;
+use crate::bitcoin::utxo::SbtcRequests;
+use crate::bitcoin::utxo::SignerBtcState;
+use crate::bitcoin::utxo::SignerUtxo;
+use crate::bitcoin::utxo::WithdrawalsToSignersPolicy;
+use crate::block_observer::BlockObserver;
+use crate::block_observer::extract_sbtc_transactions;
+use crate::config::NetworkKind;
+use crate::context::Context;
+use crate::context::SbtcLimits;
+use crate::storage::DbWrite as _;
+use crate::storage::model;
+use crate::storage::model::TaprootScriptHash;
+
+/// The amount, in sats, that the signers' address is funded with before
+/// the deposit is swept.
+const SIGNER_FUNDING_AMOUNT: u64 = 100_000_000;
+
+/// The outcome of a call to [`run_deposit_sweep_cycle`].
+pub struct CycleResult {
+    /// The outpoint of the deposit that was made and then swept.
+    pub deposit_outpoint: OutPoint,
+    /// The ID of the sweep transaction that spent the deposit.
+    pub sweep_txid: Txid,
+    /// The signers' UTXO created by the sweep transaction.
+    pub signer_utxo: SignerUtxo,
+    /// The signer that controls the signers' UTXO.
+    pub signer: Recipient,
+}
+
+/// Make a deposit on regtest and sweep it into a new signers' UTXO.
+///
+/// This function goes through the same steps that the signers go through
+/// for a deposit:
+/// 1. A depositor submits a deposit transaction that locks `amount` sats
+///    to a fresh signer key, and the transaction gets confirmed.
+/// 2. The deposit request is handed to the block observer, the way
+///    requests from Emily are, which validates it and stores it in the
+///    database.
+/// 3. A sweep transaction for the deposit is constructed, signed,
+///    broadcast and confirmed.
+/// 4. The block with the sweep transaction is written to the database
+///    along with the sweep transaction itself.
+///
+/// # Notes
+///
+/// The deposit request is given to the block observer directly, so the
+/// Emily client of the given context is not used. If the sBTC start
+/// height has not been set on the context, then it is set to the current
+/// bitcoin chain tip height so that stacks-core is never contacted.
+pub async fn run_deposit_sweep_cycle<C>(ctx: &C, faucet: &Faucet, amount: u64) -> CycleResult
+where
+    C: Context,
+{
+    let rpc = faucet.rpc;
+    let fee = regtest::BITCOIN_CORE_FALLBACK_FEE.to_sat();
+
+    if !ctx.state().is_sbtc_bitcoin_start_height_set() {
+        let start_height = rpc.get_block_count().unwrap();
+        ctx.state()
+            .set_sbtc_bitcoin_start_height(start_height.into());
+    }
+
+    let signer = Recipient::new(AddressType::P2tr);
+    let depositor = Recipient::new(AddressType::P2tr);
+    let signers_public_key = signer.keypair.x_only_public_key().0;
+
+    faucet.send_to(SIGNER_FUNDING_AMOUNT, &signer.address);
+    faucet.send_to(amount + 2 * fee, &depositor.address);
+    faucet.generate_block();
+
+    // Make the deposit transaction and get it confirmed.
+    let depositor_utxo = depositor.get_utxos(rpc, None).pop().unwrap();
+    let deposit_inputs = DepositScriptInputs {
+        signers_public_key,
+        max_fee: amount / 2,
+        recipient: PrincipalData::from(StacksAddress::burn_address(false)),
+    };
+    let reclaim_inputs = ReclaimScriptInputs::try_new(50, ScriptBuf::new()).unwrap();
+
+    let deposit_script = deposit_inputs.deposit_script();
+    let reclaim_script = reclaim_inputs.reclaim_script();
+
+    let mut deposit_tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(depositor_utxo.txid(), depositor_utxo.vout()),
+            sequence: Sequence::ZERO,
+            script_sig: ScriptBuf::new(),
+            witness: Witness::new(),
+        }],
+        output: vec![
+            TxOut {
+                value: Amount::from_sat(amount),
+                script_pubkey: sbtc::deposits::to_script_pubkey(
+                    deposit_script.clone(),
+                    reclaim_script.clone(),
+                ),
+            },
+            TxOut {
+                value: depositor_utxo.amount() - Amount::from_sat(amount + fee),
+                script_pubkey: depositor.address.script_pubkey(),
+            },
+        ],
+    };
+    regtest::p2tr_sign_transaction(&mut deposit_tx, 0, &[depositor_utxo], &depositor.keypair);
+
+    rpc.send_raw_transaction(&deposit_tx).unwrap();
+    faucet.generate_block();
+
+    let create_request = CreateDepositRequest {
+        outpoint: OutPoint::new(deposit_tx.compute_txid(), 0),
+        deposit_script,
+        reclaim_script,
+    };
+
+    // Have the block observer validate and store the deposit request.
+    let block_observer = BlockObserver {
+        context: ctx.clone(),
+        bitcoin_blocks: (),
+    };
+    block_observer
+        .load_requests(std::slice::from_ref(&create_request))
+        .await
+        .unwrap();
+
+    // Now construct the sweep transaction for the deposit.
+    let deposit = create_request.validate_tx(&deposit_tx, false).unwrap();
+    let deposit_outpoint = deposit.outpoint;
+    let signer_utxo = signer.get_utxos(rpc, None).pop().unwrap();
+
+    let requests = SbtcRequests {
+        deposits: vec![DepositRequest {
+            outpoint: deposit.outpoint,
+            max_fee: deposit.max_fee,
+            signer_bitmap: BitArray::ZERO,
+            amount: deposit.amount,
+            deposit_script: deposit.deposit_script.clone(),
+            reclaim_script: deposit.reclaim_script.clone(),
+            reclaim_script_hash: Some(TaprootScriptHash::from(&deposit.reclaim_script)),
+            signers_public_key: deposit.signers_public_key,
+        }],
+        withdrawals: Vec::new(),
+        signer_state: SignerBtcState {
+            utxo: SignerUtxo {
+                outpoint: OutPoint::new(signer_utxo.txid, signer_utxo.vout),
+                amount: signer_utxo.amount.to_sat(),
+                public_key: signers_public_key,
+            },
+            fee_rate: 10.0,
+            public_key: signers_public_key,
+            last_fees: None,
+            magic_bytes: NetworkKind::Regtest.magic_bytes(),
+            rbf_target_fee_rate: None,
+            anchor_output: false,
+        },
+        accept_threshold: 0,
+        num_signers: 1,
+        sbtc_limits: SbtcLimits::unlimited(),
+        max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+        min_batch: None,
+        withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+        min_signer_output: None,
+        shuffle_seed: None,
+    };
+
+    let mut unsigned = requests.construct_transactions().unwrap().pop().unwrap();
+    super::set_witness_data(&mut unsigned, signer.keypair);
+
+    let sweep_txid = rpc.send_raw_transaction(&unsigned.tx).unwrap();
+    let sweep_block_hash = faucet.generate_block();
+
+    // Record the sweep the same way the block observer would, except that
+    // the signers' scriptPubKey is not known to it ahead of time.
+    let block = ctx
+        .get_bitcoin_client()
+        .get_block(&sweep_block_hash)
+        .await
+        .unwrap()
+        .unwrap();
+    let db = ctx.get_storage_mut();
+    db.write_bitcoin_block(&model::BitcoinBlock::from(&block))
+        .await
+        .unwrap();
+
+    let script_pubkeys = HashSet::from([signer.script_pubkey.clone()]);
+    extract_sbtc_transactions(
+        &db,
+        &script_pubkeys,
+        NetworkKind::Regtest.magic_bytes(),
+        sweep_block_hash,
+        &block.transactions,
+    )
+    .await
+    .unwrap();
+
+    CycleResult {
+        deposit_outpoint,
+        sweep_txid,
+        signer_utxo: unsigned.new_signer_utxo(),
+        signer,
+    }
+}
@@ -8,6 +8,7 @@ pub mod blocks;
 pub mod btc;
 pub mod context;
 pub mod dummy;
+pub mod e2e;
 pub mod message;
 pub mod network;
 pub mod request_decider;
@@ -137,6 +138,93 @@ pub fn set_witness_data(unsigned: &mut UnsignedTransaction, keypair: secp256k1::
         });
 }
 
+/// Set the witness data of the transaction as if only `num_signed` of
+/// `num_shares` signers took part in the signing round.
+///
+/// The secret key of `keypair` is split into `num_shares` additive shares
+/// and every input is signed with the sum of the first `num_signed` of
+/// them. The shares are additive rather than threshold shares, so unless
+/// all `num_shares` take part the resulting signatures do not verify
+/// against the aggregate key, whatever `num_signed` is.
+///
+/// # Panics
+///
+/// Panics if `num_signed` is zero or greater than `num_shares`.
+pub fn set_partial_witness_data(
+    unsigned: &mut UnsignedTransaction,
+    keypair: secp256k1::Keypair,
+    num_shares: usize,
+    num_signed: usize,
+) {
+    assert!(0 < num_signed && num_signed <= num_shares);
+
+    let mut shares: Vec<secp256k1::SecretKey> = (1..num_shares)
+        .map(|_| secp256k1::SecretKey::new(&mut OsRng))
+        .collect();
+    let last_share = shares.iter().fold(keypair.secret_key(), |acc, share| {
+        acc.add_tweak(&share.negate().into()).unwrap()
+    });
+    shares.push(last_share);
+
+    let partial_secret = shares[1..num_signed].iter().fold(shares[0], |acc, share| {
+        acc.add_tweak(&(*share).into()).unwrap()
+    });
+    let partial_keypair = secp256k1::Keypair::from_secret_key(SECP256K1, &partial_secret);
+
+    set_witness_data(unsigned, partial_keypair);
+}
+
+/// Set the witness data of the transaction, signing each input with the
+/// keypair from `keypairs` that corresponds to the public key locking the
+/// input.
+///
+/// # Panics
+///
+/// Panics if none of the keypairs correspond to the public key locking one
+/// of the inputs.
+pub fn set_witness_data_with_keypairs(
+    unsigned: &mut UnsignedTransaction,
+    keypairs: &[secp256k1::Keypair],
+) {
+    let sighash_type = TapSighashType::All;
+    let sighashes = unsigned.construct_digests().unwrap();
+
+    let keypair_for = |public_key: &secp256k1::XOnlyPublicKey| {
+        keypairs
+            .iter()
+            .find(|keypair| keypair.x_only_public_key().0 == *public_key)
+            .expect("no keypair for the public key locking an input")
+    };
+
+    let signer_msg = secp256k1::Message::from(sighashes.signers);
+    let keypair = keypair_for(&sighashes.signers_aggregate_key);
+    let tweaked = keypair.tap_tweak(SECP256K1, None);
+    let signature = SECP256K1.sign_schnorr(&signer_msg, &tweaked.to_inner());
+    let signature = bitcoin::taproot::Signature { signature, sighash_type };
+    let signer_witness = Witness::p2tr_key_spend(&signature);
+
+    let deposit_witness = sighashes.deposits.into_iter().map(|(deposit, sighash)| {
+        let deposit_msg = secp256k1::Message::from(sighash);
+        let keypair = keypair_for(&deposit.signers_public_key);
+        let signature = SECP256K1.sign_schnorr(&deposit_msg, keypair);
+        let signature = bitcoin::taproot::Signature { signature, sighash_type };
+        deposit.construct_witness_data(signature)
+    });
+
+    let witness_data: Vec<Witness> = std::iter::once(signer_witness)
+        .chain(deposit_witness)
+        .collect();
+
+    unsigned
+        .tx
+        .input
+        .iter_mut()
+        .zip(witness_data)
+        .for_each(|(tx_in, witness)| {
+            tx_in.witness = witness;
+        });
+}
+
 /// Testing helpers for [`Vec`].
 pub trait IterTestExt<T>
 where
@@ -3,14 +3,22 @@
 use std::collections::HashSet;
 
 use bitcoin::ScriptBuf;
+use bitcoin::script::PushBytesBuf;
 use fake::Dummy as _;
 use fake::Fake;
+use sbtc::idpack::BitmapSegmenter;
+use sbtc::idpack::Encodable as _;
+use sbtc::idpack::Segmenter as _;
 
 use crate::bitcoin::utxo::BitcoinInputsOutputs;
+use crate::bitcoin::utxo::OP_RETURN_VERSION;
 use crate::bitcoin::utxo::PrevoutRef;
 use crate::bitcoin::utxo::TxDeconstructor;
+use crate::config::NetworkKind;
 use crate::keys::PublicKey;
+use crate::keys::SignerScriptPubKey as _;
 use crate::storage::DbWrite;
+use crate::storage::TransactionHandle;
 use crate::storage::model;
 use crate::storage::model::BitcoinBlock;
 use crate::storage::model::BitcoinBlockHash;
@@ -19,6 +27,10 @@ use crate::storage::model::StacksBlockHeight;
 
 use rand::seq::SliceRandom;
 
+/// The fee, in sats, paid by each sweep transaction generated by
+/// [`TestData::generate_sweep_chain`].
+pub const SIMULATED_SWEEP_FEE: u64 = 1_000;
+
 /// A slimmed down [`BitcoinTxInfo`] type that can be used to implement the
 /// [`TxDeconstructor`] trait.
 ///
@@ -53,6 +65,12 @@ impl TestBitcoinTxInfo {
             script_sig: bitcoin::ScriptBuf::new(),
         }
     }
+
+    /// Return a builder for a [`TestBitcoinTxInfo`] that follows the
+    /// layout of a sweep transaction created by the signers.
+    pub fn sweep_builder() -> TestSweepBuilder {
+        TestSweepBuilder::default()
+    }
 }
 
 impl BitcoinInputsOutputs for TestBitcoinTxInfo {
@@ -74,6 +92,132 @@ impl TxDeconstructor for TestBitcoinTxInfo {
     }
 }
 
+/// A builder for a [`TestBitcoinTxInfo`] that follows the layout of an
+/// sBTC sweep transaction.
+///
+/// The built transaction spends the signers' UTXO as its first input
+/// followed by any deposit inputs. Its outputs are the new signers' UTXO,
+/// an OP_RETURN output, and then any withdrawal outputs. The withdrawal
+/// outputs are assigned the request IDs 0, 1, 2, ... in the order that
+/// they were added, and these IDs are encoded in the OP_RETURN output.
+#[derive(Debug, Default)]
+pub struct TestSweepBuilder {
+    /// The signers' input and the output that it spends.
+    signer_input: Option<(bitcoin::TxIn, bitcoin::TxOut)>,
+    /// The deposit inputs and the outputs that they spend.
+    deposit_inputs: Vec<(bitcoin::TxIn, bitcoin::TxOut)>,
+    /// The withdrawal outputs.
+    withdrawal_outputs: Vec<bitcoin::TxOut>,
+    /// The fee paid by the transaction.
+    fee: bitcoin::Amount,
+    /// The magic bytes in the OP_RETURN output. Defaults to the regtest
+    /// magic bytes.
+    magic_bytes: Option<[u8; 2]>,
+}
+
+impl TestSweepBuilder {
+    /// Set the input spending the signers' UTXO. The new signers' UTXO is
+    /// locked by the same scriptPubKey as the `prevout` being spent.
+    pub fn signer_input(mut self, input: bitcoin::TxIn, prevout: bitcoin::TxOut) -> Self {
+        self.signer_input = Some((input, prevout));
+        self
+    }
+
+    /// Add an input sweeping in a deposit.
+    pub fn deposit_input(mut self, input: bitcoin::TxIn, prevout: bitcoin::TxOut) -> Self {
+        self.deposit_inputs.push((input, prevout));
+        self
+    }
+
+    /// Add an output fulfilling a withdrawal to the given recipient.
+    pub fn withdrawal_output(mut self, recipient: ScriptBuf, amount: bitcoin::Amount) -> Self {
+        self.withdrawal_outputs.push(bitcoin::TxOut {
+            value: amount,
+            script_pubkey: recipient,
+        });
+        self
+    }
+
+    /// Set the fee paid by the transaction, which is deducted from the
+    /// signers' output. Defaults to zero.
+    pub fn fee(mut self, fee: bitcoin::Amount) -> Self {
+        self.fee = fee;
+        self
+    }
+
+    /// Set the magic bytes at the start of the OP_RETURN data.
+    pub fn magic_bytes(mut self, magic_bytes: [u8; 2]) -> Self {
+        self.magic_bytes = Some(magic_bytes);
+        self
+    }
+
+    /// Build the sweep transaction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signers' input was not set or if the inputs do not
+    /// cover the withdrawal outputs and the fee.
+    pub fn build(self) -> TestBitcoinTxInfo {
+        let (signer_input, signer_prevout) = self
+            .signer_input
+            .expect("a sweep transaction must spend the signers' UTXO");
+
+        let total_in: bitcoin::Amount = std::iter::once(&signer_prevout)
+            .chain(self.deposit_inputs.iter().map(|(_, prevout)| prevout))
+            .map(|prevout| prevout.value)
+            .sum();
+        let total_withdrawn: bitcoin::Amount =
+            self.withdrawal_outputs.iter().map(|out| out.value).sum();
+        let signers_amount = total_in
+            .checked_sub(total_withdrawn)
+            .and_then(|amount| amount.checked_sub(self.fee))
+            .expect("the inputs must cover the withdrawals and the fee");
+
+        let signers_output = bitcoin::TxOut {
+            value: signers_amount,
+            script_pubkey: signer_prevout.script_pubkey.clone(),
+        };
+
+        let mut data = PushBytesBuf::new();
+        let magic_bytes = self
+            .magic_bytes
+            .unwrap_or(NetworkKind::Regtest.magic_bytes());
+        data.extend_from_slice(&magic_bytes).unwrap();
+        data.push(OP_RETURN_VERSION).unwrap();
+        if !self.withdrawal_outputs.is_empty() {
+            let withdrawal_ids: Vec<u64> = (0..self.withdrawal_outputs.len() as u64).collect();
+            let encoded = BitmapSegmenter.package(&withdrawal_ids).unwrap().encode();
+            data.extend_from_slice(&encoded).unwrap();
+        }
+        let op_return_output = bitcoin::TxOut {
+            value: bitcoin::Amount::ZERO,
+            script_pubkey: ScriptBuf::new_op_return(data),
+        };
+
+        let (deposit_inputs, deposit_prevouts): (Vec<_>, Vec<_>) =
+            self.deposit_inputs.into_iter().unzip();
+
+        let tx = bitcoin::Transaction {
+            version: bitcoin::transaction::Version::TWO,
+            lock_time: bitcoin::absolute::LockTime::ZERO,
+            input: std::iter::once(signer_input)
+                .chain(deposit_inputs)
+                .collect(),
+            output: [signers_output, op_return_output]
+                .into_iter()
+                .chain(self.withdrawal_outputs)
+                .collect(),
+        };
+
+        TestBitcoinTxInfo {
+            tx,
+            prevouts: std::iter::once(signer_prevout)
+                .chain(deposit_prevouts)
+                .collect(),
+        }
+    }
+}
+
 /// Collection of related data usable for database tests.
 ///
 /// The primary use case of this type is to load a database
@@ -120,10 +264,23 @@ impl TestData {
     {
         let mut test_data = Self::new();
         let mut parent: Option<BitcoinBlockRef> = None;
-        for _ in 0..params.num_bitcoin_blocks {
+        for index in 0..params.num_bitcoin_blocks {
             let (next_chunk, block_ref) =
                 test_data.new_block(rng, signer_keys, params, parent.as_ref());
             test_data.push(next_chunk);
+
+            let num_forks: usize = params
+                .fork_points
+                .iter()
+                .filter(|(fork_index, _)| *fork_index == index)
+                .map(|(_, num_children)| num_children)
+                .sum();
+            for _ in 0..num_forks {
+                let (fork_chunk, _) =
+                    test_data.new_block(rng, signer_keys, params, Some(&block_ref));
+                test_data.push(fork_chunk);
+            }
+
             if params.consecutive_blocks {
                 parent = Some(block_ref);
             }
@@ -132,6 +289,62 @@ impl TestData {
         test_data
     }
 
+    /// Generate a chain of `num_sweeps` sweep transactions, each confirmed
+    /// in its own bitcoin block that builds on the block of the previous
+    /// sweep.
+    ///
+    /// Each sweep spends the signers' output of the previous sweep and
+    /// creates a new signers' output locked by the `aggregate_key`, for
+    /// the amount of the previous output less a simulated fee. The first
+    /// sweep spends a random outpoint.
+    pub fn generate_sweep_chain<R>(
+        rng: &mut R,
+        aggregate_key: &PublicKey,
+        num_sweeps: usize,
+    ) -> Self
+    where
+        R: rand::RngCore,
+    {
+        let signers_script_pubkey = aggregate_key.signers_script_pubkey();
+        let signer_script_pubkeys = HashSet::from([signers_script_pubkey.clone()]);
+
+        let mut test_data = Self::new();
+        let mut parent: Option<BitcoinBlockRef> = None;
+        let mut signer_input = TestBitcoinTxInfo::random_prevout(rng);
+        let mut signer_prevout = bitcoin::TxOut {
+            value: bitcoin::Amount::from_sat(500_000_000),
+            script_pubkey: signers_script_pubkey,
+        };
+
+        for _ in 0..num_sweeps {
+            let block = test_data.generate_bitcoin_block(rng, parent.as_ref());
+            let block_ref = BitcoinBlockRef::summarize(&block);
+            test_data.push(Self {
+                bitcoin_blocks: vec![block],
+                ..Self::default()
+            });
+
+            let tx_info = TestBitcoinTxInfo::sweep_builder()
+                .signer_input(signer_input, signer_prevout)
+                .fee(bitcoin::Amount::from_sat(SIMULATED_SWEEP_FEE))
+                .build();
+
+            // The next sweep spends the signers' output of this one.
+            signer_input = bitcoin::TxIn {
+                previous_output: bitcoin::OutPoint::new(tx_info.tx.compute_txid(), 0),
+                sequence: bitcoin::Sequence::ZERO,
+                witness: bitcoin::Witness::new(),
+                script_sig: ScriptBuf::new(),
+            };
+            signer_prevout = tx_info.tx.output[0].clone();
+
+            test_data.push_bitcoin_txs(&block_ref, vec![tx_info], &signer_script_pubkeys);
+            parent = Some(block_ref);
+        }
+
+        test_data
+    }
+
     /// Generate a new bitcoin block with associated data on top of
     /// the current model.
     pub fn new_block<R>(
@@ -309,6 +522,17 @@ impl TestData {
         }
     }
 
+    /// Write the test data within the given database transaction.
+    ///
+    /// This is useful for tests that want their fixture discarded by
+    /// rolling back the transaction rather than by dropping the database.
+    pub async fn write_to_tx<Tx>(&self, tx: &mut Tx)
+    where
+        Tx: TransactionHandle,
+    {
+        self.write_to(&*tx).await
+    }
+
     fn generate_bitcoin_block(
         &self,
         rng: &mut impl rand::RngCore,
@@ -522,6 +746,11 @@ pub struct Params {
     pub num_signers_per_request: usize,
     /// Wheter to generate consecutive blocks or not
     pub consecutive_blocks: bool,
+    /// Points at which to fork the bitcoin blockchain. Each `(N, M)` entry
+    /// adds `M` extra children to the `N`-th generated bitcoin block, each
+    /// competing with the block that is generated after it. These extra
+    /// blocks are not counted in `num_bitcoin_blocks`.
+    pub fork_points: Vec<(usize, usize)>,
 }
 
 impl BitcoinBlockRef {
@@ -580,6 +809,9 @@ impl From<&bitcoin::Block> for model::BitcoinBlockRef {
 mod tests {
     use more_asserts::assert_ge;
 
+    use crate::keys::SignerScriptPubKey as _;
+    use crate::storage::model::TxOutputType;
+    use crate::storage::model::TxPrevoutType;
     use crate::testing::get_rng;
     use crate::{
         storage::{self, DbRead as _},
@@ -600,6 +832,7 @@ mod tests {
             num_withdraw_requests_per_block: 0,
             num_signers_per_request: 0,
             consecutive_blocks: true,
+            fork_points: Vec::new(),
         };
         let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, 7);
 
@@ -635,4 +868,112 @@ mod tests {
         // bitcoin chain itself will be fork-less because of consecutive_blocks
         assert_ge!(walk.len(), 10);
     }
+
+    #[tokio::test]
+    async fn forked_chain_canonical_tip_is_the_longer_branch() {
+        let store = storage::memory::Store::new_shared();
+        let mut rng = get_rng();
+
+        let test_model_params = Params {
+            num_bitcoin_blocks: 10,
+            num_stacks_blocks_per_bitcoin_block: 1,
+            num_deposit_requests_per_block: 0,
+            num_withdraw_requests_per_block: 0,
+            num_signers_per_request: 0,
+            consecutive_blocks: true,
+            fork_points: vec![(4, 2)],
+        };
+        let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, 7);
+
+        let test_data = TestData::generate(&mut rng, &signer_set, &test_model_params);
+        test_data.write_to(&store).await;
+        assert_eq!(test_data.bitcoin_blocks.len(), 12);
+
+        // The fork point has the block that continues the chain plus the
+        // two competing children, all at the same height.
+        let fork_point = &test_data.bitcoin_blocks[4];
+        let children: Vec<_> = test_data
+            .bitcoin_blocks
+            .iter()
+            .filter(|block| block.parent_hash == fork_point.block_hash)
+            .collect();
+        assert_eq!(children.len(), 3);
+        for child in children.iter() {
+            assert_eq!(child.block_height, fork_point.block_height + 1);
+        }
+
+        let expected_tip = test_data
+            .bitcoin_blocks
+            .iter()
+            .max_by_key(|block| block.block_height)
+            .unwrap();
+        assert_eq!(
+            expected_tip.block_height,
+            test_data.bitcoin_blocks[0].block_height + 9
+        );
+
+        let bitcoin_chain_tip = store
+            .get_bitcoin_canonical_chain_tip()
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(bitcoin_chain_tip, expected_tip.block_hash);
+    }
+
+    #[test]
+    fn sweep_builder_classifies_inputs_and_outputs() {
+        let mut rng = get_rng();
+        let aggregate_key = PublicKey::dummy_with_rng(&fake::Faker, &mut rng);
+        let signers_script_pubkey = aggregate_key.signers_script_pubkey();
+        let recipient = PublicKey::dummy_with_rng(&fake::Faker, &mut rng).signers_script_pubkey();
+
+        let tx_info = TestBitcoinTxInfo::sweep_builder()
+            .signer_input(
+                TestBitcoinTxInfo::random_prevout(&mut rng),
+                bitcoin::TxOut {
+                    value: bitcoin::Amount::from_sat(100_000),
+                    script_pubkey: signers_script_pubkey.clone(),
+                },
+            )
+            .deposit_input(
+                TestBitcoinTxInfo::random_prevout(&mut rng),
+                bitcoin::TxOut {
+                    value: bitcoin::Amount::from_sat(50_000),
+                    script_pubkey: ScriptBuf::new(),
+                },
+            )
+            .withdrawal_output(recipient.clone(), bitcoin::Amount::from_sat(20_000))
+            .fee(bitcoin::Amount::from_sat(1_000))
+            .build();
+
+        let signer_script_pubkeys = HashSet::from([signers_script_pubkey.clone()]);
+
+        let inputs = tx_info.to_inputs(&signer_script_pubkeys);
+        let prevout_types: Vec<_> = inputs.iter().map(|input| input.prevout_type).collect();
+        assert_eq!(
+            prevout_types,
+            [TxPrevoutType::SignersInput, TxPrevoutType::Deposit]
+        );
+        assert_eq!(inputs[0].amount, 100_000);
+        assert_eq!(inputs[1].amount, 50_000);
+
+        let (outputs, withdrawal_outputs) = tx_info.to_outputs(&signer_script_pubkeys).unwrap();
+        let output_types: Vec<_> = outputs.iter().map(|output| output.output_type).collect();
+        assert_eq!(
+            output_types,
+            [
+                TxOutputType::SignersOutput,
+                TxOutputType::SignersOpReturn,
+                TxOutputType::Withdrawal
+            ]
+        );
+        assert_eq!(outputs[0].amount, 100_000 + 50_000 - 20_000 - 1_000);
+        assert_eq!(*outputs[0].script_pubkey, signers_script_pubkey);
+        assert_eq!(outputs[2].amount, 20_000);
+        assert_eq!(*outputs[2].script_pubkey, recipient);
+
+        assert_eq!(withdrawal_outputs.len(), 1);
+        assert_eq!(withdrawal_outputs[0].output_index, 2);
+        assert_eq!(withdrawal_outputs[0].request_id, 0);
+    }
 }
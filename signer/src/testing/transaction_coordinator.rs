@@ -472,6 +472,7 @@ where
 
         // Get the private key of the coordinator of the signer set.
         let private_key = select_coordinator(&bitcoin_chain_tip.block_hash, &signer_info);
+        self.context.config_mut().signer.private_key = private_key;
 
         // Bootstrap the tx coordinator within an event loop harness.
         self.context.state().set_sbtc_contracts_deployed();
@@ -670,6 +671,7 @@ where
 
         // Get the private key of the coordinator of the signer set.
         let private_key = select_coordinator(&bitcoin_chain_tip.block_hash, &signer_info);
+        self.context.config_mut().signer.private_key = private_key;
 
         // Bootstrap the tx coordinator within an event loop harness.
         // We don't `set_sbtc_contracts_deployed` to force the coordinator to deploy the contracts
@@ -321,7 +321,7 @@ where
         // If we are not the coordinator, then we have no business
         // coordinating DKG or constructing bitcoin and stacks
         // transactions, might as well return early.
-        if !self.is_coordinator(bitcoin_chain_tip.as_ref()) {
+        if !self.context.is_coordinator(&bitcoin_chain_tip)? {
             // Before returning, we also check if all the smart contracts are
             // deployed: we do this as some other coordinator could have deployed
             // them, in which case we need to updated our state.
@@ -335,9 +335,8 @@ where
         metrics::counter!(Metrics::CoordinatorTenuresTotal).increment(1);
 
         tracing::debug!("determining if we need to coordinate DKG");
-        let should_coordinate_dkg =
-            should_coordinate_dkg(&self.context, &bitcoin_chain_tip).await?;
-        let aggregate_key = if should_coordinate_dkg {
+        let dkg_decision = should_coordinate_dkg(&self.context, &bitcoin_chain_tip).await?;
+        let aggregate_key = if dkg_decision.is_allowed() {
             match self.coordinate_dkg(bitcoin_chain_tip.as_ref()).await {
                 Ok(key) => key,
                 Err(error) => {
@@ -505,6 +504,7 @@ where
         &mut self,
         bitcoin_chain_tip: &model::BitcoinBlockHash,
         signer_btc_state: &utxo::SignerBtcState,
+        shuffle_seed: Option<[u8; 32]>,
         transaction_package: &[utxo::UnsignedTransaction<'_>],
     ) -> Result<(), Error> {
         // Constructing a pre-sign request with empty request IDs is
@@ -522,6 +522,8 @@ where
                 .collect(),
             fee_rate: signer_btc_state.fee_rate,
             last_fees: signer_btc_state.last_fees,
+            shuffle_seed,
+            rbf_target_fee_rate: signer_btc_state.rbf_target_fee_rate,
         };
 
         let presign_ack_filter = |event: &SignerSignal| {
@@ -664,13 +666,29 @@ where
         );
 
         // Construct the transaction package and store it in the database.
-        let transaction_package = pending_requests.construct_transactions()?;
+        let (transaction_package, skipped) =
+            pending_requests.construct_transactions_and_skipped(&[], &[])?;
+        for skip in skipped.deposits {
+            tracing::info!(
+                outpoint = %skip.outpoint,
+                reason = %skip.reason,
+                "skipping deposit request"
+            );
+        }
+        for skip in skipped.withdrawals {
+            tracing::info!(
+                request_id = %skip.id,
+                reason = %skip.reason,
+                "skipping withdrawal request"
+            );
+        }
 
         // Send the pre-sign request to the signers and wait for their
         // acknowledgments.
         self.construct_and_send_bitcoin_presign_request(
             bitcoin_chain_tip.as_ref(),
             &pending_requests.signer_state,
+            pending_requests.shuffle_seed,
             &transaction_package,
         )
         .await?;
@@ -1822,26 +1840,6 @@ where
         }
     }
 
-    // Determine if the current coordinator is the coordinator.
-    //
-    // The coordinator is decided using the hash of the bitcoin
-    // chain tip. We don't use the chain tip directly because
-    // it typically starts with a lot of leading zeros.
-    //
-    // Note that this function is technically not fallible,
-    // but for now we have chosen to return phantom errors
-    // instead of adding expects/unwraps in the code.
-    // Ideally the code should be formulated in a way to guarantee
-    // it being infallible without relying on sequentially coupling
-    // expressions. However, that is left for future work.
-    fn is_coordinator(&self, bitcoin_chain_tip: &model::BitcoinBlockHash) -> bool {
-        given_key_is_coordinator(
-            self.signer_public_key(),
-            bitcoin_chain_tip,
-            &self.context.config().signer.bootstrap_signing_set,
-        )
-    }
-
     /// Constructs a new [`utxo::SignerBtcState`] based on the current market
     /// fee rate, the signer's UTXO, and the last sweep package.
     #[tracing::instrument(skip_all)]
@@ -1868,7 +1866,9 @@ where
             utxo,
             public_key: bitcoin::XOnlyPublicKey::from(aggregate_key),
             last_fees,
-            magic_bytes: [b'T', b'3'], //TODO(#472): Use the correct magic bytes.
+            magic_bytes: self.context.config().signer.network.magic_bytes(),
+            rbf_target_fee_rate: None,
+            anchor_output: self.context.config().signer.sweep_anchor_output,
         })
     }
 
@@ -2190,6 +2190,15 @@ where
             num_signers,
             sbtc_limits,
             max_deposits_per_bitcoin_tx,
+            // The minimum batch policy is library-only, the coordinator
+            // sweeps deposits as soon as they are pending.
+            min_batch: None,
+            withdrawals_to_signers: utxo::WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: config.signer.min_signer_output,
+            shuffle_seed: config
+                .signer
+                .sweep_shuffle_deposits
+                .then(|| sweep_shuffle_seed(&bitcoin_chain_tip.block_hash)),
         }))
     }
 
@@ -2534,12 +2543,52 @@ pub fn coordinator_public_key(
         .copied()
 }
 
+/// The seed used to shuffle the deposit inputs of the sweep transactions
+/// constructed on top of the given bitcoin chain tip.
+///
+/// Every signer derives the same seed from the same chain tip, while each
+/// new block gives the sweeps a fresh ordering.
+pub fn sweep_shuffle_seed(chain_tip: &model::BitcoinBlockHash) -> [u8; 32] {
+    sha2::Sha256::new_with_prefix("SIGNER_SWEEP_SHUFFLE_SEED")
+        .chain_update(chain_tip.into_bytes())
+        .finalize()
+        .into()
+}
+
+/// The outcome of checking whether a new DKG round may be run, along
+/// with the reason when it may not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum DkgCoordinationDecision {
+    /// A new DKG round may be run.
+    Allowed,
+    /// The latest DKG shares are still unverified, and verifying them
+    /// takes priority over running DKG again.
+    BlockedPendingVerification,
+    /// The signer set and signatures required are unchanged, and no
+    /// `dkg_min_bitcoin_block_height` has been configured for a rerun.
+    BlockedUnchangedSignerSet,
+    /// The signer set and signatures required are unchanged, and the
+    /// configured `dkg_target_rounds` have already been run.
+    BlockedTargetRoundsReached,
+    /// The bitcoin chain tip is below the configured
+    /// `dkg_min_bitcoin_block_height`, so DKG may not be rerun yet.
+    BlockedInWindow,
+}
+
+impl DkgCoordinationDecision {
+    /// Whether a new DKG round may be run.
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Self::Allowed)
+    }
+}
+
 /// Determine, according to the current state of the signer and configuration,
-/// whether or not a new DKG round should be coordinated.
+/// whether or not a new DKG round should be coordinated, and if not, why.
 pub async fn should_coordinate_dkg(
     context: &impl Context,
     bitcoin_chain_tip: &model::BitcoinBlockRef,
-) -> Result<bool, Error> {
+) -> Result<DkgCoordinationDecision, Error> {
     let storage = context.get_storage();
     let config = context.config();
 
@@ -2549,7 +2598,7 @@ pub async fn should_coordinate_dkg(
     let latest_dkg_shares = storage.get_latest_encrypted_dkg_shares().await?;
     if latest_dkg_shares.map(|s| s.dkg_shares_status) == Some(model::DkgSharesStatus::Unverified) {
         tracing::debug!("latest shares are unverified; skipping DKG");
-        return Ok(false);
+        return Ok(DkgCoordinationDecision::BlockedPendingVerification);
     }
 
     // If we do not have a key rotation event in the database, we will
@@ -2558,13 +2607,13 @@ pub async fn should_coordinate_dkg(
         // Trigger DKG if signatures_required has changed
         if registry_signer_info.signatures_required != config.signer.bootstrap_signatures_required {
             tracing::info!("signatures required has changed; proceeding with DKG");
-            return Ok(true);
+            return Ok(DkgCoordinationDecision::Allowed);
         }
 
         // Trigger DKG if signer set changes
         if registry_signer_info.signer_set != config.signer.bootstrap_signing_set {
             tracing::info!("signer set has changed; proceeding with DKG");
-            return Ok(true);
+            return Ok(DkgCoordinationDecision::Allowed);
         }
     }
 
@@ -2576,7 +2625,7 @@ pub async fn should_coordinate_dkg(
     let dkg_target_rounds = config.signer.dkg_target_rounds;
 
     // Determine the action based on the DKG shares count and the rerun height (if configured)
-    match (
+    let decision = match (
         dkg_shares_entry_count,
         dkg_target_rounds,
         dkg_min_bitcoin_block_height,
@@ -2587,21 +2636,34 @@ pub async fn should_coordinate_dkg(
                 %dkg_target_rounds,
                 "no DKG shares exist; proceeding with DKG"
             );
-            Ok(true)
+            return Ok(DkgCoordinationDecision::Allowed);
+        }
+        (current, target, Some(_)) if current >= target.get() => {
+            DkgCoordinationDecision::BlockedTargetRoundsReached
         }
-        (current, target, Some(dkg_min_height))
-            if current < target.get() && bitcoin_chain_tip.block_height >= dkg_min_height =>
-        {
+        (_, _, Some(dkg_min_height)) if bitcoin_chain_tip.block_height < dkg_min_height => {
+            DkgCoordinationDecision::BlockedInWindow
+        }
+        (_, _, Some(_)) => {
             tracing::info!(
                 ?dkg_min_bitcoin_block_height,
                 %dkg_target_rounds,
                 dkg_current_rounds = %dkg_shares_entry_count,
                 "DKG rerun height has been met and we are below the target number of rounds; proceeding with DKG"
             );
-            Ok(true)
+            return Ok(DkgCoordinationDecision::Allowed);
         }
-        _ => Ok(false),
-    }
+        (_, _, None) => DkgCoordinationDecision::BlockedUnchangedSignerSet,
+    };
+
+    tracing::debug!(
+        ?dkg_min_bitcoin_block_height,
+        %dkg_target_rounds,
+        dkg_current_rounds = %dkg_shares_entry_count,
+        reason = %decision,
+        "skipping DKG"
+    );
+    Ok(decision)
 }
 
 /// Assert, given the last dkg and smart contract current aggregate key, if we
@@ -2657,8 +2719,10 @@ mod tests {
     use rand::SeedableRng as _;
     use test_case::test_case;
 
+    use super::DkgCoordinationDecision;
     use super::assert_rotate_key_action;
     use super::should_coordinate_dkg;
+    use super::sweep_shuffle_seed;
 
     #[allow(clippy::type_complexity)]
     fn test_environment() -> TestEnvironment<
@@ -2676,6 +2740,7 @@ mod tests {
             num_withdraw_requests_per_block: 5,
             num_signers_per_request: 7,
             consecutive_blocks: false,
+            fork_points: Vec::new(),
         };
 
         let context = TestContext::builder()
@@ -2771,19 +2836,22 @@ mod tests {
             .await;
     }
 
-    #[test_case(0, None, 1, 100, true; "first DKG allowed without min height")]
-    #[test_case(0, Some(100), 1, 5, true; "first DKG allowed regardless of min height")]
-    #[test_case(1, None, 2, 100, false; "subsequent DKG not allowed without min height")]
-    #[test_case(1, Some(101), 1, 100, false; "subsequent DKG not allowed with current height lower than min height")]
-    #[test_case(1, Some(100), 1, 100, false; "subsequent DKG not allowed when target rounds reached")]
-    #[test_case(1, Some(100), 2, 100, true; "subsequent DKG allowed when target rounds not reached and min height met")]
+    #[test_case(0, None, 1, 100, DkgCoordinationDecision::Allowed; "first DKG allowed without min height")]
+    #[test_case(0, Some(100), 1, 5, DkgCoordinationDecision::Allowed; "first DKG allowed regardless of min height")]
+    #[test_case(1, None, 2, 100, DkgCoordinationDecision::BlockedUnchangedSignerSet; "subsequent DKG not allowed without min height")]
+    #[test_case(1, None, 1, 100, DkgCoordinationDecision::BlockedUnchangedSignerSet; "subsequent DKG not allowed without min height when target rounds reached")]
+    #[test_case(1, Some(101), 1, 100, DkgCoordinationDecision::BlockedTargetRoundsReached; "subsequent DKG not allowed with current height lower than min height")]
+    #[test_case(2, Some(101), 1, 100, DkgCoordinationDecision::BlockedTargetRoundsReached; "subsequent DKG not allowed when target rounds exceeded, even below min height")]
+    #[test_case(1, Some(101), 2, 100, DkgCoordinationDecision::BlockedInWindow; "subsequent DKG not allowed below min height when target rounds not reached")]
+    #[test_case(1, Some(100), 1, 100, DkgCoordinationDecision::BlockedTargetRoundsReached; "subsequent DKG not allowed when target rounds reached")]
+    #[test_case(1, Some(100), 2, 100, DkgCoordinationDecision::Allowed; "subsequent DKG allowed when target rounds not reached and min height met")]
     #[test_log::test(tokio::test)]
     async fn test_should_coordinate_dkg(
         dkg_rounds_current: u32,
         dkg_min_bitcoin_block_height: Option<u64>,
         dkg_target_rounds: u32,
         chain_tip_height: u64,
-        should_allow: bool,
+        expected: DkgCoordinationDecision,
     ) {
         let chain_tip_height = chain_tip_height.into();
         let dkg_min_bitcoin_block_height =
@@ -2833,7 +2901,35 @@ mod tests {
             .expect("failed to check if DKG should be coordinated");
 
         // Assert the result
-        assert_eq!(result, should_allow);
+        assert_eq!(result, expected);
+        assert_eq!(
+            result.is_allowed(),
+            expected == DkgCoordinationDecision::Allowed
+        );
+    }
+
+    /// Test that DKG is blocked while the latest DKG shares are still
+    /// unverified.
+    #[test_log::test(tokio::test)]
+    async fn should_coordinate_dkg_blocked_pending_verification() {
+        let context = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let storage = context.get_storage_mut();
+
+        let mut shares: model::EncryptedDkgShares = Faker.fake();
+        shares.dkg_shares_status = model::DkgSharesStatus::Unverified;
+        storage.write_encrypted_dkg_shares(&shares).await.unwrap();
+
+        let bitcoin_chain_tip: model::BitcoinBlockRef = Faker.fake();
+        let result = should_coordinate_dkg(&context, &bitcoin_chain_tip)
+            .await
+            .expect("failed to check if DKG should be coordinated");
+
+        assert_eq!(result, DkgCoordinationDecision::BlockedPendingVerification);
+        assert!(!result.is_allowed());
     }
 
     fn public_key_from_seed(seed: u64) -> PublicKey {
@@ -2933,4 +3029,19 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn sweep_shuffle_seed_is_derived_from_the_chain_tip() {
+        let chain_tip: model::BitcoinBlockHash = Faker.fake();
+        let other_chain_tip: model::BitcoinBlockHash = Faker.fake();
+
+        assert_eq!(
+            sweep_shuffle_seed(&chain_tip),
+            sweep_shuffle_seed(&chain_tip)
+        );
+        assert_ne!(
+            sweep_shuffle_seed(&chain_tip),
+            sweep_shuffle_seed(&other_chain_tip)
+        );
+    }
 }
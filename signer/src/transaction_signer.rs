@@ -46,6 +46,7 @@ use crate::storage::model;
 use crate::storage::model::BitcoinBlockHash;
 use crate::storage::model::DkgSharesStatus;
 use crate::storage::model::SigHash;
+use crate::transaction_coordinator::should_coordinate_dkg;
 use crate::wsts_state_machine::FrostCoordinator;
 use crate::wsts_state_machine::SignerStateMachine;
 use crate::wsts_state_machine::StateMachineId;
@@ -1586,96 +1587,20 @@ where
 
 /// Asserts whether a `DkgBegin` WSTS message should be allowed to proceed
 /// based on the current state of the signer and the DKG configuration.
+///
+/// This uses the same rules as [`should_coordinate_dkg`], so that signers
+/// only accept a `DkgBegin` message when the coordinator was allowed to
+/// send it.
 pub async fn assert_allow_dkg_begin(
     context: &impl Context,
     bitcoin_chain_tip: &model::BitcoinBlockRef,
 ) -> Result<(), Error> {
-    let storage = context.get_storage();
-    let config = context.config();
-
-    // If the latest shares are unverified, we want to prioritize verifying them
-    // instead of doing new DKG rounds. If we fail to do so they will eventually
-    // be marked as failed, and we will resume DKG-ing.
-    let latest_dkg_shares = storage.get_latest_encrypted_dkg_shares().await?;
-    if latest_dkg_shares.map(|s| s.dkg_shares_status) == Some(model::DkgSharesStatus::Unverified) {
-        tracing::warn!("latest shares are unverified; aborting");
+    let decision = should_coordinate_dkg(context, bitcoin_chain_tip).await?;
+    if !decision.is_allowed() {
+        tracing::warn!(reason = %decision, "DKG is not allowed; aborting");
         return Err(Error::DkgHasAlreadyRun);
     }
 
-    // If we do not have a key rotation event in the database, we will
-    // allow DKG below if we have not run DKG yet.
-    if let Some(registry_signer_info) = context.state().registry_signer_set_info() {
-        // Trigger DKG if signatures_required has changed
-        if registry_signer_info.signatures_required != config.signer.bootstrap_signatures_required {
-            tracing::info!("signatures required has changed; proceeding with DKG");
-            return Ok(());
-        }
-
-        // Trigger DKG if signer set changes
-        if registry_signer_info.signer_set != config.signer.bootstrap_signing_set {
-            tracing::info!("signer set has changed; proceeding with DKG");
-            return Ok(());
-        }
-    }
-
-    // Get the number of DKG shares that have been stored
-    let dkg_shares_entry_count = storage.get_encrypted_dkg_shares_count().await?;
-
-    // Get DKG configuration parameters
-    let dkg_min_bitcoin_block_height = config.signer.dkg_min_bitcoin_block_height;
-    let dkg_target_rounds = config.signer.dkg_target_rounds;
-
-    // Determine the action based on the DKG shares count and the rerun height (if configured)
-    match (
-        dkg_shares_entry_count,
-        dkg_target_rounds,
-        dkg_min_bitcoin_block_height,
-    ) {
-        (0, _, _) => {
-            tracing::info!(
-                ?dkg_min_bitcoin_block_height,
-                %dkg_target_rounds,
-                "no DKG shares exist; proceeding with DKG"
-            );
-        }
-        (current, target, Some(dkg_min_height)) => {
-            if current >= target.get() {
-                tracing::warn!(
-                    ?dkg_min_bitcoin_block_height,
-                    %dkg_target_rounds,
-                    dkg_current_rounds = %dkg_shares_entry_count,
-                    "The target number of DKG shares has been reached; aborting"
-                );
-                return Err(Error::DkgHasAlreadyRun);
-            }
-            if bitcoin_chain_tip.block_height < dkg_min_height {
-                tracing::warn!(
-                    ?dkg_min_bitcoin_block_height,
-                    %dkg_target_rounds,
-                    dkg_current_rounds = %dkg_shares_entry_count,
-                    "bitcoin chain tip is below the minimum height for DKG rerun; aborting"
-                );
-                return Err(Error::DkgHasAlreadyRun);
-            }
-            tracing::info!(
-                ?dkg_min_bitcoin_block_height,
-                %dkg_target_rounds,
-                dkg_current_rounds = %dkg_shares_entry_count,
-                "DKG rerun height has been met and we are below the target number of rounds; proceeding with DKG"
-            );
-        }
-        // Note that we account for all (0, _, _) cases above (i.e. first DKG round)
-        (_, _, None) => {
-            tracing::warn!(
-                ?dkg_min_bitcoin_block_height,
-                %dkg_target_rounds,
-                dkg_current_rounds = %dkg_shares_entry_count,
-                "attempt to run multiple DKGs without a configured re-run height; aborting"
-            );
-            return Err(Error::DkgHasAlreadyRun);
-        }
-    }
-
     Ok(())
 }
 
@@ -1746,6 +1671,7 @@ mod tests {
             num_withdraw_requests_per_block: 5,
             num_signers_per_request: 0,
             consecutive_blocks: false,
+            fork_points: Vec::new(),
         };
 
         let context = TestContext::builder()
@@ -495,13 +495,7 @@ impl SignerStateMachine {
             .await?
             .ok_or_else(|| error::Error::MissingDkgShares(aggregate_key))?;
 
-        let decrypted = wsts::util::decrypt(
-            &signer_private_key.to_bytes(),
-            &encrypted_shares.encrypted_private_shares,
-        )
-        .map_err(|_| error::Error::Encryption)?;
-
-        let saved_state = wsts::traits::SignerState::decode(decrypted.as_slice())?;
+        let saved_state = encrypted_shares.decrypt(&signer_private_key)?;
 
         // This may panic if the saved state doesn't contain exactly one party,
         // however, that should never be the case since wsts maintains this invariant
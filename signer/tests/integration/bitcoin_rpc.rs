@@ -1,5 +1,7 @@
 //! Test the RPC clients
 
+use std::time::Duration;
+
 use bitcoin::AddressType;
 use bitcoin::Amount;
 use bitcoin::OutPoint;
@@ -20,9 +22,12 @@ use sbtc::testing::regtest::Recipient;
 use sbtc::testing::regtest::p2wpkh_sign_transaction;
 use signer::bitcoin::BitcoinInteract;
 use signer::bitcoin::rpc::BitcoinCoreClient;
+use signer::error::Error;
 use signer::storage::model::BitcoinBlockHash;
 use signer::storage::model::BitcoinTxId;
 
+use crate::utxo_construction::make_deposit_request;
+
 #[test]
 fn btc_client_getstransaction() {
     let client = BitcoinCoreClient::new(
@@ -173,6 +178,99 @@ fn btc_client_gets_transaction_info_missing_tx() {
     assert!(response.is_none());
 }
 
+#[test]
+fn btc_client_gets_many_transaction_infos_in_order() {
+    let client = BitcoinCoreClient::new(
+        "http://localhost:18443",
+        regtest::BITCOIN_CORE_RPC_USERNAME.to_string(),
+        regtest::BITCOIN_CORE_RPC_PASSWORD.to_string(),
+    )
+    .unwrap();
+    let (_, faucet) = regtest::initialize_blockchain();
+    let signer = Recipient::new(AddressType::P2tr);
+
+    // We want more transactions than the client fetches concurrently, so
+    // that the requests are split across threads.
+    let outpoints: Vec<OutPoint> = (0..20)
+        .map(|i| faucet.send_to(100_000 + i, &signer.address))
+        .collect();
+    let block_hash = faucet.generate_blocks(1).pop().unwrap();
+
+    let fake_txid: BitcoinTxId = Faker.fake_with_rng(&mut OsRng);
+    let mut requests: Vec<(Txid, bitcoin::BlockHash)> = outpoints
+        .iter()
+        .map(|outpoint| (outpoint.txid, block_hash))
+        .collect();
+    requests.insert(10, (*fake_txid, block_hash));
+
+    let responses = client.get_tx_infos(&requests).unwrap();
+    assert_eq!(responses.len(), requests.len());
+
+    for ((txid, _), response) in requests.iter().zip(responses) {
+        if *txid == *fake_txid {
+            assert!(response.is_none());
+            continue;
+        }
+        let response = response.unwrap();
+        assert_eq!(response.tx.compute_txid(), *txid);
+    }
+
+    assert!(client.get_tx_infos(&[]).unwrap().is_empty());
+}
+
+#[test]
+fn btc_client_gets_mempool_transaction_info() {
+    let client = BitcoinCoreClient::new(
+        "http://localhost:18443",
+        regtest::BITCOIN_CORE_RPC_USERNAME.to_string(),
+        regtest::BITCOIN_CORE_RPC_PASSWORD.to_string(),
+    )
+    .unwrap();
+    let (rpc, faucet) = regtest::initialize_blockchain();
+    let depositor = Recipient::new(AddressType::P2tr);
+    let signers_public_key = Recipient::new(AddressType::P2tr)
+        .keypair
+        .x_only_public_key()
+        .0;
+
+    faucet.send_to(50_000_000, &depositor.address);
+    faucet.generate_blocks(1);
+
+    // Submit a deposit transaction without confirming it.
+    let depositor_utxo = depositor.get_utxos(rpc, None).pop().unwrap();
+    let utxo_outpoint = depositor_utxo.outpoint();
+    let utxo_amount = depositor_utxo.amount();
+    let (deposit_tx, _, _) = make_deposit_request(
+        &depositor,
+        25_000_000,
+        depositor_utxo,
+        12_500_000,
+        signers_public_key,
+    );
+    let txid = rpc.send_raw_transaction(&deposit_tx).unwrap();
+
+    let tx_info = client.get_mempool_tx_info(&txid).unwrap().unwrap();
+    assert_eq!(tx_info.compute_txid(), txid);
+    assert_eq!(tx_info.fee, Some(regtest::BITCOIN_CORE_FALLBACK_FEE));
+    tx_info.validate().unwrap();
+
+    let [vin] = tx_info.vin.as_slice() else {
+        panic!("expected a single input");
+    };
+    let prevout = vin.prevout.as_ref().unwrap();
+    assert_eq!(vin.txid, Some(utxo_outpoint.txid));
+    assert_eq!(vin.vout, Some(utxo_outpoint.vout));
+    assert_eq!(prevout.value, utxo_amount);
+    assert_eq!(prevout.script_pubkey.script, depositor.script_pubkey);
+
+    // Once the transaction is confirmed it is no longer in the mempool.
+    faucet.generate_blocks(1);
+    assert!(client.get_mempool_tx_info(&txid).unwrap().is_none());
+
+    let fake_txid: BitcoinTxId = Faker.fake_with_rng(&mut OsRng);
+    assert!(client.get_mempool_tx_info(&fake_txid).unwrap().is_none());
+}
+
 #[test]
 fn btc_client_unsubmitted_tx() {
     let client = BitcoinCoreClient::new(
@@ -524,3 +622,70 @@ async fn get_tx_out_unconfirmed_with_mempool() {
     assert_eq!(txout.value, Amount::from_sat(10_000));
     assert_eq!(txout.confirmations, 0); // Unconfirmed txs will have 0 confirmations
 }
+
+#[tokio::test]
+async fn wait_for_block_resolves_for_a_new_block() {
+    let client = BitcoinCoreClient::new(
+        "http://localhost:18443",
+        regtest::BITCOIN_CORE_RPC_USERNAME.to_string(),
+        regtest::BITCOIN_CORE_RPC_PASSWORD.to_string(),
+    )
+    .unwrap();
+
+    let (_, faucet) = regtest::initialize_blockchain();
+    let block_hash = faucet.generate_blocks(1).pop().unwrap();
+
+    client
+        .wait_for_block(&block_hash, Duration::from_secs(5))
+        .await
+        .expect("block should be part of the best chain before the timeout");
+
+    // A block that bitcoin-core has never seen never becomes part of the
+    // best chain.
+    let fake_block_hash: BitcoinBlockHash = Faker.fake_with_rng(&mut OsRng);
+    let unknown_block_hash: bitcoin::BlockHash = fake_block_hash.into();
+    let result = client
+        .wait_for_block(&unknown_block_hash, Duration::from_millis(500))
+        .await;
+    assert!(matches!(
+        result,
+        Err(Error::BitcoinCoreWaitForBlockTimeout(hash)) if hash == unknown_block_hash
+    ));
+}
+
+#[tokio::test]
+async fn wait_for_tx_confirmation_resolves_once_deep_enough() {
+    let client = BitcoinCoreClient::new(
+        "http://localhost:18443",
+        regtest::BITCOIN_CORE_RPC_USERNAME.to_string(),
+        regtest::BITCOIN_CORE_RPC_PASSWORD.to_string(),
+    )
+    .unwrap();
+
+    let (_, faucet) = regtest::initialize_blockchain();
+    let recipient = Recipient::new(AddressType::P2tr);
+    let outpoint = faucet.send_to(100_000, &recipient.address);
+
+    faucet.generate_blocks(2);
+
+    let poll_interval = Duration::from_millis(50);
+    let tx_info = client
+        .wait_for_tx_confirmation_with_interval(
+            &outpoint.txid,
+            2,
+            Duration::from_secs(5),
+            poll_interval,
+        )
+        .await
+        .expect("transaction should have two confirmations before the timeout");
+    assert_eq!(tx_info.tx.compute_txid(), outpoint.txid);
+
+    // The transaction will not be a thousand blocks deep anytime soon.
+    let result = client
+        .wait_for_tx_confirmation(&outpoint.txid, 1000, Duration::from_millis(500))
+        .await;
+    assert!(matches!(
+        result,
+        Err(Error::BitcoinCoreWaitForTxConfirmationTimeout(txid, 1000)) if txid == outpoint.txid
+    ));
+}
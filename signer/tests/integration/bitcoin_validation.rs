@@ -10,6 +10,7 @@ use sbtc::testing::regtest;
 use signer::WITHDRAWAL_MIN_CONFIRMATIONS;
 use signer::bitcoin::utxo::SbtcRequests;
 use signer::bitcoin::utxo::SignerBtcState;
+use signer::bitcoin::utxo::WithdrawalsToSignersPolicy;
 use signer::bitcoin::validation::BitcoinTxContext;
 use signer::bitcoin::validation::BitcoinTxValidationData;
 use signer::bitcoin::validation::InputValidationResult;
@@ -53,6 +54,8 @@ where
         public_key: btc_ctx.aggregate_key.into(),
         last_fees: request.last_fees,
         magic_bytes: [b'T', b'3'],
+        rbf_target_fee_rate: None,
+        anchor_output: false,
     }
 }
 
@@ -135,6 +138,8 @@ async fn one_tx_per_request_set() {
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        shuffle_seed: None,
+        rbf_target_fee_rate: None,
     };
 
     let btc_ctx = BitcoinTxContext {
@@ -240,6 +245,8 @@ async fn one_invalid_deposit_invalidates_tx() {
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        shuffle_seed: None,
+        rbf_target_fee_rate: None,
     };
 
     let btc_ctx = BitcoinTxContext {
@@ -406,6 +413,8 @@ async fn withdrawals_and_deposits_can_pass_validation(amounts: Vec<SweepAmounts>
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        shuffle_seed: None,
+        rbf_target_fee_rate: None,
     };
 
     let btc_ctx = BitcoinTxContext {
@@ -512,6 +521,8 @@ async fn swept_withdrawals_fail_validation() {
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        shuffle_seed: None,
+        rbf_target_fee_rate: None,
     };
 
     let btc_ctx = BitcoinTxContext {
@@ -634,6 +645,8 @@ async fn cannot_sign_deposit_is_ok() {
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        shuffle_seed: None,
+        rbf_target_fee_rate: None,
     };
 
     let btc_ctx = BitcoinTxContext {
@@ -706,6 +719,10 @@ async fn cannot_sign_deposit_is_ok() {
         num_signers: 3,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: ctx.config().signer.max_deposits_per_bitcoin_tx.get(),
+        min_batch: None,
+        withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+        min_signer_output: None,
+        shuffle_seed: None,
     };
     let txs = sbtc_requests.construct_transactions().unwrap();
     assert_eq!(txs.len(), 1);
@@ -772,6 +789,8 @@ async fn sighashes_match_from_sbtc_requests_object() {
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        shuffle_seed: None,
+        rbf_target_fee_rate: None,
     };
 
     let btc_ctx = BitcoinTxContext {
@@ -840,6 +859,10 @@ async fn sighashes_match_from_sbtc_requests_object() {
         num_signers: 3,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: ctx.config().signer.max_deposits_per_bitcoin_tx.get(),
+        min_batch: None,
+        withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+        min_signer_output: None,
+        shuffle_seed: None,
     };
     let txs = sbtc_requests.construct_transactions().unwrap();
     assert_eq!(txs.len(), 1);
@@ -916,6 +939,8 @@ async fn outcome_is_independent_of_input_order() {
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        shuffle_seed: None,
+        rbf_target_fee_rate: None,
     };
 
     let btc_ctx = BitcoinTxContext {
@@ -1,4 +1,5 @@
 use std::collections::HashSet;
+use std::num::NonZeroUsize;
 use std::ops::Deref;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
@@ -18,6 +19,8 @@ use clarity::types::chainstate::StacksAddress;
 use clarity::vm::types::PrincipalData;
 use emily_client::apis::deposit_api;
 use emily_client::models::CreateDepositRequestBody;
+use emily_client::models::DepositStatus;
+use emily_client::models::UpdateDepositsResponse;
 use fake::Fake as _;
 use fake::Faker;
 use rand::seq::SliceRandom;
@@ -31,7 +34,9 @@ use signer::bitcoin::utxo::DepositRequest;
 use signer::bitcoin::utxo::SbtcRequests;
 use signer::bitcoin::utxo::SignerBtcState;
 use signer::bitcoin::utxo::SignerUtxo;
+use signer::bitcoin::utxo::WithdrawalsToSignersPolicy;
 use signer::block_observer::get_signer_set_info;
+use signer::config::NetworkKind;
 use signer::context::SbtcLimits;
 use signer::emily_client::EmilyClient;
 use signer::error::Error;
@@ -56,6 +61,7 @@ use signer::testing::stacks::DUMMY_TENURE_INFO;
 use testing_emily_client::apis::testing_api;
 
 use signer::block_observer::BlockObserver;
+use signer::block_observer::DepositRequestValidator as _;
 use signer::context::Context as _;
 use signer::context::SignerEvent;
 use signer::context::SignerSignal;
@@ -66,6 +72,7 @@ use signer::testing::context::TestContext;
 use signer::testing::context::*;
 use signer::testing::get_rng;
 use signer::testing::storage::model::TestData;
+use signer::transaction_coordinator::DkgCoordinationDecision;
 use signer::transaction_coordinator::should_coordinate_dkg;
 use signer::transaction_signer::assert_allow_dkg_begin;
 use url::Url;
@@ -80,6 +87,25 @@ use crate::zmq::BITCOIN_CORE_ZMQ_ENDPOINT;
 pub const GET_POX_INFO_JSON: &str =
     include_str!("../../tests/fixtures/stacksapi-get-pox-info-test-data.json");
 
+/// Wait for the block observer to signal that it has processed a new
+/// bitcoin block.
+async fn wait_for_bitcoin_block_observed(
+    signal_rx: &mut tokio::sync::broadcast::Receiver<SignerSignal>,
+) {
+    let observed = async {
+        loop {
+            if let Ok(SignerSignal::Event(SignerEvent::BitcoinBlockObserved)) =
+                signal_rx.recv().await
+            {
+                break;
+            }
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(3), observed)
+        .await
+        .expect("block observer did not process the new bitcoin block");
+}
+
 /// The [`BlockObserver::load_latest_deposit_requests`] function is
 /// supposed to fetch all deposit requests from Emily and persist the ones
 /// that pass validation, regardless of when they were confirmed.
@@ -169,8 +195,9 @@ async fn load_latest_deposit_requests_persists_requests_from_past(blocks_ago: u6
         bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(BITCOIN_CORE_ZMQ_ENDPOINT).await,
     };
 
-    // We need at least one receiver
-    let _signal = ctx.get_signal_receiver();
+    // We use this receiver to know when the block observer has processed
+    // a new block.
+    let mut signal_rx = ctx.get_signal_receiver();
 
     // Our database shouldn't have any deposit requests. In fact, our
     // database doesn't have any blockchain data at all.
@@ -204,31 +231,19 @@ async fn load_latest_deposit_requests_persists_requests_from_past(blocks_ago: u6
     // BitcoinBlockObserved signal.
     let chain_tip: BitcoinBlockHash = faucet.generate_blocks(1).pop().unwrap().into();
 
-    // We need to wait for the bitcoin-core to send us all the
-    // notifications so that we are up to date with the expected chain tip.
-    // For that we just wait until we know that we're up-to-date
-    let mut current_chain_tip = db2.get_bitcoin_canonical_chain_tip().await.unwrap();
-
-    let waiting_fut = async {
-        let db2 = db2.clone();
-        while current_chain_tip != Some(chain_tip) {
-            current_chain_tip = db2.get_bitcoin_canonical_chain_tip().await.unwrap();
-            tokio::time::sleep(Duration::from_millis(250)).await;
-        }
-    };
-
-    tokio::time::timeout(Duration::from_secs(3), waiting_fut)
+    // Once bitcoin-core reports the new block as part of its best chain,
+    // the block observer gets notified and processes it.
+    ctx.get_bitcoin_client()
+        .wait_for_block(&chain_tip.into(), Duration::from_secs(3))
         .await
         .unwrap();
+    wait_for_bitcoin_block_observed(&mut signal_rx).await;
 
     // Okay now lets check if we have these deposit requests in our
     // database. It should also have bitcoin blockchain data
-
-    assert!(
-        db2.get_bitcoin_canonical_chain_tip()
-            .await
-            .unwrap()
-            .is_some()
+    assert_eq!(
+        db2.get_bitcoin_canonical_chain_tip().await.unwrap(),
+        Some(chain_tip)
     );
     let deposit_requests = db2.get_deposit_requests(&chain_tip, 100).await.unwrap();
 
@@ -242,6 +257,146 @@ async fn load_latest_deposit_requests_persists_requests_from_past(blocks_ago: u6
     testing::storage::drop_db(db).await;
 }
 
+/// The [`BlockObserver::load_latest_deposit_requests`] function should
+/// not persist deposit requests whose lock time has already elapsed as of
+/// the bitcoin chain tip, since the depositor can reclaim them. Instead,
+/// it reports them to Emily as expired.
+#[test_log::test(tokio::test)]
+async fn load_latest_deposit_requests_skips_expired_requests() {
+    let mut rng = get_rng();
+    let (rpc, faucet) = regtest::initialize_blockchain();
+    let db = testing::storage::new_test_database().await;
+    let ctx = TestContext::builder()
+        .with_storage(db.clone())
+        .with_first_bitcoin_core_client()
+        .with_mocked_emily_client()
+        .with_mocked_stacks_client()
+        .build();
+    ctx.state().update_current_limits(SbtcLimits::unlimited());
+
+    // The deposits created here have a lock time of 50 blocks, so the
+    // first deposit will be reclaimable by the time we observe it, while
+    // the second one will not.
+    let expired_setup = TestSweepSetup::new_setup(rpc, faucet, 100_000, &mut rng);
+    faucet.generate_blocks(50);
+    let valid_setup = TestSweepSetup::new_setup(rpc, faucet, 200_000, &mut rng);
+
+    let expired_updates = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let updates = expired_updates.clone();
+
+    ctx.with_emily_client(|client| {
+        let emily_client_response = vec![
+            expired_setup.emily_deposit_request(),
+            valid_setup.emily_deposit_request(),
+        ];
+        client
+            .expect_get_deposits()
+            .times(1..)
+            .returning(move || Box::pin(std::future::ready(Ok(emily_client_response.clone()))));
+
+        client
+            .expect_get_limits()
+            .times(1..)
+            .returning(|| Box::pin(async { Ok(SbtcLimits::unlimited()) }));
+
+        client.expect_update_deposits().returning(move |deposits| {
+            updates.lock().unwrap().extend(deposits);
+            let response = UpdateDepositsResponse { deposits: Vec::new() };
+            Box::pin(std::future::ready(Ok(response)))
+        });
+    })
+    .await;
+
+    ctx.with_stacks_client(|client| {
+        client
+            .expect_get_tenure_info()
+            .returning(move || Box::pin(std::future::ready(Ok(DUMMY_TENURE_INFO.clone()))));
+
+        client.expect_get_block().returning(|_| {
+            let response = Ok(NakamotoBlock {
+                header: NakamotoBlockHeader::empty(),
+                txs: Vec::new(),
+            });
+            Box::pin(std::future::ready(response))
+        });
+
+        client
+            .expect_get_tenure()
+            .returning(|_| Box::pin(std::future::ready(TenureBlocks::nearly_empty())));
+
+        client.expect_get_pox_info().returning(|| {
+            let response = serde_json::from_str::<RPCPoxInfoData>(GET_POX_INFO_JSON)
+                .map_err(Error::JsonSerialize);
+            Box::pin(std::future::ready(response))
+        });
+
+        client
+            .expect_get_sortition_info()
+            .returning(move |_| Box::pin(std::future::ready(Ok(DUMMY_SORTITION_INFO.clone()))));
+    })
+    .await;
+
+    let start_flag = Arc::new(AtomicBool::new(false));
+    let flag = start_flag.clone();
+
+    let block_observer = BlockObserver {
+        context: ctx.clone(),
+        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(BITCOIN_CORE_ZMQ_ENDPOINT).await,
+    };
+
+    let mut signal_rx = ctx.get_signal_receiver();
+
+    tokio::spawn(async move {
+        flag.store(true, Ordering::Relaxed);
+        block_observer.run().await
+    });
+
+    while !start_flag.load(Ordering::SeqCst) {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // Let's generate a new block and wait for our block observer to
+    // process it. The block observer loads the deposit requests from
+    // Emily before it signals that it has observed the block, so Emily
+    // has been told about the expired deposit by then.
+    let chain_tip: BitcoinBlockHash = faucet.generate_blocks(1).pop().unwrap().into();
+
+    ctx.get_bitcoin_client()
+        .wait_for_block(&chain_tip.into(), Duration::from_secs(3))
+        .await
+        .unwrap();
+    wait_for_bitcoin_block_observed(&mut signal_rx).await;
+
+    let db2 = &ctx.storage;
+    assert_eq!(
+        db2.get_bitcoin_canonical_chain_tip().await.unwrap(),
+        Some(chain_tip)
+    );
+
+    let deposit_requests = db2.get_deposit_requests(&chain_tip, 100).await.unwrap();
+
+    assert_eq!(deposit_requests.len(), 1);
+    assert_eq!(
+        deposit_requests[0].outpoint(),
+        valid_setup.deposit_info.outpoint
+    );
+
+    let expired_updates = expired_updates.lock().unwrap().clone();
+    assert_eq!(expired_updates.len(), 1);
+    let expired_outpoint = expired_setup.deposit_info.outpoint;
+    assert_eq!(
+        expired_updates[0].bitcoin_txid,
+        expired_outpoint.txid.to_string()
+    );
+    assert_eq!(
+        expired_updates[0].bitcoin_tx_output_index,
+        expired_outpoint.vout
+    );
+    assert_eq!(expired_updates[0].status, DepositStatus::Expired);
+
+    testing::storage::drop_db(db).await;
+}
+
 /// Integration test for bitcoin and stack blocks link.
 ///
 /// To run this test first run:
@@ -468,9 +623,8 @@ async fn block_observer_stores_donation_and_sbtc_utxos() {
 
     // We need to have run DKG in order for the block observer to know
     // which addresses to filter on.
-    let mut shares: EncryptedDkgShares = Faker.fake_with_rng(&mut rng);
-    shares.aggregate_key = signer.keypair.public_key().into();
-    shares.script_pubkey = shares.aggregate_key.signers_script_pubkey().into();
+    let aggregate_key: PublicKey = signer.keypair.public_key().into();
+    let mut shares: EncryptedDkgShares = aggregate_key.fake_with_rng(&mut rng);
     shares.dkg_shares_status = model::DkgSharesStatus::Verified;
     db.write_encrypted_dkg_shares(&shares).await.unwrap();
 
@@ -564,11 +718,17 @@ async fn block_observer_stores_donation_and_sbtc_utxos() {
             public_key: signers_public_key,
             last_fees: None,
             magic_bytes: [b'T', b'3'],
+            rbf_target_fee_rate: None,
+            anchor_output: false,
         },
         accept_threshold: 4,
         num_signers: 7,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: ctx.config().signer.max_deposits_per_bitcoin_tx.get(),
+        min_batch: None,
+        withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+        min_signer_output: None,
+        shuffle_seed: None,
     };
 
     let mut transactions = requests.construct_transactions().unwrap();
@@ -674,8 +834,8 @@ fn generate_deposit_request<R: rand::Rng>(
 }
 
 /// This tests that a new signer, when they are coming online, can
-/// successfully pick up transactions given a bootstrap aggregate key when
-/// there is a key rotation.
+/// successfully pick up transactions given the signers' scriptPubKeys
+/// when there is a key rotation.
 ///
 /// In this test we start off with a bootstrap aggregate key of the signers
 /// where:
@@ -683,10 +843,9 @@ fn generate_deposit_request<R: rand::Rng>(
 /// 2. There are three sweep transactions confirmed within one bitcoin
 ///    block.
 ///
-/// All three transactions should be picked up so long as we know the
-/// scriptPubKey of the signers bootstrap UTXO. Note that we do not need to
-/// worry about the signers scriptPubKey changing more than once because
-/// that case is impossible.
+/// All three transactions should be picked up in a single pass, regardless
+/// of the order of the transactions, so long as we know both the old and
+/// the new scriptPubKey of the signers.
 #[tokio::test]
 async fn block_observer_picks_up_chained_unordered_sweeps() {
     let (rpc, faucet) = regtest::initialize_blockchain();
@@ -694,7 +853,7 @@ async fn block_observer_picks_up_chained_unordered_sweeps() {
     let mut rng = get_rng();
 
     let signer = Recipient::new_with_rng(AddressType::P2tr, &mut rng);
-    let aggregate_key = signer.keypair.public_key().into();
+    let aggregate_key = PublicKey::from(signer.keypair.public_key());
     let signers_public_key1 = signer.keypair.x_only_public_key().0;
 
     // Start off with some initial UTXOs to work with.
@@ -703,7 +862,8 @@ async fn block_observer_picks_up_chained_unordered_sweeps() {
     faucet.generate_block();
 
     let new_signer = Recipient::new_with_rng(AddressType::P2tr, &mut rng);
-    let signers_public_key2 = PublicKey::from(new_signer.keypair.public_key()).into();
+    let new_aggregate_key = PublicKey::from(new_signer.keypair.public_key());
+    let signers_public_key2 = new_aggregate_key.into();
 
     // Now lets make three deposit transactions, one for each sweep
     // transaction.
@@ -736,11 +896,17 @@ async fn block_observer_picks_up_chained_unordered_sweeps() {
             public_key: signers_public_key2,
             last_fees: None,
             magic_bytes: [b'T', b'3'],
+            rbf_target_fee_rate: None,
+            anchor_output: false,
         },
         accept_threshold: 2,
         num_signers: 3,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: 25,
+        min_batch: None,
+        withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+        min_signer_output: None,
+        shuffle_seed: None,
     };
 
     // By playing around with the votes above, we set things up so that we
@@ -748,18 +914,15 @@ async fn block_observer_picks_up_chained_unordered_sweeps() {
     let mut transactions = requests.construct_transactions().unwrap();
     assert_eq!(transactions.len(), 3);
 
-    // We depend on the fact that `construct_transactions` does not sort
-    // the items. In the first transaction, all inputs are locked by the
-    // second key, while the other ones are locked by the new aggregate
-    // key.
-    //
-    // TODO(1661): Technically the packager tries to sort things right now,
-    // but all requests have the same number of votes against so the
-    // current sorting implementation leaves the elements unchanged (we use
-    // a stable sorting algorithm).
-    testing::set_witness_data(&mut transactions[0], signer.keypair);
-    testing::set_witness_data(&mut transactions[1], new_signer.keypair);
-    testing::set_witness_data(&mut transactions[2], new_signer.keypair);
+    // The first transaction spends the signers' UTXO locked by the first
+    // key, while the other ones spend signers' UTXOs locked by the new
+    // aggregate key. Which deposit lands in which transaction depends on
+    // the deposit outpoints, so we sign each input with whichever key
+    // locks it.
+    let keypairs = [signer.keypair, new_signer.keypair];
+    for unsigned in transactions.iter_mut() {
+        testing::set_witness_data_with_keypairs(unsigned, &keypairs);
+    }
 
     rpc.send_raw_transaction(&transactions[0].tx).unwrap();
     rpc.send_raw_transaction(&transactions[1].tx).unwrap();
@@ -812,9 +975,14 @@ async fn block_observer_picks_up_chained_unordered_sweeps() {
     let mut transactions = block_info.transactions;
     transactions.shuffle(&mut rng);
 
+    let signer_script_pubkeys = HashSet::from([
+        aggregate_key.signers_script_pubkey(),
+        new_aggregate_key.signers_script_pubkey(),
+    ]);
     signer::block_observer::extract_sbtc_transactions(
         &db,
-        Some(aggregate_key),
+        &signer_script_pubkeys,
+        NetworkKind::Regtest.magic_bytes(),
         block_hash,
         &transactions,
     )
@@ -832,13 +1000,31 @@ async fn block_observer_picks_up_chained_unordered_sweeps() {
     assert_eq!(num_rows[1], 3);
     assert_eq!(num_rows[2], 3);
 
+    // The header of each of the signers' OP_RETURN outputs should have
+    // been parsed and stored alongside the output.
+    let op_return_headers = sqlx::query_as::<_, (Option<Vec<u8>>, Option<i16>)>(
+        r#"
+        SELECT magic_bytes, op_return_version
+        FROM bitcoin_tx_outputs
+        WHERE output_type = 'signers_op_return'"#,
+    )
+    .fetch_all(db.pool())
+    .await
+    .unwrap();
+
+    assert_eq!(op_return_headers.len(), 3);
+    for (magic_bytes, version) in op_return_headers {
+        assert_eq!(magic_bytes.as_deref(), Some(b"T3".as_slice()));
+        assert_eq!(version, Some(1));
+    }
+
     testing::storage::drop_db(db).await;
 }
 
 #[test_case::test_case(false, SbtcLimits::unlimited(); "no contracts, default limits")]
-#[test_case::test_case(false, SbtcLimits::new(Some(bitcoin::Amount::from_sat(1_000)), None, None, None, None, None, None, None); "no contracts, total cap limit")]
+#[test_case::test_case(false, SbtcLimits::new(Some(bitcoin::Amount::from_sat(1_000)), None, None, None, None, None, None, None, None); "no contracts, total cap limit")]
 #[test_case::test_case(true, SbtcLimits::unlimited(); "deployed contracts, default limits")]
-#[test_case::test_case(true, SbtcLimits::new(Some(bitcoin::Amount::from_sat(1_000)), None, None, None, None, None, None, None); "deployed contracts, total cap limit")]
+#[test_case::test_case(true, SbtcLimits::new(Some(bitcoin::Amount::from_sat(1_000)), None, None, None, None, None, None, None, None); "deployed contracts, total cap limit")]
 #[tokio::test]
 async fn block_observer_handles_update_limits(deployed: bool, sbtc_limits: SbtcLimits) {
     // We start with the typical setup with a fresh database and context
@@ -1018,7 +1204,7 @@ async fn next_headers_to_process_gets_all_headers() {
     };
 
     let headers = block_observer
-        .next_headers_to_process(chain_tip)
+        .next_headers_to_process(chain_tip, None)
         .await
         .unwrap();
     assert!(!headers.is_empty());
@@ -1040,13 +1226,36 @@ async fn next_headers_to_process_gets_all_headers() {
     let chain_tip = faucet.generate_blocks(1)[0];
 
     let headers2 = block_observer
-        .next_headers_to_process(chain_tip)
+        .next_headers_to_process(chain_tip, None)
         .await
         .unwrap();
     assert_eq!(START_HEIGHT, *headers[0].height);
     assert_eq!(headers2.len(), headers.len() + 1);
     assert_eq!(headers2.last().map(|header| header.hash), Some(chain_tip));
 
+    // Now let's cap the batch size at one header. Each call should return
+    // the header at the lowest unknown height, so as we write the returned
+    // blocks to the database, the calls walk forward one header at a time
+    // until they reach the chain tip.
+    let max_batch = NonZeroUsize::new(1);
+    for expected_header in headers2.iter() {
+        let batch = block_observer
+            .next_headers_to_process(chain_tip, max_batch)
+            .await
+            .unwrap();
+        assert_eq!(batch.as_slice(), std::slice::from_ref(expected_header));
+
+        let block = model::BitcoinBlock::from(batch[0].clone());
+        db.write_bitcoin_block(&block).await.unwrap();
+    }
+
+    // Everything up to the chain tip is known now.
+    let headers = block_observer
+        .next_headers_to_process(chain_tip, max_batch)
+        .await
+        .unwrap();
+    assert!(headers.is_empty());
+
     testing::storage::drop_db(db).await;
 }
 
@@ -1068,7 +1277,7 @@ async fn next_headers_to_process_ignores_known_headers() {
 
     let chain_tip_block_hash = rpc.get_best_block_hash().unwrap();
     let headers = block_observer
-        .next_headers_to_process(chain_tip_block_hash)
+        .next_headers_to_process(chain_tip_block_hash, None)
         .await
         .unwrap();
     let last_header = headers.last().map(|header| header.hash);
@@ -1084,7 +1293,7 @@ async fn next_headers_to_process_ignores_known_headers() {
     // We know about the chain tip now, so we should return an empty vector
     // of next headers to processes.
     let headers = block_observer
-        .next_headers_to_process(chain_tip_block_hash)
+        .next_headers_to_process(chain_tip_block_hash, None)
         .await
         .unwrap();
     assert!(headers.is_empty());
@@ -1115,6 +1324,7 @@ async fn get_signer_set_info_falls_back() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
     let test_data = TestData::generate(&mut rng, &[], &test_params);
     test_data.write_to(&db).await;
@@ -1319,6 +1529,136 @@ async fn block_observer_updates_state_after_observing_bitcoin_block() {
     testing::storage::drop_db(db).await;
 }
 
+/// This test checks that the block observer sends a
+/// [`SignerEvent::BitcoinReorgDetected`] signal when it observes a block
+/// that does not build on the chain tip it has stored.
+#[tokio::test]
+async fn block_observer_signals_bitcoin_reorgs() {
+    let (rpc, faucet) = regtest::initialize_blockchain();
+    let db = testing::storage::new_test_database().await;
+    let ctx = TestContext::builder()
+        .with_storage(db.clone())
+        .with_first_bitcoin_core_client()
+        .with_mocked_emily_client()
+        .with_mocked_stacks_client()
+        .build();
+
+    // We need to set up the stacks client as well. We use it to fetch
+    // information about the Stacks blockchain, so we need to prep it, even
+    // though it isn't necessary for our test.
+    ctx.with_stacks_client(|client| {
+        client
+            .expect_get_tenure_info()
+            .returning(|| Box::pin(std::future::ready(Ok(DUMMY_TENURE_INFO.clone()))));
+        client.expect_get_block().returning(|_| {
+            let response = Ok(NakamotoBlock {
+                header: NakamotoBlockHeader::empty(),
+                txs: Vec::new(),
+            });
+            Box::pin(std::future::ready(response))
+        });
+        client
+            .expect_get_tenure()
+            .returning(|_| Box::pin(std::future::ready(TenureBlocks::nearly_empty())));
+        client.expect_get_pox_info().returning(|| {
+            let response = serde_json::from_str::<RPCPoxInfoData>(GET_POX_INFO_JSON)
+                .map_err(Error::JsonSerialize);
+            Box::pin(std::future::ready(response))
+        });
+        client
+            .expect_get_sortition_info()
+            .returning(|_| Box::pin(std::future::ready(Ok(DUMMY_SORTITION_INFO.clone()))));
+        client
+            .expect_get_current_signer_set_info()
+            .returning(|_| Box::pin(std::future::ready(Ok(None))));
+    })
+    .await;
+
+    ctx.with_emily_client(|client| {
+        client
+            .expect_get_deposits()
+            .returning(|| Box::pin(std::future::ready(Ok(vec![]))));
+
+        client
+            .expect_get_limits()
+            .returning(|| Box::pin(std::future::ready(Ok(SbtcLimits::unlimited()))));
+    })
+    .await;
+
+    let start_flag = Arc::new(AtomicBool::new(false));
+    let flag = start_flag.clone();
+
+    let block_observer = BlockObserver {
+        context: ctx.clone(),
+        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(BITCOIN_CORE_ZMQ_ENDPOINT).await,
+    };
+
+    tokio::spawn(async move {
+        flag.store(true, Ordering::Relaxed);
+        block_observer.run().await
+    });
+
+    while !start_flag.load(Ordering::SeqCst) {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // Let the block observer store a new chain tip.
+    let orphaned_tip = faucet.generate_block();
+
+    ctx.wait_for_signal(Duration::from_secs(3), |signal| {
+        matches!(
+            signal,
+            SignerSignal::Event(SignerEvent::BitcoinBlockObserved)
+        )
+    })
+    .await
+    .unwrap();
+
+    let orphaned_tip_ref = db
+        .get_bitcoin_canonical_chain_tip_ref()
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(orphaned_tip_ref.block_hash, orphaned_tip.into());
+    let common_ancestor = rpc.get_block_header(&orphaned_tip).unwrap().prev_blockhash;
+
+    // Now we fork by invalidating the tip and mining two blocks on top of
+    // its parent. We mine to a fresh address so that the new blocks
+    // differ from the invalidated one.
+    let mut signal_rx = ctx.get_signal_receiver();
+    rpc.invalidate_block(&orphaned_tip).unwrap();
+
+    let miner = Recipient::new(AddressType::P2tr);
+    rpc.generate_to_address(2, &miner.address).unwrap();
+
+    let event = tokio::time::timeout(Duration::from_secs(3), async {
+        loop {
+            if let Ok(SignerSignal::Event(event @ SignerEvent::BitcoinReorgDetected { .. })) =
+                signal_rx.recv().await
+            {
+                break event;
+            }
+        }
+    })
+    .await
+    .unwrap();
+
+    let SignerEvent::BitcoinReorgDetected {
+        common_ancestor: ancestor_ref,
+        orphaned_tip: tip_ref,
+        depth,
+    } = event
+    else {
+        unreachable!();
+    };
+    assert_eq!(tip_ref, orphaned_tip_ref);
+    assert_eq!(ancestor_ref.block_hash, common_ancestor.into());
+    assert_eq!(ancestor_ref.block_height + 1, orphaned_tip_ref.block_height);
+    assert_eq!(depth, 1);
+
+    testing::storage::drop_db(db).await;
+}
+
 /// This test checks that the block observer correctly update the state of
 /// pending DKG shares once they exit the verification window
 #[tokio::test]
@@ -1448,7 +1788,10 @@ async fn block_observer_updates_dkg_shares_after_observing_bitcoin_block() {
     assert_eq!(storage.get_encrypted_dkg_shares_count().await.unwrap(), 0);
 
     // Signers and coordinator should allow DKG
-    assert!(should_coordinate_dkg(&ctx, &db_chain_tip).await.unwrap());
+    assert_eq!(
+        should_coordinate_dkg(&ctx, &db_chain_tip).await.unwrap(),
+        DkgCoordinationDecision::Allowed
+    );
     assert!(assert_allow_dkg_begin(&ctx, &db_chain_tip).await.is_ok());
 
     // Okay now let's add in some DKG shares into the database.
@@ -1465,7 +1808,10 @@ async fn block_observer_updates_dkg_shares_after_observing_bitcoin_block() {
     prevent_dkg_on_changed_signer_set_info(&ctx, dkg_shares.aggregate_key);
 
     // Signers and coordinator should NOT allow DKG
-    assert!(!should_coordinate_dkg(&ctx, &db_chain_tip).await.unwrap());
+    assert_eq!(
+        should_coordinate_dkg(&ctx, &db_chain_tip).await.unwrap(),
+        DkgCoordinationDecision::BlockedPendingVerification
+    );
     assert!(assert_allow_dkg_begin(&ctx, &db_chain_tip).await.is_err());
 
     // While in the verification window, we expect the share to stay in pending
@@ -1498,7 +1844,10 @@ async fn block_observer_updates_dkg_shares_after_observing_bitcoin_block() {
         assert_eq!(storage.get_encrypted_dkg_shares_count().await.unwrap(), 1);
 
         // Signers and coordinator should NOT allow DKG
-        assert!(!should_coordinate_dkg(&ctx, &db_chain_tip).await.unwrap());
+        assert_eq!(
+            should_coordinate_dkg(&ctx, &db_chain_tip).await.unwrap(),
+            DkgCoordinationDecision::BlockedPendingVerification
+        );
         assert!(assert_allow_dkg_begin(&ctx, &db_chain_tip).await.is_err());
     }
 
@@ -1533,7 +1882,10 @@ async fn block_observer_updates_dkg_shares_after_observing_bitcoin_block() {
     assert_eq!(storage.get_encrypted_dkg_shares_count().await.unwrap(), 0);
 
     // Signers and coordinator should allow again DKG
-    assert!(should_coordinate_dkg(&ctx, &db_chain_tip).await.unwrap());
+    assert_eq!(
+        should_coordinate_dkg(&ctx, &db_chain_tip).await.unwrap(),
+        DkgCoordinationDecision::Allowed
+    );
     assert!(assert_allow_dkg_begin(&ctx, &db_chain_tip).await.is_ok());
 
     testing::storage::drop_db(db).await;
@@ -1630,9 +1982,8 @@ async fn block_observer_ignores_coinbase() {
 
     // We need to have run DKG in order for the block observer to know
     // which addresses to filter on.
-    let mut shares: EncryptedDkgShares = Faker.fake_with_rng(&mut rng);
-    shares.aggregate_key = signer.keypair.public_key().into();
-    shares.script_pubkey = shares.aggregate_key.signers_script_pubkey().into();
+    let aggregate_key: PublicKey = signer.keypair.public_key().into();
+    let mut shares: EncryptedDkgShares = aggregate_key.fake_with_rng(&mut rng);
     shares.dkg_shares_status = model::DkgSharesStatus::Verified;
     db.write_encrypted_dkg_shares(&shares).await.unwrap();
 
@@ -1737,8 +2088,12 @@ async fn block_observer_ignores_coinbase() {
         deposit_script: deposit_request.deposit_script.clone(),
     };
     let bitcoin_client = ctx.get_bitcoin_client();
-    let validate_result =
-        signer::block_observer::DepositRequestValidator::validate(&request, &bitcoin_client, false);
+    let validate_result = signer::block_observer::DepositRequestValidator::validate(
+        &request,
+        &bitcoin_client,
+        false,
+        None,
+    );
     match validate_result.await {
         Err(Error::BitcoinTxCoinbase(tx)) if tx == deposit_request.outpoint.txid => {}
         _ => panic!("Expected a err, got something else"),
@@ -1747,6 +2102,131 @@ async fn block_observer_ignores_coinbase() {
     testing::storage::drop_db(db).await;
 }
 
+/// Deposits locked to a key other than the signers' current aggregate key
+/// cannot be swept by the current signers, so
+/// [`DepositRequestValidator::validate`] flags them when given the
+/// current aggregate key.
+#[tokio::test]
+async fn validate_flags_deposits_locked_to_stale_signer_key() {
+    let (rpc, faucet) = regtest::initialize_blockchain();
+    let ctx = TestContext::builder()
+        .with_in_memory_storage()
+        .with_first_bitcoin_core_client()
+        .with_mocked_emily_client()
+        .with_mocked_stacks_client()
+        .build();
+
+    let stale_signer = Recipient::new(AddressType::P2tr);
+    let current_signer = Recipient::new(AddressType::P2tr);
+    let stale_key = stale_signer.keypair.x_only_public_key().0;
+    let current_key = current_signer.keypair.x_only_public_key().0;
+
+    // Make a deposit that is locked to the stale key and get it confirmed.
+    let depositor = Recipient::new(AddressType::P2tr);
+    faucet.send_to(50_000_000, &depositor.address);
+    faucet.generate_block();
+
+    let depositor_utxo = depositor.get_utxos(rpc, None).pop().unwrap();
+    let (deposit_tx, deposit_request, _) =
+        make_deposit_request(&depositor, 10_000_000, depositor_utxo, 5_000_000, stale_key);
+    rpc.send_raw_transaction(&deposit_tx).unwrap();
+    faucet.generate_block();
+
+    let request = CreateDepositRequest {
+        outpoint: deposit_request.outpoint,
+        reclaim_script: deposit_request.reclaim_script.clone(),
+        deposit_script: deposit_request.deposit_script.clone(),
+    };
+    let bitcoin_client = ctx.get_bitcoin_client();
+
+    // Without an aggregate key to check against, the deposit is valid.
+    let deposit = request
+        .validate(&bitcoin_client, false, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(deposit.info.signers_public_key, stale_key);
+
+    // The same goes when we check against the key that it is locked to.
+    let deposit = request
+        .validate(&bitcoin_client, false, Some(stale_key.into()))
+        .await
+        .unwrap();
+    assert!(deposit.is_some());
+
+    // But it is flagged when checked against the current aggregate key.
+    let result = request
+        .validate(&bitcoin_client, false, Some(current_key.into()))
+        .await;
+    match result {
+        Err(Error::DepositWrongSignerKey(outpoint, actual, expected)) => {
+            assert_eq!(outpoint, deposit_request.outpoint);
+            assert_eq!(actual, stale_key.into());
+            assert_eq!(expected, current_key.into());
+        }
+        _ => panic!("expected a DepositWrongSignerKey error, got {result:?}"),
+    }
+}
+
+/// Check that [`testing::e2e::run_deposit_sweep_cycle`] leaves us with a
+/// stored deposit request whose funds are now in a UTXO controlled by
+/// the signer.
+#[test_log::test(tokio::test)]
+async fn deposit_sweep_cycle_moves_deposit_into_signer_utxo() {
+    let (rpc, faucet) = regtest::initialize_blockchain();
+    let db = testing::storage::new_test_database().await;
+    let ctx = TestContext::builder()
+        .with_storage(db.clone())
+        .with_first_bitcoin_core_client()
+        .with_mocked_emily_client()
+        .with_mocked_stacks_client()
+        .build();
+
+    let amount = 1_000_000;
+    let cycle = testing::e2e::run_deposit_sweep_cycle(&ctx, faucet, amount).await;
+
+    // The deposit request should have been validated and stored.
+    let deposit_request = db
+        .get_deposit_request(
+            &cycle.deposit_outpoint.txid.into(),
+            cycle.deposit_outpoint.vout,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(deposit_request.amount, amount);
+
+    // The deposit has been spent by the sweep, and the first output of
+    // the sweep is the new signer UTXO, locked to the signer's key.
+    let deposit_txout = rpc
+        .get_tx_out(
+            &cycle.deposit_outpoint.txid,
+            cycle.deposit_outpoint.vout,
+            None,
+        )
+        .unwrap();
+    assert!(deposit_txout.is_none());
+
+    assert_eq!(
+        cycle.signer_utxo.outpoint,
+        OutPoint::new(cycle.sweep_txid, 0)
+    );
+    let signer_txout = rpc.get_tx_out(&cycle.sweep_txid, 0, None).unwrap().unwrap();
+    assert_eq!(signer_txout.value.to_sat(), cycle.signer_utxo.amount);
+    assert_eq!(
+        signer_txout.script_pub_key.hex,
+        cycle.signer.script_pubkey.to_bytes()
+    );
+
+    // The signer's UTXO holds the deposited funds, less fees.
+    let utxos = cycle.signer.get_utxos(rpc, None);
+    assert_eq!(utxos.len(), 1);
+    assert_eq!(utxos[0].txid, cycle.sweep_txid);
+    assert!(cycle.signer_utxo.amount > 100_000_000 + amount - deposit_request.max_fee);
+
+    testing::storage::drop_db(db).await;
+}
+
 fn make_coinbase_deposit_request(
     rpc: &bitcoincore_rpc::Client,
     max_fee: u64,
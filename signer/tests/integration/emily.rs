@@ -82,6 +82,7 @@ where
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
     let test_data = TestData::generate(rng, &signer_keys, &test_model_parameters);
     test_data.write_to(&storage).await;
@@ -151,7 +152,7 @@ async fn deposit_flow() {
         .await
         .expect("Wiping Emily database in test setup failed.");
 
-    let context = TestContext::builder()
+    let mut context = TestContext::builder()
         .with_storage(db.clone())
         .with_mocked_bitcoin_client()
         .with_stacks_client(stacks_client.clone())
@@ -382,6 +383,7 @@ async fn deposit_flow() {
 
     // Get the private key of the coordinator of the signer set.
     let private_key = select_coordinator(&deposit_block_hash.into(), &signer_info);
+    context.config_mut().signer.private_key = private_key;
 
     // Bootstrap the tx coordinator event loop
     context.state().set_sbtc_contracts_deployed();
@@ -705,6 +707,7 @@ async fn test_get_deposits_returns_pending_and_accepted() {
             fulfillment: None,
             status: DepositStatus::Accepted,
             status_message: "accepted".to_string(),
+            reclaim_txid: None,
             replaced_by_tx: None,
         })
         .collect();
@@ -11,6 +11,7 @@ mod contracts;
 mod emily;
 mod postgres;
 mod rbf;
+mod reconciliation;
 mod request_decider;
 mod rotate_keys;
 mod setup;
@@ -5,6 +5,7 @@ use std::io::Read as _;
 use std::ops::Deref;
 use std::time::Duration;
 
+use bitcoin::Amount;
 use bitcoin::hashes::Hash as _;
 use blockstack_lib::chainstate::nakamoto::NakamotoBlock;
 use blockstack_lib::clarity::vm::Value as ClarityValue;
@@ -33,6 +34,7 @@ use strum::IntoEnumIterator;
 use time::OffsetDateTime;
 
 use signer::bitcoin::MockBitcoinInteract;
+use signer::bitcoin::utxo::SignerUtxo;
 use signer::bitcoin::validation::DepositConfirmationStatus;
 use signer::context::Context;
 use signer::emily_client::MockEmilyInteract;
@@ -70,6 +72,7 @@ use signer::storage::model::WithdrawalSigner;
 use signer::storage::postgres::PgStore;
 use signer::testing;
 use signer::testing::dummy::SignerSetConfig;
+use signer::testing::storage::model::SIMULATED_SWEEP_FEE;
 use signer::testing::storage::model::TestData;
 use signer::testing::wallet::ContractCallWrapper;
 
@@ -99,6 +102,7 @@ async fn should_be_able_to_query_bitcoin_blocks() {
         num_withdraw_requests_per_block: 5,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, 7);
@@ -332,6 +336,7 @@ async fn should_return_the_same_pending_deposit_requests_as_in_memory_store() {
         num_withdraw_requests_per_block: 5,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
     let test_data = TestData::generate(&mut rng, &signer_set, &test_model_params);
@@ -376,6 +381,387 @@ async fn should_return_the_same_pending_deposit_requests_as_in_memory_store() {
     signer::testing::storage::drop_db(pg_store).await;
 }
 
+/// This ensures that the postgres store and the in memory stores return
+/// the same deposit requests, along with the height of the canonical
+/// block that confirmed them, from [`DbRead::get_deposit_requests`].
+#[tokio::test]
+async fn get_deposit_requests_includes_confirmed_at_height() {
+    let pg_store = testing::storage::new_test_database().await;
+    let in_memory_store = storage::memory::Store::new_shared();
+
+    let mut rng = get_rng();
+
+    let num_signers = 7;
+    let context_window = 9;
+    let test_model_params = testing::storage::model::Params {
+        num_bitcoin_blocks: 20,
+        num_stacks_blocks_per_bitcoin_block: 3,
+        num_deposit_requests_per_block: 5,
+        num_withdraw_requests_per_block: 5,
+        num_signers_per_request: 0,
+        consecutive_blocks: false,
+        fork_points: Vec::new(),
+    };
+    let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
+    let test_data = TestData::generate(&mut rng, &signer_set, &test_model_params);
+
+    test_data.write_to(&in_memory_store).await;
+    test_data.write_to(&pg_store).await;
+
+    let chain_tip = in_memory_store
+        .get_bitcoin_canonical_chain_tip_ref()
+        .await
+        .expect("failed to get canonical chain tip")
+        .expect("no chain tip");
+
+    let mut deposit_requests = in_memory_store
+        .get_deposit_requests(&chain_tip.block_hash, context_window)
+        .await
+        .expect("failed to get deposit requests");
+    deposit_requests.sort();
+    assert!(!deposit_requests.is_empty());
+
+    let mut pg_deposit_requests = pg_store
+        .get_deposit_requests(&chain_tip.block_hash, context_window)
+        .await
+        .expect("failed to get deposit requests");
+    pg_deposit_requests.sort();
+
+    assert_eq!(deposit_requests, pg_deposit_requests);
+
+    // Every returned request was confirmed by a block within the context
+    // window, so the reported height must fall within the window too.
+    let min_height = chain_tip
+        .block_height
+        .saturating_sub(context_window as u64 - 1);
+    for deposit_request in pg_deposit_requests {
+        let height = deposit_request
+            .confirmed_at_height
+            .expect("missing confirmation height");
+        assert!(height <= chain_tip.block_height);
+        assert!(height >= min_height);
+    }
+
+    signer::testing::storage::drop_db(pg_store).await;
+}
+
+/// Check that [`DbRead::get_deposit_requests_by_recipient`] only returns
+/// the deposit requests for the given recipient that were confirmed on
+/// the canonical bitcoin blockchain, and that both stores agree.
+#[tokio::test]
+async fn get_deposit_requests_by_recipient_filters_by_recipient() {
+    let pg_store = testing::storage::new_test_database().await;
+    let in_memory_store = storage::memory::Store::new_shared();
+    let mut rng = get_rng();
+
+    let test_model_params = testing::storage::model::Params {
+        num_bitcoin_blocks: 10,
+        num_stacks_blocks_per_bitcoin_block: 0,
+        num_deposit_requests_per_block: 0,
+        num_withdraw_requests_per_block: 0,
+        num_signers_per_request: 0,
+        consecutive_blocks: false,
+        fork_points: Vec::new(),
+    };
+    let test_data = TestData::generate(&mut rng, &[], &test_model_params);
+    test_data.write_to(&in_memory_store).await;
+    test_data.write_to(&pg_store).await;
+
+    let chain_tip = pg_store
+        .get_bitcoin_canonical_chain_tip_ref()
+        .await
+        .unwrap()
+        .unwrap();
+    let chain_tip_block = test_data.get_bitcoin_block(&chain_tip.block_hash).unwrap();
+    let parent_block = test_data
+        .get_bitcoin_block(&chain_tip_block.parent_hash)
+        .unwrap();
+    // This block is not on the canonical bitcoin blockchain.
+    let orphaned_block: model::BitcoinBlock = Faker.fake_with_rng(&mut rng);
+
+    let recipient1: model::StacksPrincipal = Faker.fake_with_rng(&mut rng);
+    let recipient2: model::StacksPrincipal = Faker.fake_with_rng(&mut rng);
+    assert_ne!(recipient1, recipient2);
+
+    let deposits = [
+        (&recipient1, &chain_tip_block),
+        (&recipient1, &parent_block),
+        (&recipient1, &orphaned_block),
+        (&recipient2, &chain_tip_block),
+        (&recipient2, &parent_block),
+    ];
+
+    let mut expected = Vec::new();
+    for (recipient, block) in deposits {
+        let mut deposit: model::DepositRequest = Faker.fake_with_rng(&mut rng);
+        deposit.recipient = recipient.clone();
+        let tx_ref = model::BitcoinTxRef {
+            txid: deposit.txid,
+            block_hash: block.block_hash,
+        };
+
+        pg_store.write_bitcoin_block(block).await.unwrap();
+        pg_store.write_deposit_request(&deposit).await.unwrap();
+        pg_store.write_bitcoin_transaction(&tx_ref).await.unwrap();
+        in_memory_store.write_bitcoin_block(block).await.unwrap();
+        in_memory_store
+            .write_deposit_request(&deposit)
+            .await
+            .unwrap();
+        in_memory_store
+            .write_bitcoin_transaction(&tx_ref)
+            .await
+            .unwrap();
+
+        if recipient == &recipient1 && block.block_hash != orphaned_block.block_hash {
+            deposit.confirmed_at_height = Some(block.block_height);
+            expected.push(deposit);
+        }
+    }
+
+    // The deposits are returned with the most recently confirmed first.
+    expected.sort_by_key(|deposit| std::cmp::Reverse(deposit.confirmed_at_height));
+
+    let pg_deposits = pg_store
+        .get_deposit_requests_by_recipient(&recipient1, &chain_tip.block_hash, 10)
+        .await
+        .unwrap();
+    assert_eq!(pg_deposits, expected);
+
+    let mem_deposits = in_memory_store
+        .get_deposit_requests_by_recipient(&recipient1, &chain_tip.block_hash, 10)
+        .await
+        .unwrap();
+    assert_eq!(mem_deposits, expected);
+
+    // The limit caps the number of returned deposits.
+    let pg_deposits = pg_store
+        .get_deposit_requests_by_recipient(&recipient1, &chain_tip.block_hash, 1)
+        .await
+        .unwrap();
+    assert_eq!(pg_deposits, expected[..1]);
+
+    // A recipient without any deposits gets nothing back.
+    let recipient3: model::StacksPrincipal = Faker.fake_with_rng(&mut rng);
+    let pg_deposits = pg_store
+        .get_deposit_requests_by_recipient(&recipient3, &chain_tip.block_hash, 10)
+        .await
+        .unwrap();
+    assert!(pg_deposits.is_empty());
+
+    signer::testing::storage::drop_db(pg_store).await;
+}
+
+/// Check that [`DbRead::get_accepted_deposit_requests`] only returns the
+/// deposit requests that have been accepted by at least the given number
+/// of signers, and that both stores agree.
+#[tokio::test]
+async fn get_accepted_deposit_requests_filters_by_threshold() {
+    let pg_store = testing::storage::new_test_database().await;
+    let in_memory_store = storage::memory::Store::new_shared();
+    let mut rng = get_rng();
+
+    let test_model_params = testing::storage::model::Params {
+        num_bitcoin_blocks: 10,
+        num_stacks_blocks_per_bitcoin_block: 0,
+        num_deposit_requests_per_block: 0,
+        num_withdraw_requests_per_block: 0,
+        num_signers_per_request: 0,
+        consecutive_blocks: false,
+        fork_points: Vec::new(),
+    };
+    let test_data = TestData::generate(&mut rng, &[], &test_model_params);
+    test_data.write_to(&in_memory_store).await;
+    test_data.write_to(&pg_store).await;
+
+    let chain_tip = pg_store
+        .get_bitcoin_canonical_chain_tip_ref()
+        .await
+        .unwrap()
+        .unwrap();
+    let chain_tip_block = test_data.get_bitcoin_block(&chain_tip.block_hash).unwrap();
+
+    let accept_threshold = 2;
+
+    // Each deposit gets a different number of accepting signers, along
+    // with one signer that rejects it, which should never be counted.
+    let mut expected = Vec::new();
+    for num_accepts in 0..=4 {
+        let mut deposit: model::DepositRequest = Faker.fake_with_rng(&mut rng);
+        let tx_ref = model::BitcoinTxRef {
+            txid: deposit.txid,
+            block_hash: chain_tip_block.block_hash,
+        };
+
+        pg_store.write_deposit_request(&deposit).await.unwrap();
+        pg_store.write_bitcoin_transaction(&tx_ref).await.unwrap();
+        in_memory_store
+            .write_deposit_request(&deposit)
+            .await
+            .unwrap();
+        in_memory_store
+            .write_bitcoin_transaction(&tx_ref)
+            .await
+            .unwrap();
+
+        let rejecting_signer = std::iter::once(false);
+        let accepting_signers = std::iter::repeat_n(true, num_accepts);
+        for can_accept in rejecting_signer.chain(accepting_signers) {
+            let decision = model::DepositSigner {
+                txid: deposit.txid,
+                output_index: deposit.output_index,
+                signer_pub_key: Faker.fake_with_rng(&mut rng),
+                can_accept,
+                can_sign: true,
+            };
+            pg_store
+                .write_deposit_signer_decision(&decision)
+                .await
+                .unwrap();
+            in_memory_store
+                .write_deposit_signer_decision(&decision)
+                .await
+                .unwrap();
+        }
+
+        if num_accepts >= accept_threshold as usize {
+            deposit.confirmed_at_height = Some(chain_tip_block.block_height);
+            expected.push(deposit);
+        }
+    }
+
+    // All deposits were confirmed in the same block, so we sort them to
+    // compare the results.
+    let sort_key = |deposit: &model::DepositRequest| (deposit.txid, deposit.output_index);
+    expected.sort_by_key(sort_key);
+
+    let mut pg_deposits = pg_store
+        .get_accepted_deposit_requests(&chain_tip.block_hash, accept_threshold, 10)
+        .await
+        .unwrap();
+    pg_deposits.sort_by_key(sort_key);
+    assert_eq!(pg_deposits.len(), 3);
+    assert_eq!(pg_deposits, expected);
+
+    let mut mem_deposits = in_memory_store
+        .get_accepted_deposit_requests(&chain_tip.block_hash, accept_threshold, 10)
+        .await
+        .unwrap();
+    mem_deposits.sort_by_key(sort_key);
+    assert_eq!(mem_deposits, expected);
+
+    // The limit caps the number of returned deposits.
+    let pg_deposits = pg_store
+        .get_accepted_deposit_requests(&chain_tip.block_hash, accept_threshold, 1)
+        .await
+        .unwrap();
+    assert_eq!(pg_deposits.len(), 1);
+    assert!(expected.contains(&pg_deposits[0]));
+
+    signer::testing::storage::drop_db(pg_store).await;
+}
+
+/// Check that [`DbRead::get_accepted_deposit_requests`] returns every
+/// confirmed deposit request for a threshold of zero, including ones
+/// without any votes or with only rejecting votes, and that both stores
+/// agree.
+#[tokio::test]
+async fn get_accepted_deposit_requests_zero_threshold_returns_all_confirmed() {
+    let pg_store = testing::storage::new_test_database().await;
+    let in_memory_store = storage::memory::Store::new_shared();
+    let mut rng = get_rng();
+
+    let test_model_params = testing::storage::model::Params {
+        num_bitcoin_blocks: 10,
+        num_stacks_blocks_per_bitcoin_block: 0,
+        num_deposit_requests_per_block: 0,
+        num_withdraw_requests_per_block: 0,
+        num_signers_per_request: 0,
+        consecutive_blocks: false,
+        fork_points: Vec::new(),
+    };
+    let test_data = TestData::generate(&mut rng, &[], &test_model_params);
+    test_data.write_to(&in_memory_store).await;
+    test_data.write_to(&pg_store).await;
+
+    let chain_tip = pg_store
+        .get_bitcoin_canonical_chain_tip_ref()
+        .await
+        .unwrap()
+        .unwrap();
+    let chain_tip_block = test_data.get_bitcoin_block(&chain_tip.block_hash).unwrap();
+
+    // The votes on each deposit: none at all, a single rejection and a
+    // single acceptance.
+    let mut expected = Vec::new();
+    for votes in [&[][..], &[false][..], &[true][..]] {
+        let mut deposit: model::DepositRequest = Faker.fake_with_rng(&mut rng);
+        let tx_ref = model::BitcoinTxRef {
+            txid: deposit.txid,
+            block_hash: chain_tip_block.block_hash,
+        };
+
+        pg_store.write_deposit_request(&deposit).await.unwrap();
+        pg_store.write_bitcoin_transaction(&tx_ref).await.unwrap();
+        in_memory_store
+            .write_deposit_request(&deposit)
+            .await
+            .unwrap();
+        in_memory_store
+            .write_bitcoin_transaction(&tx_ref)
+            .await
+            .unwrap();
+
+        for &can_accept in votes {
+            let decision = model::DepositSigner {
+                txid: deposit.txid,
+                output_index: deposit.output_index,
+                signer_pub_key: Faker.fake_with_rng(&mut rng),
+                can_accept,
+                can_sign: true,
+            };
+            pg_store
+                .write_deposit_signer_decision(&decision)
+                .await
+                .unwrap();
+            in_memory_store
+                .write_deposit_signer_decision(&decision)
+                .await
+                .unwrap();
+        }
+
+        deposit.confirmed_at_height = Some(chain_tip_block.block_height);
+        expected.push(deposit);
+    }
+
+    // A deposit that has not been confirmed is never returned.
+    let unconfirmed: model::DepositRequest = Faker.fake_with_rng(&mut rng);
+    pg_store.write_deposit_request(&unconfirmed).await.unwrap();
+    in_memory_store
+        .write_deposit_request(&unconfirmed)
+        .await
+        .unwrap();
+
+    let sort_key = |deposit: &model::DepositRequest| (deposit.txid, deposit.output_index);
+    expected.sort_by_key(sort_key);
+
+    let mut pg_deposits = pg_store
+        .get_accepted_deposit_requests(&chain_tip.block_hash, 0, 10)
+        .await
+        .unwrap();
+    pg_deposits.sort_by_key(sort_key);
+    assert_eq!(pg_deposits, expected);
+
+    let mut mem_deposits = in_memory_store
+        .get_accepted_deposit_requests(&chain_tip.block_hash, 0, 10)
+        .await
+        .unwrap();
+    mem_deposits.sort_by_key(sort_key);
+    assert_eq!(mem_deposits, expected);
+
+    signer::testing::storage::drop_db(pg_store).await;
+}
+
 /// Test that [`DbRead::get_pending_deposit_requests`] returns deposit
 /// requests that do not have a vote on them yet.
 #[tokio::test]
@@ -505,6 +891,7 @@ async fn should_return_the_same_pending_withdraw_requests_as_in_memory_store() {
         num_withdraw_requests_per_block: 1,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -564,6 +951,79 @@ async fn should_return_the_same_pending_withdraw_requests_as_in_memory_store() {
     signer::testing::storage::drop_db(pg_store).await;
 }
 
+/// This tests that [`DbRead::get_stacks_chain_tip`] walks
+/// back to the nearest bitcoin ancestor with anchored stacks blocks when
+/// the bitcoin tip itself has none, in both the postgres and in memory
+/// stores.
+#[tokio::test]
+async fn get_stacks_chain_tip_falls_back_to_ancestor() {
+    let pg_store = testing::storage::new_test_database().await;
+    let in_memory_store = storage::memory::Store::new_shared();
+    let mut rng = get_rng();
+
+    // The ancestor has a stacks block anchored to it while the bitcoin
+    // tip, its child, has none.
+    let ancestor: BitcoinBlock = Faker.fake_with_rng(&mut rng);
+    let bitcoin_tip = BitcoinBlock {
+        block_hash: Faker.fake_with_rng(&mut rng),
+        block_height: ancestor.block_height + 1,
+        parent_hash: ancestor.block_hash,
+    };
+    let stacks_block = model::StacksBlock {
+        bitcoin_anchor: ancestor.block_hash,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    // A bitcoin block without any stacks blocks anchored to it or to any
+    // of its ancestors.
+    let unanchored: BitcoinBlock = Faker.fake_with_rng(&mut rng);
+
+    pg_store.write_bitcoin_block(&ancestor).await.unwrap();
+    pg_store.write_bitcoin_block(&bitcoin_tip).await.unwrap();
+    pg_store.write_bitcoin_block(&unanchored).await.unwrap();
+    pg_store.write_stacks_block(&stacks_block).await.unwrap();
+
+    in_memory_store
+        .write_bitcoin_block(&ancestor)
+        .await
+        .unwrap();
+    in_memory_store
+        .write_bitcoin_block(&bitcoin_tip)
+        .await
+        .unwrap();
+    in_memory_store
+        .write_bitcoin_block(&unanchored)
+        .await
+        .unwrap();
+    in_memory_store
+        .write_stacks_block(&stacks_block)
+        .await
+        .unwrap();
+
+    let pg_tip = pg_store
+        .get_stacks_chain_tip(&bitcoin_tip.block_hash)
+        .await
+        .unwrap();
+    let in_memory_tip = in_memory_store
+        .get_stacks_chain_tip(&bitcoin_tip.block_hash)
+        .await
+        .unwrap();
+    assert_eq!(pg_tip, Some(stacks_block.clone()));
+    assert_eq!(in_memory_tip, Some(stacks_block));
+
+    let pg_tip = pg_store
+        .get_stacks_chain_tip(&unanchored.block_hash)
+        .await
+        .unwrap();
+    let in_memory_tip = in_memory_store
+        .get_stacks_chain_tip(&unanchored.block_hash)
+        .await
+        .unwrap();
+    assert_eq!(pg_tip, None);
+    assert_eq!(in_memory_tip, None);
+
+    signer::testing::storage::drop_db(pg_store).await;
+}
+
 /// This ensures that the postgres store and the in memory stores returns equivalent results
 /// when fetching pending accepted deposit requests
 #[tokio::test]
@@ -582,6 +1042,7 @@ async fn should_return_the_same_pending_accepted_deposit_requests_as_in_memory_s
         num_withdraw_requests_per_block: 5,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
     let threshold = 4;
 
@@ -698,6 +1159,200 @@ async fn should_not_return_swept_deposits_as_pending_accepted() {
     signer::testing::storage::drop_db(db).await;
 }
 
+/// This tests that the unswept deposit value only sums the accepted
+/// deposits that have not been swept on the canonical chain.
+#[tokio::test]
+async fn get_unswept_deposit_value_excludes_swept_deposits() {
+    let db = testing::storage::new_test_database().await;
+    let mem = storage::memory::Store::new_shared();
+    let mut rng = get_rng();
+
+    let context_window = 10;
+    let threshold = 2;
+
+    let parent = BitcoinBlock {
+        block_hash: Faker.fake_with_rng(&mut rng),
+        block_height: 100u64.into(),
+        parent_hash: Faker.fake_with_rng(&mut rng),
+    };
+    let chain_tip = BitcoinBlock {
+        block_hash: Faker.fake_with_rng(&mut rng),
+        block_height: parent.block_height + 1,
+        parent_hash: parent.block_hash,
+    };
+    for block in [&parent, &chain_tip] {
+        db.write_bitcoin_block(block).await.unwrap();
+        mem.write_bitcoin_block(block).await.unwrap();
+    }
+
+    // We create three deposits that are confirmed in the parent block and
+    // accepted by enough signers.
+    let signer_keys: Vec<PublicKey> = (0..threshold)
+        .map(|_| Faker.fake_with_rng(&mut rng))
+        .collect();
+    let mut deposits = Vec::new();
+    for amount in [100_000, 200_000, 400_000] {
+        let mut deposit: model::DepositRequest = Faker.fake_with_rng(&mut rng);
+        deposit.amount = amount;
+        deposit.lock_time = u16::MAX as u32;
+
+        let tx_ref = model::BitcoinTxRef {
+            txid: deposit.txid,
+            block_hash: parent.block_hash,
+        };
+        db.write_bitcoin_transaction(&tx_ref).await.unwrap();
+        mem.write_bitcoin_transaction(&tx_ref).await.unwrap();
+        db.write_deposit_request(&deposit).await.unwrap();
+        mem.write_deposit_request(&deposit).await.unwrap();
+
+        for signer_pub_key in signer_keys.iter() {
+            let decision = model::DepositSigner {
+                txid: deposit.txid,
+                output_index: deposit.output_index,
+                signer_pub_key: *signer_pub_key,
+                can_accept: true,
+                can_sign: true,
+            };
+            db.write_deposit_signer_decision(&decision).await.unwrap();
+            mem.write_deposit_signer_decision(&decision).await.unwrap();
+        }
+        deposits.push(deposit);
+    }
+
+    let chain_tip_hash = chain_tip.block_hash;
+    let value = db
+        .get_unswept_deposit_value(&chain_tip_hash, context_window, threshold)
+        .await
+        .unwrap();
+    assert_eq!(value, Amount::from_sat(700_000));
+
+    // Now sweep the last deposit in the chain tip block.
+    let swept = &deposits[2];
+    let mut prevout: model::TxPrevout = Faker.fake_with_rng(&mut rng);
+    prevout.prevout_txid = swept.txid;
+    prevout.prevout_output_index = swept.output_index;
+    prevout.prevout_type = model::TxPrevoutType::Deposit;
+
+    let tx_ref = model::BitcoinTxRef {
+        txid: prevout.txid,
+        block_hash: chain_tip.block_hash,
+    };
+    db.write_bitcoin_transaction(&tx_ref).await.unwrap();
+    db.write_tx_prevout(&prevout).await.unwrap();
+    mem.write_bitcoin_transaction(&tx_ref).await.unwrap();
+    mem.write_tx_prevout(&prevout).await.unwrap();
+
+    let value = db
+        .get_unswept_deposit_value(&chain_tip_hash, context_window, threshold)
+        .await
+        .unwrap();
+    assert_eq!(value, Amount::from_sat(300_000));
+
+    let mem_value = mem
+        .get_unswept_deposit_value(&chain_tip_hash, context_window, threshold)
+        .await
+        .unwrap();
+    assert_eq!(mem_value, value);
+
+    signer::testing::storage::drop_db(db).await;
+}
+
+/// Check that `get_pending_deposit_amount_for_recipient` sums the
+/// recipient's deposits confirmed on the canonical bitcoin blockchain
+/// within the context window that have not been swept, and that the
+/// postgres and in-memory stores agree.
+#[tokio::test]
+async fn get_pending_deposit_amount_for_recipient_sums_unswept_canonical_deposits() {
+    let db = testing::storage::new_test_database().await;
+    let mem = storage::memory::Store::new_shared();
+    let mut rng = get_rng();
+
+    let [root, a1, a2, b1] = forked_bitcoin_chain(&mut rng);
+    for block in [&root, &a1, &a2, &b1] {
+        db.write_bitcoin_block(block).await.unwrap();
+        mem.write_bitcoin_block(block).await.unwrap();
+    }
+
+    // The recipient has deposits confirmed in blocks on both forks, and
+    // someone else has a deposit on the canonical one.
+    let recipient: model::StacksPrincipal = Faker.fake_with_rng(&mut rng);
+    let other_recipient: model::StacksPrincipal = Faker.fake_with_rng(&mut rng);
+    let mut deposits = Vec::new();
+    for (block, amount, recipient) in [
+        (&root, 100_000, &recipient),
+        (&a1, 200_000, &recipient),
+        (&b1, 400_000, &recipient),
+        (&a1, 800_000, &other_recipient),
+    ] {
+        let mut deposit: model::DepositRequest = Faker.fake_with_rng(&mut rng);
+        deposit.amount = amount;
+        deposit.recipient = recipient.clone();
+
+        let tx_ref = model::BitcoinTxRef {
+            txid: deposit.txid,
+            block_hash: block.block_hash,
+        };
+        db.write_bitcoin_transaction(&tx_ref).await.unwrap();
+        mem.write_bitcoin_transaction(&tx_ref).await.unwrap();
+        db.write_deposit_request(&deposit).await.unwrap();
+        mem.write_deposit_request(&deposit).await.unwrap();
+        deposits.push(deposit);
+    }
+
+    let context_window = 10;
+    let cases = [
+        // Only the deposits on the canonical chain count.
+        (a2.block_hash, context_window, 300_000),
+        (b1.block_hash, context_window, 500_000),
+        // The deposit in the root block is outside the context window.
+        (a2.block_hash, 2, 200_000),
+    ];
+    for (chain_tip, context_window, expected) in cases {
+        let amount = db
+            .get_pending_deposit_amount_for_recipient(&recipient, &chain_tip, context_window)
+            .await
+            .unwrap();
+        assert_eq!(amount, Amount::from_sat(expected));
+
+        let mem_amount = mem
+            .get_pending_deposit_amount_for_recipient(&recipient, &chain_tip, context_window)
+            .await
+            .unwrap();
+        assert_eq!(mem_amount, amount);
+    }
+
+    // Now sweep the deposit confirmed in a1 in the chain tip block, so it
+    // is no longer pending.
+    let swept = &deposits[1];
+    let mut prevout: model::TxPrevout = Faker.fake_with_rng(&mut rng);
+    prevout.prevout_txid = swept.txid;
+    prevout.prevout_output_index = swept.output_index;
+    prevout.prevout_type = model::TxPrevoutType::Deposit;
+
+    let tx_ref = model::BitcoinTxRef {
+        txid: prevout.txid,
+        block_hash: a2.block_hash,
+    };
+    db.write_bitcoin_transaction(&tx_ref).await.unwrap();
+    db.write_tx_prevout(&prevout).await.unwrap();
+    mem.write_bitcoin_transaction(&tx_ref).await.unwrap();
+    mem.write_tx_prevout(&prevout).await.unwrap();
+
+    let amount = db
+        .get_pending_deposit_amount_for_recipient(&recipient, &a2.block_hash, context_window)
+        .await
+        .unwrap();
+    assert_eq!(amount, Amount::from_sat(100_000));
+
+    let mem_amount = mem
+        .get_pending_deposit_amount_for_recipient(&recipient, &a2.block_hash, context_window)
+        .await
+        .unwrap();
+    assert_eq!(mem_amount, amount);
+
+    signer::testing::storage::drop_db(db).await;
+}
+
 /// This test ensures that the postgres store will only return the pending accepted deposit requests
 /// if they are within the reclaim bounds. If they can be reclaimed too close to the current chain tip
 /// they should not appear in the accepted pending deposit requests list.
@@ -721,6 +1376,7 @@ async fn should_return_only_accepted_pending_deposits_that_are_within_reclaim_bo
         num_withdraw_requests_per_block: 5,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
     let threshold = 4;
 
@@ -906,6 +1562,7 @@ async fn should_return_the_same_last_key_rotation_as_in_memory_store() {
         num_withdraw_requests_per_block: 1,
         num_signers_per_request: 7,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
     let num_signers = 7;
     let threshold = 4;
@@ -1341,6 +1998,7 @@ async fn fetching_deposit_signer_decisions() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: true,
+        fork_points: Vec::new(),
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -1454,6 +2112,7 @@ async fn fetching_withdrawal_signer_decisions() {
         num_withdraw_requests_per_block: 1,
         num_signers_per_request: num_signers,
         consecutive_blocks: true,
+        fork_points: Vec::new(),
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -1683,6 +2342,7 @@ async fn block_in_canonical_bitcoin_blockchain_in_other_block_chain() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -1738,6 +2398,247 @@ async fn block_in_canonical_bitcoin_blockchain_in_other_block_chain() {
     signer::testing::storage::drop_db(pg_store).await;
 }
 
+/// Generate a bitcoin block with a random hash on top of the given one.
+fn new_child_block<R: rand::Rng>(parent: &BitcoinBlock, rng: &mut R) -> BitcoinBlock {
+    BitcoinBlock {
+        block_hash: Faker.fake_with_rng(rng),
+        block_height: parent.block_height + 1,
+        parent_hash: parent.block_hash,
+    }
+}
+
+/// Generate the blocks of the following forked bitcoin blockchain, in the
+/// order `[root, a1, a2, b1]`, where the root is at height 100 and a2 is
+/// the canonical chain tip:
+///
+/// ```text
+///   root <- a1 <- a2
+///        \
+///         <- b1
+/// ```
+fn forked_bitcoin_chain<R: rand::Rng>(rng: &mut R) -> [BitcoinBlock; 4] {
+    let root = BitcoinBlock {
+        block_hash: Faker.fake_with_rng(rng),
+        block_height: 100u64.into(),
+        parent_hash: Faker.fake_with_rng(rng),
+    };
+    let a1 = new_child_block(&root, rng);
+    let a2 = new_child_block(&a1, rng);
+    let b1 = new_child_block(&root, rng);
+    [root, a1, a2, b1]
+}
+
+/// Check that `get_bitcoin_block_with_canonicality` distinguishes between
+/// unknown blocks, blocks on the canonical chain, and blocks on a branch
+/// that has been orphaned.
+#[tokio::test]
+async fn get_bitcoin_block_with_canonicality_on_forked_chain() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let [root, a1, a2, b1] = forked_bitcoin_chain(&mut rng);
+    for block in [&root, &a1, &a2, &b1] {
+        db.write_bitcoin_block(block).await.unwrap();
+    }
+
+    let chain_tip = a2.block_hash;
+    for block in [&root, &a1, &a2] {
+        let fetched = db
+            .get_bitcoin_block_with_canonicality(&block.block_hash, &chain_tip)
+            .await
+            .unwrap();
+        assert_eq!(fetched, Some((block.clone(), true)));
+    }
+
+    // The block on the orphaned branch is known, but not canonical.
+    let fetched = db
+        .get_bitcoin_block_with_canonicality(&b1.block_hash, &chain_tip)
+        .await
+        .unwrap();
+    assert_eq!(fetched, Some((b1.clone(), false)));
+
+    // If the other branch were the canonical one then the roles reverse.
+    let fetched = db
+        .get_bitcoin_block_with_canonicality(&a1.block_hash, &b1.block_hash)
+        .await
+        .unwrap();
+    assert_eq!(fetched, Some((a1.clone(), false)));
+
+    // Unknown blocks are not found at all.
+    let unknown_block_hash: BitcoinBlockHash = Faker.fake_with_rng(&mut rng);
+    let fetched = db
+        .get_bitcoin_block_with_canonicality(&unknown_block_hash, &chain_tip)
+        .await
+        .unwrap();
+    assert!(fetched.is_none());
+
+    signer::testing::storage::drop_db(db).await;
+}
+
+/// Check that `get_sweep_transactions_in_range` returns the sweeps
+/// confirmed in the height range on the canonical bitcoin blockchain, and
+/// nothing else.
+#[tokio::test]
+async fn get_sweep_transactions_in_range_uses_canonical_chain() {
+    let db = testing::storage::new_test_database().await;
+    let mem = storage::memory::Store::new_shared();
+    let mut rng = get_rng();
+
+    // We extend the forked chain by one block, so that the canonical
+    // chain tip is a3:
+    //
+    //   a0 <- a1 <- a2 <- a3
+    //      \
+    //       <- b1
+    let [a0, a1, a2, b1] = forked_bitcoin_chain(&mut rng);
+    let a3 = new_child_block(&a2, &mut rng);
+
+    for block in [&a0, &a1, &a2, &a3, &b1] {
+        db.write_bitcoin_block(block).await.unwrap();
+        mem.write_bitcoin_block(block).await.unwrap();
+    }
+
+    // Each block gets a sweep transaction. Block a2 also gets a
+    // transaction that only spends a deposit, which is not a sweep.
+    let mut prevouts = Vec::new();
+    for block in [&a0, &a1, &a2, &a3, &b1] {
+        let mut prevout: model::TxPrevout = Faker.fake_with_rng(&mut rng);
+        prevout.prevout_type = model::TxPrevoutType::SignersInput;
+        prevouts.push((block.block_hash, prevout));
+    }
+    let mut deposit_prevout: model::TxPrevout = Faker.fake_with_rng(&mut rng);
+    deposit_prevout.prevout_type = model::TxPrevoutType::Deposit;
+    prevouts.push((a2.block_hash, deposit_prevout));
+
+    for (block_hash, prevout) in prevouts.iter() {
+        let tx_ref = model::BitcoinTxRef {
+            txid: prevout.txid,
+            block_hash: *block_hash,
+        };
+        db.write_bitcoin_transaction(&tx_ref).await.unwrap();
+        db.write_tx_prevout(prevout).await.unwrap();
+        mem.write_bitcoin_transaction(&tx_ref).await.unwrap();
+        mem.write_tx_prevout(prevout).await.unwrap();
+    }
+
+    let sweep_of = |block: &BitcoinBlock| {
+        let (_, prevout) = prevouts
+            .iter()
+            .find(|(block_hash, _)| *block_hash == block.block_hash)
+            .unwrap();
+        model::SweepTx {
+            txid: prevout.txid,
+            block_hash: block.block_hash,
+            block_height: block.block_height,
+        }
+    };
+
+    // The range is inclusive on both ends and skips the sweep in the
+    // orphaned block b1.
+    let from = a1.block_height;
+    let to = a2.block_height;
+    let sweeps = db.get_sweep_transactions_in_range(from, to).await.unwrap();
+    assert_eq!(sweeps, vec![sweep_of(&a1), sweep_of(&a2)]);
+
+    let mem_sweeps = mem.get_sweep_transactions_in_range(from, to).await.unwrap();
+    assert_eq!(mem_sweeps, sweeps);
+
+    // A range that covers the whole canonical chain and then some.
+    let from = a0.block_height;
+    let to = a3.block_height + 10;
+    let sweeps = db.get_sweep_transactions_in_range(from, to).await.unwrap();
+    let expected = [&a0, &a1, &a2, &a3].map(sweep_of).to_vec();
+    assert_eq!(sweeps, expected);
+
+    let mem_sweeps = mem.get_sweep_transactions_in_range(from, to).await.unwrap();
+    assert_eq!(mem_sweeps, sweeps);
+
+    // A range above the chain tip has no sweeps.
+    let from = a3.block_height + 1;
+    let to = a3.block_height + 10;
+    let sweeps = db.get_sweep_transactions_in_range(from, to).await.unwrap();
+    assert!(sweeps.is_empty());
+
+    signer::testing::storage::drop_db(db).await;
+}
+
+/// Check that `get_stacks_blocks_by_bitcoin_height` returns the stacks
+/// blocks anchored to any bitcoin block at the given height.
+#[tokio::test]
+async fn get_stacks_blocks_by_bitcoin_height_returns_anchored_blocks() {
+    let db = testing::storage::new_test_database().await;
+    let mem = storage::memory::Store::new_shared();
+    let mut rng = get_rng();
+
+    // Two bitcoin blocks at the same height on different forks, and one
+    // block above them.
+    let [root, a1, a2, b1] = forked_bitcoin_chain(&mut rng);
+
+    for block in [&root, &a1, &b1, &a2] {
+        db.write_bitcoin_block(block).await.unwrap();
+        mem.write_bitcoin_block(block).await.unwrap();
+    }
+
+    // Anchor a couple of stacks blocks to each bitcoin block.
+    let mut stacks_blocks = Vec::new();
+    for (index, anchor) in [&root, &root, &a1, &a1, &b1, &a2].into_iter().enumerate() {
+        let block = StacksBlock {
+            block_hash: Faker.fake_with_rng(&mut rng),
+            block_height: (1000 + index as u64).into(),
+            parent_hash: Faker.fake_with_rng(&mut rng),
+            bitcoin_anchor: anchor.block_hash,
+        };
+        db.write_stacks_block(&block).await.unwrap();
+        mem.write_stacks_block(&block).await.unwrap();
+        stacks_blocks.push(block);
+    }
+
+    let anchored_at = |height: BitcoinBlockHeight| {
+        let mut blocks = stacks_blocks
+            .iter()
+            .filter(|block| {
+                [&root, &a1, &b1, &a2].iter().any(|anchor| {
+                    anchor.block_hash == block.bitcoin_anchor && anchor.block_height == height
+                })
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        blocks.sort_by_key(|block| (block.block_height, block.block_hash));
+        blocks
+    };
+
+    for height in [root.block_height, a1.block_height, a2.block_height] {
+        let blocks = db
+            .get_stacks_blocks_by_bitcoin_height(height)
+            .await
+            .unwrap();
+        assert_eq!(blocks, anchored_at(height));
+        assert!(!blocks.is_empty());
+
+        let mem_blocks = mem
+            .get_stacks_blocks_by_bitcoin_height(height)
+            .await
+            .unwrap();
+        assert_eq!(mem_blocks, blocks);
+    }
+
+    // The blocks anchored to both forks at the same height are returned.
+    let blocks = db
+        .get_stacks_blocks_by_bitcoin_height(a1.block_height)
+        .await
+        .unwrap();
+    assert_eq!(blocks.len(), 3);
+
+    // Nothing is anchored above the chain tip.
+    let blocks = db
+        .get_stacks_blocks_by_bitcoin_height(a2.block_height + 1)
+        .await
+        .unwrap();
+    assert!(blocks.is_empty());
+
+    signer::testing::storage::drop_db(db).await;
+}
+
 /// Check that `is_signer_script_pub_key` correctly returns whether a
 /// scriptPubKey value exists in the dkg_shares table.
 #[tokio::test]
@@ -1910,26 +2811,178 @@ async fn get_signers_script_pubkeys_also_uses_bitcoin_tx_outputs() {
         .collect::<BTreeSet<_>>();
     assert_eq!(keys.len(), 1);
 
-    assert!(keys.contains(&shares.script_pubkey));
-    assert!(!keys.contains(&tx_output.script_pubkey));
+    assert!(keys.contains(&shares.script_pubkey));
+    assert!(!keys.contains(&tx_output.script_pubkey));
+
+    // Okay, now let's add a row for the signers' output and make sure that
+    // it gets picked up.
+    tx_output.output_type = model::TxOutputType::SignersOutput;
+    tx_output.txid = Faker.fake_with_rng(&mut rng);
+    db.write_tx_output(&tx_output).await.unwrap();
+
+    let keys = db
+        .get_signers_script_pubkeys()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(ScriptPubKey::from_bytes)
+        .collect::<BTreeSet<_>>();
+    assert_eq!(keys.len(), 2);
+
+    assert!(keys.contains(&shares.script_pubkey));
+    assert!(keys.contains(&tx_output.script_pubkey));
+
+    testing::storage::drop_db(db).await;
+}
+
+/// The [`DbWrite::write_tx_outputs`] and [`DbWrite::write_tx_prevouts`]
+/// functions should write all rows in a single batch, and writing the same
+/// rows again should be a no-op.
+#[tokio::test]
+async fn writing_tx_outputs_and_prevouts_in_batches() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let txid: BitcoinTxId = Faker.fake_with_rng(&mut rng);
+    let outputs: Vec<model::TxOutput> = (0..100)
+        .map(|output_index| model::TxOutput {
+            txid,
+            output_index,
+            ..Faker.fake_with_rng(&mut rng)
+        })
+        .collect();
+    let prevouts: Vec<model::TxPrevout> = (0..100)
+        .map(|prevout_output_index| model::TxPrevout {
+            txid,
+            prevout_output_index,
+            ..Faker.fake_with_rng(&mut rng)
+        })
+        .collect();
+
+    db.write_tx_outputs(&outputs).await.unwrap();
+    db.write_tx_prevouts(&prevouts).await.unwrap();
+
+    // Writing the same rows again should not error.
+    db.write_tx_outputs(&outputs).await.unwrap();
+    db.write_tx_prevouts(&prevouts).await.unwrap();
+
+    let mut stored_outputs = sqlx::query_as::<_, model::TxOutput>(
+        r#"
+        SELECT txid, output_index, amount, script_pubkey, output_type
+        FROM sbtc_signer.bitcoin_tx_outputs
+        WHERE txid = $1
+        "#,
+    )
+    .bind(txid)
+    .fetch_all(db.pool())
+    .await
+    .unwrap();
+    stored_outputs.sort_by_key(|output| output.output_index);
+    assert_eq!(stored_outputs, outputs);
+
+    let mut stored_prevouts = sqlx::query_as::<_, model::TxPrevout>(
+        r#"
+        SELECT txid, prevout_txid, prevout_output_index, amount, script_pubkey, prevout_type
+        FROM sbtc_signer.bitcoin_tx_inputs
+        WHERE txid = $1
+        "#,
+    )
+    .bind(txid)
+    .fetch_all(db.pool())
+    .await
+    .unwrap();
+    stored_prevouts.sort_by_key(|prevout| prevout.prevout_output_index);
+    assert_eq!(stored_prevouts, prevouts);
+
+    testing::storage::drop_db(db).await;
+}
+
+/// The limits written with [`DbWrite::write_effective_limits`] should be
+/// returned by [`DbRead::get_effective_limits_history`] alongside the
+/// chain tip that they were written for, most recent block first.
+#[tokio::test]
+async fn writing_effective_limits_for_blocks_and_reading_history() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let parent: model::BitcoinBlock = Faker.fake_with_rng(&mut rng);
+    let child = model::BitcoinBlock {
+        block_height: parent.block_height + 1,
+        parent_hash: parent.block_hash,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    db.write_bitcoin_block(&parent).await.unwrap();
+    db.write_bitcoin_block(&child).await.unwrap();
+
+    // Nothing has been recorded yet.
+    let history = db.get_effective_limits_history().await.unwrap();
+    assert!(history.is_empty());
+
+    let parent_limits = SbtcLimits::unlimited();
+    let child_limits = SbtcLimits::new(
+        Some(Amount::from_sat(1_000_000)),
+        Some(Amount::from_sat(10_000)),
+        Some(Amount::from_sat(100_000)),
+        Some(Amount::from_sat(50_000)),
+        Some(144),
+        Some(500_000),
+        Some(25_000),
+        Some(Amount::from_sat(900_000)),
+        Some(Amount::from_sat(200_000)),
+    );
+
+    db.write_effective_limits(&parent.block_hash, &parent_limits)
+        .await
+        .unwrap();
+    db.write_effective_limits(&child.block_hash, &child_limits)
+        .await
+        .unwrap();
+
+    let history = db.get_effective_limits_history().await.unwrap();
+    assert_eq!(history.len(), 2);
+
+    assert_eq!(history[0].bitcoin_chain_tip, child.block_hash);
+    assert_eq!(history[0].limits, child_limits);
+    assert_eq!(history[1].bitcoin_chain_tip, parent.block_hash);
+    assert_eq!(history[1].limits, parent_limits);
+
+    testing::storage::drop_db(db).await;
+}
+
+/// [`DbWrite::prune_effective_limits`] should only delete the limits
+/// recorded for bitcoin blocks below the given height.
+#[tokio::test]
+async fn pruning_effective_limits_keeps_recent_blocks() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
 
-    // Okay, now let's add a row for the signers' output and make sure that
-    // it gets picked up.
-    tx_output.output_type = model::TxOutputType::SignersOutput;
-    tx_output.txid = Faker.fake_with_rng(&mut rng);
-    db.write_tx_output(&tx_output).await.unwrap();
+    let mut blocks: Vec<model::BitcoinBlock> = Vec::new();
+    for height in 100u64..104 {
+        let mut block: model::BitcoinBlock = Faker.fake_with_rng(&mut rng);
+        block.block_height = height.into();
+        if let Some(parent) = blocks.last() {
+            block.parent_hash = parent.block_hash;
+        }
+        db.write_bitcoin_block(&block).await.unwrap();
+        db.write_effective_limits(&block.block_hash, &SbtcLimits::unlimited())
+            .await
+            .unwrap();
+        blocks.push(block);
+    }
 
-    let keys = db
-        .get_signers_script_pubkeys()
-        .await
-        .unwrap()
-        .into_iter()
-        .map(ScriptPubKey::from_bytes)
-        .collect::<BTreeSet<_>>();
-    assert_eq!(keys.len(), 2);
+    let num_pruned = db.prune_effective_limits(102u64.into()).await.unwrap();
+    assert_eq!(num_pruned, 2);
 
-    assert!(keys.contains(&shares.script_pubkey));
-    assert!(keys.contains(&tx_output.script_pubkey));
+    let history = db.get_effective_limits_history().await.unwrap();
+    let kept: Vec<_> = history
+        .iter()
+        .map(|entry| entry.bitcoin_chain_tip)
+        .collect();
+    assert_eq!(kept, vec![blocks[3].block_hash, blocks[2].block_hash]);
+
+    // Pruning again below the same height deletes nothing.
+    let num_pruned = db.prune_effective_limits(102u64.into()).await.unwrap();
+    assert_eq!(num_pruned, 0);
 
     testing::storage::drop_db(db).await;
 }
@@ -2059,6 +3112,65 @@ async fn get_last_verified_dkg_shares_does_whats_advertised() {
     signer::testing::storage::drop_db(db).await;
 }
 
+/// The [`DbRead::get_verified_dkg_shares_for_key`] function is supposed
+/// to fetch the verified DKG shares for the given aggregate key, even when
+/// they are not the most recent verified shares.
+#[tokio::test]
+async fn get_verified_dkg_shares_for_key_does_whats_advertised() {
+    let db = testing::storage::new_test_database().await;
+
+    let mut rng = get_rng();
+
+    // Let's write two sets of verified shares, like we would have during
+    // a key rotation.
+    let mut shares0: model::EncryptedDkgShares = fake::Faker.fake_with_rng(&mut rng);
+    shares0.dkg_shares_status = model::DkgSharesStatus::Verified;
+    db.write_encrypted_dkg_shares(&shares0).await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(5)).await;
+
+    let mut shares1: model::EncryptedDkgShares = fake::Faker.fake_with_rng(&mut rng);
+    shares1.dkg_shares_status = model::DkgSharesStatus::Verified;
+    db.write_encrypted_dkg_shares(&shares1).await.unwrap();
+
+    assert_ne!(shares0.aggregate_key, shares1.aggregate_key);
+
+    // Each of the shares can be fetched using their aggregate key.
+    let stored_shares = db
+        .get_verified_dkg_shares_for_key(&shares0.aggregate_key)
+        .await
+        .unwrap();
+    assert_eq!(stored_shares.as_ref(), Some(&shares0));
+
+    let stored_shares = db
+        .get_verified_dkg_shares_for_key(&shares1.aggregate_key)
+        .await
+        .unwrap();
+    assert_eq!(stored_shares.as_ref(), Some(&shares1));
+
+    // Shares that have not been verified are not returned.
+    let mut shares2: model::EncryptedDkgShares = fake::Faker.fake_with_rng(&mut rng);
+    shares2.dkg_shares_status = model::DkgSharesStatus::Unverified;
+    db.write_encrypted_dkg_shares(&shares2).await.unwrap();
+
+    let no_shares = db
+        .get_verified_dkg_shares_for_key(&shares2.aggregate_key)
+        .await
+        .unwrap();
+    assert!(no_shares.is_none());
+
+    // And neither are shares for an aggregate key that we do not know
+    // about.
+    let aggregate_key: PublicKey = fake::Faker.fake_with_rng(&mut rng);
+    let no_shares = db
+        .get_verified_dkg_shares_for_key(&aggregate_key)
+        .await
+        .unwrap();
+    assert!(no_shares.is_none());
+
+    signer::testing::storage::drop_db(db).await;
+}
+
 /// The [`DbRead::deposit_request_exists`] function is return true we have
 /// a record of the deposit request and false otherwise.
 #[tokio::test]
@@ -2100,6 +3212,7 @@ async fn is_known_bitcoin_block_hash_works() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -2208,6 +3321,7 @@ async fn get_swept_withdrawal_requests_returns_swept_withdrawal_requests() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -2334,6 +3448,7 @@ async fn get_swept_withdrawal_requests_does_not_return_unswept_withdrawal_reques
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -2602,6 +3717,7 @@ async fn get_swept_withdrawal_requests_does_not_return_withdrawal_requests_with_
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -2733,9 +3849,9 @@ async fn can_sign_deposit_tx_rejects_not_in_signer_set() {
     let signer_set_public_keys = std::iter::repeat_with(|| fake::Faker.fake_with_rng(&mut rng))
         .take(3)
         .collect::<Vec<PublicKey>>();
-    let mut shares: model::EncryptedDkgShares = fake::Faker.fake_with_rng(&mut rng);
-    shares.aggregate_key = aggregate_key;
+    let mut shares: model::EncryptedDkgShares = aggregate_key.fake_with_rng(&mut rng);
     shares.signer_set_public_keys = signer_set_public_keys;
+    shares.signature_share_threshold = 2;
     db.write_encrypted_dkg_shares(&shares).await.unwrap();
 
     // For each public key in the signing set, we will correctly say that
@@ -3005,6 +4121,7 @@ async fn get_swept_withdrawal_requests_response_tx_reorged() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -3136,6 +4253,7 @@ async fn transaction_coordinator_test_environment(
         num_withdraw_requests_per_block: 5,
         num_signers_per_request: 7,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let context = TestContext::builder()
@@ -3247,6 +4365,7 @@ async fn deposit_report_with_only_deposit_request() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -3340,6 +4459,7 @@ async fn deposit_report_with_deposit_request_reorged() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -3408,6 +4528,7 @@ async fn deposit_report_with_deposit_request_spent() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -3492,6 +4613,7 @@ async fn deposit_report_with_deposit_request_swept_but_swept_reorged() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -3609,6 +4731,7 @@ async fn deposit_report_with_deposit_request_confirmed() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -3694,6 +4817,7 @@ async fn withdrawal_report_with_no_withdrawal_request_or_no_block() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_public_keys = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -3768,6 +4892,7 @@ async fn withdrawal_report_with_no_withdrawal_votes() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_public_keys = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -3886,6 +5011,7 @@ async fn withdrawal_report_with_withdrawal_request_reorged() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_public_keys = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -3968,6 +5094,7 @@ async fn withdrawal_report_with_withdrawal_request_fulfilled() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_public_keys = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -4078,6 +5205,7 @@ async fn withdrawal_report_with_withdrawal_request_swept_but_swept_reorged() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: true,
+        fork_points: Vec::new(),
     };
 
     let signer_public_keys = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -4224,6 +5352,7 @@ async fn withdrawal_report_with_withdrawal_request_swept_but_swept_reorged2() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: true,
+        fork_points: Vec::new(),
     };
 
     let signer_public_keys = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -4380,6 +5509,7 @@ async fn withdrawal_report_with_withdrawal_request_confirmed() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_public_keys = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -4627,6 +5757,7 @@ async fn signer_utxo_reorg_suite<const N: usize>(desc: ReorgDescription<N>) {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     // Let's generate some dummy data and write it into the database.
@@ -4738,6 +5869,133 @@ async fn signer_utxo_reorg_suite<const N: usize>(desc: ReorgDescription<N>) {
     signer::testing::storage::drop_db(db).await;
 }
 
+/// Check that the signers' UTXO is the output of the last sweep in a chain
+/// of sweeps, where each sweep spends the output of the one before it.
+#[tokio::test]
+async fn get_signer_utxo_follows_sweep_chain() {
+    let db = testing::storage::new_test_database().await;
+    let mem = storage::memory::Store::new_shared();
+    let mut rng = get_rng();
+
+    // We identify the signers' UTXO by the fact that the signers can sign
+    // for it, so we need DKG shares.
+    let dkg_shares: model::EncryptedDkgShares = Faker.fake_with_rng(&mut rng);
+    db.write_encrypted_dkg_shares(&dkg_shares).await.unwrap();
+    mem.write_encrypted_dkg_shares(&dkg_shares).await.unwrap();
+
+    let num_sweeps = 5;
+    let test_data = TestData::generate_sweep_chain(&mut rng, &dkg_shares.aggregate_key, num_sweeps);
+    test_data.write_to(&db).await;
+    test_data.write_to(&mem).await;
+
+    let last_signers_output = test_data
+        .tx_outputs
+        .iter()
+        .filter(|output| output.output_type == model::TxOutputType::SignersOutput)
+        .last()
+        .unwrap();
+
+    let chain_tip = db.get_bitcoin_canonical_chain_tip().await.unwrap().unwrap();
+    let utxo = db.get_signer_utxo(&chain_tip).await.unwrap().unwrap();
+
+    assert_eq!(utxo.outpoint.txid, *last_signers_output.txid);
+    assert_eq!(utxo.outpoint.vout, 0);
+    let expected_amount = 500_000_000 - num_sweeps as u64 * SIMULATED_SWEEP_FEE;
+    assert_eq!(utxo.amount, expected_amount);
+
+    let mem_utxo = mem.get_signer_utxo(&chain_tip).await.unwrap().unwrap();
+    assert_eq!(mem_utxo, utxo);
+
+    signer::testing::storage::drop_db(db).await;
+}
+
+/// Check that when more than one signer-controlled output is unspent,
+/// `get_signer_utxo` returns the output of the most recent sweep, even if
+/// a donation or an older sweep output is larger, and that it falls back
+/// to the largest donation before there are any sweeps.
+#[tokio::test]
+async fn get_signer_utxo_prefers_latest_sweep_over_donations() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let dkg_shares: model::EncryptedDkgShares = Faker.fake_with_rng(&mut rng);
+    db.write_encrypted_dkg_shares(&dkg_shares).await.unwrap();
+
+    // The scenario is:
+    // [block 1 with two donations] - [block 2 with a sweep] - [block 3 with a sweep]
+    let block1: BitcoinBlock = Faker.fake_with_rng(&mut rng);
+    let block2 = BitcoinBlock {
+        block_hash: Faker.fake_with_rng(&mut rng),
+        block_height: block1.block_height + 1,
+        parent_hash: block1.block_hash,
+    };
+    let block3 = BitcoinBlock {
+        block_hash: Faker.fake_with_rng(&mut rng),
+        block_height: block2.block_height + 1,
+        parent_hash: block2.block_hash,
+    };
+    for block in [&block1, &block2, &block3] {
+        db.write_bitcoin_block(block).await.unwrap();
+    }
+
+    let outputs = [
+        (&block1, model::TxOutputType::Donation, 50_000),
+        (&block1, model::TxOutputType::Donation, 90_000),
+        (&block2, model::TxOutputType::SignersOutput, 70_000),
+        (&block3, model::TxOutputType::SignersOutput, 20_000),
+    ];
+
+    let mut expected = Vec::new();
+    for (block, output_type, amount) in outputs {
+        let output = model::TxOutput {
+            txid: Faker.fake_with_rng(&mut rng),
+            output_index: 0,
+            script_pubkey: dkg_shares.script_pubkey.clone(),
+            amount,
+            output_type,
+            ..Faker.fake_with_rng(&mut rng)
+        };
+        let tx_ref = model::BitcoinTxRef {
+            txid: output.txid,
+            block_hash: block.block_hash,
+        };
+        db.write_bitcoin_transaction(&tx_ref).await.unwrap();
+        db.write_tx_output(&output).await.unwrap();
+
+        // Sweep transactions spend some other signers' output, which is
+        // how we know that the signers have swept funds before.
+        if output_type == model::TxOutputType::SignersOutput {
+            let prevout = model::TxPrevout {
+                txid: output.txid,
+                prevout_type: model::TxPrevoutType::SignersInput,
+                ..Faker.fake_with_rng(&mut rng)
+            };
+            db.write_tx_prevout(&prevout).await.unwrap();
+        }
+
+        expected.push(SignerUtxo {
+            outpoint: bitcoin::OutPoint::new(output.txid.into(), 0),
+            amount,
+            public_key: dkg_shares.aggregate_key.into(),
+        });
+    }
+
+    // Before any sweeps, the largest donation is the signers' UTXO.
+    let utxo = db.get_signer_utxo(&block1.block_hash).await.unwrap();
+    assert_eq!(utxo, Some(expected[1]));
+
+    // Once there is a sweep, its output wins over the larger donation.
+    let utxo = db.get_signer_utxo(&block2.block_hash).await.unwrap();
+    assert_eq!(utxo, Some(expected[2]));
+
+    // And the most recent sweep output wins over the larger output of
+    // the earlier sweep.
+    let utxo = db.get_signer_utxo(&block3.block_hash).await.unwrap();
+    assert_eq!(utxo, Some(expected[3]));
+
+    signer::testing::storage::drop_db(db).await;
+}
+
 fn hex_to_block_hash(hash: &str) -> [u8; 32] {
     hex::decode(hash).unwrap().as_slice().try_into().unwrap()
 }
@@ -4855,13 +6113,14 @@ async fn compare_in_memory_stacks_chain_tip() {
 async fn write_and_get_dkg_shares_is_pending() {
     let db = testing::storage::new_test_database().await;
 
+    let aggregate_key: PublicKey = fake::Faker.fake();
     let insert = EncryptedDkgShares {
-        aggregate_key: fake::Faker.fake(),
-        tweaked_aggregate_key: fake::Faker.fake(),
+        aggregate_key,
+        tweaked_aggregate_key: aggregate_key.signers_tweaked_pubkey().unwrap(),
         encrypted_private_shares: vec![],
-        script_pubkey: fake::Faker.fake(),
+        script_pubkey: aggregate_key.signers_script_pubkey().into(),
         public_shares: vec![],
-        signer_set_public_keys: vec![],
+        signer_set_public_keys: vec![fake::Faker.fake()],
         signature_share_threshold: 1,
         dkg_shares_status: DkgSharesStatus::Unverified,
         ..fake::Faker.fake()
@@ -4880,6 +6139,102 @@ async fn write_and_get_dkg_shares_is_pending() {
     signer::testing::storage::drop_db(db).await;
 }
 
+/// Assert that the given store refuses to write DKG shares whose threshold
+/// is inconsistent with the signer set, or whose tweaked aggregate key or
+/// `scriptPubKey` was not derived from the aggregate key, while accepting
+/// the consistent shares.
+async fn assert_inconsistent_dkg_shares_rejected<S: DbWrite>(
+    store: &S,
+    shares: &EncryptedDkgShares,
+    other_key: PublicKey,
+) {
+    let num_signers = shares.signer_set_public_keys.len();
+    let threshold = u16::try_from(num_signers).unwrap() + 1;
+
+    let too_high_threshold = EncryptedDkgShares {
+        signature_share_threshold: threshold,
+        ..shares.clone()
+    };
+    let result = store.write_encrypted_dkg_shares(&too_high_threshold).await;
+    assert!(matches!(
+        result,
+        Err(Error::InvalidDkgSharesThreshold(t, n)) if t == threshold && n == num_signers
+    ));
+
+    let zero_threshold = EncryptedDkgShares {
+        signature_share_threshold: 0,
+        ..shares.clone()
+    };
+    let result = store.write_encrypted_dkg_shares(&zero_threshold).await;
+    assert!(matches!(
+        result,
+        Err(Error::InvalidDkgSharesThreshold(0, n)) if n == num_signers
+    ));
+
+    let mismatched_key = EncryptedDkgShares {
+        aggregate_key: other_key,
+        tweaked_aggregate_key: other_key.signers_tweaked_pubkey().unwrap(),
+        ..shares.clone()
+    };
+    let result = store.write_encrypted_dkg_shares(&mismatched_key).await;
+    assert!(matches!(
+        result,
+        Err(Error::DkgSharesAggregateKeyMismatch(key)) if key == other_key
+    ));
+
+    let mismatched_tweaked_key = EncryptedDkgShares {
+        tweaked_aggregate_key: other_key,
+        ..shares.clone()
+    };
+    let result = store
+        .write_encrypted_dkg_shares(&mismatched_tweaked_key)
+        .await;
+    assert!(matches!(
+        result,
+        Err(Error::DkgSharesAggregateKeyMismatch(key)) if key == shares.aggregate_key
+    ));
+
+    store.write_encrypted_dkg_shares(shares).await.unwrap();
+}
+
+#[tokio::test]
+async fn write_encrypted_dkg_shares_rejects_inconsistent_shares() {
+    let pg_store = testing::storage::new_test_database().await;
+    let in_memory_store = storage::memory::Store::new_shared();
+    let mut rng = get_rng();
+
+    let shares: EncryptedDkgShares = fake::Faker.fake_with_rng(&mut rng);
+    let other_key: PublicKey = fake::Faker.fake_with_rng(&mut rng);
+
+    assert_inconsistent_dkg_shares_rejected(&pg_store, &shares, other_key).await;
+    assert_inconsistent_dkg_shares_rejected(&in_memory_store, &shares, other_key).await;
+
+    // Only the consistent shares should have made it into either store.
+    for select in [
+        pg_store.get_encrypted_dkg_shares(other_key).await.unwrap(),
+        in_memory_store
+            .get_encrypted_dkg_shares(other_key)
+            .await
+            .unwrap(),
+    ] {
+        assert!(select.is_none());
+    }
+
+    let select = pg_store
+        .get_encrypted_dkg_shares(shares.aggregate_key)
+        .await
+        .unwrap();
+    assert_eq!(select, Some(shares.clone()));
+
+    let select = in_memory_store
+        .get_encrypted_dkg_shares(shares.aggregate_key)
+        .await
+        .unwrap();
+    assert_eq!(select, Some(shares));
+
+    signer::testing::storage::drop_db(pg_store).await;
+}
+
 #[test(tokio::test)]
 async fn verify_dkg_shares_succeeds() {
     let db = testing::storage::new_test_database().await;
@@ -4950,6 +6305,76 @@ async fn revoke_dkg_shares_succeeds() {
     signer::testing::storage::drop_db(db).await;
 }
 
+/// [`DbWrite::prune_failed_dkg_shares`] should only delete failed DKG
+/// shares that were started below the given bitcoin block height. Verified
+/// and unverified shares are never deleted.
+#[tokio::test]
+async fn prune_failed_dkg_shares_deletes_stale_failed_shares() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let mut all_shares = Vec::new();
+    for (status, started_at) in [
+        (DkgSharesStatus::Failed, 90u64),
+        (DkgSharesStatus::Verified, 90),
+        (DkgSharesStatus::Unverified, 90),
+        (DkgSharesStatus::Failed, 97),
+    ] {
+        let shares = EncryptedDkgShares {
+            dkg_shares_status: status,
+            started_at_bitcoin_block_height: started_at.into(),
+            ..Faker.fake_with_rng(&mut rng)
+        };
+        db.write_encrypted_dkg_shares(&shares).await.unwrap();
+        all_shares.push(shares);
+    }
+    let [
+        stale_failed,
+        stale_verified,
+        stale_unverified,
+        recent_failed,
+    ] = &all_shares[..]
+    else {
+        unreachable!("we wrote four DKG shares");
+    };
+
+    // Only the failed shares started below height 95 are deleted.
+    let num_pruned = db.prune_failed_dkg_shares(95u64.into()).await.unwrap();
+    assert_eq!(num_pruned, 1);
+
+    for (shares, kept) in [
+        (stale_failed, false),
+        (stale_verified, true),
+        (stale_unverified, true),
+        (recent_failed, true),
+    ] {
+        let stored = db
+            .get_encrypted_dkg_shares(shares.aggregate_key)
+            .await
+            .unwrap();
+        assert_eq!(stored.is_some(), kept);
+    }
+
+    // Once the height passes the recent failed shares they are deleted
+    // too, while the verified and unverified shares are still kept.
+    let num_pruned = db.prune_failed_dkg_shares(100u64.into()).await.unwrap();
+    assert_eq!(num_pruned, 1);
+
+    for (shares, kept) in [
+        (stale_verified, true),
+        (stale_unverified, true),
+        (recent_failed, false),
+    ] {
+        let stored = db
+            .get_encrypted_dkg_shares(shares.aggregate_key)
+            .await
+            .unwrap();
+        assert_eq!(stored.is_some(), kept);
+    }
+
+    signer::testing::storage::drop_db(db).await;
+}
+
 /// This test checks that DKG shares verification status follows a one-way state transition:
 ///
 /// 1. Unverified -> Verified: Once shares are verified, they cannot be revoked
@@ -5096,6 +6521,7 @@ async fn pending_rejected_withdrawal_no_events() {
         num_withdraw_requests_per_block: 5,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -5196,6 +6622,7 @@ async fn pending_rejected_withdrawal_expiration() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: true,
+        fork_points: Vec::new(),
     };
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
     let test_data = TestData::generate(&mut rng, &signer_set, &test_model_params);
@@ -5297,6 +6724,7 @@ async fn pending_rejected_withdrawal_rejected_already_rejected() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: true,
+        fork_points: Vec::new(),
     };
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
     let test_data = TestData::generate(&mut rng, &signer_set, &test_model_params);
@@ -5435,6 +6863,7 @@ async fn pending_rejected_withdrawal_already_accepted() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: true,
+        fork_points: Vec::new(),
     };
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
     let test_data = TestData::generate(&mut rng, &signer_set, &test_model_params);
@@ -5818,6 +7247,7 @@ async fn compute_withdrawn_total_gets_all_amounts_in_chain() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: true,
+        fork_points: Vec::new(),
     };
     // The number of signers does not matter
     let num_signers = 1;
@@ -5954,6 +7384,7 @@ async fn compute_withdrawn_total_ignores_withdrawals_not_identified_blockchain()
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: true,
+        fork_points: Vec::new(),
     };
     // The number of signers does not matter
     let num_signers = 1;
@@ -7403,6 +8834,56 @@ mod sqlx_transactions {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_data_written_to_transaction_is_rolled_back() -> Result<(), Box<Error>> {
+        let db = storage::new_test_database().await;
+        let mut rng = get_rng();
+
+        let test_model_params = storage::model::Params {
+            num_bitcoin_blocks: 10,
+            num_stacks_blocks_per_bitcoin_block: 1,
+            num_deposit_requests_per_block: 2,
+            num_withdraw_requests_per_block: 2,
+            num_signers_per_request: 0,
+            consecutive_blocks: false,
+            fork_points: Vec::new(),
+        };
+        let signer_set = signer::testing::wsts::generate_signer_set_public_keys(&mut rng, 3);
+        let test_data = TestData::generate(&mut rng, &signer_set, &test_model_params);
+
+        // Write the whole fixture within the transaction, and check that
+        // it is visible there.
+        let mut tx = db.begin_transaction().await?;
+        test_data.write_to_tx(&mut tx).await;
+
+        for block in test_data.bitcoin_blocks.iter() {
+            assert!(tx.get_bitcoin_block(&block.block_hash).await?.is_some());
+        }
+        for req in test_data.deposit_requests.iter() {
+            assert!(
+                tx.deposit_request_exists(&req.txid, req.output_index)
+                    .await?
+            );
+        }
+
+        tx.rollback().await?;
+
+        // None of the fixture should have made it into the database.
+        assert!(db.get_bitcoin_canonical_chain_tip().await?.is_none());
+        for block in test_data.bitcoin_blocks.iter() {
+            assert!(db.get_bitcoin_block(&block.block_hash).await?.is_none());
+        }
+        for req in test_data.deposit_requests.iter() {
+            assert!(
+                !db.deposit_request_exists(&req.txid, req.output_index)
+                    .await?
+            );
+        }
+
+        storage::drop_db(db).await;
+        Ok(())
+    }
+
     #[test(tokio::test)]
     async fn test_pgsql_transaction_implicit_rollback_on_drop() -> Result<(), Box<Error>> {
         let db = storage::new_test_database().await;
@@ -7427,3 +8908,147 @@ mod sqlx_transactions {
         Ok(())
     }
 }
+
+/// Check that re-writing a withdrawal request is idempotent, while
+/// re-writing it with a different amount returns an error, for both the
+/// postgres and in-memory stores.
+#[tokio::test]
+async fn write_withdrawal_request_detects_mutation() {
+    let db = testing::storage::new_test_database().await;
+    let in_memory_store = storage::memory::Store::new_shared();
+    let mut rng = get_rng();
+
+    let stacks_block: model::StacksBlock = Faker.fake_with_rng(&mut rng);
+    db.write_stacks_block(&stacks_block).await.unwrap();
+
+    let withdrawal_request = WithdrawalRequest {
+        block_hash: stacks_block.block_hash,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    let mutated_request = WithdrawalRequest {
+        amount: withdrawal_request.amount + 1,
+        ..withdrawal_request.clone()
+    };
+
+    db.write_withdrawal_request(&withdrawal_request)
+        .await
+        .unwrap();
+    in_memory_store
+        .write_withdrawal_request(&withdrawal_request)
+        .await
+        .unwrap();
+
+    // Writing the exact same request again is fine.
+    db.write_withdrawal_request(&withdrawal_request)
+        .await
+        .unwrap();
+    in_memory_store
+        .write_withdrawal_request(&withdrawal_request)
+        .await
+        .unwrap();
+
+    // But changing the amount for the same request is not.
+    let qualified_id = withdrawal_request.qualified_id();
+    let result = db.write_withdrawal_request(&mutated_request).await;
+    assert!(matches!(result, Err(Error::WithdrawalRequestMutation(id)) if id == qualified_id));
+
+    let result = in_memory_store
+        .write_withdrawal_request(&mutated_request)
+        .await;
+    assert!(matches!(result, Err(Error::WithdrawalRequestMutation(id)) if id == qualified_id));
+
+    // The stored request is left untouched.
+    let stored_amounts =
+        sqlx::query_scalar::<_, i64>("SELECT amount FROM sbtc_signer.withdrawal_requests")
+            .fetch_all(db.pool())
+            .await
+            .unwrap();
+    assert_eq!(stored_amounts, vec![withdrawal_request.amount as i64]);
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that `PgStore::stream_deposit_requests` yields every deposit
+/// request confirmed on the canonical bitcoin blockchain within the
+/// context window, one at a time, leaves out deposit requests confirmed
+/// on a fork, and agrees with `DbRead::get_deposit_requests`.
+#[tokio::test]
+async fn stream_deposit_requests_yields_all_canonical_deposits() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let block: BitcoinBlock = Faker.fake_with_rng(&mut rng);
+    let fork = BitcoinBlock {
+        block_hash: Faker.fake_with_rng(&mut rng),
+        block_height: block.block_height,
+        parent_hash: block.parent_hash,
+    };
+    db.write_bitcoin_block(&block).await.unwrap();
+    db.write_bitcoin_block(&fork).await.unwrap();
+
+    let deposits: Vec<model::DepositRequest> =
+        (0..500).map(|_| Faker.fake_with_rng(&mut rng)).collect();
+    let txs = deposits
+        .iter()
+        .map(|deposit| model::BitcoinTxRef {
+            txid: deposit.txid,
+            block_hash: block.block_hash,
+        })
+        .collect();
+    db.write_bitcoin_transactions(txs).await.unwrap();
+    db.write_deposit_requests(deposits.clone()).await.unwrap();
+
+    let forked_deposit: model::DepositRequest = Faker.fake_with_rng(&mut rng);
+    let forked_tx = model::BitcoinTxRef {
+        txid: forked_deposit.txid,
+        block_hash: fork.block_hash,
+    };
+    db.write_bitcoin_transaction(&forked_tx).await.unwrap();
+    db.write_deposit_request(&forked_deposit).await.unwrap();
+
+    let streamed = {
+        let mut stream = std::pin::pin!(db.stream_deposit_requests(&block.block_hash, 1000));
+
+        // We can take the first deposit request before the rest have been
+        // read from the database.
+        let first = stream.next().await.unwrap().unwrap();
+        let mut streamed = vec![first];
+        while let Some(deposit) = stream.next().await {
+            streamed.push(deposit.unwrap());
+        }
+        streamed
+    };
+
+    assert_eq!(streamed.len(), deposits.len());
+    for deposit in streamed.iter() {
+        assert_eq!(deposit.confirmed_at_height, Some(block.block_height));
+    }
+
+    let expected: BTreeSet<_> = deposits
+        .iter()
+        .map(|deposit| (deposit.txid, deposit.output_index))
+        .collect();
+    let actual: BTreeSet<_> = streamed
+        .iter()
+        .map(|deposit| (deposit.txid, deposit.output_index))
+        .collect();
+    assert_eq!(actual, expected);
+
+    // The stream yields the same deposit requests, in the same order, as
+    // the buffered query.
+    let fetched = db
+        .get_deposit_requests(&block.block_hash, 1000)
+        .await
+        .unwrap();
+    let fetched: Vec<_> = fetched
+        .iter()
+        .map(|deposit| (deposit.txid, deposit.output_index))
+        .collect();
+    let streamed: Vec<_> = streamed
+        .iter()
+        .map(|deposit| (deposit.txid, deposit.output_index))
+        .collect();
+    assert_eq!(streamed, fetched);
+
+    testing::storage::drop_db(db).await;
+}
@@ -20,6 +20,7 @@ use signer::bitcoin::utxo::SignerBtcState;
 use signer::bitcoin::utxo::SignerUtxo;
 use signer::bitcoin::utxo::UnsignedTransaction;
 use signer::bitcoin::utxo::WithdrawalRequest;
+use signer::bitcoin::utxo::WithdrawalsToSignersPolicy;
 use signer::context::SbtcLimits;
 use signer::storage::model::ScriptPubKey;
 
@@ -226,11 +227,17 @@ pub fn transaction_with_rbf(
             // The value here isn't important, but it matches what happens
             // in Nakamoto testnet.
             magic_bytes: [b'T', b'3'],
+            rbf_target_fee_rate: None,
+            anchor_output: false,
         },
         accept_threshold: failure_threshold,
         num_signers: 2 * failure_threshold,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+        min_batch: None,
+        withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+        min_signer_output: None,
+        shuffle_seed: None,
     };
 
     // Okay, lets submit the transaction. We also do a sanity check where
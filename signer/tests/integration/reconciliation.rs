@@ -0,0 +1,134 @@
+use emily_client::models::WithdrawalInfo;
+use emily_client::models::WithdrawalStatus;
+use fake::Fake as _;
+use fake::Faker;
+use signer::emily_client::MockEmilyInteract;
+use signer::reconciliation::WithdrawalReconciliationReport;
+use signer::reconciliation::reconcile_withdrawals;
+use signer::storage::DbWrite as _;
+use signer::storage::model;
+use signer::storage::model::BitcoinWithdrawalOutput;
+use signer::testing;
+use signer::testing::get_rng;
+
+/// Convert the withdrawal request into what Emily would return for it
+/// when it has the given status.
+fn emily_withdrawal(
+    request: &model::WithdrawalRequest,
+    status: WithdrawalStatus,
+) -> WithdrawalInfo {
+    WithdrawalInfo::new(
+        request.amount,
+        request.block_hash.to_string(),
+        0,
+        request.recipient.to_hex_string(),
+        request.request_id,
+        request.sender_address.to_string(),
+        request.block_hash.to_string(),
+        0,
+        status,
+        request.txid.to_string(),
+    )
+}
+
+/// Check that `reconcile_withdrawals` reports withdrawals that Emily
+/// considers pending but that the signers have swept, and withdrawals that
+/// Emily considers accepted or confirmed but that the signers have not
+/// swept, while leaving out withdrawals where the two agree.
+#[tokio::test]
+async fn reconcile_withdrawals_reports_mismatched_states() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let bitcoin_block: model::BitcoinBlock = Faker.fake_with_rng(&mut rng);
+    db.write_bitcoin_block(&bitcoin_block).await.unwrap();
+
+    // We have six withdrawal requests, three of which have been swept out
+    // in a transaction confirmed in our bitcoin block.
+    let requests: Vec<model::WithdrawalRequest> = (1..=6)
+        .map(|request_id| model::WithdrawalRequest {
+            request_id,
+            bitcoin_block_height: bitcoin_block.block_height,
+            ..Faker.fake_with_rng(&mut rng)
+        })
+        .collect();
+    for request in requests.iter() {
+        db.write_withdrawal_request(request).await.unwrap();
+    }
+
+    let [
+        swept_pending,
+        unswept_confirmed,
+        swept_confirmed,
+        unswept_pending,
+        unswept_accepted,
+        swept_accepted,
+    ] = &requests[..]
+    else {
+        unreachable!()
+    };
+
+    let mut sweeps = Vec::new();
+    for request in [swept_pending, swept_confirmed, swept_accepted] {
+        let swept_output = BitcoinWithdrawalOutput {
+            request_id: request.request_id,
+            stacks_txid: request.txid,
+            stacks_block_hash: request.block_hash,
+            bitcoin_chain_tip: bitcoin_block.block_hash,
+            ..Faker.fake_with_rng(&mut rng)
+        };
+        let sweep_tx_ref = model::BitcoinTxRef {
+            txid: swept_output.bitcoin_txid,
+            block_hash: bitcoin_block.block_hash,
+        };
+        db.write_bitcoin_transaction(&sweep_tx_ref).await.unwrap();
+        db.write_bitcoin_withdrawals_outputs(&[swept_output])
+            .await
+            .unwrap();
+        sweeps.push(sweep_tx_ref);
+    }
+
+    // Emily only agrees with the signers about three of the requests. It
+    // also has a withdrawal that we cannot parse, which gets skipped.
+    let mut malformed = emily_withdrawal(unswept_pending, WithdrawalStatus::Pending);
+    malformed.txid = "not a txid".to_string();
+
+    let pending = vec![
+        emily_withdrawal(swept_pending, WithdrawalStatus::Pending),
+        emily_withdrawal(unswept_pending, WithdrawalStatus::Pending),
+        malformed,
+    ];
+    let accepted = vec![
+        emily_withdrawal(unswept_accepted, WithdrawalStatus::Accepted),
+        emily_withdrawal(swept_accepted, WithdrawalStatus::Accepted),
+    ];
+    let confirmed = vec![
+        emily_withdrawal(unswept_confirmed, WithdrawalStatus::Confirmed),
+        emily_withdrawal(swept_confirmed, WithdrawalStatus::Confirmed),
+    ];
+
+    let mut emily = MockEmilyInteract::new();
+    emily
+        .expect_get_withdrawals_with_status()
+        .returning(move |status| {
+            let withdrawals = match status {
+                WithdrawalStatus::Pending => pending.clone(),
+                WithdrawalStatus::Accepted => accepted.clone(),
+                WithdrawalStatus::Confirmed => confirmed.clone(),
+                _ => Vec::new(),
+            };
+            Box::pin(async move { Ok(withdrawals) })
+        });
+
+    let report = reconcile_withdrawals(&emily, &db).await.unwrap();
+
+    let expected = WithdrawalReconciliationReport {
+        swept_but_pending_in_emily: vec![(swept_pending.qualified_id(), sweeps[0])],
+        accepted_in_emily_but_not_swept: vec![unswept_accepted.qualified_id()],
+        confirmed_in_emily_but_not_swept: vec![unswept_confirmed.qualified_id()],
+    };
+    assert_eq!(report, expected);
+    assert!(!report.is_empty());
+
+    testing::storage::drop_db(db).await;
+}
@@ -61,6 +61,7 @@ fn test_environment(
         num_withdraw_requests_per_block: 5,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
 
     let context = TestContext::builder()
@@ -178,6 +178,7 @@ async fn rotate_key_validation_happy_path() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
     let test_data = TestData::generate(&mut rng, &[], &test_model_params);
     test_data.write_to(&db).await;
@@ -228,6 +229,7 @@ async fn rotate_key_validation_no_dkg() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
     let test_data = TestData::generate(&mut rng, &[], &test_model_params);
     test_data.write_to(&db).await;
@@ -266,6 +268,7 @@ async fn rotate_key_validation_wrong_deployer() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
     let test_data = TestData::generate(&mut rng, &[], &test_model_params);
     test_data.write_to(&db).await;
@@ -310,6 +313,7 @@ async fn rotate_key_validation_wrong_signing_set() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
     let test_data = TestData::generate(&mut rng, &[], &test_model_params);
     test_data.write_to(&db).await;
@@ -360,6 +364,7 @@ async fn rotate_key_validation_wrong_aggregate_key() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
     let test_data = TestData::generate(&mut rng, &[], &test_model_params);
     test_data.write_to(&db).await;
@@ -410,6 +415,7 @@ async fn rotate_key_validation_wrong_signatures_required() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
     let test_data = TestData::generate(&mut rng, &[], &test_model_params);
     test_data.write_to(&db).await;
@@ -465,6 +471,7 @@ async fn rotate_key_validation_replay() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
     let test_data = TestData::generate(&mut rng, &[], &test_model_params);
     test_data.write_to(&db).await;
@@ -504,6 +511,7 @@ async fn rotate_key_validation_replay() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
     let test_data = TestData::generate(&mut rng, &[], &test_model_params);
     test_data.write_to(&db).await;
@@ -530,6 +538,7 @@ async fn rotate_key_validation_not_verfied() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
     let test_data = TestData::generate(&mut rng, &[], &test_model_params);
     test_data.write_to(&db).await;
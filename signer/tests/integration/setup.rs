@@ -30,6 +30,7 @@ use signer::bitcoin::utxo::SbtcRequests;
 use signer::bitcoin::utxo::SignerBtcState;
 use signer::bitcoin::utxo::SignerUtxo;
 use signer::bitcoin::utxo::TxDeconstructor as _;
+use signer::bitcoin::utxo::WithdrawalsToSignersPolicy;
 use signer::bitcoin::validation::WithdrawalValidationResult;
 use signer::block_observer;
 use signer::block_observer::Deposit;
@@ -207,11 +208,17 @@ impl TestSweepSetup {
                 public_key: signers_public_key,
                 last_fees: None,
                 magic_bytes: [b'T', b'3'],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
             },
             accept_threshold: 4,
             num_signers: 7,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
 
         // There should only be one transaction here since there is only
@@ -822,15 +829,15 @@ impl TestSweepSetup2 {
 
         let settings = Settings::new_from_default_config().unwrap();
         let client = BitcoinCoreClient::try_from(&settings.bitcoin.rpc_endpoints[0]).unwrap();
+        let tx_info_requests: Vec<_> = deposits
+            .iter()
+            .map(|(tx, _, _)| (tx.compute_txid(), deposit_block_hash))
+            .collect();
+        let tx_infos = client.get_tx_infos(&tx_info_requests).unwrap();
         let deposits: Vec<(DepositInfo, utxo::DepositRequest, BitcoinTxInfo)> = deposits
             .into_iter()
-            .map(|(tx, request, info)| {
-                let tx_info = client
-                    .get_tx_info(&tx.compute_txid(), &deposit_block_hash)
-                    .unwrap()
-                    .unwrap();
-                (info, request, tx_info)
-            })
+            .zip(tx_infos)
+            .map(|((_, request, info), tx_info)| (info, request, tx_info.unwrap()))
             .collect::<Vec<_>>();
 
         TestSweepSetup2 {
@@ -896,10 +903,17 @@ impl TestSweepSetup2 {
             .unwrap()
             .unwrap();
 
-        let bootstrap_script_pubkey = context.config().signer.bootstrap_aggregate_key;
+        let bootstrap_script_pubkeys = context
+            .config()
+            .signer
+            .bootstrap_aggregate_key
+            .map(|key| key.signers_script_pubkey())
+            .into_iter()
+            .collect();
         block_observer::extract_sbtc_transactions(
             db,
-            bootstrap_script_pubkey,
+            &bootstrap_script_pubkeys,
+            NetworkKind::Regtest.magic_bytes(),
             self.donation_block_hash,
             &[tx_info],
         )
@@ -925,14 +939,28 @@ impl TestSweepSetup2 {
         let outpoint = OutPoint::new(signer_utxo.txid, signer_utxo.vout);
         let txids = btc.get_tx_spending_prevout(&outpoint).unwrap();
 
+        // Like `assess_mempool_sweep_transaction_fees`, the fees are those
+        // of the sweep paying the highest fee along with all of its mempool
+        // descendants, since a replacement evicts all of them.
         let last_fees = txids
             .iter()
-            .filter_map(|txid| btc.get_mempool_entry(txid).unwrap())
-            .map(|entry| Fees {
-                total: entry.fees.base.to_sat(),
-                rate: entry.fees.base.to_sat() as f64 / entry.vsize as f64,
-            })
-            .max_by_key(|fees| fees.total);
+            .filter_map(|txid| Some((txid, btc.get_mempool_entry(txid).unwrap()?)))
+            .max_by_key(|(_, entry)| entry.fees.base)
+            .map(|(txid, root)| {
+                let descendants = btc
+                    .get_mempool_descendants(txid)
+                    .unwrap()
+                    .into_iter()
+                    .filter_map(|txid| btc.get_mempool_entry(&txid).unwrap());
+                let package = std::iter::once(root).chain(descendants);
+                let (total, vsize) = package.fold((0, 0), |(total, vsize), entry| {
+                    (total + entry.fees.base.to_sat(), vsize + entry.vsize)
+                });
+                Fees {
+                    total,
+                    rate: total as f64 / vsize as f64,
+                }
+            });
 
         let withdrawals = self
             .withdrawals
@@ -957,11 +985,17 @@ impl TestSweepSetup2 {
                 public_key: aggregated_signer.keypair.x_only_public_key().0,
                 last_fees,
                 magic_bytes: [b'T', b'3'],
+                rbf_target_fee_rate: None,
+                anchor_output: false,
             },
             accept_threshold: 4,
             num_signers: 7,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            min_batch: None,
+            withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+            min_signer_output: None,
+            shuffle_seed: None,
         };
 
         // There should only be one transaction here since there is only
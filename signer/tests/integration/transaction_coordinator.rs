@@ -57,6 +57,7 @@ use signer::bitcoin::utxo::Fees;
 use signer::bitcoin::utxo::TxDeconstructor as _;
 use signer::bitcoin::validation::WithdrawalValidationResult;
 use signer::block_observer;
+use signer::config::NetworkKind;
 use signer::context::RequestDeciderEvent;
 use signer::message::Payload;
 use signer::network::MessageTransfer;
@@ -449,6 +450,7 @@ async fn process_complete_deposit() {
     // Get the private key of the coordinator of the signer set.
     let private_key = select_coordinator(&setup.sweep_block_hash.into(), &signer_info);
     let config = context.config_mut();
+    config.signer.private_key = private_key;
     config.signer.bootstrap_signing_set = signer_info
         .first()
         .map(|signer| signer.signer_public_keys.clone())
@@ -682,6 +684,9 @@ async fn deploy_smart_contracts_coordinator() {
             .with_first_bitcoin_core_client()
             .with_emily_client(emily_client.clone())
             .with_mocked_stacks_client()
+            .modify_settings(|settings| {
+                settings.signer.private_key = kp.secret_key().into();
+            })
             .build();
 
         backfill_bitcoin_blocks(&db, rpc, &chain_tip_info.hash).await;
@@ -869,6 +874,7 @@ async fn run_dkg_from_scratch() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
     let test_data = TestData::generate(&mut rng, &[], &test_params);
 
@@ -1106,7 +1112,12 @@ async fn run_dkg_if_signer_set_changes(signer_set_changed: bool) {
     prevent_dkg_on_changed_signer_set_info(&ctx, dkg_shares.aggregate_key);
 
     // Before we actually change the signer set, the DKG won't be triggered
-    assert!(!should_coordinate_dkg(&ctx, &chaintip).await.unwrap());
+    assert!(
+        !should_coordinate_dkg(&ctx, &chaintip)
+            .await
+            .unwrap()
+            .is_allowed()
+    );
     assert!(assert_allow_dkg_begin(&ctx, &chaintip).await.is_err());
 
     // Now we change context signer set.
@@ -1119,10 +1130,20 @@ async fn run_dkg_if_signer_set_changes(signer_set_changed: bool) {
     ctx.state().update_registry_signer_set_info(signer_set_info);
 
     if signer_set_changed {
-        assert!(should_coordinate_dkg(&ctx, &chaintip).await.unwrap());
+        assert!(
+            should_coordinate_dkg(&ctx, &chaintip)
+                .await
+                .unwrap()
+                .is_allowed()
+        );
         assert!(assert_allow_dkg_begin(&ctx, &chaintip).await.is_ok());
     } else {
-        assert!(!should_coordinate_dkg(&ctx, &chaintip).await.unwrap());
+        assert!(
+            !should_coordinate_dkg(&ctx, &chaintip)
+                .await
+                .unwrap()
+                .is_allowed()
+        );
         assert!(assert_allow_dkg_begin(&ctx, &chaintip).await.is_err());
     }
     testing::storage::drop_db(db).await;
@@ -1171,17 +1192,32 @@ async fn run_dkg_if_signatures_required_changes(change_signatures_required: bool
     let chaintip: model::BitcoinBlockRef = Faker.fake_with_rng(&mut rng);
 
     // Before we actually change the signatures_required, the DKG won't be triggered
-    assert!(!should_coordinate_dkg(&ctx, &chaintip).await.unwrap());
+    assert!(
+        !should_coordinate_dkg(&ctx, &chaintip)
+            .await
+            .unwrap()
+            .is_allowed()
+    );
     assert!(assert_allow_dkg_begin(&ctx, &chaintip).await.is_err());
 
     // Change bootstrap_signatures_required to trigger dkg
     if change_signatures_required {
         ctx.config_mut().signer.bootstrap_signatures_required = 2;
 
-        assert!(should_coordinate_dkg(&ctx, &chaintip).await.unwrap());
+        assert!(
+            should_coordinate_dkg(&ctx, &chaintip)
+                .await
+                .unwrap()
+                .is_allowed()
+        );
         assert!(assert_allow_dkg_begin(&ctx, &chaintip).await.is_ok());
     } else {
-        assert!(!should_coordinate_dkg(&ctx, &chaintip).await.unwrap());
+        assert!(
+            !should_coordinate_dkg(&ctx, &chaintip)
+                .await
+                .unwrap()
+                .is_allowed()
+        );
         assert!(assert_allow_dkg_begin(&ctx, &chaintip).await.is_err());
     }
     testing::storage::drop_db(db).await;
@@ -1220,10 +1256,20 @@ async fn skip_dkg_if_latest_shares_unverified(
     let chaintip: model::BitcoinBlockRef = Faker.fake_with_rng(&mut rng);
 
     if should_run_dkg {
-        assert!(should_coordinate_dkg(&ctx, &chaintip).await.unwrap());
+        assert!(
+            should_coordinate_dkg(&ctx, &chaintip)
+                .await
+                .unwrap()
+                .is_allowed()
+        );
         assert!(assert_allow_dkg_begin(&ctx, &chaintip).await.is_ok());
     } else {
-        assert!(!should_coordinate_dkg(&ctx, &chaintip).await.unwrap());
+        assert!(
+            !should_coordinate_dkg(&ctx, &chaintip)
+                .await
+                .unwrap()
+                .is_allowed()
+        );
         assert!(assert_allow_dkg_begin(&ctx, &chaintip).await.is_err());
     }
 
@@ -1248,6 +1294,7 @@ async fn run_subsequent_dkg() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        fork_points: Vec::new(),
     };
     let test_data = TestData::generate(&mut rng, &[], &test_params);
 
@@ -1281,6 +1328,7 @@ async fn run_subsequent_dkg() {
             .with_storage(db.clone())
             .with_mocked_clients()
             .modify_settings(|settings| {
+                settings.signer.private_key = kp.secret_key().into();
                 settings.signer.dkg_target_rounds = NonZeroU32::new(2).unwrap();
                 settings.signer.dkg_min_bitcoin_block_height = Some(10u64.into());
             })
@@ -1296,8 +1344,9 @@ async fn run_subsequent_dkg() {
         db.write_encrypted_dkg_shares(&EncryptedDkgShares {
             aggregate_key: aggregate_key_1,
             signer_set_public_keys: signer_set_public_keys.iter().copied().collect(),
+            signature_share_threshold: 1,
             dkg_shares_status: DkgSharesStatus::Verified,
-            ..Faker.fake()
+            ..aggregate_key_1.fake()
         })
         .await
         .expect("failed to write dkg shares");
@@ -1535,6 +1584,9 @@ async fn sign_bitcoin_transaction() {
             .with_first_bitcoin_core_client()
             .with_emily_client(emily_client.clone())
             .with_mocked_stacks_client()
+            .modify_settings(|settings| {
+                settings.signer.private_key = kp.secret_key().into();
+            })
             .build();
 
         backfill_bitcoin_blocks(&db, rpc, &chain_tip_info.hash).await;
@@ -1870,6 +1922,7 @@ async fn sign_bitcoin_transaction_multiple_locking_keys() {
             .with_emily_client(emily_client.clone())
             .with_mocked_stacks_client()
             .modify_settings(|settings| {
+                settings.signer.private_key = kp.secret_key().into();
                 settings.signer.dkg_target_rounds = NonZeroU32::new(2).unwrap();
                 settings.signer.dkg_min_bitcoin_block_height = Some(dkg_run_two_height.into());
                 settings.signer.bitcoin_processing_delay = Duration::from_millis(200);
@@ -2402,6 +2455,7 @@ async fn skip_signer_activites_after_key_rotation() {
             .with_emily_client(emily_client.clone())
             .with_mocked_stacks_client()
             .modify_settings(|settings| {
+                settings.signer.private_key = kp.secret_key().into();
                 settings.signer.dkg_target_rounds = NonZeroU32::new(2).unwrap();
                 settings.signer.dkg_min_bitcoin_block_height = Some(dkg_run_two_height.into());
                 settings.signer.bitcoin_processing_delay = Duration::from_millis(200);
@@ -2876,6 +2930,9 @@ async fn skip_smart_contract_deployment_and_key_rotation_if_up_to_date() {
             .with_first_bitcoin_core_client()
             .with_emily_client(emily_client.clone())
             .with_mocked_stacks_client()
+            .modify_settings(|settings| {
+                settings.signer.private_key = kp.secret_key().into();
+            })
             .build();
 
         backfill_bitcoin_blocks(&db, rpc, &chain_tip_info.hash).await;
@@ -3141,7 +3198,7 @@ async fn test_get_btc_state_with_no_available_sweep_transactions() {
         aggregate_key: *aggregate_key,
         script_pubkey: aggregate_key.signers_script_pubkey().into(),
         dkg_shares_status: DkgSharesStatus::Unverified,
-        ..Faker.fake_with_rng(&mut rng)
+        ..aggregate_key.fake_with_rng(&mut rng)
     };
     db.write_encrypted_dkg_shares(&dkg_shares).await.unwrap();
 
@@ -3264,7 +3321,7 @@ async fn test_get_btc_state_with_available_sweep_transactions_and_rbf() {
         aggregate_key: *aggregate_key,
         script_pubkey: aggregate_key.signers_script_pubkey().into(),
         dkg_shares_status: DkgSharesStatus::Unverified,
-        ..Faker.fake_with_rng(&mut rng)
+        ..aggregate_key.fake_with_rng(&mut rng)
     };
     db.write_encrypted_dkg_shares(&dkg_shares).await.unwrap();
 
@@ -3539,6 +3596,7 @@ async fn test_conservative_initial_sbtc_limits() {
             .with_mocked_stacks_client()
             .with_mocked_emily_client()
             .modify_settings(|settings| {
+                settings.signer.private_key = kp.secret_key().into();
                 settings.signer.bootstrap_signing_set = signer_set_public_keys.clone();
                 settings.signer.bootstrap_signatures_required = signatures_required;
             })
@@ -3914,6 +3972,9 @@ async fn sign_bitcoin_transaction_withdrawals() {
             .with_first_bitcoin_core_client()
             .with_emily_client(emily_client.clone())
             .with_mocked_stacks_client()
+            .modify_settings(|settings| {
+                settings.signer.private_key = kp.secret_key().into();
+            })
             .build();
 
         backfill_bitcoin_blocks(&db, rpc, &chain_tip_info.hash).await;
@@ -4412,10 +4473,17 @@ async fn process_rejected_withdrawal(is_completed: bool, is_in_mempool: bool) {
         .get_tx_info(&donation.txid, &bitcoin_chain_tip)
         .unwrap()
         .unwrap();
-    let bootstrap_script_pubkey = context.config().signer.bootstrap_aggregate_key;
+    let bootstrap_script_pubkeys = context
+        .config()
+        .signer
+        .bootstrap_aggregate_key
+        .map(|key| key.signers_script_pubkey())
+        .into_iter()
+        .collect();
     block_observer::extract_sbtc_transactions(
         &db,
-        bootstrap_script_pubkey,
+        &bootstrap_script_pubkeys,
+        NetworkKind::Regtest.magic_bytes(),
         bitcoin_chain_tip,
         &[tx],
     )
@@ -4657,6 +4725,7 @@ async fn coordinator_skip_onchain_completed_deposits(deposit_completed: bool) {
         .with_mocked_clients()
         .modify_settings(|settings| {
             let public_key = signer.keypair.public_key().into();
+            settings.signer.private_key = signer.keypair.secret_key().into();
             settings.signer.bootstrap_signing_set = [public_key].into_iter().collect();
             settings.signer.bootstrap_signatures_required = 1;
         })
@@ -4927,8 +4996,9 @@ mod get_eligible_pending_withdrawal_requests {
             started_at_bitcoin_block_hash: bitcoin_chain_tip.block_hash,
             started_at_bitcoin_block_height: bitcoin_chain_tip.block_height,
             signer_set_public_keys: signer_set.signer_keys().to_vec(),
+            signature_share_threshold: 1,
             dkg_shares_status: DkgSharesStatus::Verified,
-            ..Faker.fake()
+            ..signer_set.aggregate_key().fake()
         };
         db.write_encrypted_dkg_shares(&dkg_shares).await.unwrap();
 
@@ -5113,9 +5183,11 @@ mod get_eligible_pending_withdrawal_requests {
 #[test_log::test(tokio::test)]
 async fn should_handle_dkg_coordination_failure() {
     let mut rng = get_rng();
+    let private_key = PrivateKey::new(&mut rng);
     let context = TestContext::builder()
         .with_in_memory_storage()
         .with_mocked_clients()
+        .modify_settings(|settings| settings.signer.private_key = private_key)
         .build();
 
     let storage = context.get_storage_mut();
@@ -5162,7 +5234,8 @@ async fn should_handle_dkg_coordination_failure() {
     assert!(
         transaction_coordinator::should_coordinate_dkg(&context, &chain_tip)
             .await
-            .unwrap(),
+            .unwrap()
+            .is_allowed(),
         "DKG should be triggered since no shares exist yet"
     );
 
@@ -5171,7 +5244,7 @@ async fn should_handle_dkg_coordination_failure() {
     let mut coordinator = TxCoordinatorEventLoop {
         context: context.clone(),
         network: network.spawn(),
-        private_key: PrivateKey::new(&mut rng),
+        private_key,
         threshold: 3,
         context_window: 5,
         signing_round_max_duration: std::time::Duration::from_secs(5),
@@ -5435,6 +5508,7 @@ async fn reuse_nonce_attack() {
             .with_emily_client(emily_client.clone())
             .with_stacks_client(stacks.clone())
             .modify_settings(|settings| {
+                settings.signer.private_key = kp.secret_key().into();
                 settings.signer.bootstrap_signatures_required = signatures_required;
                 settings.signer.bootstrap_signing_set = signer_set_public_keys.clone();
                 settings.signer.deployer = deployer;
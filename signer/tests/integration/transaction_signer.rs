@@ -549,6 +549,8 @@ async fn assert_should_be_able_to_handle_sbtc_requests() {
         request_package: vec![sbtc_requests],
         fee_rate,
         last_fees: None,
+        shuffle_seed: None,
+        rbf_target_fee_rate: None,
     };
 
     let sbtc_state = signer::bitcoin::utxo::SignerBtcState {
@@ -562,6 +564,8 @@ async fn assert_should_be_able_to_handle_sbtc_requests() {
         last_fees: None,
         public_key: setup.aggregated_signer.keypair.public_key().into(),
         magic_bytes: [b'T', b'3'],
+        rbf_target_fee_rate: None,
+        anchor_output: false,
     };
 
     // Create an unsigned transaction with the deposit request
@@ -698,6 +702,8 @@ async fn presign_requests_with_dkg_shares_status(status: DkgSharesStatus, is_ok:
         request_package: vec![sbtc_requests],
         fee_rate: 2.0,
         last_fees: None,
+        shuffle_seed: None,
+        rbf_target_fee_rate: None,
     };
 
     let result = tx_signer
@@ -795,6 +801,8 @@ pub async fn presign_request_ignore_request_if_already_processed_this_block() {
         request_package: vec![sbtc_requests],
         fee_rate: 2.0,
         last_fees: None,
+        shuffle_seed: None,
+        rbf_target_fee_rate: None,
     };
 
     let result = tx_signer
@@ -1143,7 +1151,7 @@ mod validate_dkg_verification_message {
     async fn latest_key_mismatch() {
         let mut rng = get_rng();
         let db = testing::storage::new_test_database().await;
-        let latest_aggregate_key = Keypair::new_global(&mut rng).public_key().into();
+        let latest_aggregate_key: PublicKey = Keypair::new_global(&mut rng).public_key().into();
         let new_aggregate_key = Keypair::new_global(&mut rng).x_only_public_key().into();
 
         // Create new DKG shares and store them in the database. We expect the
@@ -1151,7 +1159,7 @@ mod validate_dkg_verification_message {
         // know won't match.
         let shares = EncryptedDkgShares {
             aggregate_key: latest_aggregate_key,
-            ..Faker.fake()
+            ..latest_aggregate_key.fake()
         };
         db.write_encrypted_dkg_shares(&shares).await.unwrap();
 
@@ -1189,7 +1197,7 @@ mod validate_dkg_verification_message {
         let shares = EncryptedDkgShares {
             aggregate_key,
             dkg_shares_status: DkgSharesStatus::Failed,
-            ..Faker.fake()
+            ..aggregate_key.fake()
         };
         db.write_encrypted_dkg_shares(&shares).await.unwrap();
 
@@ -1218,7 +1226,7 @@ mod validate_dkg_verification_message {
             aggregate_key,
             dkg_shares_status: DkgSharesStatus::Unverified,
             started_at_bitcoin_block_height: 0u64.into(),
-            ..Faker.fake()
+            ..aggregate_key.fake()
         };
         db.write_encrypted_dkg_shares(&shares).await.unwrap();
 
@@ -1256,7 +1264,7 @@ mod validate_dkg_verification_message {
             aggregate_key,
             dkg_shares_status: DkgSharesStatus::Unverified,
             started_at_bitcoin_block_height: 0u64.into(),
-            ..Faker.fake()
+            ..aggregate_key.fake()
         };
         db.write_encrypted_dkg_shares(&shares).await.unwrap();
 
@@ -1289,7 +1297,7 @@ mod validate_dkg_verification_message {
             aggregate_key,
             dkg_shares_status: DkgSharesStatus::Unverified,
             started_at_bitcoin_block_height: 0u64.into(),
-            ..Faker.fake()
+            ..aggregate_key.fake()
         };
         db.write_encrypted_dkg_shares(&shares).await.unwrap();
 
@@ -1320,7 +1328,7 @@ mod validate_dkg_verification_message {
             aggregate_key,
             dkg_shares_status: DkgSharesStatus::Unverified,
             started_at_bitcoin_block_height: 0u64.into(),
-            ..Faker.fake()
+            ..aggregate_key.fake()
         };
         db.write_encrypted_dkg_shares(&shares).await.unwrap();
 
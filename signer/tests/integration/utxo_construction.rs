@@ -34,6 +34,7 @@ use signer::bitcoin::utxo::SignerBtcState;
 use signer::bitcoin::utxo::SignerUtxo;
 use signer::bitcoin::utxo::TxDeconstructor;
 use signer::bitcoin::utxo::WithdrawalRequest;
+use signer::bitcoin::utxo::WithdrawalsToSignersPolicy;
 use signer::config::Settings;
 use signer::context::SbtcLimits;
 use signer::keys::SignerScriptPubKey;
@@ -233,11 +234,17 @@ fn deposits_add_to_controlled_amounts() {
             public_key: signers_public_key,
             last_fees: None,
             magic_bytes: [b'T', b'3'],
+            rbf_target_fee_rate: None,
+            anchor_output: false,
         },
         accept_threshold: 4,
         num_signers: 7,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+        min_batch: None,
+        withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+        min_signer_output: None,
+        shuffle_seed: None,
     };
 
     // There should only be one transaction here since there is only one
@@ -260,6 +267,83 @@ fn deposits_add_to_controlled_amounts() {
     more_asserts::assert_lt!(signers_balance.to_sat(), 125_000_000);
 }
 
+#[test]
+fn partial_witness_data_is_rejected() {
+    let (rpc, faucet) = regtest::initialize_blockchain();
+
+    let signer = Recipient::new(AddressType::P2tr);
+    let depositor = Recipient::new(AddressType::P2tr);
+    let signers_public_key = signer.keypair.x_only_public_key().0;
+
+    // Start off with some initial UTXOs to work with.
+    faucet.send_to(100_000_000, &signer.address);
+    faucet.send_to(50_000_000, &depositor.address);
+    faucet.generate_blocks(1);
+
+    let depositor_utxo = depositor.get_utxos(rpc, None).pop().unwrap();
+    let deposit_amount = 25_000_000;
+    let max_fee = deposit_amount / 2;
+
+    let (deposit_tx, deposit_request, _) = make_deposit_request(
+        &depositor,
+        deposit_amount,
+        depositor_utxo,
+        max_fee,
+        signers_public_key,
+    );
+    rpc.send_raw_transaction(&deposit_tx).unwrap();
+    faucet.generate_blocks(1);
+
+    let signer_utxo = signer.get_utxos(rpc, None).pop().unwrap();
+
+    let requests = SbtcRequests {
+        deposits: vec![deposit_request],
+        withdrawals: Vec::new(),
+        signer_state: SignerBtcState {
+            utxo: SignerUtxo {
+                outpoint: OutPoint::new(signer_utxo.txid, signer_utxo.vout),
+                amount: signer_utxo.amount.to_sat(),
+                public_key: signers_public_key,
+            },
+            fee_rate: 10.0,
+            public_key: signers_public_key,
+            last_fees: None,
+            magic_bytes: [b'T', b'3'],
+            rbf_target_fee_rate: None,
+            anchor_output: false,
+        },
+        accept_threshold: 4,
+        num_signers: 7,
+        sbtc_limits: SbtcLimits::unlimited(),
+        max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+        min_batch: None,
+        withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+        min_signer_output: None,
+        shuffle_seed: None,
+    };
+
+    let mut transactions = requests.construct_transactions().unwrap();
+    assert_eq!(transactions.len(), 1);
+    let mut unsigned = transactions.pop().unwrap();
+
+    // Only three of the seven signers take part in signing. The shares
+    // are additive, so any signing set smaller than all seven produces
+    // invalid signatures, regardless of the accept threshold.
+    signer::testing::set_partial_witness_data(&mut unsigned, signer.keypair, 7, 3);
+
+    let result = rpc.send_raw_transaction(&unsigned.tx);
+    match result {
+        Err(bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::Error::Rpc(error))) => {
+            assert!(error.message.contains("script-verify-flag-failed"));
+        }
+        _ => panic!("expected a script verification failure, got {result:?}"),
+    }
+
+    // Once every signer takes part, the transaction is accepted.
+    signer::testing::set_partial_witness_data(&mut unsigned, signer.keypair, 7, 7);
+    rpc.send_raw_transaction(&unsigned.tx).unwrap();
+}
+
 #[test]
 fn withdrawals_reduce_to_signers_amounts() {
     const FEE_RATE: f64 = 10.0;
@@ -298,11 +382,17 @@ fn withdrawals_reduce_to_signers_amounts() {
             public_key: signers_public_key,
             last_fees: None,
             magic_bytes: [b'T', b'3'],
+            rbf_target_fee_rate: None,
+            anchor_output: false,
         },
         accept_threshold: 4,
         num_signers: 7,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+        min_batch: None,
+        withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+        min_signer_output: None,
+        shuffle_seed: None,
     };
 
     // There should only be one transaction here since there is only one
@@ -443,11 +533,17 @@ fn parse_withdrawal_ids(withdrawal_numbers: u64) {
             public_key: signers_public_key,
             last_fees: None,
             magic_bytes: [b'T', b'3'],
+            rbf_target_fee_rate: None,
+            anchor_output: false,
         },
         accept_threshold: 4,
         num_signers: 7,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+        min_batch: None,
+        withdrawals_to_signers: WithdrawalsToSignersPolicy::Reject,
+        min_signer_output: None,
+        shuffle_seed: None,
     };
 
     // There should only be one transaction here since there are only